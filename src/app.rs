@@ -1,29 +1,105 @@
+mod adopt;
+mod apply_all;
+mod autosave;
+pub(crate) mod backups;
+pub(crate) mod cache;
+pub(crate) mod changelog;
+mod commands;
+mod difftool;
+pub(crate) mod facets;
+mod full_text;
+mod git;
+pub(crate) mod glob_tester;
+pub(crate) mod help;
+pub(crate) mod history;
+pub(crate) mod log;
+mod jobs;
+mod marks;
+pub(crate) mod members;
+mod mouse;
+pub(crate) mod notes;
+pub(crate) mod orphans;
+mod overlay;
+pub(crate) mod plan;
+pub(crate) mod preview;
+mod profile;
+pub(crate) mod projects;
+pub(crate) mod prune;
+pub(crate) mod reader;
+mod report;
+pub(crate) mod repos;
+mod reset;
+pub(crate) mod save_report;
+mod session;
+pub(crate) mod staging;
+pub(crate) mod unmanaged;
+mod vscode;
+pub(crate) mod warnings;
+mod watch;
+pub(crate) mod wizard;
+mod yank;
+
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     io::{self, stdout},
     path::{Path, PathBuf},
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 
 use anyhow::{Context, Result};
 use clap::Parser;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
+use ratatui::{backend::CrosstermBackend, layout::Rect, Terminal};
 
 use crate::{
-    domain::{model::AssetKind, state::DomainState},
+    domain::{
+        model::{AssetKind, Catalog, EnablementFile, PRIMARY_SOURCE},
+        query::Query,
+        state::{sort_assets, DomainState, SortMode, StateFilter},
+    },
     io::{
-        catalog, enablement,
-        paths::RepoPaths,
-        sync::{self},
+        changelog::Changelog,
+        content_index::ContentIndex,
+        config::{self, Confirmations},
+        enablement,
+        history::{HistoryEntry, HistoryEvent},
+        loader::LoadedState,
+        lock::InstanceLock,
+        log_file::{self, RollingFile},
+        paths::{RepoPaths, Target},
+        profile::{ImportMode, ImportPlan},
+        sync::{ApplyMode, LineEndings, LocalStatus},
+        upstream::{
+            self, FetchOptions, Freshness, Refresh, SnapshotProgress, UpstreamRoots,
+            UpstreamSource,
+        },
+        watcher::RepoWatcher,
+        worker::{FileOp, Job, Worker},
+        workspace::{self, WorkspaceState},
+    },
+    ui::{
+        draw,
+        keymap::{self, Action, Keymap, KeymapConfig, Resolution},
+        theme::{self, Theme, ThemeConfig},
     },
-    ui::draw,
 };
 
+pub(crate) use overlay::Overlay;
+use reset::ResetScope;
+use warnings::{Warning, WarningSource};
+
+const DEFAULT_TICK_MS: u64 = 250;
+const DEFAULT_TABLE_WIDTH: u16 = 60;
+const MIN_TABLE_WIDTH: i16 = 20;
+const MAX_TABLE_WIDTH: i16 = 80;
+const SPLIT_STEP: i16 = 5;
+
 #[derive(Debug, Parser)]
 #[command(
     author,
@@ -35,16 +111,91 @@ struct Cli {
     #[arg(long, value_name = "PATH")]
     repo: Option<PathBuf>,
 
-    /// UI tick rate in milliseconds for handling periodic events.
-    #[arg(long = "tick", default_value_t = 250)]
-    tick_ms: u64,
+    /// Where enabled assets are applied: the repository, or the VS Code user
+    /// profile with its own enablement state [default: repo].
+    #[arg(long, value_enum, conflicts_with = "repo")]
+    target: Option<Target>,
+
+    /// Monorepo sub-project to manage instead of the repository root; one of
+    /// `[monorepo] projects`.
+    #[arg(long, value_name = "DIR", conflicts_with = "target")]
+    project: Option<String>,
+
+    /// UI tick rate in milliseconds for handling periodic events [default: 250].
+    #[arg(long = "tick")]
+    tick_ms: Option<u64>,
+
+    /// Additional upstream source merged into the catalog (repeatable).
+    #[arg(long = "source", value_name = "ID=OWNER/REPO[@REF]")]
+    sources: Vec<UpstreamSource>,
+
+    /// Pin the official snapshot to a commit SHA or tag (persisted for the repo).
+    #[arg(long, value_name = "REF", conflicts_with = "unpin")]
+    pin: Option<String>,
+
+    /// Remove a persisted pin and follow `main` again.
+    #[arg(long)]
+    unpin: bool,
+
+    /// How applying an asset treats an existing local file [default: overwrite].
+    #[arg(long, value_enum)]
+    apply_mode: Option<ApplyMode>,
+
+    /// Keymap preset (default or vim); overrides the preset in keymap.toml.
+    #[arg(long, value_name = "PRESET", value_parser = keymap::parse_preset)]
+    keymap: Option<String>,
+
+    /// Color theme (dark, light or high-contrast); overrides the base in theme.toml.
+    #[arg(long, value_name = "NAME", value_parser = theme::parse_theme)]
+    theme: Option<String>,
+
+    /// Draw badges, borders and markers with ASCII characters only.
+    #[arg(long)]
+    ascii: bool,
+
+    /// Save the enablement file automatically shortly after every change.
+    #[arg(long)]
+    auto_save: bool,
+
+    /// Queue the file changes of toggles and run them on save.
+    #[arg(long)]
+    defer_apply: bool,
+
+    /// Hours a cached snapshot is used before it is downloaded again, or `never`
+    /// to only refresh on `r` [default: 12].
+    #[arg(long, value_name = "HOURS|never")]
+    freshness: Option<Freshness>,
+
+    /// Cached snapshots kept per source [default: 5].
+    #[arg(long, value_name = "N")]
+    keep_snapshots: Option<usize>,
+
+    /// Write tracing output to this file (rolled over at 5 MiB) instead of stderr.
+    #[arg(long, value_name = "PATH", global = true)]
+    log_file: Option<PathBuf>,
+
+    /// Log more: -v info, -vv debug, -vvv trace [default: RUST_LOG, else errors only].
+    #[arg(long, short, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+
+    #[command(subcommand)]
+    command: Option<commands::Command>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum PendingPrompt {
     Quit,
     Reload,
+    /// Reload after downloading the snapshots again.
+    Refresh,
     ToggleCollection,
+    BulkToggle,
+    BulkApply,
+    Import,
+    ExternalReload,
+    ApplyAll,
+    Reset(ResetScope),
+    Adopt,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -52,6 +203,8 @@ pub(crate) struct SearchState {
     active: bool,
     query: String,
     draft: String,
+    /// Also match the text of upstream files.
+    full_text: bool,
 }
 
 impl SearchState {
@@ -66,105 +219,350 @@ impl SearchState {
     pub(crate) fn draft(&self) -> &str {
         &self.draft
     }
+
+    pub(crate) fn full_text(&self) -> bool {
+        self.full_text
+    }
 }
 
 pub struct App {
     paths: RepoPaths,
-    upstream_dir: PathBuf,
+    workspace: WorkspaceState,
+    sources: Vec<UpstreamSource>,
+    upstream: UpstreamRoots,
+    worker: Worker,
+    loading: bool,
+    load_progress: Option<SnapshotProgress>,
+    /// Number of the latest local-status request; older answers are dropped.
+    status_generation: u64,
+    apply_mode: ApplyMode,
+    keymap: Keymap,
+    theme: Theme,
+    confirm: Confirmations,
+    git_stage: bool,
+    vscode_settings: bool,
+    /// File whose marked asset list is rewritten on save (`[report] inject`).
+    report_section: Option<PathBuf>,
+    /// Keep the enabled instructions in `.github/copilot-instructions.md`
+    /// (`[instructions] aggregate`).
+    aggregate_instructions: bool,
+    /// External diff command (`$DIFFTOOL` or `[diff] tool`).
+    diff_tool: Option<String>,
+    /// Set on every loaded catalog's upstream (`line-endings`).
+    line_endings: Option<LineEndings>,
+    /// Files the diff tool compares once the event loop suspends the TUI.
+    pending_diff: Option<difftool::DiffRequest>,
+    /// Text of the loaded catalog's files for full-text search, once the
+    /// worker has indexed them.
+    content_index: Option<ContentIndex>,
+    /// Save pending changes once input has been idle for a moment.
+    auto_save: bool,
+    /// Queue the file changes of toggles in `staged` until the next save.
+    defer_apply: bool,
+    staged: Vec<FileOp>,
+    fetch: FetchOptions,
     domain: DomainState,
-    warnings: Vec<String>,
+    warnings: Vec<Warning>,
     message: Option<String>,
     error: Option<String>,
     dirty: bool,
+    /// Whether unmanaged files under `.github/` were already offered for
+    /// adoption this session.
+    adoption_offered: bool,
     tab: AssetKind,
     selections: BTreeMap<AssetKind, usize>,
+    /// Paths to select on each tab once the first catalog has loaded.
+    resume_selections: BTreeMap<AssetKind, String>,
+    marks: BTreeMap<AssetKind, BTreeSet<String>>,
+    visual_anchor: Option<usize>,
+    detail_focused: bool,
+    detail_scroll: u16,
+    facets: facets::FacetPanel,
+    state_filter: StateFilter,
+    preview_enabled: bool,
+    preview: Option<preview::AssetPreview>,
     search: SearchState,
+    note_editor: Option<notes::NoteEditor>,
     prompt: Option<PendingPrompt>,
+    overlay: Option<Overlay>,
+    body_area: Rect, // last drawn body, for paging in the reader
+    watcher: Option<RepoWatcher>,
+    enablement_stamp: Option<SystemTime>,
+    last_input: Instant,
     pending_toggle_asset: Option<crate::domain::state::AssetView>, // Store asset for collection toggle confirmation
+    pending_import: Option<ImportPlan>,
     tick_rate: Duration,
     last_tick: Instant,
+    /// Set when something on screen may have changed; the loop only draws
+    /// then.
+    redraw: bool,
     should_quit: bool,
-    shadow_current_assets: Option<Vec<crate::domain::state::AssetView>>, // filtered list with local statuses
+    /// Repository or sub-project to open once the loop ends, chosen in a
+    /// switcher.
+    switch_to: Option<RepoPaths>,
+    /// Monorepo sub-projects (`[monorepo] projects`).
+    projects: Vec<String>,
+    saved: save_report::SavedBaseline,
+    changelog: Changelog, // upstream changes since the previous snapshot
+    history: Vec<HistoryEntry>,
+    /// Entries before this index were loaded from earlier sessions.
+    history_session_start: usize,
+    persist_history: bool,
+    log: log::MessageLog,
+    /// Append footer messages to `messages.log` as well.
+    log_to_file: bool,
+    pending_updates: usize, // enabled, applied assets with a newer upstream version
 }
 
 pub fn run() -> Result<()> {
-    install_tracing();
-    let cli = Cli::parse();
-    let repo = cli
-        .repo
-        .as_deref()
-        .map(Path::to_path_buf)
-        .unwrap_or_else(|| std::env::current_dir().expect("working directory"));
-    let paths = RepoPaths::new(repo);
-
-    let catalog_load = catalog::load_catalog(&paths)?;
-    let enablement_load = enablement::load_enablement(&paths)?;
-    let mut warnings = catalog_load.warnings;
-    warnings.extend(
-        enablement_load
-            .warnings
-            .into_iter()
-            .map(|warning| warning.to_string()),
-    );
-    let domain = DomainState::new(catalog_load.catalog, enablement_load.file);
+    let mut cli = Cli::parse();
+    install_tracing(cli.log_file.as_deref(), cli.verbose)?;
+    let mut paths = match cli.target.unwrap_or_default() {
+        Target::Repo => {
+            let root = cli
+                .repo
+                .as_deref()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| std::env::current_dir().expect("working directory"));
+            match cli.project.as_deref() {
+                Some(project) => config::project_paths(&root, project)?,
+                None => RepoPaths::new(root),
+            }
+        }
+        Target::User => config::user_target_paths()?,
+    };
+    let mut command = cli.command.take();
+    // Each switch to another registered repository or sub-project opens it
+    // like a launch; the pin flags only apply to the first.
+    while let Some(next) = open(&cli, paths, command.take())? {
+        cli.pin = None;
+        cli.unpin = false;
+        paths = next;
+    }
+    Ok(())
+}
+
+/// Run `command`, or the TUI, on `paths`. Returns the paths to switch to
+/// when the TUI quit to open another repository or sub-project.
+fn open(
+    cli: &Cli,
+    paths: RepoPaths,
+    command: Option<commands::Command>,
+) -> Result<Option<RepoPaths>> {
+    tracing::info!(root = %paths.root.display(), target = ?paths.target, "starting");
+    // Held until `open` returns; read-only commands don't need it.
+    let _lock = match &command {
+        Some(command) if !command.writes() && cli.pin.is_none() && !cli.unpin => None,
+        _ => Some(InstanceLock::acquire(&paths)?),
+    };
+    let mut config = config::load_config(&paths)?;
+    config.network.freshness = cli.freshness.or(config.network.freshness);
+    config.network.keep_snapshots = cli.keep_snapshots.or(config.network.keep_snapshots);
+    let fetch = config.network.resolve();
+    let apply_mode = cli.apply_mode.or(config.apply_mode).unwrap_or_default();
+    let confirm = config.confirm.resolve();
+    let mut workspace = workspace::load_workspace(&paths)?;
+    if cli.unpin || cli.pin.is_some() {
+        workspace.pinned_ref = cli.pin.clone();
+        workspace::save_workspace(&paths, &workspace)?;
+    }
+    // Sources from the command line override configured ones with the same id.
+    let mut extra_sources = config.upstream_sources()?;
+    extra_sources.extend(cli.sources.iter().cloned());
+    let sources = upstream::load_sources(&paths, &extra_sources, workspace.pinned_ref.as_deref())?;
+    // The user profile has no repository to stage in, workspace settings or
+    // documents to keep in step.
+    if paths.target == Target::User {
+        config.git.stage = None;
+        config.vscode.settings = None;
+        config.report.inject = None;
+        config.instructions.aggregate = None;
+    }
+    if let Some(command) = command {
+        let hooks = commands::SaveHooks {
+            vscode_settings: config.vscode.settings.unwrap_or(false),
+            report_section: config.report.inject.map(|path| paths.root.join(path)),
+            aggregate_instructions: config.instructions.aggregate.unwrap_or(false),
+        };
+        commands::run_command(
+            command,
+            &paths,
+            &sources,
+            apply_mode,
+            config.line_endings,
+            confirm,
+            hooks,
+            fetch,
+        )?;
+        return Ok(None);
+    }
+    if paths.target == Target::Repo {
+        if let Err(err) = crate::io::repos::record_opened(&paths.root) {
+            tracing::warn!(error = %format!("{err:#}"), "registering the repository failed");
+        }
+    }
 
+    // The catalog is loaded in the background; start from an empty state so
+    // the UI can show download progress right away.
+    let keymap = load_keymap(cli.keymap.as_deref().or(config.keymap.as_deref()))?;
+    let mut theme = load_theme(cli.theme.as_deref().or(config.theme.as_deref()))?;
+    // https://no-color.org: any non-empty value turns color off.
+    theme.color = std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty());
+    theme.ascii = cli.ascii || config.ascii.unwrap_or(false);
     let mut app = App::new(
         paths,
-        catalog_load.upstream_dir,
-        domain,
-        warnings,
-        Duration::from_millis(cli.tick_ms),
+        workspace,
+        sources,
+        apply_mode,
+        keymap,
+        theme,
+        Duration::from_millis(cli.tick_ms.or(config.tick).unwrap_or(DEFAULT_TICK_MS)),
     );
+    app.confirm = confirm;
+    app.git_stage = config.git.stage.unwrap_or(false);
+    app.auto_save = cli.auto_save || config.auto_save.unwrap_or(false);
+    app.defer_apply = cli.defer_apply || config.defer_apply.unwrap_or(false);
+    app.vscode_settings = config.vscode.settings.unwrap_or(false);
+    app.report_section = config.report.inject.map(|path| app.paths.root.join(path));
+    app.aggregate_instructions = config.instructions.aggregate.unwrap_or(false);
+    app.projects = config.monorepo.projects;
+    app.diff_tool = crate::io::difftool::resolve(config.diff.tool.as_deref());
+    app.line_endings = config.line_endings;
+    app.search.full_text = config.search.full_text.unwrap_or(false);
+    app.fetch = fetch;
+    app.persist_history = config.history.persist.unwrap_or(false);
+    app.log_to_file = config.log.file.unwrap_or(false);
+    if app.persist_history {
+        app.load_history();
+    }
+    app.restore_session();
+    app.start_loading(Refresh::Cached);
     app.run()?;
-    Ok(())
+    Ok(app.switch_to.take())
+}
+
+/// Build the keymap from `keymap.toml` in the user config directory, if any.
+fn load_keymap(preset: Option<&str>) -> Result<Keymap> {
+    let (config, path) = config::read_user_file::<KeymapConfig>("keymap.toml")?;
+    config
+        .build(preset)
+        .with_context(|| format!("building keymap from {path}"))
+}
+
+/// Build the theme from `theme.toml` in the user config directory, if any.
+fn load_theme(name: Option<&str>) -> Result<Theme> {
+    let (config, path) = config::read_user_file::<ThemeConfig>("theme.toml")?;
+    config
+        .build(name)
+        .with_context(|| format!("building theme from {path}"))
 }
 
+
 impl App {
     fn new(
         paths: RepoPaths,
-        upstream_dir: PathBuf,
-        domain: DomainState,
-        warnings: Vec<String>,
+        workspace: WorkspaceState,
+        sources: Vec<UpstreamSource>,
+        apply_mode: ApplyMode,
+        keymap: Keymap,
+        theme: Theme,
         tick_rate: Duration,
     ) -> Self {
         let mut selections = BTreeMap::new();
         selections.insert(AssetKind::Prompt, 0);
         selections.insert(AssetKind::Instruction, 0);
         selections.insert(AssetKind::ChatMode, 0);
+        selections.insert(AssetKind::Agent, 0);
+        selections.insert(AssetKind::McpServer, 0);
         selections.insert(AssetKind::Collection, 0);
         Self {
+            worker: Worker::spawn(paths.clone()),
             paths,
-            upstream_dir,
-            domain,
-            warnings,
+            workspace,
+            sources,
+            upstream: UpstreamRoots::default(),
+            loading: false,
+            load_progress: None,
+            status_generation: 0,
+            apply_mode,
+            keymap,
+            theme,
+            confirm: Confirmations::default(),
+            git_stage: false,
+            vscode_settings: false,
+            report_section: None,
+            aggregate_instructions: false,
+            diff_tool: None,
+            line_endings: None,
+            pending_diff: None,
+            content_index: None,
+            auto_save: false,
+            defer_apply: false,
+            staged: Vec::new(),
+            fetch: FetchOptions::default(),
+            domain: DomainState::new(Catalog::default(), EnablementFile::default()),
+            warnings: Vec::new(),
             message: None,
             error: None,
             dirty: false,
+            adoption_offered: false,
             tab: AssetKind::Instruction,
             selections,
+            resume_selections: BTreeMap::new(),
+            marks: BTreeMap::new(),
+            visual_anchor: None,
+            detail_focused: false,
+            detail_scroll: 0,
+            facets: facets::FacetPanel::default(),
+            state_filter: StateFilter::default(),
+            preview_enabled: false,
+            preview: None,
             search: SearchState::default(),
+            note_editor: None,
             prompt: None,
+            overlay: None,
+            body_area: Rect::default(),
+            watcher: None,
+            enablement_stamp: None,
+            last_input: Instant::now(),
             pending_toggle_asset: None,
+            pending_import: None,
             tick_rate,
             last_tick: Instant::now(),
+            redraw: true,
             should_quit: false,
-            shadow_current_assets: None,
+            switch_to: None,
+            projects: Vec::new(),
+            saved: save_report::SavedBaseline::default(),
+            changelog: Changelog::default(),
+            history: Vec::new(),
+            history_session_start: 0,
+            persist_history: false,
+            log: Default::default(),
+            log_to_file: false,
+            pending_updates: 0,
         }
     }
 
     fn run(&mut self) -> Result<()> {
         enable_raw_mode().context("Failed to enable raw mode")?;
         let mut stdout = stdout();
-        execute!(stdout, EnterAlternateScreen).context("Failed to enter alternate screen")?;
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)
+            .context("Failed to enter alternate screen")?;
         let backend = CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend).context("Failed to initialize TUI terminal")?;
         terminal.clear()?;
 
         let res = self.event_loop(&mut terminal);
+        self.save_session();
 
         disable_raw_mode().context("Failed to disable raw mode")?;
-        execute!(terminal.backend_mut(), LeaveAlternateScreen)
-            .context("Failed to leave alternate screen")?;
+        execute!(
+            terminal.backend_mut(),
+            DisableMouseCapture,
+            LeaveAlternateScreen
+        )
+        .context("Failed to leave alternate screen")?;
         terminal.show_cursor()?;
 
         res
@@ -172,9 +570,19 @@ impl App {
 
     fn event_loop(&mut self, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
         loop {
-            self.ensure_selection_bounds();
-            self.compute_local_statuses()?;
-            terminal.draw(|frame| draw::render(frame, self))?;
+            self.poll_worker();
+            self.poll_watcher();
+            self.auto_save_if_due();
+            self.capture_messages();
+            if self.redraw {
+                self.redraw = false;
+                self.ensure_selection_bounds();
+                self.refresh_preview();
+                self.body_area =
+                    draw::screen_layout(terminal.size()?, self.table_width(), self.facets.is_open())
+                        .body;
+                terminal.draw(|frame| draw::render(frame, self))?;
+            }
 
             if self.should_quit() {
                 break;
@@ -187,10 +595,18 @@ impl App {
 
             if event::poll(timeout)? {
                 match event::read()? {
-                    Event::Key(key) => self.handle_key(key)?,
-                    Event::Resize(_, _) => {
-                        // redraw on next loop iteration
+                    Event::Key(key) => {
+                        self.note_input();
+                        self.handle_key(key)?;
+                        self.run_pending_diff(terminal)?;
+                        self.redraw = true;
+                    }
+                    Event::Mouse(mouse) => {
+                        let size = terminal.size()?;
+                        self.handle_mouse(mouse, size);
+                        self.redraw = true;
                     }
+                    Event::Resize(_, _) => self.redraw = true,
                     _ => {}
                 }
             }
@@ -209,114 +625,195 @@ impl App {
             return Ok(());
         }
 
+        if self.note_editor.is_some() {
+            self.handle_note_key(key);
+            return Ok(());
+        }
+
         if let Some(prompt) = self.prompt {
             self.handle_prompt_key(prompt, key)?;
             return Ok(());
         }
 
-        match key {
-            KeyEvent {
-                code: KeyCode::Char('q'),
-                modifiers: KeyModifiers::NONE,
-                ..
-            } => self.request_quit(),
-            KeyEvent {
-                code: KeyCode::Char('c'),
-                modifiers: KeyModifiers::NONE,
-                ..
-            } => self.cleanup_orphans(),
-            KeyEvent {
-                code: KeyCode::Char('r'),
-                modifiers: KeyModifiers::NONE,
-                ..
-            } => self.request_reload(),
-            KeyEvent {
-                code: KeyCode::Char('a'),
-                modifiers: KeyModifiers::NONE,
-                ..
-            } => {
-                if let Err(err) = self.apply_selected() {
-                    self.error = Some(format!("Apply failed: {err}"));
+        if self.overlay.is_some() {
+            return self.handle_overlay_key(key);
+        }
+
+        let action = match self.keymap.resolve(key) {
+            Resolution::Action(action) => action,
+            Resolution::Pending | Resolution::Unbound => return Ok(()),
+        };
+
+        if self.loading && !matches!(action, Action::Quit | Action::Cancel) {
+            self.message = Some("Catalog is still loading…".into());
+            return Ok(());
+        }
+
+        if self.facets.is_focused() && self.handle_facet_action(action) {
+            return Ok(());
+        }
+
+        if self.detail_focused {
+            let delta = match action {
+                Action::Down => 1,
+                Action::Up => -1,
+                Action::PageDown => 10,
+                Action::PageUp => -10,
+                Action::First => {
+                    self.detail_scroll = 0;
+                    return Ok(());
+                }
+                Action::Cancel | Action::FocusDetail => {
+                    self.detail_focused = false;
+                    return Ok(());
+                }
+                _ => 0,
+            };
+            if delta != 0 {
+                self.scroll_detail(delta);
+                return Ok(());
+            }
+        }
+
+        self.perform_action(action)
+    }
+
+    fn perform_action(&mut self, action: Action) -> Result<()> {
+        match action {
+            Action::Quit => self.request_quit(),
+            Action::Cleanup => self.cleanup_orphans(),
+            Action::Reload => self.request_reload(Refresh::Check),
+            Action::Refresh => self.request_reload(Refresh::Force),
+            Action::Apply => {
+                let result = if self.mark_count() > 0 {
+                    self.request_bulk_apply()
                 } else {
-                    self.message = Some("Applied from upstream".into());
+                    self.apply_selected()
+                };
+                if let Err(err) = result {
+                    self.error = Some(format!("Apply failed: {err}"));
+                }
+            }
+            Action::ApplyAll => {
+                if let Err(err) = self.request_apply_all() {
+                    self.error = Some(format!("Apply all failed: {err}"));
+                }
+            }
+            Action::Mark => self.toggle_mark(),
+            Action::Favorite => self.toggle_favorite(),
+            Action::Note => self.edit_note(),
+            Action::Visual => self.toggle_visual(),
+            Action::CycleSort => self.cycle_sort(),
+            Action::CycleStateFilter => self.cycle_state_filter(),
+            Action::WidenTable => self.resize_split(SPLIT_STEP),
+            Action::NarrowTable => self.resize_split(-SPLIT_STEP),
+            Action::Preview => self.toggle_preview(),
+            Action::Reader => self.open_reader(),
+            Action::YankPath => self.yank_path(),
+            Action::YankUrl => self.yank_url(),
+            Action::YankContent => self.yank_content(),
+            Action::CycleApplyMode => self.cycle_apply_mode(),
+            Action::Backups => {
+                if let Err(err) = self.open_backups() {
+                    self.error = Some(format!("Listing backups failed: {err}"));
+                }
+            }
+            Action::Orphans => self.open_orphans(),
+            Action::Changelog => self.open_changelog(),
+            Action::Members => self.open_member_picker(),
+            Action::Facets => self.toggle_facets(),
+            Action::GlobTester => self.open_glob_tester(),
+            Action::History => self.open_history(),
+            Action::Log => self.open_log(),
+            Action::Prune => {
+                if let Err(err) = self.open_prune() {
+                    self.error = Some(format!("Prune failed: {err}"));
+                }
+            }
+            Action::Staged => self.open_staged(),
+            Action::Warnings => self.open_warnings(),
+            Action::Cache => self.open_cache(),
+            Action::Projects => self.open_projects(),
+            Action::Repos => {
+                if let Err(err) = self.open_repos() {
+                    self.error = Some(format!("Listing repositories failed: {err}"));
+                }
+            }
+            Action::Unmanaged => {
+                if let Err(err) = self.open_unmanaged() {
+                    self.error = Some(format!("Listing unmanaged files failed: {err}"));
+                }
+            }
+            Action::Help => self.open_help(),
+            Action::Import => {
+                if let Err(err) = self.request_import() {
+                    self.error = Some(format!("Import failed: {err}"));
+                }
+            }
+            Action::Export => {
+                if let Err(err) = self.export_profile() {
+                    self.error = Some(format!("Export failed: {err}"));
+                }
+            }
+            Action::ExportReport => {
+                if let Err(err) = self.export_report() {
+                    self.error = Some(format!("Report failed: {err}"));
                 }
             }
-            KeyEvent {
-                code: KeyCode::Char('/'),
-                modifiers: KeyModifiers::NONE,
-                ..
-            } => self.activate_search(),
-            KeyEvent {
-                code: KeyCode::Char('s'),
-                modifiers,
-                ..
-            } if modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::DiffTool => self.request_diff_tool(),
+            Action::FocusDetail => {
+                self.detail_focused = true;
+                self.facets.blur();
+            }
+            Action::Search => self.activate_search(),
+            Action::NewAsset => self.open_wizard(),
+            Action::Commit => {
+                if let Err(err) = self.commit_changes() {
+                    self.error = Some(format!("Commit failed: {err:#}"));
+                }
+            }
+            Action::Save => {
                 if let Err(err) = self.save() {
                     self.error = Some(format!("Save failed: {err}"));
                 }
             }
-            KeyEvent {
-                code: KeyCode::Char('x'),
-                modifiers: KeyModifiers::NONE,
-                ..
-            } => {
-                if let Err(err) = self.reset_assets() {
-                    self.error = Some(format!("Reset failed: {err}"));
-                }
+            Action::Reset => self.request_reset(ResetScope::All),
+            Action::ResetTab => self.request_scoped_reset(),
+            Action::NextTab => self.next_tab(),
+            Action::PrevTab => self.prev_tab(),
+            Action::Down => self.move_selection(1),
+            Action::Up => self.move_selection(-1),
+            Action::PageDown => self.move_selection(10),
+            Action::PageUp => self.move_selection(-10),
+            Action::First => self.select_index(0),
+            Action::Last => self.select_last(),
+            Action::Open
+                if self.mark_count() == 0
+                    && self
+                        .selected_asset()
+                        .is_some_and(|asset| asset.kind == AssetKind::Collection) =>
+            {
+                self.open_member_picker()
             }
-            KeyEvent {
-                code: KeyCode::Tab,
-                modifiers: KeyModifiers::NONE,
-                ..
-            } => self.next_tab(),
-            KeyEvent {
-                code: KeyCode::BackTab,
-                ..
-            } => self.prev_tab(),
-            KeyEvent {
-                code: KeyCode::Down,
-                ..
-            } => self.move_selection(1),
-            KeyEvent {
-                code: KeyCode::Up, ..
-            } => self.move_selection(-1),
-            KeyEvent {
-                code: KeyCode::PageDown,
-                ..
-            } => self.move_selection(10),
-            KeyEvent {
-                code: KeyCode::PageUp,
-                ..
-            } => self.move_selection(-10),
-            KeyEvent {
-                code: KeyCode::Home,
-                ..
-            } => self.select_index(0),
-            KeyEvent {
-                code: KeyCode::End, ..
-            } => self.select_last(),
-            KeyEvent {
-                code: KeyCode::Enter,
-                ..
-            }
-            | KeyEvent {
-                code: KeyCode::Char(' '),
-                ..
-            } => {
-                if let Err(err) = self.toggle_selection() {
+            Action::Toggle | Action::Open => {
+                let result = if self.mark_count() > 0 {
+                    self.request_bulk_toggle()
+                } else {
+                    self.toggle_selection()
+                };
+                if let Err(err) = result {
                     self.error = Some(format!("Toggle failed: {err}"));
                 }
             }
-            KeyEvent {
-                code: KeyCode::Esc, ..
-            } => {
-                self.clear_filter();
+            Action::Cancel => {
+                if !self.cancel_visual() {
+                    self.clear_marks();
+                    self.clear_filter();
+                }
                 self.message = None;
                 self.error = None;
             }
-            _ => {}
         }
-
         Ok(())
     }
 
@@ -334,6 +831,9 @@ impl App {
             KeyCode::Backspace => {
                 self.search.draft.pop();
             }
+            KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.toggle_full_text();
+            }
             KeyCode::Char(ch) => {
                 if !key.modifiers.contains(KeyModifiers::CONTROL) {
                     self.search.draft.push(ch);
@@ -351,25 +851,41 @@ impl App {
 
     fn handle_prompt_key(&mut self, prompt: PendingPrompt, key: KeyEvent) -> Result<()> {
         match key.code {
+            _ if self.scroll_plan(key.code) => {}
             KeyCode::Esc | KeyCode::Char('n') => {
+                self.close_plan();
                 self.prompt = None;
                 self.pending_toggle_asset = None;
+                self.pending_import = None;
                 self.message = Some("Cancelled".to_string());
             }
+            KeyCode::Char('R') if prompt == PendingPrompt::Import => {
+                self.prompt = None;
+                self.perform_import(ImportMode::Replace)?;
+            }
             KeyCode::Char('y') | KeyCode::Enter => {
+                self.close_plan();
                 self.prompt = None;
                 match prompt {
                     PendingPrompt::Quit => {
                         self.set_quit();
                     }
                     PendingPrompt::Reload => {
-                        self.reload()?;
+                        self.reload(Refresh::Check)?;
                     }
+                    PendingPrompt::Refresh => self.reload(Refresh::Force)?,
                     PendingPrompt::ToggleCollection => {
                         if let Some(asset) = self.pending_toggle_asset.take() {
                             self.perform_toggle(asset)?;
                         }
                     }
+                    PendingPrompt::BulkToggle => self.bulk_toggle()?,
+                    PendingPrompt::BulkApply => self.bulk_apply()?,
+                    PendingPrompt::Import => self.perform_import(ImportMode::Merge)?,
+                    PendingPrompt::ExternalReload => self.reload(Refresh::Cached)?,
+                    PendingPrompt::ApplyAll => self.apply_all()?,
+                    PendingPrompt::Reset(scope) => self.reset_assets(scope),
+                    PendingPrompt::Adopt => self.adopt_existing(),
                 }
             }
             _ => {}
@@ -378,7 +894,12 @@ impl App {
     }
 
     fn request_quit(&mut self) {
-        if self.dirty {
+        if self.dirty && self.auto_save {
+            match self.write_enablement() {
+                Ok(()) => self.set_quit(),
+                Err(err) => self.error = Some(format!("Save failed: {err}")),
+            }
+        } else if self.dirty && self.confirm.quit {
             self.prompt = Some(PendingPrompt::Quit);
             self.message =
                 Some("Unsaved changes. Confirm quit with 'y' or cancel with Esc.".into());
@@ -387,12 +908,21 @@ impl App {
         }
     }
 
-    fn request_reload(&mut self) {
-        if self.dirty {
-            self.prompt = Some(PendingPrompt::Reload);
-            self.message =
-                Some("Unsaved changes. Reload and discard with 'y' or cancel with Esc.".into());
-        } else if let Err(err) = self.reload() {
+    /// Reload now, or ask first when that would discard unsaved changes.
+    /// `Refresh::Force` downloads the snapshots again on the way.
+    fn request_reload(&mut self, refresh: Refresh) {
+        if self.dirty && self.confirm.reload {
+            if refresh == Refresh::Force {
+                self.prompt = Some(PendingPrompt::Refresh);
+                self.message = Some(
+                    "Unsaved changes. Refresh and discard with 'y' or cancel with Esc.".into(),
+                );
+            } else {
+                self.prompt = Some(PendingPrompt::Reload);
+                self.message =
+                    Some("Unsaved changes. Reload and discard with 'y' or cancel with Esc.".into());
+            }
+        } else if let Err(err) = self.reload(refresh) {
             self.error = Some(format!("Reload failed: {err}"));
         }
     }
@@ -404,13 +934,17 @@ impl App {
     }
 
     fn clear_filter(&mut self) {
-        if !self.search.query.is_empty() {
+        let tags_cleared = self.facets.clear(self.tab);
+        let states_cleared = self.state_filter != StateFilter::All;
+        if !self.search.query.is_empty() || tags_cleared || states_cleared {
             self.search.query.clear();
+            self.state_filter = StateFilter::All;
             self.normalize_selection_after_filter();
         }
     }
 
     fn move_selection(&mut self, delta: i32) {
+        self.detail_scroll = 0;
         let len = self.filtered_assets(self.tab).len();
         if len == 0 {
             self.selections.insert(self.tab, 0);
@@ -426,6 +960,7 @@ impl App {
     }
 
     fn select_index(&mut self, index: usize) {
+        self.detail_scroll = 0;
         let len = self.filtered_assets(self.tab).len();
         if len == 0 {
             self.selections.insert(self.tab, 0);
@@ -435,6 +970,7 @@ impl App {
     }
 
     fn select_last(&mut self) {
+        self.detail_scroll = 0;
         let len = self.filtered_assets(self.tab).len();
         if len == 0 {
             self.selections.insert(self.tab, 0);
@@ -445,19 +981,9 @@ impl App {
 
     fn toggle_selection(&mut self) -> Result<()> {
         if let Some(asset) = self.selected_asset().cloned() {
-            if asset.kind == AssetKind::Collection {
+            if asset.kind == AssetKind::Collection && self.confirm.collection_toggle {
                 // Show confirmation prompt for collections
-                self.pending_toggle_asset = Some(asset.clone());
-                self.prompt = Some(PendingPrompt::ToggleCollection);
-                
-                let action = if asset.effective { "disable" } else { "enable" };
-                self.message = Some(format!(
-                    "{} collection '{}' and affect {} members? y=Yes / n=No",
-                    action.chars().next().unwrap().to_uppercase().collect::<String>() + &action[1..],
-                    asset.name,
-                    asset.member_count
-                ));
-                return Ok(());
+                return self.confirm_collection_toggle(asset);
             }
             
             // For non-collections, toggle immediately
@@ -496,18 +1022,68 @@ impl App {
                     "explicit"
                 } else if result.asset.inherited.is_some() {
                     "inherited"
+                } else if result.asset.rule.is_some() {
+                    "rule"
                 } else {
                     "default"
                 }
             )
         };
         
+        let success_msg = success_msg + &self.dependency_note(&result);
+        self.record(HistoryEvent::Toggle, success_msg.clone());
         self.message = Some(success_msg);
         self.error = None;
         self.normalize_selection_after_filter();
         Ok(())
     }
 
+    /// File changes for the requirements a toggle enabled; a required
+    /// collection stands for its members.
+    fn dependency_ops(&self, result: &crate::domain::toggle::ToggleResult) -> Vec<FileOp> {
+        let mut ops = Vec::new();
+        for (kind, path) in &result.enabled_dependencies {
+            if *kind == AssetKind::Collection {
+                ops.extend(self.domain.collection_members(path).iter().map(|member| {
+                    FileOp::for_state(member.kind, member.path.clone(), member.effective)
+                }));
+            } else {
+                ops.push(FileOp::Apply(*kind, path.clone()));
+            }
+        }
+        ops
+    }
+
+    /// Footer suffix describing what a toggle did to the `requires:` chain.
+    fn dependency_note(&self, result: &crate::domain::toggle::ToggleResult) -> String {
+        let mut note = String::new();
+        if !result.enabled_dependencies.is_empty() {
+            let names: Vec<_> = result
+                .enabled_dependencies
+                .iter()
+                .map(|(_, path)| {
+                    self.domain
+                        .asset_by_path(path)
+                        .map_or(path.as_str(), |view| view.name.as_str())
+                })
+                .collect();
+            note.push_str(&format!(" · also enabled {}", names.join(", ")));
+        }
+        if !result.missing_dependencies.is_empty() {
+            note.push_str(&format!(
+                " · ⚠ missing requirement {}",
+                result.missing_dependencies.join(", ")
+            ));
+        }
+        if !result.required_by.is_empty() {
+            note.push_str(&format!(
+                " · ⚠ still required by {}",
+                result.required_by.join(", ")
+            ));
+        }
+        note
+    }
+
     fn apply_after_toggle(
         &mut self,
         kind: AssetKind,
@@ -517,74 +1093,40 @@ impl App {
         match kind {
             AssetKind::Collection => {
                 // For collections, iterate member assets and sync each according to new effective state
-                if let Some(collection) = self.domain.catalog.collection_by_path(path) {
+                if let Some(collection) = self.domain.catalog.collection_by_path(path).cloned() {
+                    let mut ops = Vec::new();
                     for item in &collection.items {
                         // Find asset view for the item to know its effective state after toggle
-                        let views = self.domain.assets(item.kind);
-                        if let Some(view) = views.iter().find(|v| v.path == item.path) {
-                            if view.effective {
-                                // Ensure applied
-                                sync::apply_from_upstream(
-                                    &self.paths,
-                                    &self.upstream_dir,
-                                    item.kind,
-                                    &item.path,
-                                )?;
-                            } else {
-                                // Remove if exists
-                                let _ = sync::remove_local(&self.paths, item.kind, &item.path)?;
-                            }
+                        let effective = self
+                            .domain
+                            .assets(item.kind)
+                            .iter()
+                            .find(|v| v.path == item.path)
+                            .map(|view| view.effective);
+                        if let Some(effective) = effective {
+                            ops.push(FileOp::for_state(item.kind, item.path.clone(), effective));
                         }
                     }
-                    // Refresh local statuses for current list
-                    self.compute_local_statuses()?;
+                    ops.extend(self.dependency_ops(result));
+                    self.follow_enablement(ops);
+                    self.compute_local_statuses();
                 }
             }
-            AssetKind::Prompt | AssetKind::Instruction | AssetKind::ChatMode => {
-                if result.asset.effective {
-                    sync::apply_from_upstream(&self.paths, &self.upstream_dir, kind, path)?;
-                } else {
-                    let _ = sync::remove_local(&self.paths, kind, path)?;
-                }
-                self.compute_local_statuses()?;
+            AssetKind::Prompt
+            | AssetKind::Instruction
+            | AssetKind::ChatMode
+            | AssetKind::Agent
+            | AssetKind::McpServer => {
+                let op = FileOp::for_state(kind, path.to_string(), result.asset.effective);
+                let mut ops = vec![op];
+                ops.extend(self.dependency_ops(result));
+                self.follow_enablement(ops);
+                self.compute_local_statuses();
             }
         }
         Ok(())
     }
 
-    fn reset_assets(&mut self) -> Result<()> {
-        use crate::domain::model::AssetKind::{ChatMode, Instruction, Prompt};
-
-        let kinds = [Prompt, Instruction, ChatMode];
-        for kind in kinds {
-            let paths: Vec<String> = self
-                .domain
-                .assets(kind)
-                .iter()
-                .map(|asset| asset.path.clone())
-                .collect();
-            for asset_path in paths {
-                let _ = sync::remove_local(&self.paths, kind, &asset_path)?;
-            }
-        }
-
-        self.domain.enablement.prompts.clear();
-        self.domain.enablement.instructions.clear();
-        self.domain.enablement.chat_modes.clear();
-        self.domain.enablement.collections.clear();
-        self.domain.enablement.overrides.clear();
-        self.domain.enablement.updated_at = None;
-
-        self.domain.recompute();
-        self.shadow_current_assets = None;
-        self.compute_local_statuses()?;
-
-        self.dirty = true;
-        self.message = Some("Cleared local assets and enablement state".into());
-        self.error = None;
-        Ok(())
-    }
-
     fn cleanup_orphans(&mut self) {
         let removed = self.domain.cleanup_orphans();
         if removed > 0 {
@@ -597,50 +1139,213 @@ impl App {
     }
 
     fn save(&mut self) -> Result<()> {
+        let report = self.save_report();
+        self.write_enablement()?;
+        self.show_save_report(report);
+        Ok(())
+    }
+
+    /// Write the enablement file and everything that follows a save, without
+    /// showing the save summary.
+    fn write_enablement(&mut self) -> Result<()> {
         enablement::save_enablement(&self.paths, &mut self.domain.enablement)
             .context("failed to write enablement file")?;
+        self.run_staged();
         self.dirty = false;
-        self.message = Some("Enablement saved".to_string());
+        self.stamp_enablement();
+        self.record(HistoryEvent::Save, "Saved the enablement file");
+        self.mark_saved();
+        self.sync_vscode_settings();
+        self.sync_report_section();
+        self.sync_aggregated_instructions();
+        self.stage_if_enabled();
         self.error = None;
         Ok(())
     }
 
-    fn reload(&mut self) -> Result<()> {
-        let catalog_load = catalog::load_catalog(&self.paths)?;
-        let enablement_load = enablement::load_enablement(&self.paths)?;
-        self.warnings = catalog_load.warnings;
-        self.warnings.extend(
-            enablement_load
-                .warnings
-                .into_iter()
-                .map(|warning| warning.to_string()),
-        );
-        self.domain = DomainState::new(catalog_load.catalog, enablement_load.file);
-        self.upstream_dir = catalog_load.upstream_dir;
-        self.dirty = false;
+    fn reload(&mut self, refresh: Refresh) -> Result<()> {
         self.prompt = None;
-        self.message = Some("Reloaded from disk".into());
-        self.error = None;
-        self.shadow_current_assets = None;
-        self.compute_local_statuses()?;
+        self.start_loading(refresh);
+        self.message = Some(match refresh {
+            Refresh::Force => "Downloading fresh snapshots…".into(),
+            Refresh::Cached | Refresh::Check => "Reloading…".into(),
+        });
         Ok(())
     }
 
-    fn compute_local_statuses(&mut self) -> Result<()> {
-        // Update local status for current filtered list to keep it cheap and consistent with UI.
-        let upstream = self.upstream_dir.clone();
-        let filtered = self.filtered_assets(self.tab);
-        let mut shadow = Vec::with_capacity(filtered.len());
-        for view in filtered.into_iter().cloned() {
-            let status = sync::compute_local_status(&self.paths, &upstream, view.kind, &view.path)?;
-            let mut v = view;
-            v.local = status;
-            shadow.push(v);
-        }
-        self.shadow_current_assets = Some(shadow);
+    fn start_loading(&mut self, refresh: Refresh) {
+        if !self.loading {
+            self.loading = true;
+            self.load_progress = None;
+            self.send_job(Job::Load {
+                sources: self.sources.clone(),
+                fetch: FetchOptions {
+                    refresh,
+                    ..self.fetch
+                },
+            });
+            self.error = None;
+        }
+    }
+
+    fn finish_loading(&mut self, result: Result<LoadedState>) {
+        self.loading = false;
+        self.load_progress = None;
+        match result.and_then(|loaded| self.install_loaded(loaded)) {
+            Ok(()) => {
+                let renamed = self
+                    .domain
+                    .orphans()
+                    .iter()
+                    .filter(|orphan| orphan.successor.is_some())
+                    .count();
+                self.message = Some(match (self.changelog.changes.len(), renamed) {
+                    (0, 0) => "Catalog loaded".into(),
+                    (count, 0) => {
+                        format!("Catalog loaded; {count} upstream changes (w shows them)")
+                    }
+                    (_, renamed) => format!(
+                        "Catalog loaded; {renamed} enablement entries point at moved assets \
+                         (O remaps them)"
+                    ),
+                });
+                self.restore_selections();
+                self.error = None;
+            }
+            Err(err) => self.error = Some(format!("Loading catalog failed: {err:#}")),
+        }
+    }
+
+    fn install_loaded(&mut self, loaded: LoadedState) -> Result<()> {
+        let LoadedState {
+            catalog: catalog_load,
+            enablement: enablement_load,
+            changelog,
+            known_hashes,
+        } = loaded;
+        self.warnings.clear();
+        for warning in catalog_load.upstream_warnings {
+            self.warn(WarningSource::Upstream, warning);
+        }
+        for warning in catalog_load.warnings {
+            self.warn(WarningSource::Catalog, warning);
+        }
+        for warning in enablement_load.warnings {
+            self.warn(WarningSource::Enablement, warning.to_string());
+        }
+        self.domain = DomainState::new(catalog_load.catalog, enablement_load.file);
+        self.domain.set_known_hashes(known_hashes);
+        self.upstream = catalog_load.upstream;
+        self.upstream.set_line_endings(self.line_endings);
+        self.changelog = changelog;
+        self.staged.clear();
+        self.dirty = false;
+        self.stamp_enablement();
+        self.mark_saved();
+        self.start_watching();
+        self.compute_local_statuses();
+        self.start_indexing();
         Ok(())
     }
 
+    /// Count enabled assets on every tab whose applied file is behind
+    /// upstream, for the header.
+    fn count_pending_updates(&mut self) {
+        self.pending_updates = [
+            AssetKind::Prompt,
+            AssetKind::Instruction,
+            AssetKind::ChatMode,
+            AssetKind::Agent,
+        ]
+        .into_iter()
+        .flat_map(|kind| self.domain.assets(kind))
+        .filter(|view| view.effective && view.local == LocalStatus::Update)
+        .count();
+    }
+
+    fn cycle_sort(&mut self) {
+        let selected = self.selected_asset().map(|asset| asset.path.clone());
+        self.workspace.sort = self.workspace.sort.next();
+        if let Some(path) = selected {
+            self.reselect(&path);
+        }
+        self.visual_anchor = None;
+        self.message = Some(format!("Sort: {}", self.workspace.sort.label()));
+        self.persist_workspace("sort order");
+    }
+
+    fn cycle_state_filter(&mut self) {
+        self.state_filter = self.state_filter.next();
+        self.visual_anchor = None;
+        self.normalize_selection_after_filter();
+        self.message = Some(format!("Showing: {}", self.state_filter.label()));
+    }
+
+    /// Keep the selection on `path` after the table order changed.
+    fn reselect(&mut self, path: &str) {
+        if let Some(index) = self
+            .filtered_assets(self.tab)
+            .iter()
+            .position(|asset| asset.path == path)
+        {
+            self.selections.insert(self.tab, index);
+        }
+    }
+
+    fn toggle_favorite(&mut self) {
+        let Some(asset) = self.selected_asset() else {
+            return;
+        };
+        let (path, name) = (asset.path.clone(), asset.name.clone());
+        let starred = !self.workspace.favorites.remove(&path);
+        if starred {
+            self.workspace.favorites.insert(path.clone());
+        }
+        self.reselect(&path);
+        self.visual_anchor = None;
+        self.message = Some(if starred {
+            format!("Starred {name}")
+        } else {
+            format!("Unstarred {name}")
+        });
+        self.persist_workspace("favorites");
+    }
+
+    pub fn is_favorite(&self, path: &str) -> bool {
+        self.workspace.favorites.contains(path)
+    }
+
+    pub fn state_filter(&self) -> StateFilter {
+        self.state_filter
+    }
+
+    pub fn sort_mode(&self) -> SortMode {
+        self.workspace.sort
+    }
+
+    /// Widen (positive) or narrow the table by `delta` percent.
+    fn resize_split(&mut self, delta: i16) {
+        let width = (self.table_width() as i16 + delta).clamp(MIN_TABLE_WIDTH, MAX_TABLE_WIDTH);
+        if width as u16 == self.table_width() {
+            return;
+        }
+        self.workspace.table_width = Some(width as u16);
+        self.message = Some(format!("Table width: {width}%"));
+        self.persist_workspace("pane split");
+    }
+
+    pub fn table_width(&self) -> u16 {
+        self.workspace.table_width.unwrap_or(DEFAULT_TABLE_WIDTH)
+    }
+
+    /// Save a UI preference kept in the workspace file, reporting failures
+    /// in the footer rather than aborting.
+    fn persist_workspace(&mut self, what: &str) {
+        if let Err(err) = workspace::save_workspace(&self.paths, &self.workspace) {
+            self.error = Some(format!("Saving {what} failed: {err}"));
+        }
+    }
+
     fn apply_selected(&mut self) -> Result<()> {
         if let Some(asset) = self.selected_asset().cloned() {
             if asset.kind == AssetKind::Collection {
@@ -648,43 +1353,71 @@ impl App {
                 self.message = Some("Collections have no files to apply".into());
                 return Ok(());
             }
-            sync::apply_from_upstream(&self.paths, &self.upstream_dir, asset.kind, &asset.path)?;
+            self.message = Some(format!("Applying {}…", asset.path));
+            self.sync_files(vec![FileOp::Apply(asset.kind, asset.path)], true);
             // Recompute local statuses to reflect updated file
-            self.compute_local_statuses()?;
+            self.compute_local_statuses();
         }
         Ok(())
     }
 
+    fn cycle_apply_mode(&mut self) {
+        self.apply_mode = match self.apply_mode {
+            ApplyMode::Overwrite => ApplyMode::Merge,
+            ApplyMode::Merge => ApplyMode::Patch,
+            ApplyMode::Patch => ApplyMode::Symlink,
+            ApplyMode::Symlink => ApplyMode::Overwrite,
+        };
+        self.message = Some(format!("Apply mode: {}", apply_mode_label(self.apply_mode)));
+    }
+
     fn next_tab(&mut self) {
-        self.tab = match self.tab {
+        self.switch_tab(match self.tab {
             AssetKind::Prompt => AssetKind::Instruction,
             AssetKind::Instruction => AssetKind::ChatMode,
-            AssetKind::ChatMode => AssetKind::Collection,
+            AssetKind::ChatMode => AssetKind::Agent,
+            AssetKind::Agent => AssetKind::McpServer,
+            AssetKind::McpServer => AssetKind::Collection,
             AssetKind::Collection => AssetKind::Prompt,
-        };
-        self.normalize_selection_after_filter();
+        });
     }
 
     fn prev_tab(&mut self) {
-        self.tab = match self.tab {
+        self.switch_tab(match self.tab {
             AssetKind::Prompt => AssetKind::Collection,
             AssetKind::Instruction => AssetKind::Prompt,
             AssetKind::ChatMode => AssetKind::Instruction,
-            AssetKind::Collection => AssetKind::ChatMode,
-        };
+            AssetKind::Agent => AssetKind::ChatMode,
+            AssetKind::McpServer => AssetKind::Agent,
+            AssetKind::Collection => AssetKind::McpServer,
+        });
+    }
+
+    fn switch_tab(&mut self, kind: AssetKind) {
+        self.visual_anchor = None;
+        self.detail_scroll = 0;
+        self.tab = kind;
+        self.facets.rewind();
         self.normalize_selection_after_filter();
     }
 
     fn filtered_assets(&self, kind: AssetKind) -> Vec<&crate::domain::state::AssetView> {
-        let assets = self.domain.assets(kind);
-        if self.search.query.is_empty() {
-            return assets.iter().collect();
-        }
-        let query = self.search.query.to_lowercase();
-        assets
+        let query = Query::parse(&self.search.query);
+        let mut assets: Vec<_> = self
+            .domain
+            .assets(kind)
             .iter()
-            .filter(|asset| asset_matches(asset, &query))
-            .collect()
+            .filter(|asset| query.matches(asset, |text| self.body_contains(asset, text)))
+            .filter(|asset| self.facets.matches(asset))
+            .filter(|asset| self.state_filter.matches(asset))
+            .collect();
+        sort_assets(
+            &mut assets,
+            self.workspace.sort,
+            |asset| asset.local,
+            |asset| self.is_favorite(&asset.path),
+        );
+        assets
     }
 
     fn selected_asset(&self) -> Option<&crate::domain::state::AssetView> {
@@ -729,6 +1462,26 @@ impl App {
         &self.paths.root
     }
 
+    pub fn paths(&self) -> &RepoPaths {
+        &self.paths
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loading
+    }
+
+    pub fn load_progress(&self) -> Option<&SnapshotProgress> {
+        self.load_progress.as_ref()
+    }
+
+    pub fn apply_mode(&self) -> ApplyMode {
+        self.apply_mode
+    }
+
+    pub fn pinned_ref(&self) -> Option<&str> {
+        self.workspace.pinned_ref.as_deref()
+    }
+
     pub fn tab(&self) -> AssetKind {
         self.tab
     }
@@ -737,10 +1490,11 @@ impl App {
         &self.search
     }
 
-    pub fn warnings(&self) -> &[String] {
-        &self.warnings
+    pub(crate) fn facets(&self) -> &facets::FacetPanel {
+        &self.facets
     }
 
+
     pub fn message(&self) -> Option<&str> {
         self.error.as_deref().or(self.message.as_deref())
     }
@@ -765,14 +1519,15 @@ impl App {
         self.dirty
     }
 
+    pub fn auto_save(&self) -> bool {
+        self.auto_save
+    }
+
     pub fn prompt(&self) -> Option<PendingPrompt> {
         self.prompt
     }
 
     pub fn current_assets(&self) -> Vec<&crate::domain::state::AssetView> {
-        if let Some(shadow) = &self.shadow_current_assets {
-            return shadow.iter().collect();
-        }
         self.filtered_assets(self.tab)
     }
 
@@ -786,59 +1541,75 @@ impl App {
     }
 
     pub fn selected_asset_view(&self) -> Option<&crate::domain::state::AssetView> {
-        if let Some(shadow) = &self.shadow_current_assets {
-            if shadow.is_empty() {
-                return None;
-            }
-            let idx = self.current_selection().min(shadow.len() - 1);
-            return shadow.get(idx);
-        }
         self.selected_asset()
     }
 
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn pending_updates(&self) -> usize {
+        self.pending_updates
+    }
+
+    pub fn upstream_change_count(&self) -> usize {
+        self.changelog.changes.len()
+    }
+
     pub fn orphan_count(&self) -> usize {
         self.domain.orphans().len()
     }
+
+    /// Commit and fetch time of the loaded official snapshot.
+    pub fn snapshot_info(&self) -> Option<(&str, chrono::DateTime<chrono::Utc>)> {
+        let commit = self.upstream.commits().get(PRIMARY_SOURCE)?;
+        let fetched_at = self.upstream.fetched_at().get(PRIMARY_SOURCE)?;
+        Some((commit, *fetched_at))
+    }
 }
 
-fn install_tracing() {
-    let _ = tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .with_target(false)
-        .try_init();
+fn install_tracing(log_file: Option<&Path>, verbose: u8) -> Result<()> {
+    use tracing_subscriber::EnvFilter;
+
+    let filter = match verbose {
+        0 => EnvFilter::from_default_env(),
+        1 => EnvFilter::new("info"),
+        2 => EnvFilter::new("debug"),
+        _ => EnvFilter::new("trace"),
+    };
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_target(false);
+    // stderr shares the terminal with the TUI, so a log file keeps the
+    // alternate screen intact while debugging.
+    let _ = match log_file {
+        Some(path) => {
+            let file = RollingFile::open(path, log_file::MAX_LOG_BYTES)?;
+            subscriber
+                .with_ansi(false)
+                .with_writer(std::sync::Mutex::new(file))
+                .try_init()
+        }
+        None => subscriber.try_init(),
+    };
+    Ok(())
+}
+
+pub(crate) fn apply_mode_label(mode: ApplyMode) -> &'static str {
+    match mode {
+        ApplyMode::Overwrite => "overwrite",
+        ApplyMode::Merge => "merge",
+        ApplyMode::Patch => "patch",
+        ApplyMode::Symlink => "symlink",
+    }
 }
 
 // TODO: collect_warnings removed - warnings now come directly from catalog load
 
-fn asset_matches(asset: &crate::domain::state::AssetView, query: &str) -> bool {
-    let haystacks = [
-        asset.name.as_str(),
-        asset.path.as_str(),
-        asset.slug.as_deref().unwrap_or(""),
-        &asset.description,
-    ];
-    if haystacks.iter().any(|v| v.to_lowercase().contains(query)) {
-        return true;
-    }
-    if asset
-        .tags
-        .iter()
-        .any(|tag| tag.to_lowercase().contains(query))
-    {
-        return true;
-    }
-    if asset
-        .apply_to
-        .iter()
-        .any(|item| item.to_lowercase().contains(query))
-    {
-        return true;
-    }
-    asset
-        .collections
-        .iter()
-        .any(|c| c.id.to_lowercase().contains(query) || c.name.to_lowercase().contains(query))
-}
 
 // TODO: EnablementWarning Display implementation removed with new architecture
 
@@ -0,0 +1,34 @@
+use crate::io::history::HistoryEvent;
+
+use super::{App, PendingPrompt};
+
+impl App {
+    /// Once per session, when the first local statuses are in, ask to adopt
+    /// files under `.github/` that match catalog assets nothing enables.
+    pub(super) fn offer_adoption(&mut self) {
+        if self.adoption_offered || self.prompt.is_some() {
+            return;
+        }
+        self.adoption_offered = true;
+        let count = self.domain.adoptable().len();
+        if count == 0 {
+            return;
+        }
+        self.prompt = Some(PendingPrompt::Adopt);
+        self.message = Some(format!(
+            "{count} file(s) under .github match catalog assets without an enablement entry"
+        ));
+    }
+
+    /// Enable the adoptable assets without touching their files.
+    pub(super) fn adopt_existing(&mut self) {
+        let adopted = self.domain.adopt();
+        if adopted == 0 {
+            return;
+        }
+        self.dirty = true;
+        let summary = format!("Adopted {adopted} existing file(s) as enabled");
+        self.record(HistoryEvent::Toggle, summary.clone());
+        self.message = Some(summary);
+    }
+}
@@ -0,0 +1,79 @@
+use anyhow::Result;
+
+use crate::{
+    domain::{model::AssetKind, state::DomainState},
+    io::{plan::SyncPlan, worker::Job},
+};
+
+use super::{App, PendingPrompt};
+
+/// Kinds whose assets are copied into `.github/`.
+pub(super) const FILE_KINDS: [AssetKind; 4] = [
+    AssetKind::Prompt,
+    AssetKind::Instruction,
+    AssetKind::ChatMode,
+    AssetKind::Agent,
+];
+
+/// Kinds that apply-all and `sync` bring in line with upstream: the file
+/// kinds plus MCP servers, which are merged into `.vscode/mcp.json`.
+pub(super) const APPLIED_KINDS: [AssetKind; 5] = [
+    AssetKind::Prompt,
+    AssetKind::Instruction,
+    AssetKind::ChatMode,
+    AssetKind::Agent,
+    AssetKind::McpServer,
+];
+
+/// Every effectively enabled asset that is applied locally.
+pub(super) fn enabled_assets(domain: &DomainState) -> Vec<(AssetKind, String)> {
+    APPLIED_KINDS
+        .into_iter()
+        .flat_map(|kind| domain.assets(kind))
+        .filter(|asset| asset.effective)
+        .map(|asset| (asset.kind, asset.path.clone()))
+        .collect()
+}
+
+impl App {
+    pub(super) fn request_apply_all(&mut self) -> Result<()> {
+        let assets = enabled_assets(&self.domain);
+        let plan = SyncPlan::for_apply(
+            &self.paths,
+            &self.upstream,
+            assets.iter().map(|(kind, path)| (*kind, path.as_str())),
+            self.apply_mode,
+        )?;
+        if plan.is_empty() {
+            self.message = Some(format!(
+                "All {} enabled assets already match upstream",
+                assets.len()
+            ));
+            return Ok(());
+        }
+        let question = format!("Apply all {} enabled assets from upstream?", assets.len());
+        if !self.confirm_plan(
+            PendingPrompt::ApplyAll,
+            question,
+            "apply all enabled assets",
+            plan,
+        ) {
+            return self.apply_all();
+        }
+        Ok(())
+    }
+
+    /// Bring every enabled asset's local file in line with upstream, on the
+    /// worker.
+    pub(super) fn apply_all(&mut self) -> Result<()> {
+        let assets = enabled_assets(&self.domain);
+        self.message = Some(format!("Applying {} enabled assets…", assets.len()));
+        self.send_job(Job::ApplyAll {
+            upstream: self.upstream.clone(),
+            assets,
+            mode: self.apply_mode,
+        });
+        self.compute_local_statuses();
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use super::App;
+
+/// Idle time after the last key press before pending changes are saved, so
+/// a burst of toggles is written once.
+const AUTO_SAVE_DELAY: Duration = Duration::from_millis(1000);
+
+impl App {
+    /// With auto-save on, write pending changes once input has settled and
+    /// nothing is waiting for an answer.
+    pub(super) fn auto_save_if_due(&mut self) {
+        if !self.auto_save
+            || !self.dirty
+            || self.loading
+            || self.prompt.is_some()
+            || self.note_editor.is_some()
+            || self.last_input.elapsed() < AUTO_SAVE_DELAY
+        {
+            return;
+        }
+        self.redraw = true;
+        match self.write_enablement() {
+            Ok(()) => self.message = Some("Enablement saved automatically".into()),
+            Err(err) => {
+                // Retrying on every loop would only repeat the error.
+                self.auto_save = false;
+                self.error = Some(format!("Auto-save failed and is now off: {err:#}"));
+            }
+        }
+    }
+}
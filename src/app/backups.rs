@@ -0,0 +1,120 @@
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::io::backups::{self, BackupEntry};
+
+use super::{overlay::Overlay, App};
+
+/// State of the backup browser: the list of backups and the diff of the
+/// selected one against the current file.
+pub(crate) struct BackupBrowser {
+    entries: Vec<BackupEntry>,
+    selected: usize,
+    diff: Vec<String>,
+    scroll: u16,
+}
+
+impl BackupBrowser {
+    pub(crate) fn entries(&self) -> &[BackupEntry] {
+        &self.entries
+    }
+
+    pub(crate) fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub(crate) fn diff(&self) -> &[String] {
+        &self.diff
+    }
+
+    pub(crate) fn scroll(&self) -> u16 {
+        self.scroll
+    }
+
+    fn selected_entry(&self) -> Option<&BackupEntry> {
+        self.entries.get(self.selected)
+    }
+}
+
+impl App {
+    pub(super) fn open_backups(&mut self) -> Result<()> {
+        let entries = backups::list_backups(&self.paths)?;
+        if entries.is_empty() {
+            self.message = Some("No backups yet".into());
+            return Ok(());
+        }
+        let mut browser = BackupBrowser {
+            entries,
+            selected: 0,
+            diff: Vec::new(),
+            scroll: 0,
+        };
+        self.load_backup_diff(&mut browser);
+        self.overlay = Some(Overlay::Backups(browser));
+        self.message = Some("Backups: Enter restores the selected file, Esc closes".into());
+        Ok(())
+    }
+
+    pub(super) fn handle_backups_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(Overlay::Backups(mut browser)) = self.overlay.take() else {
+            return Ok(());
+        };
+        let len = browser.entries.len();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.message = None;
+                return Ok(());
+            }
+            KeyCode::Up if browser.selected > 0 => {
+                browser.selected -= 1;
+                self.load_backup_diff(&mut browser);
+            }
+            KeyCode::Down if browser.selected + 1 < len => {
+                browser.selected += 1;
+                self.load_backup_diff(&mut browser);
+            }
+            KeyCode::PageDown => browser.scroll = browser.scroll.saturating_add(10),
+            KeyCode::PageUp => browser.scroll = browser.scroll.saturating_sub(10),
+            KeyCode::Enter => {
+                if let Some(entry) = browser.selected_entry().cloned() {
+                    match backups::restore_backup(&self.paths, &entry) {
+                        Ok(target) => {
+                            self.message = Some(format!(
+                                "Restored {} from {}",
+                                target.display(),
+                                entry.timestamp
+                            ));
+                            self.error = None;
+                            // Restoring may have created a new backup of the replaced file.
+                            if let Ok(entries) = backups::list_backups(&self.paths) {
+                                browser.entries = entries;
+                                browser.selected = browser
+                                    .entries
+                                    .iter()
+                                    .position(|e| e == &entry)
+                                    .unwrap_or(0);
+                            }
+                            self.load_backup_diff(&mut browser);
+                        }
+                        Err(err) => self.error = Some(format!("Restore failed: {err}")),
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.overlay = Some(Overlay::Backups(browser));
+        Ok(())
+    }
+
+    fn load_backup_diff(&mut self, browser: &mut BackupBrowser) {
+        browser.scroll = 0;
+        browser.diff = match browser.selected_entry() {
+            Some(entry) => match backups::diff_against_current(&self.paths, entry) {
+                Ok(diff) if diff.is_empty() => vec!["(identical to the current file)".into()],
+                Ok(diff) => diff.lines().map(str::to_string).collect(),
+                Err(err) => vec![format!("Unable to diff: {err}")],
+            },
+            None => Vec::new(),
+        };
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::io::upstream::{self, CachedSnapshot, Refresh, UpstreamSource};
+
+use super::{overlay::Overlay, App};
+
+/// Cached snapshots of every source, to switch the official one to, or to
+/// delete.
+pub(crate) struct CacheView {
+    /// Source id and snapshot, grouped by source.
+    snapshots: Vec<(String, CachedSnapshot)>,
+    selected: usize,
+}
+
+impl CacheView {
+    pub(crate) fn snapshots(&self) -> &[(String, CachedSnapshot)] {
+        &self.snapshots
+    }
+
+    pub(crate) fn selected(&self) -> usize {
+        self.selected
+    }
+}
+
+impl App {
+    /// Whether the loaded catalog reads from `snapshot`.
+    pub(crate) fn snapshot_in_use(&self, source: &str, snapshot: &CachedSnapshot) -> bool {
+        self.upstream.commits().get(source) == Some(&snapshot.commit)
+    }
+
+    pub(super) fn open_cache(&mut self) {
+        let snapshots: Vec<_> = self
+            .sources
+            .iter()
+            .flat_map(|source| {
+                upstream::cached_snapshots(&self.paths, source)
+                    .into_iter()
+                    .map(|snapshot| (source.id.clone(), snapshot))
+            })
+            .collect();
+        if snapshots.is_empty() {
+            self.message = Some("No cached snapshots".into());
+            return;
+        }
+        self.overlay = Some(Overlay::Cache(CacheView {
+            snapshots,
+            selected: 0,
+        }));
+        self.message = Some(
+            "Cache: Enter switches the official snapshot, u follows its branch again, \
+             d deletes, D deletes all unused"
+                .into(),
+        );
+    }
+
+    pub(super) fn handle_cache_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(Overlay::Cache(mut view)) = self.overlay.take() else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.message = None;
+                return Ok(());
+            }
+            KeyCode::Up if view.selected > 0 => view.selected -= 1,
+            KeyCode::Down if view.selected + 1 < view.snapshots.len() => view.selected += 1,
+            KeyCode::Enter => {
+                let (source, snapshot) = &view.snapshots[view.selected];
+                self.switch_snapshot(source, &snapshot.commit)?;
+            }
+            KeyCode::Char('u') => self.unpin_snapshot()?,
+            KeyCode::Char('d') | KeyCode::Delete => {
+                let (source, snapshot) = &view.snapshots[view.selected];
+                if self.snapshot_in_use(source, snapshot) {
+                    self.message = Some(
+                        "The loaded catalog reads from this snapshot; switch to another first"
+                            .into(),
+                    );
+                } else {
+                    upstream::remove_snapshot(snapshot)?;
+                    let (_, snapshot) = view.snapshots.remove(view.selected);
+                    self.message = Some(format!("Deleted snapshot {}", snapshot.commit));
+                }
+            }
+            KeyCode::Char('D') => {
+                let (unused, in_use): (Vec<_>, Vec<_>) = view
+                    .snapshots
+                    .into_iter()
+                    .partition(|(source, snapshot)| !self.snapshot_in_use(source, snapshot));
+                for (_, snapshot) in &unused {
+                    upstream::remove_snapshot(snapshot)?;
+                }
+                self.message = Some(format!(
+                    "Deleted {} cached snapshot(s); kept the {} in use",
+                    unused.len(),
+                    in_use.len()
+                ));
+                view.snapshots = in_use;
+            }
+            _ => {}
+        }
+        // A reload replaces the snapshots in use, so the list is stale.
+        if view.snapshots.is_empty() || self.loading {
+            return Ok(());
+        }
+        view.selected = view.selected.min(view.snapshots.len() - 1);
+        self.overlay = Some(Overlay::Cache(view));
+        Ok(())
+    }
+
+    /// Pin the official source to `commit` and reload from that snapshot.
+    fn switch_snapshot(&mut self, source: &str, commit: &str) -> Result<()> {
+        let Some(official) = self.sources.first().filter(|s| s.id == source) else {
+            self.message = Some(format!(
+                "Only the official snapshot can be switched; pin '{source}' with @REF in its spec"
+            ));
+            return Ok(());
+        };
+        if official.reference == commit && self.upstream.commits().get(source).is_some() {
+            self.message = Some(format!("Already on {commit}"));
+            return Ok(());
+        }
+        if self.dirty {
+            self.message = Some("Save first; switching snapshots reloads the catalog".into());
+            return Ok(());
+        }
+        self.workspace.pinned_ref = Some(commit.to_string());
+        self.persist_workspace("the pin");
+        self.sources[0].reference = commit.to_string();
+        self.reload(Refresh::Cached)?;
+        self.message = Some(format!("Pinned to {commit}; reloading…"));
+        Ok(())
+    }
+
+    /// Drop the pin of the official source and reload from its branch.
+    fn unpin_snapshot(&mut self) -> Result<()> {
+        if self.workspace.pinned_ref.is_none() {
+            self.message = Some("The official snapshot is not pinned".into());
+            return Ok(());
+        }
+        if self.dirty {
+            self.message = Some("Save first; unpinning reloads the catalog".into());
+            return Ok(());
+        }
+        self.workspace.pinned_ref = None;
+        self.persist_workspace("the pin");
+        self.sources[0].reference = UpstreamSource::official().reference;
+        self.reload(Refresh::Check)?;
+        self.message = Some(format!("Following {}; reloading…", self.sources[0]));
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::io::changelog::Changelog;
+
+use super::{overlay::Overlay, App};
+
+/// The "What changed" screen: what the last upstream refresh brought in.
+pub(crate) struct ChangelogView {
+    scroll: u16,
+}
+
+impl ChangelogView {
+    pub(crate) fn scroll(&self) -> u16 {
+        self.scroll
+    }
+}
+
+impl App {
+    pub(crate) fn changelog(&self) -> &Changelog {
+        &self.changelog
+    }
+
+    pub(super) fn open_changelog(&mut self) {
+        if self.changelog.changes.is_empty() {
+            self.message = Some("No upstream changes since the previous snapshot".into());
+            return;
+        }
+        self.overlay = Some(Overlay::Changelog(ChangelogView { scroll: 0 }));
+    }
+
+    pub(super) fn handle_changelog_key(&mut self, key: KeyEvent) {
+        let Some(Overlay::Changelog(view)) = &mut self.overlay else {
+            return;
+        };
+        view.scroll = match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => {
+                self.overlay = None;
+                return;
+            }
+            KeyCode::Down | KeyCode::Char('j') => view.scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => view.scroll.saturating_sub(1),
+            KeyCode::PageDown => view.scroll.saturating_add(10),
+            KeyCode::PageUp => view.scroll.saturating_sub(10),
+            _ => view.scroll,
+        };
+    }
+}
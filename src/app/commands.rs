@@ -0,0 +1,983 @@
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use serde::Serialize;
+
+use crate::{
+    domain::{
+        model::AssetKind,
+        state::{AssetView, DomainState},
+        toggle,
+    },
+    io::{
+        aggregate,
+        check::{self, CheckFailed, CheckFailure},
+        config::{self, Confirmations},
+        doctor::{self, Severity},
+        enablement::{self, EnablementWarning},
+        git, loader, manifest,
+        paths::RepoPaths,
+        plan::{PlanEntry, PlannedChange, SyncPlan},
+        profile::{self, ImportMode, DEFAULT_PROFILE_FILE},
+        prune, report,
+        script::{self, ScriptAction},
+        sync::{self, ApplyMode, LineEndings, LocalStatus, SyncSummary},
+        upstream::{FetchOptions, UpstreamRoots, UpstreamSource},
+        vscode, workspace,
+    },
+    ui::{components, draw},
+};
+
+use super::apply_all::{self, APPLIED_KINDS, FILE_KINDS};
+
+/// How `list`, `diff` and `sync` print their results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(super) enum OutputFormat {
+    /// Human-readable lines.
+    #[default]
+    Text,
+    /// One JSON document on stdout, for scripts.
+    Json,
+}
+
+/// Non-interactive commands; without one the TUI starts.
+#[derive(Debug, Subcommand)]
+pub(super) enum Command {
+    /// Write the enablement selection to a portable profile (JSON or YAML by extension).
+    Export {
+        /// Destination file, relative to the repository root unless absolute.
+        #[arg(value_name = "FILE", default_value = DEFAULT_PROFILE_FILE)]
+        output: PathBuf,
+    },
+    /// Write a markdown list of the enabled assets, with descriptions and
+    /// upstream links, for a repository's contributing docs.
+    Report {
+        /// Destination file, relative to the repository root unless absolute;
+        /// stdout when omitted.
+        #[arg(value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Only rewrite the `<!-- copilot-assets:start/end -->` section of
+        /// FILE, appending it when missing, and keep the rest.
+        #[arg(long, requires = "output")]
+        inject: bool,
+    },
+    /// Import a profile, an enablement file, or another repository's selection.
+    Import {
+        /// Profile or enablement file path, repository directory, or http(s) URL.
+        #[arg(value_name = "SOURCE")]
+        source: String,
+
+        /// Merge into or replace the local enablement entries.
+        #[arg(long, value_enum, default_value_t = ImportMode::Merge)]
+        mode: ImportMode,
+
+        /// Skip the confirmation prompt.
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// List catalog assets with their enablement and local file status.
+    List {
+        /// Only list assets of this kind.
+        #[arg(long, value_enum)]
+        kind: Option<AssetKind>,
+
+        /// Only list enabled assets.
+        #[arg(long)]
+        enabled: bool,
+
+        /// Output format.
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Run `enable`, `disable` and `apply` operations from a script, one
+    /// `<action> <path-or-glob>` per line, then save and sync the result.
+    ApplyScript {
+        /// Script file.
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Print what would change without writing anything.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Summarize enablement, local files and snapshots; exits non-zero when an
+    /// enabled asset is missing or out of date.
+    Status {
+        /// Output format.
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Show how local files differ from upstream.
+    Diff {
+        /// Catalog paths to compare; every enabled asset when omitted.
+        #[arg(value_name = "PATH")]
+        assets: Vec<String>,
+
+        /// Output format.
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Apply every enabled asset from upstream into `.github/`.
+    Sync {
+        /// Print the plan of file changes without writing anything.
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Skip the confirmation prompt.
+        #[arg(long, short)]
+        yes: bool,
+
+        /// Also delete local files of disabled or unknown assets, backing each
+        /// up first, so `.github/` matches the enablement file exactly.
+        #[arg(long)]
+        prune: bool,
+
+        /// Output format.
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Delete local files of disabled or unknown assets, backing each up first.
+    Prune {
+        /// Skip the confirmation prompt.
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Check the repository for common problems and print how to fix them;
+    /// exits non-zero when one needs fixing.
+    Doctor,
+    /// Verify for CI that the enablement file is valid, every enabled asset's
+    /// local file matches upstream and the `[policy]` rules hold; exits 3, 4
+    /// or 5 for the first of these that fails.
+    Check,
+}
+
+impl Command {
+    /// Whether the command writes repository files and so needs the
+    /// instance lock.
+    pub(super) fn writes(&self) -> bool {
+        match self {
+            Command::Export { .. }
+            | Command::Report { .. }
+            | Command::List { .. }
+            | Command::Status { .. }
+            | Command::Diff { .. }
+            | Command::Doctor
+            | Command::Check => false,
+            Command::Import { .. }
+            | Command::ApplyScript { .. }
+            | Command::Sync { .. }
+            | Command::Prune { .. } => true,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn run_command(
+    command: Command,
+    paths: &RepoPaths,
+    sources: &[UpstreamSource],
+    apply_mode: ApplyMode,
+    line_endings: Option<LineEndings>,
+    confirmations: Confirmations,
+    hooks: SaveHooks,
+    fetch: FetchOptions,
+) -> Result<()> {
+    match command {
+        Command::Doctor => return run_doctor(paths, sources, fetch),
+        Command::Check => return run_check(paths, sources, fetch, line_endings),
+        _ => {}
+    }
+    // Read-only commands run without the instance lock.
+    let loaded = if command.writes() {
+        loader::load_state(paths, sources, fetch, &|_| {})?
+    } else {
+        loader::read_state(paths, sources, fetch)?
+    };
+    for warning in loaded
+        .catalog
+        .upstream_warnings
+        .iter()
+        .chain(&loaded.catalog.warnings)
+    {
+        eprintln!("warning: {warning}");
+    }
+    let mut upstream = loaded.catalog.upstream;
+    upstream.set_line_endings(line_endings);
+    let mut state = DomainState::new(loaded.catalog.catalog, loaded.enablement.file);
+    state.set_known_hashes(loaded.known_hashes);
+    match command {
+        Command::Export { output } => {
+            let profile = profile::build_profile(&state, &upstream);
+            let output = paths.root.join(output);
+            profile::write_profile(&output, &profile)?;
+            println!(
+                "Exported {} entries to {}",
+                profile.entries.len(),
+                output.display()
+            );
+        }
+        Command::Report { output, inject } => match output {
+            Some(output) if inject => {
+                let output = paths.root.join(output);
+                let section = report::section(&state, &upstream, sources);
+                if report::inject(&output, &section)? {
+                    println!("Updated the asset list in {}", output.display());
+                } else {
+                    println!("The asset list in {} is up to date", output.display());
+                }
+            }
+            Some(output) => {
+                let output = paths.root.join(output);
+                report::write(&output, &report::render(&state, &upstream, sources))?;
+                println!("Wrote the asset report to {}", output.display());
+            }
+            None => print!("{}", report::render(&state, &upstream, sources)),
+        },
+        Command::Import { source, mode, yes } => {
+            let plan = profile::plan_import(profile::read_import(&source)?, &state.catalog);
+            println!("Import from {source}: {}", plan.summary());
+            for entry in &plan.orphans {
+                println!("  orphan: {}", entry.path);
+            }
+            for entry in &plan.drifted {
+                println!("  changed upstream: {}", entry.path);
+            }
+            if !yes && confirmations.import && !confirm(&format!("Import with mode '{mode:?}'?"))? {
+                bail!("Import cancelled");
+            }
+            let before = state.clone();
+            profile::apply_import(&mut state.enablement, &plan, mode);
+            state.recompute();
+            manifest::with_manifest(paths, |manifest| {
+                for (kind, path, enabled) in state.effective_changes(&before) {
+                    if enabled {
+                        sync::apply_from_upstream(
+                            paths, &upstream, manifest, kind, &path, apply_mode,
+                        )?;
+                    } else {
+                        sync::remove_local(paths, manifest, kind, &path)?;
+                    }
+                }
+                Ok(())
+            })?;
+            enablement::save_enablement(paths, &mut state.enablement)?;
+            println!("Imported {} entries", plan.entries.len());
+            hooks.run(paths, &state, &upstream, sources)?;
+        }
+        Command::ApplyScript { file, dry_run } => {
+            let ops = script::read_script(&file)?;
+            let before = state.clone();
+            let mut to_apply: Vec<(AssetKind, String)> = Vec::new();
+            for op in &ops {
+                let matched: Vec<(AssetKind, String)> = ALL_KINDS
+                    .into_iter()
+                    .flat_map(|kind| state.assets(kind))
+                    .filter(|view| op.matches(&view.path))
+                    .map(|view| (view.kind, view.path.clone()))
+                    .collect();
+                if matched.is_empty() {
+                    bail!(
+                        "line {}: '{}' matches no asset in the catalog",
+                        op.line,
+                        op.pattern
+                    );
+                }
+                println!("{} {} ({} asset(s))", op.action, op.pattern, matched.len());
+                for (kind, path) in matched {
+                    match op.action {
+                        ScriptAction::Enable => {
+                            toggle::set_asset(&mut state, kind, &path, true)?;
+                        }
+                        ScriptAction::Disable => {
+                            toggle::set_asset(&mut state, kind, &path, false)?;
+                        }
+                        // Collections have no file of their own.
+                        ScriptAction::Apply if kind == AssetKind::Collection => {}
+                        ScriptAction::Apply => {
+                            if !to_apply.contains(&(kind, path.clone())) {
+                                to_apply.push((kind, path));
+                            }
+                        }
+                    }
+                }
+            }
+            let changes = state.effective_changes(&before);
+            for (_, path, enabled) in &changes {
+                println!("  {} {path}", if *enabled { "+ on " } else { "- off" });
+            }
+            if dry_run {
+                println!(
+                    "{} asset(s) would change state and {} would be applied",
+                    changes.len(),
+                    to_apply.len()
+                );
+                return Ok(());
+            }
+            let summary = manifest::with_manifest(paths, |manifest| {
+                for (kind, path, enabled) in &changes {
+                    if *enabled {
+                        sync::apply_from_upstream(
+                            paths, &upstream, manifest, *kind, path, apply_mode,
+                        )?;
+                    } else {
+                        sync::remove_local(paths, manifest, *kind, path)?;
+                    }
+                }
+                sync::sync_assets(
+                    paths,
+                    &upstream,
+                    manifest,
+                    to_apply.iter().map(|(kind, path)| (*kind, path.as_str())),
+                    apply_mode,
+                )
+            })?;
+            for path in &summary.conflicted {
+                eprintln!("conflict: {path} has merge markers to resolve");
+            }
+            if !state.enablement.changes_from(&before.enablement).is_empty() {
+                enablement::save_enablement(paths, &mut state.enablement)?;
+                hooks.run(paths, &state, &upstream, sources)?;
+            }
+            println!(
+                "Ran {} operation(s): {} asset(s) changed state; applied {summary}",
+                ops.len(),
+                changes.len()
+            );
+        }
+        Command::List {
+            kind,
+            enabled,
+            format,
+        } => {
+            let manifest = manifest::load_manifest(paths)?;
+            let mut assets = Vec::new();
+            for kind in kind.map_or(ALL_KINDS.to_vec(), |kind| vec![kind]) {
+                for view in state.assets(kind) {
+                    if enabled && !view.effective {
+                        continue;
+                    }
+                    let mut view = view.clone();
+                    view.local =
+                        sync::compute_local_status(paths, &upstream, &manifest, kind, &view.path)?;
+                    assets.push(view);
+                }
+            }
+            match format {
+                OutputFormat::Json => print_json(&assets)?,
+                OutputFormat::Text => {
+                    for view in &assets {
+                        println!(
+                            "{:<3} {:<7} {}  {}",
+                            if view.effective { "on" } else { "off" },
+                            components::local_status(view),
+                            view.path,
+                            view.name
+                        );
+                    }
+                }
+            }
+        }
+        Command::Status { format } => {
+            let report = status_report(paths, &state, &upstream)?;
+            match format {
+                OutputFormat::Json => print_json(&report)?,
+                OutputFormat::Text => print_status(&report),
+            }
+            let behind: usize = report
+                .kinds
+                .iter()
+                .map(|counts| counts.missing + counts.diff + counts.update)
+                .sum();
+            if behind > 0 {
+                bail!("{behind} enabled asset(s) missing or out of date; run `sync`");
+            }
+        }
+        Command::Diff { assets, format } => {
+            let selected: Vec<&AssetView> = FILE_KINDS
+                .into_iter()
+                .flat_map(|kind| state.assets(kind))
+                .filter(|view| {
+                    if assets.is_empty() {
+                        view.effective
+                    } else {
+                        assets.contains(&view.path)
+                    }
+                })
+                .collect();
+            for path in &assets {
+                if !selected.iter().any(|view| &view.path == path) {
+                    bail!("{path} is not a prompt, instruction, chat mode or agent in the catalog");
+                }
+            }
+            let manifest = manifest::load_manifest(paths)?;
+            let mut diffs = Vec::new();
+            for view in selected {
+                let status =
+                    sync::compute_local_status(paths, &upstream, &manifest, view.kind, &view.path)?;
+                if !matches!(status, LocalStatus::Diff | LocalStatus::Update) {
+                    continue;
+                }
+                let read = |path: PathBuf| {
+                    fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))
+                };
+                let local = read(sync::local_path(paths, view.kind, &view.path))?;
+                let theirs =
+                    String::from_utf8_lossy(&sync::upstream_content(&upstream, &view.path)?)
+                        .into_owned();
+                diffs.push(AssetDiff {
+                    kind: view.kind,
+                    path: view.path.clone(),
+                    status,
+                    patch: diffy::create_patch(&local, &theirs).to_string(),
+                });
+            }
+            match format {
+                OutputFormat::Json => print_json(&diffs)?,
+                OutputFormat::Text if diffs.is_empty() => println!("No differences"),
+                OutputFormat::Text => {
+                    for diff in &diffs {
+                        println!("diff {} (local → upstream)", diff.path);
+                        print!("{}", diff.patch);
+                    }
+                }
+            }
+        }
+        Command::Sync {
+            dry_run,
+            yes,
+            prune,
+            format,
+        } => {
+            let text = format == OutputFormat::Text;
+            let assets = apply_all::enabled_assets(&state);
+            let plan = SyncPlan::for_apply(
+                paths,
+                &upstream,
+                assets.iter().map(|(kind, path)| (*kind, path.as_str())),
+                apply_mode,
+            )?;
+            let candidates = if prune {
+                let ignored = workspace::load_workspace(paths)?.ignored_files;
+                prune::find_candidates(paths, &state, &ignored)?
+            } else {
+                Vec::new()
+            };
+            let mut report = SyncReport {
+                dry_run,
+                plan: Vec::new(),
+                pruned: Vec::new(),
+                summary: None,
+                removed: None,
+            };
+            if plan.is_empty() && candidates.is_empty() {
+                return match format {
+                    OutputFormat::Json => print_json(&report),
+                    OutputFormat::Text => {
+                        println!("All {} enabled assets already match upstream", assets.len());
+                        Ok(())
+                    }
+                };
+            }
+            let dirty = git::uncommitted(paths)?;
+            for entry in &plan.entries {
+                let local = sync::local_path(paths, entry.kind, &entry.path);
+                let uncommitted = entry.change != PlannedChange::Create && dirty.contains(&local);
+                if text && uncommitted {
+                    println!("  {entry}  (uncommitted changes)");
+                } else if text {
+                    println!("  {entry}");
+                }
+                report.plan.push(PlanRecord {
+                    entry: entry.clone(),
+                    uncommitted,
+                });
+            }
+            for candidate in &candidates {
+                let record = PruneRecord {
+                    path: candidate.display_path(paths),
+                    reason: candidate.reason.label(),
+                    uncommitted: dirty.contains(&candidate.local_path),
+                };
+                if text {
+                    println!(
+                        "  - prune     {} ({}){}",
+                        record.path,
+                        record.reason,
+                        if record.uncommitted {
+                            "  (uncommitted changes)"
+                        } else {
+                            ""
+                        }
+                    );
+                }
+                report.pruned.push(record);
+            }
+            if text {
+                println!("Plan: {plan}");
+                if prune {
+                    println!("Prune: {} file(s)", candidates.len());
+                }
+            }
+            let at_stake = report
+                .plan
+                .iter()
+                .map(|record| record.uncommitted)
+                .chain(report.pruned.iter().map(|record| record.uncommitted))
+                .filter(|uncommitted| *uncommitted)
+                .count();
+            if at_stake > 0 {
+                eprintln!(
+                    "warning: {at_stake} file(s) with uncommitted changes would be overwritten or removed"
+                );
+            }
+            if dry_run {
+                return if text { Ok(()) } else { print_json(&report) };
+            }
+            let ask = confirmations.bulk || at_stake > 0;
+            if !yes && ask && !confirm("Apply this plan?")? {
+                bail!("Sync cancelled");
+            }
+            let summary = manifest::with_manifest(paths, |manifest| {
+                sync::sync_assets(
+                    paths,
+                    &upstream,
+                    manifest,
+                    assets.iter().map(|(kind, path)| (*kind, path.as_str())),
+                    apply_mode,
+                )
+            })?;
+            let removed = prune::prune(paths, &candidates)?;
+            for path in &summary.conflicted {
+                eprintln!("conflict: {path} has merge markers to resolve");
+            }
+            if text {
+                println!("Synced {} enabled assets: {summary}", assets.len());
+                if prune {
+                    println!(
+                        "Pruned {removed} file(s); backups are in {}",
+                        paths.backups_dir.display()
+                    );
+                }
+            } else {
+                report.summary = Some(summary);
+                report.removed = prune.then_some(removed);
+                print_json(&report)?;
+            }
+        }
+        Command::Prune { yes } => {
+            let ignored = workspace::load_workspace(paths)?.ignored_files;
+            let candidates = prune::find_candidates(paths, &state, &ignored)?;
+            if candidates.is_empty() {
+                println!("Nothing to prune");
+                return Ok(());
+            }
+            for candidate in &candidates {
+                println!(
+                    "  {} ({})",
+                    candidate.display_path(paths),
+                    candidate.reason.label()
+                );
+            }
+            let question = format!("Delete {} file(s)?", candidates.len());
+            if !yes && confirmations.bulk && !confirm(&question)? {
+                bail!("Prune cancelled");
+            }
+            let removed = prune::prune(paths, &candidates)?;
+            println!(
+                "Pruned {removed} file(s); backups are in {}",
+                paths.backups_dir.display()
+            );
+        }
+        // Runs before loading, which would fail on some of what it checks.
+        Command::Doctor | Command::Check => {
+            unreachable!("doctor and check load the catalog themselves")
+        }
+    }
+    Ok(())
+}
+
+/// Check files first, then the loaded catalog if it loads, and fail when a
+/// problem needs fixing.
+fn run_doctor(paths: &RepoPaths, sources: &[UpstreamSource], fetch: FetchOptions) -> Result<()> {
+    let mut findings = doctor::check_files(paths, sources);
+    match loader::read_state(paths, sources, fetch) {
+        Ok(loaded) => {
+            let mut state = DomainState::new(loaded.catalog.catalog, loaded.enablement.file);
+            state.set_known_hashes(loaded.known_hashes);
+            findings.extend(doctor::check_state(&state));
+            for warning in loaded
+                .catalog
+                .upstream_warnings
+                .iter()
+                .chain(&loaded.catalog.warnings)
+            {
+                println!("! {warning}");
+            }
+        }
+        Err(err) => findings.push(doctor::Finding::problem(
+            format!("the catalog could not be loaded: {err:#}"),
+            "check the network connection, or delete the cached snapshots to download them again",
+        )),
+    }
+    for finding in &findings {
+        println!("{finding}");
+    }
+    let problems = findings
+        .iter()
+        .filter(|finding| finding.severity == Severity::Problem)
+        .count();
+    if problems > 0 {
+        bail!("{problems} problem(s) need fixing");
+    }
+    if findings.is_empty() {
+        println!("No problems found");
+    }
+    Ok(())
+}
+
+fn run_check(
+    paths: &RepoPaths,
+    sources: &[UpstreamSource],
+    fetch: FetchOptions,
+    line_endings: Option<LineEndings>,
+) -> Result<()> {
+    // Only `check` reads the policy, so it isn't threaded through `run`.
+    let policy = check::Policy::new(&config::load_config(paths)?.policy)?;
+    let loaded = loader::read_state(paths, sources, fetch)?;
+    let mut failures = Vec::new();
+
+    let invalid: Vec<_> = loaded
+        .enablement
+        .warnings
+        .iter()
+        .filter(|warning| {
+            matches!(
+                warning,
+                EnablementWarning::ParseError(_) | EnablementWarning::SchemaValidation(_)
+            )
+        })
+        .collect();
+    for warning in &invalid {
+        println!("✗ {warning}");
+    }
+    if invalid.is_empty() {
+        println!("✓ enablement file is valid");
+    } else {
+        failures.push((
+            CheckFailure::Enablement,
+            "the enablement file is invalid".to_string(),
+        ));
+    }
+
+    let mut upstream = loaded.catalog.upstream;
+    upstream.set_line_endings(line_endings);
+    let state = DomainState::new(loaded.catalog.catalog, loaded.enablement.file);
+    let manifest = manifest::load_manifest(paths)?;
+    let mut stale = 0;
+    for view in APPLIED_KINDS
+        .into_iter()
+        .flat_map(|kind| state.assets(kind))
+        .filter(|view| view.effective)
+    {
+        let status =
+            sync::compute_local_status(paths, &upstream, &manifest, view.kind, &view.path)?;
+        let problem = match status {
+            LocalStatus::Missing => "missing",
+            LocalStatus::Diff => "modified",
+            LocalStatus::Update => "out of date",
+            LocalStatus::Same | LocalStatus::Unmanaged | LocalStatus::NA => continue,
+        };
+        println!("✗ {}: {problem}", view.path);
+        stale += 1;
+    }
+    if stale == 0 {
+        println!("✓ every enabled asset matches upstream");
+    } else {
+        failures.push((
+            CheckFailure::LocalFiles,
+            format!("{stale} enabled asset(s) missing or out of date"),
+        ));
+    }
+
+    let violations = policy.violations(&state);
+    for violation in &violations {
+        println!("✗ policy: {violation}");
+    }
+    if violations.is_empty() {
+        println!("✓ policy rules hold");
+    } else {
+        failures.push((
+            CheckFailure::Policy,
+            format!("{} policy violation(s)", violations.len()),
+        ));
+    }
+
+    // Failures are pushed in exit code order.
+    let Some(&(failure, _)) = failures.first() else {
+        return Ok(());
+    };
+    let summary: Vec<_> = failures.into_iter().map(|(_, summary)| summary).collect();
+    Err(CheckFailed {
+        failure,
+        summary: summary.join("; "),
+    }
+    .into())
+}
+
+const ALL_KINDS: [AssetKind; 6] = [
+    AssetKind::Prompt,
+    AssetKind::Instruction,
+    AssetKind::ChatMode,
+    AssetKind::Agent,
+    AssetKind::McpServer,
+    AssetKind::Collection,
+];
+
+/// `status` output.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatusReport {
+    snapshots: Vec<SnapshotStatus>,
+    enablement: EnablementStatus,
+    kinds: Vec<KindCounts>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotStatus {
+    source: String,
+    commit: String,
+    fetched_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EnablementStatus {
+    path: PathBuf,
+    exists: bool,
+    /// Uncommitted changes in git; `None` outside a repository.
+    modified: Option<bool>,
+}
+
+/// Enabled assets of one kind by local status, plus orphaned entries and
+/// unmanaged files.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct KindCounts {
+    kind: AssetKind,
+    enabled: usize,
+    applied: usize,
+    missing: usize,
+    diff: usize,
+    update: usize,
+    orphans: usize,
+    unmanaged: usize,
+}
+
+fn status_report(
+    paths: &RepoPaths,
+    state: &DomainState,
+    upstream: &UpstreamRoots,
+) -> Result<StatusReport> {
+    let snapshots = upstream
+        .commits()
+        .iter()
+        .map(|(source, commit)| SnapshotStatus {
+            source: source.clone(),
+            commit: commit.clone(),
+            fetched_at: upstream.fetched_at().get(source).copied(),
+        })
+        .collect();
+    let (path, _) = enablement::enablement_location(paths);
+    let enablement = EnablementStatus {
+        exists: path.exists(),
+        modified: git::has_changes(paths, &path)?,
+        path: path
+            .strip_prefix(&paths.root)
+            .unwrap_or(&path)
+            .to_path_buf(),
+    };
+    let ignored = workspace::load_workspace(paths)?.ignored_files;
+    let unmanaged = prune::find_unmanaged(paths, state, &ignored)?;
+    let manifest = manifest::load_manifest(paths)?;
+    let mut kinds = Vec::new();
+    for kind in ALL_KINDS {
+        let mut counts = KindCounts {
+            kind,
+            enabled: 0,
+            applied: 0,
+            missing: 0,
+            diff: 0,
+            update: 0,
+            orphans: state
+                .orphans()
+                .iter()
+                .filter(|orphan| orphan.kind == kind)
+                .count(),
+            unmanaged: unmanaged.iter().filter(|file| file.kind == kind).count(),
+        };
+        for view in state.assets(kind).iter().filter(|view| view.effective) {
+            counts.enabled += 1;
+            match sync::compute_local_status(paths, upstream, &manifest, kind, &view.path)? {
+                LocalStatus::Same => counts.applied += 1,
+                LocalStatus::Missing => counts.missing += 1,
+                LocalStatus::Diff => counts.diff += 1,
+                LocalStatus::Update => counts.update += 1,
+                LocalStatus::Unmanaged | LocalStatus::NA => {}
+            }
+        }
+        kinds.push(counts);
+    }
+    Ok(StatusReport {
+        snapshots,
+        enablement,
+        kinds,
+    })
+}
+
+fn print_status(report: &StatusReport) {
+    let now = Utc::now();
+    for snapshot in &report.snapshots {
+        let short: String = snapshot.commit.chars().take(7).collect();
+        match snapshot.fetched_at {
+            Some(at) => println!(
+                "Snapshot {}: {short}, fetched {} ago",
+                snapshot.source,
+                components::format_age(now - at)
+            ),
+            None => println!("Snapshot {}: {short}", snapshot.source),
+        }
+    }
+    let enablement = &report.enablement;
+    let state = match (enablement.exists, enablement.modified) {
+        (false, _) => "not created yet",
+        (true, Some(true)) => "uncommitted changes",
+        (true, Some(false)) => "clean",
+        (true, None) => "not in a git repository",
+    };
+    println!("Enablement {}: {state}", enablement.path.display());
+    println!();
+    println!(
+        "{:<14} {:>7} {:>7} {:>7} {:>5} {:>6} {:>7} {:>9}",
+        "Kind", "Enabled", "Applied", "Missing", "Diff", "Update", "Orphans", "Unmanaged"
+    );
+    for counts in &report.kinds {
+        println!(
+            "{:<14} {:>7} {:>7} {:>7} {:>5} {:>6} {:>7} {:>9}",
+            draw::tab_title(counts.kind),
+            counts.enabled,
+            counts.applied,
+            counts.missing,
+            counts.diff,
+            counts.update,
+            counts.orphans,
+            counts.unmanaged
+        );
+    }
+}
+
+/// `diff --format json` entry.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AssetDiff {
+    kind: AssetKind,
+    path: String,
+    status: LocalStatus,
+    /// Unified diff from the local file to upstream.
+    patch: String,
+}
+
+/// `sync --format json` output.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncReport {
+    dry_run: bool,
+    plan: Vec<PlanRecord>,
+    /// Files `--prune` deletes.
+    pruned: Vec<PruneRecord>,
+    /// Missing when nothing was applied.
+    summary: Option<SyncSummary>,
+    /// Files deleted by `--prune`; missing without it or when nothing ran.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    removed: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct PlanRecord {
+    #[serde(flatten)]
+    entry: PlanEntry,
+    uncommitted: bool,
+}
+
+#[derive(Serialize)]
+struct PruneRecord {
+    /// Relative to the repository root.
+    path: String,
+    reason: &'static str,
+    uncommitted: bool,
+}
+
+fn print_json(value: &impl Serialize) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// What follows a save of the enablement file, from `config.toml`.
+pub(super) struct SaveHooks {
+    /// `[vscode] settings`.
+    pub(super) vscode_settings: bool,
+    /// `[report] inject`, resolved against the repository root.
+    pub(super) report_section: Option<PathBuf>,
+    /// `[instructions] aggregate`.
+    pub(super) aggregate_instructions: bool,
+}
+
+impl SaveHooks {
+    /// Point VS Code at `.github/chatmodes` while any chat mode is enabled,
+    /// and rewrite the report section and the aggregated instructions, when
+    /// configured.
+    fn run(
+        &self,
+        paths: &RepoPaths,
+        state: &DomainState,
+        upstream: &UpstreamRoots,
+        sources: &[UpstreamSource],
+    ) -> Result<()> {
+        if self.vscode_settings {
+            let chat_modes = state
+                .assets(AssetKind::ChatMode)
+                .iter()
+                .any(|asset| asset.effective);
+            if vscode::sync_chat_modes(paths, chat_modes)? {
+                println!("Updated {}", paths.vscode_settings.display());
+            }
+        }
+        if let Some(path) = &self.report_section {
+            if report::inject(path, &report::section(state, upstream, sources))? {
+                println!("Updated the asset list in {}", path.display());
+            }
+        }
+        if self.aggregate_instructions
+            && aggregate::write(paths, &aggregate::render(state, upstream)?)?
+        {
+            println!("Updated {}", paths.copilot_instructions.display());
+        }
+        Ok(())
+    }
+}
+
+/// Asks on stderr so stdout stays parseable with `--format json`.
+fn confirm(question: &str) -> Result<bool> {
+    eprint!("{question} [y/N] ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
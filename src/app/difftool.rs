@@ -0,0 +1,114 @@
+use std::{io, path::PathBuf};
+
+use anyhow::{Context, Result};
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, Terminal};
+
+use crate::{
+    domain::model::AssetKind,
+    io::{
+        difftool,
+        sync::{self, LocalStatus},
+    },
+};
+
+use super::App;
+
+/// Files to compare once the event loop has suspended the TUI.
+pub(super) struct DiffRequest {
+    tool: String,
+    name: String,
+    /// Kept until the tool exits, which removes the file.
+    upstream: difftool::UpstreamCopy,
+    local: PathBuf,
+}
+
+impl App {
+    /// Queue the selected asset's upstream content and local file for the
+    /// external diff tool, if its local file differs.
+    pub(super) fn request_diff_tool(&mut self) {
+        let Some(asset) = self.selected_asset() else {
+            return;
+        };
+        if matches!(asset.kind, AssetKind::Collection | AssetKind::McpServer) {
+            self.message = Some(
+                "The diff tool compares prompt, instruction, chat mode and agent files".into(),
+            );
+            return;
+        }
+        if !matches!(asset.local, LocalStatus::Diff | LocalStatus::Update) {
+            self.message = Some(format!(
+                "{} has no local file that differs from upstream",
+                asset.name
+            ));
+            return;
+        }
+        let Some(tool) = self.diff_tool.clone() else {
+            self.message = Some(format!(
+                "Set ${} or [diff] tool in config.toml to compare files",
+                difftool::DIFFTOOL_ENV
+            ));
+            return;
+        };
+        let upstream = match difftool::upstream_copy(&self.upstream, &asset.path) {
+            Ok(upstream) => upstream,
+            Err(err) => {
+                self.error = Some(format!("{err:#}"));
+                return;
+            }
+        };
+        self.pending_diff = Some(DiffRequest {
+            tool,
+            name: asset.name.clone(),
+            upstream,
+            local: sync::local_path(&self.paths, asset.kind, &asset.path),
+        });
+    }
+
+    /// Leave the alternate screen, run the queued diff tool until it exits
+    /// and restore the TUI. The local file may have been edited, so its
+    /// status is checked again.
+    pub(super) fn run_pending_diff(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    ) -> Result<()> {
+        let Some(request) = self.pending_diff.take() else {
+            return Ok(());
+        };
+        disable_raw_mode().context("Failed to disable raw mode")?;
+        execute!(
+            terminal.backend_mut(),
+            DisableMouseCapture,
+            LeaveAlternateScreen
+        )
+        .context("Failed to leave alternate screen")?;
+        terminal.show_cursor()?;
+
+        let status = difftool::run(&request.tool, &request.upstream.path, &request.local);
+
+        enable_raw_mode().context("Failed to enable raw mode")?;
+        execute!(
+            terminal.backend_mut(),
+            EnterAlternateScreen,
+            EnableMouseCapture
+        )
+        .context("Failed to enter alternate screen")?;
+        terminal.clear()?;
+
+        match status {
+            // Tools like `diff` exit non-zero when the files differ, so any
+            // exit counts as done.
+            Ok(_) => {
+                self.message = Some(format!("Compared {} with {}", request.name, request.tool));
+                self.error = None;
+            }
+            Err(err) => self.error = Some(format!("Diff tool failed: {err:#}")),
+        }
+        self.compute_local_statuses();
+        Ok(())
+    }
+}
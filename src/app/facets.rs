@@ -0,0 +1,143 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    domain::{model::AssetKind, state::AssetView},
+    ui::keymap::Action,
+};
+
+use super::App;
+
+/// The tag sidebar. Chosen tags narrow the table to assets carrying all of
+/// them, on top of the search query, and are kept per kind.
+#[derive(Debug, Default)]
+pub(crate) struct FacetPanel {
+    open: bool,
+    focused: bool,
+    selected: usize,
+    chosen: BTreeMap<AssetKind, BTreeSet<String>>,
+}
+
+impl FacetPanel {
+    pub(crate) fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub(crate) fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub(crate) fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub(crate) fn chosen(&self, kind: AssetKind) -> Vec<&str> {
+        self.chosen
+            .get(&kind)
+            .map(|tags| tags.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn is_chosen(&self, kind: AssetKind, tag: &str) -> bool {
+        self.chosen
+            .get(&kind)
+            .is_some_and(|tags| tags.contains(tag))
+    }
+
+    /// Whether `asset` carries every tag chosen for its kind.
+    pub(super) fn matches(&self, asset: &AssetView) -> bool {
+        self.chosen
+            .get(&asset.kind)
+            .is_none_or(|tags| tags.iter().all(|tag| asset.tags.contains(tag)))
+    }
+
+    /// Drop the tags chosen for `kind`; returns whether there were any.
+    pub(super) fn clear(&mut self, kind: AssetKind) -> bool {
+        self.chosen
+            .remove(&kind)
+            .is_some_and(|tags| !tags.is_empty())
+    }
+
+    pub(super) fn blur(&mut self) {
+        self.focused = false;
+    }
+
+    /// Start at the top of the list, e.g. after switching tabs.
+    pub(super) fn rewind(&mut self) {
+        self.selected = 0;
+    }
+}
+
+impl App {
+    /// Tags of the current kind with their counts, as listed in the panel.
+    pub(crate) fn tag_facets(&self) -> Vec<(String, usize)> {
+        self.domain.tag_counts(self.tab)
+    }
+
+    /// Open and focus the panel, focus it if it is open but unfocused, or
+    /// hide it. Chosen tags keep filtering while it is hidden.
+    pub(super) fn toggle_facets(&mut self) {
+        let panel = &mut self.facets;
+        if panel.open && panel.focused {
+            panel.open = false;
+            panel.focused = false;
+            self.message = None;
+            return;
+        }
+        panel.open = true;
+        panel.focused = true;
+        self.detail_focused = false;
+        self.message = Some("Tags: Space includes or excludes a tag, Esc returns".into());
+    }
+
+    /// Run `action` against the focused panel. Returns `false` for actions
+    /// the table handles as usual.
+    pub(super) fn handle_facet_action(&mut self, action: Action) -> bool {
+        let len = self.tag_facets().len();
+        let last = len.saturating_sub(1);
+        let panel = &mut self.facets;
+        let selected = panel.selected.min(last);
+        panel.selected = match action {
+            Action::Down => (selected + 1).min(last),
+            Action::Up => selected.saturating_sub(1),
+            Action::PageDown => (selected + 10).min(last),
+            Action::PageUp => selected.saturating_sub(10),
+            Action::First => 0,
+            Action::Last => last,
+            Action::Toggle => {
+                self.toggle_facet(selected);
+                return true;
+            }
+            Action::Cancel => {
+                panel.focused = false;
+                self.message = None;
+                return true;
+            }
+            _ => return false,
+        };
+        true
+    }
+
+    /// Choose or drop the tag at `index` of the panel.
+    pub(super) fn toggle_facet(&mut self, index: usize) {
+        let Some((tag, _)) = self.tag_facets().into_iter().nth(index) else {
+            return;
+        };
+        self.facets.selected = index;
+        let chosen = self.facets.chosen.entry(self.tab).or_default();
+        if !chosen.remove(&tag) {
+            chosen.insert(tag);
+        }
+        let shown = self.filtered_assets(self.tab).len();
+        self.message = Some(match self.facets.chosen(self.tab).as_slice() {
+            [] => "Tag filter cleared".to_string(),
+            tags => format!("Tags {}: {shown} match", tags.join(" + ")),
+        });
+        self.normalize_selection_after_filter();
+    }
+
+    /// Focus the panel from a click.
+    pub(super) fn focus_facets(&mut self) {
+        self.facets.focused = true;
+        self.detail_focused = false;
+    }
+}
@@ -0,0 +1,66 @@
+use anyhow::Result;
+
+use crate::{
+    domain::state::AssetView,
+    io::{content_index::ContentIndex, worker::Job},
+};
+
+use super::{warnings::WarningSource, App};
+
+impl App {
+    /// Switch whether searches also look inside upstream files.
+    pub(super) fn toggle_full_text(&mut self) {
+        self.search.full_text = !self.search.full_text;
+        self.message = Some(match (&self.content_index, self.search.full_text) {
+            (Some(index), true) => format!(
+                "Full-text search on ({} files indexed); Ctrl+F switches back",
+                index.len()
+            ),
+            (None, true) => "Full-text search on; upstream files are still being indexed".into(),
+            (_, false) => {
+                "Full-text search off; matching names, paths, descriptions and tags".into()
+            }
+        });
+    }
+
+    /// Index the text of every catalog asset's upstream file on the worker,
+    /// reusing the index saved with each snapshot. Searches match metadata
+    /// only until it arrives.
+    pub(super) fn start_indexing(&mut self) {
+        self.content_index = None;
+        let paths = self
+            .domain
+            .all_assets()
+            .map(|asset| asset.path.clone())
+            .collect();
+        self.send_job(Job::Index {
+            upstream: self.upstream.clone(),
+            paths,
+        });
+    }
+
+    pub(super) fn finish_indexing(&mut self, index: Result<ContentIndex>) {
+        match index {
+            Ok(index) => {
+                self.content_index = Some(index);
+                if self.search.full_text && !self.search.query.is_empty() {
+                    self.normalize_selection_after_filter();
+                }
+            }
+            Err(err) => self.warn(
+                WarningSource::Upstream,
+                format!("Indexing upstream files for full-text search failed: {err:#}"),
+            ),
+        }
+    }
+
+    /// Whether full-text search is on and `asset`'s file contains the
+    /// lowercase `text`.
+    pub(super) fn body_contains(&self, asset: &AssetView, text: &str) -> bool {
+        self.search.full_text
+            && self
+                .content_index
+                .as_ref()
+                .is_some_and(|index| index.contains(&asset.path, text))
+    }
+}
@@ -0,0 +1,70 @@
+use std::{collections::BTreeSet, path::PathBuf};
+
+use anyhow::Result;
+
+use crate::io::{
+    git,
+    plan::{PlannedChange, SyncPlan},
+    sync,
+};
+
+use super::{warnings::WarningSource, App};
+
+impl App {
+    /// Stage `.github/` and the enablement file when `[git] stage` is on.
+    /// Failures become warnings; they never undo the save or apply.
+    pub(super) fn stage_if_enabled(&mut self) {
+        if !self.git_stage || !git::is_repository(&self.paths) {
+            return;
+        }
+        if let Err(err) = git::stage(&self.paths) {
+            self.warn(WarningSource::Local, format!("Staging failed: {err:#}"));
+        }
+    }
+
+    /// Files under `.github/` with uncommitted changes. A failing `git` is
+    /// reported as a warning rather than blocking the operation.
+    fn uncommitted_files(&mut self) -> BTreeSet<PathBuf> {
+        git::uncommitted(&self.paths).unwrap_or_else(|err| {
+            self.warn(
+                WarningSource::Local,
+                format!("Could not check git status: {err:#}"),
+            );
+            BTreeSet::new()
+        })
+    }
+
+    /// Entries of `plan` that would overwrite or delete uncommitted work.
+    pub(super) fn uncommitted_in(&mut self, plan: &SyncPlan) -> Vec<String> {
+        let dirty = self.uncommitted_files();
+        plan.entries
+            .iter()
+            .filter(|entry| entry.change != PlannedChange::Create)
+            .filter(|entry| dirty.contains(&sync::local_path(&self.paths, entry.kind, &entry.path)))
+            .map(|entry| entry.path.clone())
+            .collect()
+    }
+
+    /// Commit the managed paths with a message listing what was enabled and
+    /// disabled since `HEAD`.
+    pub(super) fn commit_changes(&mut self) -> Result<()> {
+        if self.dirty {
+            self.message = Some("Save the enablement file (Ctrl+S) before committing".into());
+            return Ok(());
+        }
+        if !git::is_repository(&self.paths) {
+            self.message = Some("Not a git repository".into());
+            return Ok(());
+        }
+        let changes = self
+            .domain
+            .enablement
+            .changes_from(&git::committed_enablement(&self.paths));
+        let message = git::commit_message(&changes);
+        let hash = git::commit(&self.paths, &message)?;
+        let subject = message.lines().next().unwrap_or_default();
+        self.message = Some(format!("Committed {hash}: {subject}"));
+        self.error = None;
+        Ok(())
+    }
+}
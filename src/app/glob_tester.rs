@@ -0,0 +1,65 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::domain::apply_to::{self, ApplyToMatch};
+
+use super::{overlay::Overlay, App};
+
+/// Type a file path and see which instructions' `applyTo` globs pick it up.
+/// Matches are recomputed on every key so the list follows the input.
+pub(crate) struct GlobTester {
+    input: String,
+    scroll: u16,
+}
+
+impl GlobTester {
+    pub(crate) fn input(&self) -> &str {
+        &self.input
+    }
+
+    pub(crate) fn scroll(&self) -> u16 {
+        self.scroll
+    }
+}
+
+impl App {
+    pub(crate) fn apply_to_matches(&self, file: &str) -> Vec<ApplyToMatch<'_>> {
+        apply_to::matching_instructions(&self.domain, file)
+    }
+
+    pub(super) fn open_glob_tester(&mut self) {
+        self.overlay = Some(Overlay::GlobTester(GlobTester {
+            input: String::new(),
+            scroll: 0,
+        }));
+        self.message = Some("applyTo tester: type a path relative to the repository".into());
+    }
+
+    pub(super) fn handle_glob_tester_key(&mut self, key: KeyEvent) {
+        let Some(Overlay::GlobTester(tester)) = &mut self.overlay else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.overlay = None;
+                self.message = None;
+            }
+            KeyCode::Backspace => {
+                tester.input.pop();
+                tester.scroll = 0;
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                tester.input.clear();
+                tester.scroll = 0;
+            }
+            KeyCode::Char(ch) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                tester.input.push(ch);
+                tester.scroll = 0;
+            }
+            KeyCode::Down => tester.scroll = tester.scroll.saturating_add(1),
+            KeyCode::Up => tester.scroll = tester.scroll.saturating_sub(1),
+            KeyCode::PageDown => tester.scroll = tester.scroll.saturating_add(10),
+            KeyCode::PageUp => tester.scroll = tester.scroll.saturating_sub(10),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::ui::input::KeyContext;
+
+use super::{overlay::Overlay, App};
+
+/// The keybinding reference. Remembers the overlay it was opened from so
+/// closing it returns there.
+pub(crate) struct HelpView {
+    context: KeyContext,
+    scroll: u16,
+    previous: Option<Box<Overlay>>,
+}
+
+impl HelpView {
+    /// Bindings of this context are listed first.
+    pub(crate) fn context(&self) -> KeyContext {
+        self.context
+    }
+
+    pub(crate) fn scroll(&self) -> u16 {
+        self.scroll
+    }
+}
+
+impl App {
+    pub(super) fn open_help(&mut self) {
+        let previous = self.overlay.take();
+        let context = match previous {
+            Some(Overlay::Backups(_)) => KeyContext::Backups,
+            Some(Overlay::Orphans(_)) => KeyContext::Orphans,
+            Some(Overlay::Members(_)) => KeyContext::Members,
+            Some(Overlay::Changelog(_)) => KeyContext::Changelog,
+            Some(Overlay::Prune(_)) => KeyContext::Prune,
+            Some(Overlay::Unmanaged(_)) => KeyContext::Unmanaged,
+            Some(Overlay::Staged(_)) => KeyContext::Staged,
+            Some(Overlay::Warnings(_)) => KeyContext::Warnings,
+            Some(Overlay::Cache(_)) => KeyContext::Cache,
+            Some(Overlay::Repos(_)) => KeyContext::Repos,
+            Some(Overlay::Projects(_)) => KeyContext::Projects,
+            Some(Overlay::Plan(_) | Overlay::Impact(_)) => KeyContext::Prompt,
+            Some(Overlay::SaveReport(_)) => KeyContext::SaveReport,
+            Some(Overlay::Wizard(_)) => KeyContext::Wizard,
+            Some(Overlay::GlobTester(_)) => KeyContext::GlobTester,
+            Some(Overlay::History(_)) => KeyContext::History,
+            Some(Overlay::Log(_)) => KeyContext::Log,
+            Some(Overlay::Reader(_)) => KeyContext::Reader,
+            Some(Overlay::Help(_)) | None if self.detail_focused => KeyContext::Detail,
+            Some(Overlay::Help(_)) | None if self.facets.is_focused() => KeyContext::Facets,
+            Some(Overlay::Help(_)) | None => KeyContext::Normal,
+        };
+        self.overlay = Some(Overlay::Help(HelpView {
+            context,
+            scroll: 0,
+            previous: previous.map(Box::new),
+        }));
+    }
+
+    pub(super) fn handle_help_key(&mut self, key: KeyEvent) {
+        let Some(Overlay::Help(mut view)) = self.overlay.take() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => {
+                self.overlay = view.previous.map(|previous| *previous);
+                return;
+            }
+            KeyCode::Down => view.scroll = view.scroll.saturating_add(1),
+            KeyCode::Up => view.scroll = view.scroll.saturating_sub(1),
+            KeyCode::PageDown => view.scroll = view.scroll.saturating_add(10),
+            KeyCode::PageUp => view.scroll = view.scroll.saturating_sub(10),
+            KeyCode::Home => view.scroll = 0,
+            _ => {}
+        }
+        self.overlay = Some(Overlay::Help(view));
+    }
+}
@@ -0,0 +1,77 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::io::history::{self, HistoryEntry, HistoryEvent};
+
+use super::{overlay::Overlay, warnings::WarningSource, App};
+
+/// Recent toggles, applies, resets and saves, newest first.
+pub(crate) struct HistoryView {
+    scroll: u16,
+}
+
+impl HistoryView {
+    pub(crate) fn scroll(&self) -> u16 {
+        self.scroll
+    }
+}
+
+impl App {
+    /// The log, oldest first, and how many entries came from earlier
+    /// sessions.
+    pub(crate) fn history(&self) -> (&[HistoryEntry], usize) {
+        (&self.history, self.history_session_start)
+    }
+
+    /// Read the persisted log so this session continues it.
+    pub(super) fn load_history(&mut self) {
+        match history::load_history(&self.paths) {
+            Ok(entries) => {
+                self.history_session_start = entries.len();
+                self.history = entries;
+            }
+            Err(err) => self.warn(
+                WarningSource::Local,
+                format!("Activity log not loaded: {err:#}"),
+            ),
+        }
+    }
+
+    pub(super) fn record(&mut self, event: HistoryEvent, summary: impl Into<String>) {
+        let entry = HistoryEntry::now(event, summary);
+        if self.persist_history {
+            if let Err(err) = history::append_history(&self.paths, &entry) {
+                self.warn(
+                    WarningSource::Local,
+                    format!("Activity log not written: {err:#}"),
+                );
+            }
+        }
+        self.history.push(entry);
+    }
+
+    pub(super) fn open_history(&mut self) {
+        if self.history.is_empty() {
+            self.message = Some("Nothing toggled, applied or saved yet".into());
+            return;
+        }
+        self.overlay = Some(Overlay::History(HistoryView { scroll: 0 }));
+    }
+
+    pub(super) fn handle_history_key(&mut self, key: KeyEvent) {
+        let Some(Overlay::History(view)) = &mut self.overlay else {
+            return;
+        };
+        view.scroll = match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => {
+                self.overlay = None;
+                return;
+            }
+            KeyCode::Down | KeyCode::Char('j') => view.scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => view.scroll.saturating_sub(1),
+            KeyCode::PageDown => view.scroll.saturating_add(10),
+            KeyCode::PageUp => view.scroll.saturating_sub(10),
+            KeyCode::Home => 0,
+            _ => view.scroll,
+        };
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::Result;
+
+use crate::io::{
+    history::HistoryEvent,
+    sync::{ApplyOutcome, SyncSummary},
+    worker::{FileOp, Job, WorkerEvent},
+};
+
+use super::{apply_all::APPLIED_KINDS, warnings::WarningSource, App};
+
+impl App {
+    pub(super) fn send_job(&mut self, job: Job) {
+        if let Err(err) = self.worker.send(job) {
+            self.error = Some(format!("{err:#}"));
+        }
+    }
+
+    /// Handle everything the worker finished since the last pass.
+    pub(super) fn poll_worker(&mut self) {
+        let events = match self.worker.poll() {
+            Ok(events) => events,
+            Err(err) => {
+                self.loading = false;
+                self.error = Some(format!("{err:#}"));
+                return;
+            }
+        };
+        for event in events {
+            self.redraw = true;
+            match event {
+                WorkerEvent::Progress(progress) => self.load_progress = Some(progress),
+                WorkerEvent::Loaded(result) => self.finish_loading(*result),
+                WorkerEvent::Statuses {
+                    generation,
+                    statuses,
+                } => {
+                    if generation != self.status_generation {
+                        continue;
+                    }
+                    match statuses {
+                        Ok(statuses) => {
+                            self.domain.set_local_statuses(statuses);
+                            self.count_pending_updates();
+                            self.offer_adoption();
+                        }
+                        Err(err) => {
+                            self.error = Some(format!("Checking local files failed: {err:#}"))
+                        }
+                    }
+                }
+                WorkerEvent::Synced { outcomes, announce } => self.finish_sync(outcomes, announce),
+                WorkerEvent::AppliedAll(summary) => self.finish_apply_all(summary),
+                WorkerEvent::Indexed(index) => self.finish_indexing(index),
+            }
+        }
+    }
+
+    /// Hash the local copy of every asset on every tab on the worker. The
+    /// statuses land on the domain state when the answer arrives; call this
+    /// after anything that writes applied files (toggle, apply, reload,
+    /// external edits).
+    pub(super) fn compute_local_statuses(&mut self) {
+        let assets = APPLIED_KINDS
+            .into_iter()
+            .flat_map(|kind| self.domain.assets(kind))
+            .map(|view| (view.kind, view.path.clone()))
+            .collect();
+        self.status_generation += 1;
+        self.send_job(Job::Statuses {
+            generation: self.status_generation,
+            upstream: self.upstream.clone(),
+            assets,
+        });
+    }
+
+    /// Apply or remove local files on the worker using the active apply
+    /// mode. With `announce`, the outcome replaces the footer message and
+    /// the files are staged.
+    pub(super) fn sync_files(&mut self, ops: Vec<FileOp>, announce: bool) {
+        if ops.is_empty() {
+            return;
+        }
+        self.send_job(Job::Sync {
+            upstream: self.upstream.clone(),
+            ops,
+            mode: self.apply_mode,
+            announce,
+        });
+    }
+
+    fn finish_sync(&mut self, outcomes: Result<Vec<(String, ApplyOutcome)>>, announce: bool) {
+        let outcomes = match outcomes {
+            Ok(outcomes) => outcomes,
+            Err(err) => {
+                self.error = Some(format!("Updating local files failed: {err:#}"));
+                return;
+            }
+        };
+        // Warnings survive the next status message.
+        for (path, outcome) in &outcomes {
+            if *outcome == ApplyOutcome::Conflicted {
+                self.warn(
+                    WarningSource::Local,
+                    format!("Merge conflicts in {path}; resolve the markers"),
+                );
+            }
+        }
+        if !announce {
+            return;
+        }
+        let message = match outcomes.as_slice() {
+            [(path, outcome)] => match outcome {
+                ApplyOutcome::Written => "Applied from upstream".to_string(),
+                ApplyOutcome::Unchanged => "Already up to date".to_string(),
+                ApplyOutcome::Merged => "Merged upstream changes with local edits".to_string(),
+                ApplyOutcome::Conflicted => {
+                    format!("Merge conflicts in {path}; resolve the markers")
+                }
+            },
+            _ => format!("Applied {} asset(s) from upstream", outcomes.len()),
+        };
+        let summary = match outcomes.as_slice() {
+            [(_, ApplyOutcome::Conflicted)] => message.clone(),
+            [(path, _)] => format!("{path}: {message}"),
+            _ => message.clone(),
+        };
+        self.record(HistoryEvent::Apply, summary);
+        self.message = Some(message);
+        self.error = None;
+        self.stage_if_enabled();
+    }
+
+    fn finish_apply_all(&mut self, summary: Result<SyncSummary>) {
+        let summary = match summary {
+            Ok(summary) => summary,
+            Err(err) => {
+                self.error = Some(format!("Applying enabled assets failed: {err:#}"));
+                return;
+            }
+        };
+        for path in &summary.conflicted {
+            self.warn(
+                WarningSource::Local,
+                format!("Merge conflicts in {path}; resolve the markers"),
+            );
+        }
+        let message = format!("Applied all enabled assets: {summary}");
+        self.record(HistoryEvent::Apply, message.clone());
+        self.message = Some(message);
+        self.error = None;
+        self.stage_if_enabled();
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::VecDeque;
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::io::message_log::{self, MessageEntry, MessageLevel};
+
+use super::{overlay::Overlay, App};
+
+/// Footer messages kept in memory; older ones are dropped.
+const LOG_CAPACITY: usize = 500;
+
+/// Every info and error message shown this session, so nothing is lost
+/// when the next action replaces the footer.
+#[derive(Debug, Default)]
+pub(super) struct MessageLog {
+    entries: VecDeque<MessageEntry>,
+    /// What the footer showed when it was last captured.
+    shown: (Option<String>, Option<String>),
+}
+
+/// The message log, newest first.
+pub(crate) struct LogView {
+    scroll: u16,
+}
+
+impl LogView {
+    pub(crate) fn scroll(&self) -> u16 {
+        self.scroll
+    }
+}
+
+impl App {
+    pub(crate) fn message_log(&self) -> &VecDeque<MessageEntry> {
+        &self.log.entries
+    }
+
+    /// Record footer messages that changed since the last pass.
+    pub(super) fn capture_messages(&mut self) {
+        let (shown_message, shown_error) = &self.log.shown;
+        let mut captured = Vec::new();
+        if self.message != *shown_message {
+            if let Some(message) = &self.message {
+                captured.push(MessageEntry::now(MessageLevel::Info, message.clone()));
+            }
+        }
+        if self.error != *shown_error {
+            if let Some(error) = &self.error {
+                captured.push(MessageEntry::now(MessageLevel::Error, error.clone()));
+            }
+        }
+        self.log.shown = (self.message.clone(), self.error.clone());
+        for entry in captured {
+            if self.log_to_file {
+                if let Err(err) = message_log::append_message(&self.paths, &entry) {
+                    // Once is enough; the next failure would say the same.
+                    self.log_to_file = false;
+                    self.error = Some(format!("Message log not written: {err:#}"));
+                }
+            }
+            if self.log.entries.len() == LOG_CAPACITY {
+                self.log.entries.pop_front();
+            }
+            self.log.entries.push_back(entry);
+        }
+    }
+
+    pub(super) fn open_log(&mut self) {
+        if self.log.entries.is_empty() {
+            self.message = Some("No messages yet".into());
+            return;
+        }
+        self.overlay = Some(Overlay::Log(LogView { scroll: 0 }));
+    }
+
+    pub(super) fn handle_log_key(&mut self, key: KeyEvent) {
+        let Some(Overlay::Log(view)) = &mut self.overlay else {
+            return;
+        };
+        view.scroll = match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => {
+                self.overlay = None;
+                return;
+            }
+            KeyCode::Down | KeyCode::Char('j') => view.scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => view.scroll.saturating_sub(1),
+            KeyCode::PageDown => view.scroll.saturating_add(10),
+            KeyCode::PageUp => view.scroll.saturating_sub(10),
+            KeyCode::Home => 0,
+            _ => view.scroll,
+        };
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+
+use crate::{
+    domain::toggle,
+    io::{history::HistoryEvent, worker::FileOp},
+};
+
+use super::{App, PendingPrompt};
+
+impl App {
+    /// Mark or unmark the selected asset on the current tab.
+    pub(super) fn toggle_mark(&mut self) {
+        let Some(path) = self.selected_asset().map(|asset| asset.path.clone()) else {
+            return;
+        };
+        let marks = self.marks.entry(self.tab).or_default();
+        if !marks.remove(&path) {
+            marks.insert(path);
+        }
+        self.report_marks();
+    }
+
+    /// Start a visual range at the selection, or mark the range when one is
+    /// already active.
+    pub(super) fn toggle_visual(&mut self) {
+        match self.visual_anchor.take() {
+            Some(anchor) => {
+                let paths: Vec<String> = self
+                    .visual_range(anchor)
+                    .filter_map(|index| {
+                        self.filtered_assets(self.tab)
+                            .get(index)
+                            .map(|a| a.path.clone())
+                    })
+                    .collect();
+                self.marks.entry(self.tab).or_default().extend(paths);
+                self.report_marks();
+            }
+            None => {
+                self.visual_anchor = Some(self.current_selection());
+                self.message =
+                    Some("Visual mode: move to extend, v to mark the range, Esc to cancel".into());
+            }
+        }
+    }
+
+    pub(super) fn cancel_visual(&mut self) -> bool {
+        self.visual_anchor.take().is_some()
+    }
+
+    pub(super) fn clear_marks(&mut self) {
+        self.marks.remove(&self.tab);
+    }
+
+    pub(super) fn marked_paths(&self) -> Vec<String> {
+        self.marks
+            .get(&self.tab)
+            .map(|marks| marks.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub(super) fn request_bulk_toggle(&mut self) -> Result<()> {
+        let count = self.marked_paths().len();
+        let plan = self.bulk_toggle_plan()?;
+        let question = format!(
+            "Toggle {count} marked asset{}?",
+            if count == 1 { "" } else { "s" }
+        );
+        if !self.confirm_plan(
+            PendingPrompt::BulkToggle,
+            question,
+            "toggle marked assets",
+            plan,
+        ) {
+            return self.bulk_toggle();
+        }
+        Ok(())
+    }
+
+    pub(super) fn request_bulk_apply(&mut self) -> Result<()> {
+        let count = self.marked_paths().len();
+        let plan = self.bulk_apply_plan()?;
+        if plan.is_empty() {
+            self.clear_marks();
+            self.message = Some("Marked assets already match upstream".into());
+            return Ok(());
+        }
+        let question = format!(
+            "Apply {count} marked asset{} from upstream?",
+            if count == 1 { "" } else { "s" }
+        );
+        if !self.confirm_plan(
+            PendingPrompt::BulkApply,
+            question,
+            "apply marked assets",
+            plan,
+        ) {
+            return self.bulk_apply();
+        }
+        Ok(())
+    }
+
+    /// Toggle every marked asset of the current tab through `toggle_asset`
+    /// and sync its files.
+    pub(super) fn bulk_toggle(&mut self) -> Result<()> {
+        let kind = self.tab;
+        let mut enabled = 0;
+        let mut disabled = 0;
+        for path in self.marked_paths() {
+            let result = toggle::toggle_asset(&mut self.domain, kind, &path)?;
+            self.apply_after_toggle(kind, &path, &result)?;
+            if result.asset.effective {
+                enabled += 1;
+            } else {
+                disabled += 1;
+            }
+        }
+        self.clear_marks();
+        self.dirty = true;
+        let summary = format!("Bulk toggle: {enabled} enabled, {disabled} disabled");
+        self.record(HistoryEvent::Toggle, summary.clone());
+        self.message = Some(summary);
+        self.error = None;
+        self.normalize_selection_after_filter();
+        Ok(())
+    }
+
+    pub(super) fn bulk_apply(&mut self) -> Result<()> {
+        let kind = self.tab;
+        let ops: Vec<_> = self
+            .marked_paths()
+            .into_iter()
+            .map(|path| FileOp::Apply(kind, path))
+            .collect();
+        self.message = Some(format!("Applying {} marked asset(s)…", ops.len()));
+        self.error = None;
+        self.sync_files(ops, true);
+        self.clear_marks();
+        self.compute_local_statuses();
+        Ok(())
+    }
+
+    pub fn is_marked(&self, path: &str) -> bool {
+        self.marks
+            .get(&self.tab)
+            .is_some_and(|marks| marks.contains(path))
+    }
+
+    /// Whether the row at `index` lies inside an active visual range.
+    pub fn in_visual_range(&self, index: usize) -> bool {
+        self.visual_anchor
+            .is_some_and(|anchor| self.visual_range(anchor).any(|i| i == index))
+    }
+
+    pub fn mark_count(&self) -> usize {
+        self.marks.get(&self.tab).map(BTreeSet::len).unwrap_or(0)
+    }
+
+    fn visual_range(&self, anchor: usize) -> std::ops::RangeInclusive<usize> {
+        let current = self.current_selection();
+        anchor.min(current)..=anchor.max(current)
+    }
+
+    fn report_marks(&mut self) {
+        self.message = Some(format!(
+            "{} marked • Enter toggles, a applies all marked",
+            self.mark_count()
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{
+    domain::{model::AssetKind, state::AssetView, toggle},
+    io::{history::HistoryEvent, worker::FileOp},
+};
+
+use super::{overlay::Overlay, App};
+
+/// Drill-down into one collection where members are enabled or disabled one
+/// by one. The members are read from the domain state so their states stay
+/// current after each toggle.
+pub(crate) struct MemberPicker {
+    collection: String,
+    selected: usize,
+}
+
+impl MemberPicker {
+    pub(crate) fn collection(&self) -> &str {
+        &self.collection
+    }
+
+    pub(crate) fn selected(&self) -> usize {
+        self.selected
+    }
+}
+
+impl App {
+    pub(crate) fn collection_members(&self, collection: &str) -> Vec<&AssetView> {
+        self.domain.collection_members(collection)
+    }
+
+    pub(super) fn open_member_picker(&mut self) {
+        let Some(asset) = self.selected_asset() else {
+            return;
+        };
+        if asset.kind != AssetKind::Collection {
+            self.message = Some("Pick members from the Collections tab".into());
+            return;
+        }
+        if self.domain.collection_members(&asset.path).is_empty() {
+            self.message = Some(format!("{} has no members in the catalog", asset.name));
+            return;
+        }
+        self.overlay = Some(Overlay::Members(MemberPicker {
+            collection: asset.path.clone(),
+            selected: 0,
+        }));
+        self.message = Some(
+            "Members: Space toggles the selected member, a applies its file, Esc goes back".into(),
+        );
+    }
+
+    pub(super) fn handle_members_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(Overlay::Members(mut picker)) = self.overlay.take() else {
+            return Ok(());
+        };
+        let members = self.domain.collection_members(&picker.collection);
+        let len = members.len();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.message = None;
+                return Ok(());
+            }
+            KeyCode::Up | KeyCode::Char('k') if picker.selected > 0 => picker.selected -= 1,
+            KeyCode::Down | KeyCode::Char('j') if picker.selected + 1 < len => picker.selected += 1,
+            KeyCode::Char('a') => {
+                if let Some(member) = members.get(picker.selected) {
+                    if matches!(member.kind, AssetKind::Collection | AssetKind::McpServer) {
+                        self.message = Some(format!("{} has no file to apply", member.name));
+                    } else {
+                        let (kind, path) = (member.kind, member.path.clone());
+                        self.message = Some(format!("Applying {path}…"));
+                        self.sync_files(vec![FileOp::Apply(kind, path)], true);
+                        self.compute_local_statuses();
+                    }
+                }
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                if let Some(member) = members.get(picker.selected) {
+                    let (kind, path, name) =
+                        (member.kind, member.path.clone(), member.name.clone());
+                    let enable = !member.effective;
+                    let result = toggle::set_asset(&mut self.domain, kind, &path, enable)?;
+                    let mut ops = vec![FileOp::for_state(kind, path, enable)];
+                    ops.extend(self.dependency_ops(&result));
+                    self.follow_enablement(ops);
+                    self.compute_local_statuses();
+                    self.dirty = true;
+                    let summary = format!(
+                        "{} {name}{}",
+                        if enable { "Enabled" } else { "Disabled" },
+                        self.dependency_note(&result)
+                    );
+                    self.record(HistoryEvent::Toggle, summary.clone());
+                    self.message = Some(summary);
+                    self.error = None;
+                }
+            }
+            _ => {}
+        }
+        self.overlay = Some(Overlay::Members(picker));
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+
+use crate::ui::{draw, keymap::Action};
+
+use super::App;
+
+impl App {
+    pub(super) fn handle_mouse(&mut self, event: MouseEvent, size: Rect) {
+        if self.search.is_active()
+            || self.note_editor.is_some()
+            || self.prompt.is_some()
+            || self.overlay.is_some()
+            || self.loading
+        {
+            return;
+        }
+        let layout = draw::screen_layout(size, self.table_width(), self.facets.is_open());
+        let position = Position {
+            x: event.column,
+            y: event.row,
+        };
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if layout.header.contains(position) && !self.projects.is_empty() {
+                    self.open_projects();
+                } else if layout.tabs.contains(position) {
+                    if let Some(kind) = draw::tab_at(layout.tabs, self, event.column) {
+                        self.switch_tab(kind);
+                    }
+                } else if layout.facets.contains(position) {
+                    self.focus_facets();
+                    let selected = self.facets.selected();
+                    if let Some(index) = draw::facet_row_at(layout.facets, selected, event.row) {
+                        self.toggle_facet(index);
+                    }
+                } else if layout.table.contains(position) {
+                    self.detail_focused = false;
+                    self.facets.blur();
+                    let selected = self.selection_index().unwrap_or(0);
+                    if let Some(index) = draw::table_row_at(layout.table, selected, event.row) {
+                        if index < self.filtered_assets(self.tab).len() {
+                            self.select_index(index);
+                        }
+                    }
+                } else if layout.detail.contains(position) {
+                    self.detail_focused = true;
+                    self.facets.blur();
+                }
+            }
+            MouseEventKind::ScrollDown => {
+                if layout.facets.contains(position) {
+                    self.handle_facet_action(Action::Down);
+                } else if layout.detail.contains(position) {
+                    self.scroll_detail(3);
+                } else if layout.table.contains(position) {
+                    self.move_selection(1);
+                }
+            }
+            MouseEventKind::ScrollUp => {
+                if layout.facets.contains(position) {
+                    self.handle_facet_action(Action::Up);
+                } else if layout.detail.contains(position) {
+                    self.scroll_detail(-3);
+                } else if layout.table.contains(position) {
+                    self.move_selection(-1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub(super) fn scroll_detail(&mut self, delta: i16) {
+        self.detail_scroll = self.detail_scroll.saturating_add_signed(delta);
+    }
+
+    pub fn detail_focused(&self) -> bool {
+        self.detail_focused
+    }
+
+    pub fn detail_scroll(&self) -> u16 {
+        self.detail_scroll
+    }
+}
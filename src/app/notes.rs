@@ -0,0 +1,79 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::App;
+
+/// The footer prompt editing the note of one asset. Notes live in the
+/// enablement file's `overrides`, so they are written on the next save.
+pub(crate) struct NoteEditor {
+    path: String,
+    name: String,
+    draft: String,
+}
+
+impl NoteEditor {
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn draft(&self) -> &str {
+        &self.draft
+    }
+}
+
+impl App {
+    pub(crate) fn note_editor(&self) -> Option<&NoteEditor> {
+        self.note_editor.as_ref()
+    }
+
+    /// Start editing the note of the selected asset, prefilled with the
+    /// current one.
+    pub(super) fn edit_note(&mut self) {
+        let Some(asset) = self.selected_asset() else {
+            return;
+        };
+        let draft = self
+            .domain
+            .enablement
+            .note(&asset.path)
+            .unwrap_or_default()
+            .to_string();
+        self.note_editor = Some(NoteEditor {
+            path: asset.path.clone(),
+            name: asset.name.clone(),
+            draft,
+        });
+        self.message = None;
+    }
+
+    pub(super) fn handle_note_key(&mut self, key: KeyEvent) {
+        let Some(editor) = &mut self.note_editor else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.note_editor = None,
+            KeyCode::Enter => {
+                let Some(editor) = self.note_editor.take() else {
+                    return;
+                };
+                if !self.domain.enablement.set_note(&editor.path, &editor.draft) {
+                    return;
+                }
+                self.dirty = true;
+                self.message = Some(match self.domain.enablement.note(&editor.path) {
+                    Some(_) => format!("Note saved for {}", editor.name),
+                    None => format!("Note removed from {}", editor.name),
+                });
+            }
+            KeyCode::Backspace => {
+                editor.draft.pop();
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                editor.draft.clear();
+            }
+            KeyCode::Char(ch) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                editor.draft.push(ch);
+            }
+            _ => {}
+        }
+    }
+}
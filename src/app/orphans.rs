@@ -0,0 +1,95 @@
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{domain::state::OrphanEntry, io::worker::FileOp};
+
+use super::{overlay::Overlay, App};
+
+/// Selection within the orphans view. The entries themselves are read from
+/// the domain state so they stay current after each deletion or remap.
+pub(crate) struct OrphanBrowser {
+    selected: usize,
+}
+
+impl OrphanBrowser {
+    pub(crate) fn selected(&self) -> usize {
+        self.selected
+    }
+}
+
+impl App {
+    pub(crate) fn orphans(&self) -> &[OrphanEntry] {
+        self.domain.orphans()
+    }
+
+    pub(super) fn open_orphans(&mut self) {
+        if self.domain.orphans().is_empty() {
+            self.message = Some("No orphan entries".into());
+            return;
+        }
+        self.overlay = Some(Overlay::Orphans(OrphanBrowser { selected: 0 }));
+        self.message =
+            Some("Orphans: d deletes, Enter remaps to the suggested path, Esc closes".into());
+    }
+
+    pub(super) fn handle_orphans_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(Overlay::Orphans(mut browser)) = self.overlay.take() else {
+            return Ok(());
+        };
+        let len = self.domain.orphans().len();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.message = None;
+                return Ok(());
+            }
+            KeyCode::Up if browser.selected > 0 => browser.selected -= 1,
+            KeyCode::Down if browser.selected + 1 < len => browser.selected += 1,
+            KeyCode::Char('d') | KeyCode::Delete => {
+                if let Some(orphan) = self.domain.orphans().get(browser.selected).cloned() {
+                    self.domain.remove_orphan(orphan.kind, &orphan.path);
+                    self.dirty = true;
+                    self.message = Some(format!("Removed orphan entry {}", orphan.path));
+                }
+            }
+            KeyCode::Enter | KeyCode::Char('r') => {
+                if let Some(orphan) = self.domain.orphans().get(browser.selected).cloned() {
+                    self.remap_orphan(&orphan)?;
+                }
+            }
+            _ => {}
+        }
+        let remaining = self.domain.orphans().len();
+        if remaining == 0 {
+            self.message = Some("All orphan entries resolved; save with Ctrl+S".into());
+            return Ok(());
+        }
+        browser.selected = browser.selected.min(remaining - 1);
+        self.overlay = Some(Overlay::Orphans(browser));
+        Ok(())
+    }
+
+    fn remap_orphan(&mut self, orphan: &OrphanEntry) -> Result<()> {
+        let Some(successor) = orphan.successor.clone() else {
+            self.message = Some(format!("No likely successor for {}", orphan.path));
+            return Ok(());
+        };
+        let before = self.domain.clone();
+        if !self
+            .domain
+            .remap_orphan(orphan.kind, &orphan.path, &successor)
+        {
+            return Ok(());
+        }
+        let ops = self
+            .domain
+            .effective_changes(&before)
+            .into_iter()
+            .map(|(kind, path, enabled)| FileOp::for_state(kind, path, enabled))
+            .collect();
+        self.follow_enablement(ops);
+        self.compute_local_statuses();
+        self.dirty = true;
+        self.message = Some(format!("Remapped {} → {successor}", orphan.path));
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::{
+    backups::BackupBrowser,
+    cache::CacheView,
+    changelog::ChangelogView,
+    glob_tester::GlobTester,
+    help::HelpView,
+    history::HistoryView,
+    log::LogView,
+    members::MemberPicker,
+    orphans::OrphanBrowser,
+    plan::{ImpactView, PlanView},
+    projects::ProjectPicker,
+    prune::PruneView,
+    reader::ContentReader,
+    repos::RepoSwitcher,
+    save_report::SaveReport,
+    staging::StagedView,
+    unmanaged::UnmanagedView,
+    warnings::WarningsView,
+    wizard::NewAssetWizard,
+    App,
+};
+
+/// A screen drawn over the asset table that captures keyboard input until it
+/// is closed.
+pub(crate) enum Overlay {
+    Backups(BackupBrowser),
+    Orphans(OrphanBrowser),
+    Members(MemberPicker),
+    Changelog(ChangelogView),
+    Help(HelpView),
+    Reader(ContentReader),
+    Prune(PruneView),
+    Unmanaged(UnmanagedView),
+    Staged(StagedView),
+    Warnings(WarningsView),
+    Cache(CacheView),
+    Repos(RepoSwitcher),
+    Projects(ProjectPicker),
+    /// Shown while a bulk prompt is pending; the prompt handles its keys.
+    Plan(PlanView),
+    /// Shown while a collection toggle prompt is pending.
+    Impact(ImpactView),
+    SaveReport(SaveReport),
+    Wizard(NewAssetWizard),
+    GlobTester(GlobTester),
+    History(HistoryView),
+    Log(LogView),
+}
+
+impl App {
+    pub(crate) fn overlay(&self) -> Option<&Overlay> {
+        self.overlay.as_ref()
+    }
+
+    pub(super) fn handle_overlay_key(&mut self, key: KeyEvent) -> Result<()> {
+        // The wizard and the tester take text, so `?` is typed rather than
+        // opening help.
+        let typing = match &self.overlay {
+            Some(Overlay::Help(_) | Overlay::Wizard(_) | Overlay::GlobTester(_)) => true,
+            Some(Overlay::Reader(reader)) => reader.input().is_some(),
+            _ => false,
+        };
+        if key.code == KeyCode::Char('?') && !typing {
+            self.open_help();
+            return Ok(());
+        }
+        match self.overlay {
+            Some(Overlay::Backups(_)) => self.handle_backups_key(key),
+            Some(Overlay::Orphans(_)) => self.handle_orphans_key(key),
+            Some(Overlay::Members(_)) => self.handle_members_key(key),
+            Some(Overlay::Prune(_)) => self.handle_prune_key(key),
+            Some(Overlay::Unmanaged(_)) => self.handle_unmanaged_key(key),
+            Some(Overlay::Staged(_)) => self.handle_staged_key(key),
+            Some(Overlay::Cache(_)) => self.handle_cache_key(key),
+            Some(Overlay::Repos(_)) => self.handle_repos_key(key),
+            Some(Overlay::Projects(_)) => {
+                self.handle_projects_key(key);
+                Ok(())
+            }
+            Some(Overlay::Wizard(_)) => self.handle_wizard_key(key),
+            Some(Overlay::Reader(_)) => {
+                self.handle_reader_key(key);
+                Ok(())
+            }
+            Some(Overlay::Changelog(_)) => {
+                self.handle_changelog_key(key);
+                Ok(())
+            }
+            Some(Overlay::SaveReport(_)) => {
+                self.handle_save_report_key(key);
+                Ok(())
+            }
+            Some(Overlay::Log(_)) => {
+                self.handle_log_key(key);
+                Ok(())
+            }
+            Some(Overlay::Warnings(_)) => {
+                self.handle_warnings_key(key);
+                Ok(())
+            }
+            Some(Overlay::Help(_)) => {
+                self.handle_help_key(key);
+                Ok(())
+            }
+            Some(Overlay::GlobTester(_)) => {
+                self.handle_glob_tester_key(key);
+                Ok(())
+            }
+            Some(Overlay::History(_)) => {
+                self.handle_history_key(key);
+                Ok(())
+            }
+            Some(Overlay::Plan(_) | Overlay::Impact(_)) | None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use anyhow::Result;
+use crossterm::event::KeyCode;
+
+use crate::{
+    domain::{
+        state::AssetView,
+        toggle::{self, CollectionToggleImpact},
+    },
+    io::{manifest, plan::SyncPlan},
+};
+
+use super::{apply_all::APPLIED_KINDS, overlay::Overlay, App, PendingPrompt};
+
+/// Files a pending bulk operation would touch, shown above its confirmation
+/// prompt.
+pub(crate) struct PlanView {
+    operation: String,
+    plan: SyncPlan,
+    /// Entries whose local file has changes not committed to git.
+    uncommitted: Vec<String>,
+    scroll: u16,
+}
+
+impl PlanView {
+    pub(crate) fn operation(&self) -> &str {
+        &self.operation
+    }
+
+    pub(crate) fn plan(&self) -> &SyncPlan {
+        &self.plan
+    }
+
+    pub(crate) fn is_uncommitted(&self, path: &str) -> bool {
+        self.uncommitted.iter().any(|entry| entry == path)
+    }
+
+    pub(crate) fn uncommitted_count(&self) -> usize {
+        self.uncommitted.len()
+    }
+
+    pub(crate) fn scroll(&self) -> u16 {
+        self.scroll
+    }
+}
+
+/// How a pending collection toggle changes each member, shown above its
+/// confirmation prompt.
+pub(crate) struct ImpactView {
+    impact: CollectionToggleImpact,
+    scroll: u16,
+}
+
+impl ImpactView {
+    pub(crate) fn impact(&self) -> &CollectionToggleImpact {
+        &self.impact
+    }
+
+    pub(crate) fn scroll(&self) -> u16 {
+        self.scroll
+    }
+}
+
+impl App {
+    /// Ask `question` before `prompt` runs, showing `plan` and any
+    /// uncommitted work it would overwrite. Returns false when no
+    /// confirmation is needed: bulk prompts are off and git has nothing at
+    /// stake.
+    pub(super) fn confirm_plan(
+        &mut self,
+        prompt: PendingPrompt,
+        question: String,
+        operation: &str,
+        plan: SyncPlan,
+    ) -> bool {
+        let uncommitted = self.uncommitted_in(&plan);
+        if !self.confirm.bulk && uncommitted.is_empty() {
+            return false;
+        }
+        self.ask_plan(prompt, question, operation, plan, uncommitted);
+        true
+    }
+
+    /// Ask `question` before `prompt` runs, whatever the confirm settings.
+    pub(super) fn ask_plan(
+        &mut self,
+        prompt: PendingPrompt,
+        question: String,
+        operation: &str,
+        plan: SyncPlan,
+        uncommitted: Vec<String>,
+    ) {
+        self.prompt = Some(prompt);
+        self.message = Some(if uncommitted.is_empty() {
+            format!("{question} Files: {plan}")
+        } else {
+            format!(
+                "{question} Files: {plan}. {} with uncommitted changes would be overwritten or removed",
+                uncommitted.len()
+            )
+        });
+        if !plan.is_empty() {
+            self.overlay = Some(Overlay::Plan(PlanView {
+                operation: operation.to_string(),
+                plan,
+                uncommitted,
+                scroll: 0,
+            }));
+        }
+    }
+
+    /// Ask before toggling `asset`, a collection, listing which members
+    /// would be enabled or disabled.
+    pub(super) fn confirm_collection_toggle(&mut self, asset: AssetView) -> Result<()> {
+        let impact = toggle::analyze_collection_toggle_impact(&self.domain, &asset.path)?;
+        let action = if impact.collection_will_enable {
+            "Enable"
+        } else {
+            "Disable"
+        };
+        self.message = Some(format!(
+            "{action} collection '{}'? {} members enable, {} disable, {} unchanged, {} requirements",
+            impact.collection_name,
+            impact.enable_count,
+            impact.disable_count,
+            impact.unchanged_count,
+            impact.dependencies.len()
+        ));
+        self.pending_toggle_asset = Some(asset);
+        self.prompt = Some(PendingPrompt::ToggleCollection);
+        self.overlay = Some(Overlay::Impact(ImpactView { impact, scroll: 0 }));
+        Ok(())
+    }
+
+    /// Close the plan or impact list once its prompt is answered.
+    pub(super) fn close_plan(&mut self) {
+        if matches!(self.overlay, Some(Overlay::Plan(_) | Overlay::Impact(_))) {
+            self.overlay = None;
+        }
+    }
+
+    /// Scroll the plan or impact list under a pending prompt; returns whether
+    /// `code` was consumed.
+    pub(super) fn scroll_plan(&mut self, code: KeyCode) -> bool {
+        let (scroll, lines) = match &mut self.overlay {
+            Some(Overlay::Plan(view)) => (&mut view.scroll, view.plan.entries.len()),
+            Some(Overlay::Impact(view)) => (
+                &mut view.scroll,
+                view.impact.affected_members.len() + view.impact.dependencies.len(),
+            ),
+            _ => return false,
+        };
+        let page = self.body_area.height.saturating_sub(3).max(1);
+        let max = (lines as u16).saturating_sub(page);
+        *scroll = match code {
+            KeyCode::Down | KeyCode::Char('j') => scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => scroll.saturating_sub(1),
+            KeyCode::PageDown => scroll.saturating_add(page),
+            KeyCode::PageUp => scroll.saturating_sub(page),
+            KeyCode::Home => 0,
+            KeyCode::End => max,
+            _ => return false,
+        }
+        .min(max);
+        true
+    }
+
+    /// What toggling the marked assets would do: replay the toggles on a copy
+    /// of the domain and compare effective states.
+    pub(super) fn bulk_toggle_plan(&self) -> Result<SyncPlan> {
+        let mut after = self.domain.clone();
+        for path in self.marked_paths() {
+            toggle::toggle_asset(&mut after, self.tab, &path)?;
+        }
+        let manifest = manifest::load_manifest(&self.paths)?;
+        let mut plan = SyncPlan::default();
+        // Views are rebuilt in catalog order, so the two lists line up.
+        for kind in APPLIED_KINDS {
+            for (before, now) in self.domain.assets(kind).iter().zip(after.assets(kind)) {
+                match (before.effective, now.effective) {
+                    (false, true) => plan.add_apply(
+                        &self.paths,
+                        &self.upstream,
+                        &manifest,
+                        kind,
+                        &now.path,
+                        self.apply_mode,
+                    )?,
+                    (true, false) => plan.add_remove(&self.paths, kind, &now.path),
+                    _ => {}
+                }
+            }
+        }
+        Ok(plan)
+    }
+
+    pub(super) fn bulk_apply_plan(&self) -> Result<SyncPlan> {
+        let marked = self.marked_paths();
+        SyncPlan::for_apply(
+            &self.paths,
+            &self.upstream,
+            marked.iter().map(|path| (self.tab, path.as_str())),
+            self.apply_mode,
+        )
+    }
+}
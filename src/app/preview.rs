@@ -0,0 +1,78 @@
+use std::fs;
+
+use crate::io::upstream::UpstreamRoots;
+
+use super::App;
+
+/// Upstream content of the asset shown in the preview pane.
+#[derive(Debug, Clone)]
+pub struct AssetPreview {
+    path: String,
+    content: Result<String, String>,
+}
+
+impl AssetPreview {
+    /// Read the snapshot copy of the asset at `path`.
+    pub(super) fn load(upstream: &UpstreamRoots, path: String) -> Self {
+        let content = upstream
+            .resolve(&path)
+            .map_err(|err| format!("{err:#}"))
+            .and_then(|file| {
+                fs::read_to_string(&file)
+                    .map_err(|err| format!("Cannot read {}: {err}", file.display()))
+            });
+        Self { path, content }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// File content, or the reason it could not be read.
+    pub fn content(&self) -> Result<&str, &str> {
+        self.content.as_deref().map_err(String::as_str)
+    }
+
+    /// Whether the content should be rendered as markdown.
+    pub fn is_markdown(&self) -> bool {
+        self.path.ends_with(".md")
+    }
+}
+
+impl App {
+    pub(super) fn toggle_preview(&mut self) {
+        self.preview_enabled = !self.preview_enabled;
+        self.detail_scroll = 0;
+        if !self.preview_enabled {
+            self.preview = None;
+        }
+    }
+
+    /// Load the selected asset's upstream file when the preview is on and the
+    /// selection changed since the last load.
+    pub(super) fn refresh_preview(&mut self) {
+        if !self.preview_enabled {
+            return;
+        }
+        let Some(path) = self.selected_asset().map(|asset| asset.path.clone()) else {
+            self.preview = None;
+            return;
+        };
+        if self
+            .preview
+            .as_ref()
+            .is_some_and(|preview| preview.path == path)
+        {
+            return;
+        }
+        self.preview = Some(AssetPreview::load(&self.upstream, path));
+    }
+
+    pub fn preview(&self) -> Option<&AssetPreview> {
+        if self.preview_enabled {
+            self.preview.as_ref()
+        } else {
+            None
+        }
+    }
+}
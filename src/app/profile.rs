@@ -0,0 +1,79 @@
+use anyhow::Result;
+
+use crate::io::{
+    profile::{self, ImportMode, DEFAULT_PROFILE_FILE},
+    worker::FileOp,
+};
+
+use super::{warnings::WarningSource, App, PendingPrompt};
+
+impl App {
+    /// Export the in-memory selection, including unsaved toggles.
+    pub(super) fn export_profile(&mut self) -> Result<()> {
+        let profile = profile::build_profile(&self.domain, &self.upstream);
+        let output = self.paths.root.join(DEFAULT_PROFILE_FILE);
+        profile::write_profile(&output, &profile)?;
+        self.message = Some(format!(
+            "Exported {} entries to {}",
+            profile.entries.len(),
+            output.display()
+        ));
+        self.error = None;
+        Ok(())
+    }
+
+    /// Read the default profile and ask whether to merge or replace.
+    pub(super) fn request_import(&mut self) -> Result<()> {
+        let source = self.paths.root.join(DEFAULT_PROFILE_FILE);
+        let plan = profile::plan_import(
+            profile::read_import(&source.to_string_lossy())?,
+            &self.domain.catalog,
+        );
+        self.message = Some(format!(
+            "Import {}: {}",
+            DEFAULT_PROFILE_FILE,
+            plan.summary()
+        ));
+        for entry in &plan.orphans {
+            self.warn(
+                WarningSource::Enablement,
+                format!("Import skips orphaned entry {}", entry.path),
+            );
+        }
+        self.pending_import = Some(plan);
+        if !self.confirm.import {
+            return self.perform_import(ImportMode::Merge);
+        }
+        self.prompt = Some(PendingPrompt::Import);
+        Ok(())
+    }
+
+    /// Apply the pending import and sync files whose effective state changed.
+    pub(super) fn perform_import(&mut self, mode: ImportMode) -> Result<()> {
+        let Some(plan) = self.pending_import.take() else {
+            return Ok(());
+        };
+        let before = self.domain.clone();
+        profile::apply_import(&mut self.domain.enablement, &plan, mode);
+        self.domain.recompute();
+        let ops = self
+            .domain
+            .effective_changes(&before)
+            .into_iter()
+            .map(|(kind, path, enabled)| FileOp::for_state(kind, path, enabled))
+            .collect();
+        self.follow_enablement(ops);
+        self.dirty = true;
+        self.compute_local_statuses();
+        self.message = Some(format!(
+            "Imported {} entries ({}); save with Ctrl+S",
+            plan.entries.len(),
+            match mode {
+                ImportMode::Merge => "merged",
+                ImportMode::Replace => "replaced",
+            }
+        ));
+        self.error = None;
+        Ok(())
+    }
+}
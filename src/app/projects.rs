@@ -0,0 +1,71 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::io::paths::RepoPaths;
+
+use super::{overlay::Overlay, App};
+
+/// The repository root and its monorepo sub-projects, to switch the session
+/// to.
+pub(crate) struct ProjectPicker {
+    /// `None` stands for the repository root.
+    projects: Vec<Option<String>>,
+    selected: usize,
+}
+
+impl ProjectPicker {
+    pub(crate) fn projects(&self) -> &[Option<String>] {
+        &self.projects
+    }
+
+    pub(crate) fn selected(&self) -> usize {
+        self.selected
+    }
+}
+
+impl App {
+    pub(crate) fn projects(&self) -> &[String] {
+        &self.projects
+    }
+
+    pub(super) fn open_projects(&mut self) {
+        if self.projects.is_empty() {
+            self.message = Some("No sub-projects; list them in [monorepo] projects".into());
+            return;
+        }
+        let projects: Vec<_> = std::iter::once(None)
+            .chain(self.projects.iter().cloned().map(Some))
+            .collect();
+        let selected = projects
+            .iter()
+            .position(|project| *project == self.paths.project)
+            .unwrap_or(0);
+        self.overlay = Some(Overlay::Projects(ProjectPicker { projects, selected }));
+    }
+
+    pub(super) fn handle_projects_key(&mut self, key: KeyEvent) {
+        let Some(Overlay::Projects(mut view)) = self.overlay.take() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return,
+            KeyCode::Up if view.selected > 0 => view.selected -= 1,
+            KeyCode::Down if view.selected + 1 < view.projects.len() => view.selected += 1,
+            KeyCode::Enter => {
+                let project = &view.projects[view.selected];
+                if *project == self.paths.project {
+                    self.message = Some("Already on this project".into());
+                } else {
+                    let paths = match project {
+                        Some(project) => RepoPaths::project(&self.paths.root, project),
+                        None => RepoPaths::new(&self.paths.root),
+                    };
+                    if self.reopen(paths) {
+                        return;
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.overlay = Some(Overlay::Projects(view));
+    }
+}
@@ -0,0 +1,74 @@
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::io::prune::{self, PruneCandidate};
+
+use super::{overlay::Overlay, App};
+
+/// Local files that no longer match the enablement state, offered for
+/// deletion.
+pub(crate) struct PruneView {
+    candidates: Vec<PruneCandidate>,
+    selected: usize,
+}
+
+impl PruneView {
+    pub(crate) fn candidates(&self) -> &[PruneCandidate] {
+        &self.candidates
+    }
+
+    pub(crate) fn selected(&self) -> usize {
+        self.selected
+    }
+}
+
+impl App {
+    pub(super) fn open_prune(&mut self) -> Result<()> {
+        let candidates =
+            prune::find_candidates(&self.paths, &self.domain, &self.workspace.ignored_files)?;
+        if candidates.is_empty() {
+            self.message = Some("Nothing to prune: .github matches the enablement state".into());
+            return Ok(());
+        }
+        self.overlay = Some(Overlay::Prune(PruneView {
+            candidates,
+            selected: 0,
+        }));
+        self.message = Some("Prune: d deletes the selected file, y deletes all, Esc closes".into());
+        Ok(())
+    }
+
+    pub(super) fn handle_prune_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(Overlay::Prune(mut view)) = self.overlay.take() else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.message = None;
+                return Ok(());
+            }
+            KeyCode::Up if view.selected > 0 => view.selected -= 1,
+            KeyCode::Down if view.selected + 1 < view.candidates.len() => view.selected += 1,
+            KeyCode::Char('d') | KeyCode::Delete => {
+                let candidate = view.candidates.remove(view.selected);
+                prune::prune(&self.paths, std::slice::from_ref(&candidate))?;
+                self.message = Some(format!(
+                    "Deleted {} (backed up)",
+                    candidate.display_path(&self.paths)
+                ));
+            }
+            KeyCode::Char('y') | KeyCode::Enter => {
+                let removed = prune::prune(&self.paths, &view.candidates)?;
+                self.message = Some(format!("Pruned {removed} file(s); backups kept"));
+                return Ok(());
+            }
+            _ => {}
+        }
+        if view.candidates.is_empty() {
+            return Ok(());
+        }
+        view.selected = view.selected.min(view.candidates.len() - 1);
+        self.overlay = Some(Overlay::Prune(view));
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+use std::ops::Range;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::text::Line;
+
+use crate::ui::markdown;
+
+use super::{overlay::Overlay, preview::AssetPreview, App};
+
+/// Full-screen view of the selected asset's upstream file.
+pub(crate) struct ContentReader {
+    name: String,
+    preview: AssetPreview,
+    lines: Vec<Line<'static>>,
+    scroll: u16,
+    /// The search being typed after `/`, if any.
+    input: Option<String>,
+    query: String,
+    /// Line index and byte range of every match of `query`.
+    matches: Vec<(usize, Range<usize>)>,
+    current: usize,
+}
+
+impl ContentReader {
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn path(&self) -> &str {
+        self.preview.path()
+    }
+
+    pub(crate) fn lines(&self) -> &[Line<'static>] {
+        &self.lines
+    }
+
+    pub(crate) fn scroll(&self) -> u16 {
+        self.scroll
+    }
+
+    pub(crate) fn input(&self) -> Option<&str> {
+        self.input.as_deref()
+    }
+
+    pub(crate) fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub(crate) fn matches(&self) -> &[(usize, Range<usize>)] {
+        &self.matches
+    }
+
+    /// Index into `matches` of the match `n`/`N` moved to.
+    pub(crate) fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Largest scroll offset that still fills a pane of `width` x `height`
+    /// cells, borders included.
+    pub(crate) fn max_scroll(&self, width: u16, height: u16) -> u16 {
+        let visible = height.saturating_sub(2) as usize;
+        self.row_of(self.lines.len(), width)
+            .saturating_sub(visible)
+            .min(u16::MAX as usize) as u16
+    }
+
+    /// Wrapped rows above line `index` in a pane of `width` cells.
+    fn row_of(&self, index: usize, width: u16) -> usize {
+        let width = width.saturating_sub(2).max(1) as usize;
+        self.lines[..index]
+            .iter()
+            .map(|line| line.width().div_ceil(width).max(1))
+            .sum()
+    }
+
+    /// Scroll so the current match is on screen, a few rows below the top.
+    fn show_current(&mut self, width: u16, height: u16) {
+        if let Some((line, _)) = self.matches.get(self.current) {
+            let row = self.row_of(*line, width).saturating_sub(2);
+            self.scroll = (row.min(u16::MAX as usize) as u16).min(self.max_scroll(width, height));
+        }
+    }
+
+    /// Run `query` and move to the first match at or below the top row.
+    fn search(&mut self, query: String, width: u16, height: u16) {
+        self.matches = markdown::find_matches(&self.lines, &query);
+        self.query = query;
+        let top = self.scroll as usize;
+        self.current = self
+            .matches
+            .iter()
+            .position(|(line, _)| self.row_of(*line, width) >= top)
+            .unwrap_or(0);
+        self.show_current(width, height);
+    }
+
+    fn step(&mut self, forward: bool, width: u16, height: u16) {
+        let count = self.matches.len();
+        if count == 0 {
+            return;
+        }
+        self.current = if forward {
+            (self.current + 1) % count
+        } else {
+            (self.current + count - 1) % count
+        };
+        self.show_current(width, height);
+    }
+}
+
+impl App {
+    pub(super) fn open_reader(&mut self) {
+        let Some(asset) = self.selected_asset() else {
+            self.message = Some("No asset selected".into());
+            return;
+        };
+        let name = asset.name.clone();
+        let preview = AssetPreview::load(&self.upstream, asset.path.clone());
+        let lines = markdown::preview_lines(&preview, &self.theme);
+        self.overlay = Some(Overlay::Reader(ContentReader {
+            name,
+            preview,
+            lines,
+            scroll: 0,
+            input: None,
+            query: String::new(),
+            matches: Vec::new(),
+            current: 0,
+        }));
+    }
+
+    pub(super) fn handle_reader_key(&mut self, key: KeyEvent) {
+        let Some(Overlay::Reader(mut reader)) = self.overlay.take() else {
+            return;
+        };
+        let (width, height) = (self.body_area.width, self.body_area.height);
+        if let Some(input) = &mut reader.input {
+            match key.code {
+                KeyCode::Esc => reader.input = None,
+                KeyCode::Enter => {
+                    let query = reader.input.take().unwrap_or_default();
+                    reader.search(query, width, height);
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(ch) => input.push(ch),
+                _ => {}
+            }
+            self.overlay = Some(Overlay::Reader(reader));
+            return;
+        }
+        let page = height.saturating_sub(3).max(1);
+        let max = reader.max_scroll(width, height);
+        reader.scroll = match key.code {
+            // Esc clears the search before it closes the reader.
+            KeyCode::Esc if !reader.query.is_empty() => {
+                reader.search(String::new(), width, height);
+                reader.scroll
+            }
+            KeyCode::Esc | KeyCode::Char('q') => return,
+            KeyCode::Char('/') => {
+                reader.input = Some(String::new());
+                reader.scroll
+            }
+            KeyCode::Char('n') => {
+                reader.step(true, width, height);
+                reader.scroll
+            }
+            KeyCode::Char('N') => {
+                reader.step(false, width, height);
+                reader.scroll
+            }
+            KeyCode::Down | KeyCode::Char('j') => reader.scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => reader.scroll.saturating_sub(1),
+            KeyCode::PageDown | KeyCode::Char(' ') => reader.scroll.saturating_add(page),
+            KeyCode::PageUp => reader.scroll.saturating_sub(page),
+            KeyCode::Home | KeyCode::Char('g') => 0,
+            KeyCode::End | KeyCode::Char('G') => max,
+            _ => reader.scroll,
+        }
+        .min(max);
+        self.overlay = Some(Overlay::Reader(reader));
+    }
+}
@@ -0,0 +1,69 @@
+use anyhow::Result;
+
+use crate::io::{
+    aggregate,
+    report::{self, DEFAULT_REPORT_FILE},
+};
+
+use super::{warnings::WarningSource, App};
+
+impl App {
+    /// Write the markdown report of the in-memory selection, including
+    /// unsaved toggles.
+    pub(super) fn export_report(&mut self) -> Result<()> {
+        let rendered = report::render(&self.domain, &self.upstream, &self.sources);
+        let output = self.paths.root.join(DEFAULT_REPORT_FILE);
+        report::write(&output, &rendered)?;
+        self.message = Some(format!("Wrote the asset report to {}", output.display()));
+        self.error = None;
+        Ok(())
+    }
+
+    /// Rewrite the asset list in the `[report] inject` file after a save.
+    /// Failures become warnings.
+    pub(super) fn sync_report_section(&mut self) {
+        let Some(path) = &self.report_section else {
+            return;
+        };
+        let section = report::section(&self.domain, &self.upstream, &self.sources);
+        match report::inject(path, &section) {
+            Ok(true) => {
+                let note = format!("updated the asset list in {}", path.display());
+                self.message = Some(match self.message.take() {
+                    Some(message) => format!("{message}; {note}"),
+                    None => note,
+                });
+            }
+            Ok(false) => {}
+            Err(err) => self.warn(
+                WarningSource::Local,
+                format!("Asset list not updated: {err:#}"),
+            ),
+        }
+    }
+
+    /// Rewrite the aggregated instructions in `.github/copilot-instructions.md`
+    /// after a save, when `[instructions] aggregate` is on. Failures become
+    /// warnings.
+    pub(super) fn sync_aggregated_instructions(&mut self) {
+        if !self.aggregate_instructions {
+            return;
+        }
+        let written = aggregate::render(&self.domain, &self.upstream)
+            .and_then(|section| aggregate::write(&self.paths, &section));
+        match written {
+            Ok(true) => {
+                let note = "updated .github/copilot-instructions.md".to_string();
+                self.message = Some(match self.message.take() {
+                    Some(message) => format!("{message}; {note}"),
+                    None => note,
+                });
+            }
+            Ok(false) => {}
+            Err(err) => self.warn(
+                WarningSource::Local,
+                format!("Aggregated instructions not updated: {err:#}"),
+            ),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::io::{
+    paths::{RepoPaths, Target},
+    repos::{self, RegisteredRepo, RepoRegistry},
+};
+
+use super::{overlay::Overlay, App};
+
+/// Registered repositories, to switch the session to or forget.
+pub(crate) struct RepoSwitcher {
+    repos: Vec<RegisteredRepo>,
+    selected: usize,
+}
+
+impl RepoSwitcher {
+    pub(crate) fn repos(&self) -> &[RegisteredRepo] {
+        &self.repos
+    }
+
+    pub(crate) fn selected(&self) -> usize {
+        self.selected
+    }
+}
+
+impl App {
+    /// Whether `path` is the repository this session manages.
+    pub(crate) fn is_current_repo(&self, path: &Path) -> bool {
+        self.paths.target == Target::Repo
+            && std::fs::canonicalize(&self.paths.root).is_ok_and(|root| root == path)
+    }
+
+    pub(super) fn open_repos(&mut self) -> Result<()> {
+        let file = repos::registry_file().context("no user config directory on this platform")?;
+        let registry = RepoRegistry::load(&file)?;
+        if registry.repos.is_empty() {
+            self.message =
+                Some("No registered repositories; each one opened with --repo is added".into());
+            return Ok(());
+        }
+        self.overlay = Some(Overlay::Repos(RepoSwitcher {
+            repos: registry.repos,
+            selected: 0,
+        }));
+        self.message = Some("Repositories: Enter switches, d forgets".into());
+        Ok(())
+    }
+
+    pub(super) fn handle_repos_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(Overlay::Repos(mut view)) = self.overlay.take() else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.message = None;
+                return Ok(());
+            }
+            KeyCode::Up if view.selected > 0 => view.selected -= 1,
+            KeyCode::Down if view.selected + 1 < view.repos.len() => view.selected += 1,
+            KeyCode::Enter => {
+                let path = view.repos[view.selected].path.clone();
+                if self.switch_repo(&path) {
+                    return Ok(());
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                let repo = view.repos.remove(view.selected);
+                let file = repos::registry_file().context("no user config directory")?;
+                let mut registry = RepoRegistry::load(&file)?;
+                registry.remove(&repo.path);
+                registry.save(&file)?;
+                self.message = Some(format!("Forgot {}", repo.path.display()));
+                if view.repos.is_empty() {
+                    return Ok(());
+                }
+            }
+            _ => {}
+        }
+        view.selected = view.selected.min(view.repos.len() - 1);
+        self.overlay = Some(Overlay::Repos(view));
+        Ok(())
+    }
+
+    fn switch_repo(&mut self, path: &Path) -> bool {
+        if self.is_current_repo(path) {
+            self.message = Some(format!("Already on {}", path.display()));
+            return false;
+        }
+        if !path.is_dir() {
+            self.message = Some(format!("{} no longer exists; d forgets it", path.display()));
+            return false;
+        }
+        self.reopen(RepoPaths::new(path))
+    }
+
+    /// End the session so `run` reopens it on `paths`. Unsaved changes are
+    /// saved first with auto-save and block the switch otherwise.
+    pub(super) fn reopen(&mut self, paths: RepoPaths) -> bool {
+        if self.dirty && self.auto_save {
+            if let Err(err) = self.write_enablement() {
+                self.error = Some(format!("Save failed: {err}"));
+                return false;
+            }
+        } else if self.dirty {
+            self.message = Some("Save first; switching discards unsaved changes".into());
+            return false;
+        }
+        self.switch_to = Some(paths);
+        self.should_quit = true;
+        true
+    }
+}
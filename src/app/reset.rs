@@ -0,0 +1,160 @@
+use crate::{
+    domain::{
+        model::{AssetKind, Overrides},
+        state::StateFilter,
+    },
+    io::{
+        history::HistoryEvent,
+        plan::{PlannedChange, SyncPlan},
+        worker::FileOp,
+    },
+    ui::draw,
+};
+
+use super::{apply_all::APPLIED_KINDS, App, PendingPrompt};
+
+/// Which assets a reset deletes the local files and enablement entries of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ResetScope {
+    /// Every asset, along with all overrides.
+    All,
+    /// Every asset of one kind.
+    Tab(AssetKind),
+    /// The assets of one kind matching the search query, chosen tags and
+    /// state filter.
+    Filtered(AssetKind),
+}
+
+impl ResetScope {
+    fn describe(self) -> String {
+        match self {
+            ResetScope::All => "every asset".into(),
+            ResetScope::Tab(kind) => format!("the {} tab", draw::tab_title(kind)),
+            ResetScope::Filtered(kind) => {
+                format!("the filtered {} rows", draw::tab_title(kind))
+            }
+        }
+    }
+}
+
+impl App {
+    /// Reset the current tab, or only its rows matching the search query,
+    /// chosen tags and state filter when any is set.
+    pub(super) fn request_scoped_reset(&mut self) {
+        let filtered = !self.search.query.is_empty()
+            || !self.facets.chosen(self.tab).is_empty()
+            || self.state_filter != StateFilter::All;
+        self.request_reset(if filtered {
+            ResetScope::Filtered(self.tab)
+        } else {
+            ResetScope::Tab(self.tab)
+        });
+    }
+
+    /// Always ask before a reset, listing the local files it would delete.
+    pub(super) fn request_reset(&mut self, scope: ResetScope) {
+        let targets = self.reset_targets(scope);
+        if targets.is_empty() {
+            self.message = Some(format!("Nothing to reset in {}", scope.describe()));
+            return;
+        }
+        let mut plan = SyncPlan::default();
+        for (kind, path) in &targets {
+            plan.add_remove(&self.paths, *kind, path);
+        }
+        let removed = plan.count(PlannedChange::Remove);
+        let question = match scope {
+            ResetScope::All => format!(
+                "Reset? {removed} local asset file{} will be removed and all enablement entries cleared.",
+                if removed == 1 { "" } else { "s" }
+            ),
+            _ => format!(
+                "Reset {}? {removed} local asset file{} will be removed and {} enablement entr{} cleared.",
+                scope.describe(),
+                if removed == 1 { "" } else { "s" },
+                targets.len(),
+                if targets.len() == 1 { "y" } else { "ies" }
+            ),
+        };
+        let uncommitted = self.uncommitted_in(&plan);
+        self.ask_plan(
+            PendingPrompt::Reset(scope),
+            question,
+            "reset",
+            plan,
+            uncommitted,
+        );
+    }
+
+    /// Kind and path of every asset `scope` covers.
+    fn reset_targets(&self, scope: ResetScope) -> Vec<(AssetKind, String)> {
+        let assets = match scope {
+            ResetScope::All => APPLIED_KINDS
+                .into_iter()
+                .chain([AssetKind::Collection])
+                .flat_map(|kind| self.domain.assets(kind))
+                .collect(),
+            ResetScope::Tab(kind) => self.domain.assets(kind).iter().collect(),
+            ResetScope::Filtered(kind) => self.filtered_assets(kind),
+        };
+        assets
+            .into_iter()
+            .map(|asset| (asset.kind, asset.path.clone()))
+            .collect()
+    }
+
+    pub(super) fn reset_assets(&mut self, scope: ResetScope) {
+        let targets = self.reset_targets(scope);
+        let before = self.domain.clone();
+        let enablement = &mut self.domain.enablement;
+        if scope == ResetScope::All {
+            for kind in APPLIED_KINDS.into_iter().chain([AssetKind::Collection]) {
+                enablement.map_for_mut(kind).clear();
+            }
+            enablement.overrides = Overrides::default();
+            enablement.updated_at = None;
+        } else {
+            for (kind, path) in &targets {
+                enablement.remove(*kind, path);
+                enablement.overrides.notes.remove(path);
+                enablement.overrides.patches.remove(path);
+            }
+        }
+        self.domain.recompute();
+
+        // The reset assets lose their files whatever their new state; members
+        // of a reset collection follow their new effective state.
+        let mut ops: Vec<_> = targets
+            .iter()
+            .filter(|(kind, _)| *kind != AssetKind::Collection)
+            .map(|(kind, path)| FileOp::Remove(*kind, path.clone()))
+            .collect();
+        ops.extend(
+            self.domain
+                .effective_changes(&before)
+                .into_iter()
+                .filter(|(kind, path, _)| !targets.iter().any(|(k, p)| k == kind && p == path))
+                .map(|(kind, path, enabled)| FileOp::for_state(kind, path, enabled)),
+        );
+        self.staged.retain(|op| {
+            let (kind, path) = op.asset();
+            !ops.iter().any(|reset| reset.asset() == (kind, path))
+        });
+        self.sync_files(ops, false);
+        self.compute_local_statuses();
+
+        let summary = match scope {
+            ResetScope::All => "Cleared local assets and enablement state".to_string(),
+            _ => format!(
+                "Reset {}: cleared {} asset{}",
+                scope.describe(),
+                targets.len(),
+                if targets.len() == 1 { "" } else { "s" }
+            ),
+        };
+        self.dirty = true;
+        self.message = Some(summary.clone());
+        self.record(HistoryEvent::Reset, summary);
+        self.error = None;
+    }
+}
@@ -0,0 +1,132 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{
+    domain::model::{EnablementChange, EnablementFile},
+    io::changes::{FileChanges, LocalFiles},
+};
+
+use super::{overlay::Overlay, App};
+
+/// State as of the last load or save, which the next save is compared with.
+#[derive(Debug, Default)]
+pub(super) struct SavedBaseline {
+    enablement: EnablementFile,
+    files: Option<LocalFiles>,
+    warnings: usize,
+}
+
+impl SavedBaseline {
+    /// Keep the baseline's warning count in step when the warning at `index`
+    /// is dismissed.
+    pub(super) fn forget_warning(&mut self, index: usize) {
+        if index < self.warnings {
+            self.warnings -= 1;
+        }
+    }
+
+    pub(super) fn forget_warnings(&mut self) {
+        self.warnings = 0;
+    }
+}
+
+/// What a save persisted: enablement entries that changed, files copied or
+/// deleted since the previous save, and warnings raised meanwhile.
+pub(crate) struct SaveReport {
+    entries: Vec<EnablementChange>,
+    files: FileChanges,
+    warnings: Vec<String>,
+    scroll: u16,
+}
+
+impl SaveReport {
+    pub(crate) fn entries(&self) -> &[EnablementChange] {
+        &self.entries
+    }
+
+    pub(crate) fn files(&self) -> &FileChanges {
+        &self.files
+    }
+
+    pub(crate) fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    pub(crate) fn scroll(&self) -> u16 {
+        self.scroll
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.files.is_empty() && self.warnings.is_empty()
+    }
+}
+
+impl App {
+    /// Remember the current state as what is on disk.
+    pub(super) fn mark_saved(&mut self) {
+        self.saved = SavedBaseline {
+            enablement: self.domain.enablement.clone(),
+            // Without a baseline the report just leaves files out.
+            files: LocalFiles::capture(&self.paths).ok(),
+            warnings: self.warnings.len(),
+        };
+    }
+
+    /// Compare the state about to be saved with the previous baseline.
+    pub(super) fn save_report(&self) -> SaveReport {
+        let files = match (&self.saved.files, LocalFiles::capture(&self.paths)) {
+            (Some(before), Ok(now)) => now.changes_since(before),
+            _ => FileChanges::default(),
+        };
+        SaveReport {
+            entries: self.domain.enablement.changes_from(&self.saved.enablement),
+            files,
+            warnings: self
+                .warnings
+                .get(self.saved.warnings..)
+                .unwrap_or_default()
+                .iter()
+                .map(|warning| warning.text.clone())
+                .collect(),
+            scroll: 0,
+        }
+    }
+
+    /// Summarize `report` in the footer and open it when there is detail to
+    /// show.
+    pub(super) fn show_save_report(&mut self, report: SaveReport) {
+        self.message = Some(if report.is_empty() {
+            "Enablement saved; nothing changed since the last save".to_string()
+        } else {
+            format!(
+                "Enablement saved: {} entr{} changed; files {}",
+                report.entries.len(),
+                if report.entries.len() == 1 {
+                    "y"
+                } else {
+                    "ies"
+                },
+                report.files
+            )
+        });
+        if !report.is_empty() {
+            self.overlay = Some(Overlay::SaveReport(report));
+        }
+    }
+
+    pub(super) fn handle_save_report_key(&mut self, key: KeyEvent) {
+        let Some(Overlay::SaveReport(report)) = &mut self.overlay else {
+            return;
+        };
+        report.scroll = match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => {
+                self.overlay = None;
+                return;
+            }
+            KeyCode::Down | KeyCode::Char('j') => report.scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => report.scroll.saturating_sub(1),
+            KeyCode::PageDown => report.scroll.saturating_add(10),
+            KeyCode::PageUp => report.scroll.saturating_sub(10),
+            _ => report.scroll,
+        };
+    }
+}
@@ -0,0 +1,48 @@
+use crate::io::workspace::SessionState;
+
+use super::App;
+
+impl App {
+    /// Remember the tab, each tab's selected asset and the search query in
+    /// the workspace file, for the next launch to resume from.
+    pub(super) fn save_session(&mut self) {
+        // Selections still waiting for a catalog that never loaded are kept.
+        let mut selected = std::mem::take(&mut self.resume_selections);
+        selected.extend(self.selections.iter().filter_map(|(kind, index)| {
+            let asset = self.filtered_assets(*kind).get(*index).copied()?;
+            Some((*kind, asset.path.clone()))
+        }));
+        self.workspace.session = SessionState {
+            tab: Some(self.tab),
+            selected,
+            query: self.search.query.clone(),
+        };
+        self.persist_workspace("the session");
+    }
+
+    /// Restore the tab and search query of the last session. The selections
+    /// wait for the catalog; see [`App::restore_selections`].
+    pub(super) fn restore_session(&mut self) {
+        let session = &self.workspace.session;
+        if let Some(tab) = session.tab {
+            self.tab = tab;
+        }
+        self.search.query = session.query.clone();
+        self.resume_selections = session.selected.clone();
+    }
+
+    /// Select the assets selected when the last session ended, once the
+    /// first catalog has loaded. Assets that are gone or filtered out keep
+    /// the first row.
+    pub(super) fn restore_selections(&mut self) {
+        for (kind, path) in std::mem::take(&mut self.resume_selections) {
+            if let Some(index) = self
+                .filtered_assets(kind)
+                .iter()
+                .position(|asset| asset.path == path)
+            {
+                self.selections.insert(kind, index);
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::io::worker::FileOp;
+
+use super::{overlay::Overlay, App};
+
+/// The file changes queued by toggles while applies are deferred, reviewed
+/// before the save that runs them.
+pub(crate) struct StagedView {
+    selected: usize,
+}
+
+impl StagedView {
+    pub(crate) fn selected(&self) -> usize {
+        self.selected
+    }
+}
+
+impl App {
+    pub(crate) fn staged(&self) -> &[FileOp] {
+        &self.staged
+    }
+
+    pub fn defer_apply(&self) -> bool {
+        self.defer_apply
+    }
+
+    /// Bring local files in line with an enablement change: right away, or
+    /// queued for the next save when applies are deferred. A later change to
+    /// the same asset replaces the queued one.
+    pub(super) fn follow_enablement(&mut self, ops: Vec<FileOp>) {
+        if !self.defer_apply {
+            self.sync_files(ops, false);
+            return;
+        }
+        for op in ops {
+            let (kind, path) = op.asset();
+            self.staged.retain(|staged| staged.asset() != (kind, path));
+            self.staged.push(op);
+        }
+    }
+
+    /// Hand the queued file changes to the worker; part of every save.
+    pub(super) fn run_staged(&mut self) {
+        let ops = std::mem::take(&mut self.staged);
+        self.sync_files(ops, true);
+        self.compute_local_statuses();
+    }
+
+    pub(super) fn open_staged(&mut self) {
+        if self.staged.is_empty() {
+            self.message = Some(if self.defer_apply {
+                "No file changes staged".into()
+            } else {
+                "Applies are not deferred; start with --defer-apply to stage them".into()
+            });
+            return;
+        }
+        self.overlay = Some(Overlay::Staged(StagedView { selected: 0 }));
+    }
+
+    pub(super) fn handle_staged_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(Overlay::Staged(view)) = &mut self.overlay else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.overlay = None,
+            KeyCode::Up if view.selected > 0 => view.selected -= 1,
+            KeyCode::Down if view.selected + 1 < self.staged.len() => view.selected += 1,
+            KeyCode::Char('d') | KeyCode::Delete => {
+                let op = self.staged.remove(view.selected);
+                view.selected = view.selected.min(self.staged.len().saturating_sub(1));
+                self.message = Some(format!(
+                    "Unstaged {}; its file stays as it is",
+                    op.asset().1
+                ));
+                if self.staged.is_empty() {
+                    self.overlay = None;
+                }
+            }
+            KeyCode::Char('y') | KeyCode::Enter => {
+                self.overlay = None;
+                self.save()?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{
+    domain::model::LOCAL_SOURCE,
+    io::{
+        catalog, local,
+        prune::{self, PruneCandidate},
+    },
+};
+
+use super::{overlay::Overlay, App};
+
+/// Asset files under `.github/` that no catalog asset maps to, to ignore,
+/// adopt into the local catalog or delete one by one.
+pub(crate) struct UnmanagedView {
+    files: Vec<PruneCandidate>,
+    selected: usize,
+}
+
+impl UnmanagedView {
+    pub(crate) fn files(&self) -> &[PruneCandidate] {
+        &self.files
+    }
+
+    pub(crate) fn selected(&self) -> usize {
+        self.selected
+    }
+}
+
+impl App {
+    pub(super) fn open_unmanaged(&mut self) -> Result<()> {
+        let files =
+            prune::find_unmanaged(&self.paths, &self.domain, &self.workspace.ignored_files)?;
+        if files.is_empty() {
+            self.message = Some("No unmanaged files under .github".into());
+            return Ok(());
+        }
+        self.overlay = Some(Overlay::Unmanaged(UnmanagedView { files, selected: 0 }));
+        self.message =
+            Some("Unmanaged: i ignores, a adopts as a local asset, d deletes, Esc closes".into());
+        Ok(())
+    }
+
+    pub(super) fn handle_unmanaged_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(Overlay::Unmanaged(mut view)) = self.overlay.take() else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.message = None;
+                return Ok(());
+            }
+            KeyCode::Up if view.selected > 0 => view.selected -= 1,
+            KeyCode::Down if view.selected + 1 < view.files.len() => view.selected += 1,
+            KeyCode::Char('i') => {
+                let file = view.files.remove(view.selected);
+                let shown = file.display_path(&self.paths);
+                self.workspace.ignored_files.insert(shown.clone());
+                self.persist_workspace("ignored files");
+                self.message = Some(format!("Ignoring {shown}"));
+            }
+            KeyCode::Char('a') => {
+                let file = view.files[view.selected].clone();
+                match self.adopt_unmanaged(&file) {
+                    Ok(name) => {
+                        view.files.remove(view.selected);
+                        self.message = Some(format!("Adopted {name} as an enabled local asset"));
+                    }
+                    Err(err) => self.message = Some(format!("Cannot adopt: {err:#}")),
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                let file = view.files.remove(view.selected);
+                prune::prune(&self.paths, std::slice::from_ref(&file))?;
+                self.message = Some(format!(
+                    "Deleted {} (backed up)",
+                    file.display_path(&self.paths)
+                ));
+            }
+            _ => {}
+        }
+        if view.files.is_empty() {
+            return Ok(());
+        }
+        view.selected = view.selected.min(view.files.len() - 1);
+        self.overlay = Some(Overlay::Unmanaged(view));
+        Ok(())
+    }
+
+    /// Copy `file` into the local catalog and enable it, so the file under
+    /// `.github/` becomes its applied copy. Returns the asset name.
+    fn adopt_unmanaged(&mut self, file: &PruneCandidate) -> Result<String> {
+        let relative = local::adopt_file(&self.paths, file.kind, &file.local_path)?;
+        let path =
+            catalog::add_local_asset(&mut self.domain.catalog, &self.paths, file.kind, &relative)?;
+        self.domain.catalog = std::mem::take(&mut self.domain.catalog).finalize();
+        self.domain.enablement.set(file.kind, &path, true);
+        self.domain.recompute();
+        self.upstream
+            .insert(LOCAL_SOURCE, self.paths.local_dir.clone());
+        self.dirty = true;
+        self.compute_local_statuses();
+        Ok(self
+            .domain
+            .asset_by_path(&path)
+            .map_or(relative, |view| view.name.clone()))
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{domain::model::AssetKind, io::vscode};
+
+use super::{warnings::WarningSource, App};
+
+impl App {
+    /// Point VS Code at `.github/chatmodes` while any chat mode is enabled,
+    /// when `[vscode] settings` is on. Failures become warnings.
+    pub(super) fn sync_vscode_settings(&mut self) {
+        if !self.vscode_settings {
+            return;
+        }
+        let enabled = self
+            .domain
+            .assets(AssetKind::ChatMode)
+            .iter()
+            .any(|asset| asset.effective);
+        match vscode::sync_chat_modes(&self.paths, enabled) {
+            Ok(true) => {
+                let note = format!(
+                    "updated {} in .vscode/settings.json",
+                    vscode::MODE_LOCATIONS
+                );
+                self.message = Some(match self.message.take() {
+                    Some(message) => format!("{message}; {note}"),
+                    None => note,
+                });
+            }
+            Ok(false) => {}
+            Err(err) => self.warn(
+                WarningSource::Local,
+                format!("VS Code settings not updated: {err:#}"),
+            ),
+        }
+    }
+}
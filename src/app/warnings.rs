@@ -0,0 +1,98 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::{overlay::Overlay, App};
+
+/// Where a warning came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WarningSource {
+    /// Fetching snapshots: cache fallbacks and skipped sources.
+    Upstream,
+    /// Parsing the catalog files of a snapshot or the local catalog.
+    Catalog,
+    /// Reading or importing enablement entries.
+    Enablement,
+    /// Local files and integrations: merges, git, VS Code, the watcher.
+    Local,
+}
+
+impl WarningSource {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            WarningSource::Upstream => "upstream",
+            WarningSource::Catalog => "catalog",
+            WarningSource::Enablement => "enablement",
+            WarningSource::Local => "local",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Warning {
+    pub(crate) source: WarningSource,
+    pub(crate) text: String,
+}
+
+/// Every warning on its own line, dismissable one by one.
+pub(crate) struct WarningsView {
+    selected: usize,
+}
+
+impl WarningsView {
+    pub(crate) fn selected(&self) -> usize {
+        self.selected
+    }
+}
+
+impl App {
+    pub(crate) fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    pub(super) fn warn(&mut self, source: WarningSource, text: impl Into<String>) {
+        self.warnings.push(Warning {
+            source,
+            text: text.into(),
+        });
+    }
+
+    pub(super) fn open_warnings(&mut self) {
+        if self.warnings.is_empty() {
+            self.message = Some("No warnings".into());
+            return;
+        }
+        self.overlay = Some(Overlay::Warnings(WarningsView {
+            selected: self.warnings.len() - 1,
+        }));
+    }
+
+    pub(super) fn handle_warnings_key(&mut self, key: KeyEvent) {
+        let Some(Overlay::Warnings(view)) = &mut self.overlay else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.overlay = None,
+            KeyCode::Up if view.selected > 0 => view.selected -= 1,
+            KeyCode::Down if view.selected + 1 < self.warnings.len() => view.selected += 1,
+            KeyCode::Home => view.selected = 0,
+            KeyCode::End => view.selected = self.warnings.len() - 1,
+            KeyCode::Char('d') | KeyCode::Delete => {
+                let index = view.selected;
+                self.warnings.remove(index);
+                self.saved.forget_warning(index);
+                if self.warnings.is_empty() {
+                    self.overlay = None;
+                    self.message = Some("All warnings dismissed".into());
+                } else {
+                    view.selected = index.min(self.warnings.len() - 1);
+                }
+            }
+            KeyCode::Char('D') => {
+                self.saved.forget_warnings();
+                self.warnings.clear();
+                self.overlay = None;
+                self.message = Some("All warnings dismissed".into());
+            }
+            _ => {}
+        }
+    }
+}
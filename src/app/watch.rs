@@ -0,0 +1,82 @@
+use std::{
+    fs,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::io::{
+    enablement,
+    watcher::{ExternalChange, RepoWatcher},
+};
+
+use super::{warnings::WarningSource, App, PendingPrompt};
+
+/// File events this soon after a key press are assumed to come from the
+/// TUI's own writes (apply, save, restore, …) rather than another program.
+const OWN_WRITE_WINDOW: Duration = Duration::from_millis(750);
+
+impl App {
+    /// Start watching once the catalog load has created the project folders.
+    pub(super) fn start_watching(&mut self) {
+        if self.watcher.is_some() {
+            return;
+        }
+        match RepoWatcher::spawn(&self.paths) {
+            Ok(watcher) => self.watcher = Some(watcher),
+            Err(err) => self.warn(
+                WarningSource::Local,
+                format!("External changes will not be detected: {err:#}"),
+            ),
+        }
+    }
+
+    /// Remember the enablement file's modification time so later events can
+    /// tell our own saves from external edits.
+    pub(super) fn stamp_enablement(&mut self) {
+        self.enablement_stamp = enablement_modified(self);
+    }
+
+    pub(super) fn poll_watcher(&mut self) {
+        // Leave events queued while the user is answering something else.
+        if self.prompt.is_some() || self.loading {
+            return;
+        }
+        let Some(watcher) = &self.watcher else {
+            return;
+        };
+        let changes = watcher.poll();
+        if changes.is_empty() {
+            return;
+        }
+        self.redraw = true;
+        if changes.contains(&ExternalChange::Enablement) {
+            let modified = enablement_modified(self);
+            if modified != self.enablement_stamp {
+                self.enablement_stamp = modified;
+                self.prompt = Some(PendingPrompt::ExternalReload);
+                self.message = Some(if self.dirty {
+                    "Enablement file changed on disk; reloading discards unsaved changes".into()
+                } else {
+                    "Enablement file changed on disk".into()
+                });
+                return;
+            }
+        }
+        if changes.contains(&ExternalChange::LocalFiles)
+            && self.last_input.elapsed() > OWN_WRITE_WINDOW
+        {
+            self.message = Some("Files under .github changed; local statuses refreshed".into());
+        }
+        if changes.contains(&ExternalChange::LocalFiles) {
+            self.compute_local_statuses();
+        }
+    }
+
+    pub(super) fn note_input(&mut self) {
+        self.last_input = Instant::now();
+    }
+}
+
+fn enablement_modified(app: &App) -> Option<SystemTime> {
+    let (path, _) = enablement::enablement_location(&app.paths);
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
@@ -0,0 +1,170 @@
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    domain::model::{AssetKind, LOCAL_SOURCE},
+    io::{
+        catalog,
+        local::{self, NewAsset},
+    },
+};
+
+use super::{overlay::Overlay, App};
+
+/// One text input of the new-asset form.
+pub(crate) struct WizardField {
+    pub(crate) label: &'static str,
+    pub(crate) hint: &'static str,
+    pub(crate) value: String,
+}
+
+/// Form for scaffolding a prompt, instruction, chat mode or agent into the local
+/// catalog.
+pub(crate) struct NewAssetWizard {
+    kind: AssetKind,
+    fields: Vec<WizardField>,
+    current: usize,
+}
+
+impl NewAssetWizard {
+    fn new(kind: AssetKind) -> Self {
+        let field = |label, hint, value: &str| WizardField {
+            label,
+            hint,
+            value: value.to_string(),
+        };
+        let mut fields = vec![
+            field("Title", "becomes the heading and file name", ""),
+            field("Description", "one line shown in the table", ""),
+        ];
+        match kind {
+            AssetKind::Prompt => {
+                fields.push(field("Mode", "agent, ask or edit", "agent"));
+                fields.push(field("Tools", "comma-separated, optional", ""));
+            }
+            AssetKind::Instruction => {
+                fields.push(field("Apply to", "glob of matching files", "**"));
+            }
+            AssetKind::ChatMode | AssetKind::Agent => {
+                fields.push(field("Tools", "comma-separated, optional", ""));
+            }
+            AssetKind::McpServer | AssetKind::Collection => {}
+        }
+        Self {
+            kind,
+            fields,
+            current: 0,
+        }
+    }
+
+    pub(crate) fn kind(&self) -> AssetKind {
+        self.kind
+    }
+
+    pub(crate) fn fields(&self) -> &[WizardField] {
+        &self.fields
+    }
+
+    pub(crate) fn current(&self) -> usize {
+        self.current
+    }
+
+    fn value(&self, label: &str) -> String {
+        self.fields
+            .iter()
+            .find(|field| field.label == label)
+            .map(|field| field.value.trim().to_string())
+            .unwrap_or_default()
+    }
+
+    fn asset(&self) -> NewAsset {
+        NewAsset {
+            kind: self.kind,
+            title: self.value("Title"),
+            description: self.value("Description"),
+            mode: self.value("Mode"),
+            apply_to: self.value("Apply to"),
+            tools: self
+                .value("Tools")
+                .split(',')
+                .map(str::trim)
+                .filter(|tool| !tool.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+impl App {
+    pub(super) fn open_wizard(&mut self) {
+        if matches!(self.tab, AssetKind::McpServer | AssetKind::Collection) {
+            self.message =
+                Some("Switch to a prompt, instruction, chat mode or agent tab first".into());
+            return;
+        }
+        self.overlay = Some(Overlay::Wizard(NewAssetWizard::new(self.tab)));
+        self.message = Some("New asset: Enter moves to the next field, Esc cancels".into());
+    }
+
+    pub(super) fn handle_wizard_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(Overlay::Wizard(wizard)) = &mut self.overlay else {
+            return Ok(());
+        };
+        let last = wizard.fields.len() - 1;
+        let value = &mut wizard.fields[wizard.current].value;
+        match key.code {
+            KeyCode::Esc => {
+                self.overlay = None;
+                self.message = Some("Cancelled".into());
+            }
+            KeyCode::Backspace => {
+                value.pop();
+            }
+            KeyCode::Char(ch) if !key.modifiers.contains(KeyModifiers::CONTROL) => value.push(ch),
+            KeyCode::Up | KeyCode::BackTab => wizard.current = wizard.current.saturating_sub(1),
+            KeyCode::Down | KeyCode::Tab | KeyCode::Enter if wizard.current < last => {
+                wizard.current += 1;
+            }
+            KeyCode::Enter => return self.create_asset(),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Write the file, add it to the catalog and select it.
+    fn create_asset(&mut self) -> Result<()> {
+        let Some(Overlay::Wizard(wizard)) = &self.overlay else {
+            return Ok(());
+        };
+        let asset = wizard.asset();
+        // Keep the form open so a bad title can be fixed.
+        let relative = match local::scaffold(&self.paths, &asset) {
+            Ok(relative) => relative,
+            Err(err) => {
+                self.message = Some(format!("Cannot create asset: {err:#}"));
+                return Ok(());
+            }
+        };
+        self.overlay = None;
+        let path =
+            catalog::add_local_asset(&mut self.domain.catalog, &self.paths, asset.kind, &relative)?;
+        self.domain.catalog = std::mem::take(&mut self.domain.catalog).finalize();
+        self.domain.recompute();
+        self.upstream
+            .insert(LOCAL_SOURCE, self.paths.local_dir.clone());
+        self.search = Default::default();
+        self.compute_local_statuses();
+        if let Some(index) = self
+            .filtered_assets(self.tab)
+            .iter()
+            .position(|view| view.path == path)
+        {
+            self.select_index(index);
+        }
+        self.message = Some(format!(
+            "Created {} in .awesome-copilot-tui/local; toggle it to apply",
+            relative
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{
+    domain::model::{split_source, AssetKind, LOCAL_SOURCE},
+    io::{clipboard, sync},
+};
+
+use super::App;
+
+impl App {
+    /// Copy where the selected asset lives in the repository: its file under
+    /// `.github/`, or the catalog path for collections.
+    pub(super) fn yank_path(&mut self) {
+        let Some(asset) = self.selected_asset() else {
+            return;
+        };
+        let path = if asset.kind == AssetKind::Collection {
+            asset.path.clone()
+        } else {
+            let local = sync::local_path(&self.paths, asset.kind, &asset.path);
+            local
+                .strip_prefix(&self.paths.root)
+                .unwrap_or(&local)
+                .display()
+                .to_string()
+        };
+        self.copy_to_clipboard(&path, "path");
+    }
+
+    /// Copy the GitHub link to the selected asset at the loaded snapshot.
+    pub(super) fn yank_url(&mut self) {
+        let Some(asset) = self.selected_asset() else {
+            return;
+        };
+        let (source_id, relative) = split_source(&asset.path);
+        if source_id == LOCAL_SOURCE {
+            self.message = Some("Local assets have no upstream URL".into());
+            return;
+        }
+        let Some(source) = self.sources.iter().find(|source| source.id == source_id) else {
+            self.message = Some(format!("Source '{source_id}' is no longer configured"));
+            return;
+        };
+        let commit = self
+            .upstream
+            .commits()
+            .get(source_id)
+            .unwrap_or(&source.reference);
+        let url = source.file_url(commit, relative);
+        self.copy_to_clipboard(&url, "upstream URL");
+    }
+
+    /// Copy the upstream file of the selected asset, with its content patch
+    /// applied: what applying it would write.
+    pub(super) fn yank_content(&mut self) {
+        let Some(asset) = self.selected_asset() else {
+            return;
+        };
+        match sync::upstream_content(&self.upstream, &asset.path) {
+            Ok(content) => {
+                let content = String::from_utf8_lossy(&content).into_owned();
+                self.copy_to_clipboard(&content, "content");
+            }
+            Err(err) => self.error = Some(format!("Copy failed: {err:#}")),
+        }
+    }
+
+    fn copy_to_clipboard(&mut self, text: &str, what: &str) {
+        match clipboard::copy(text) {
+            Ok(()) => self.message = Some(format!("Copied the {what} to the clipboard")),
+            Err(err) => self.error = Some(format!("Copy failed: {err:#}")),
+        }
+    }
+}
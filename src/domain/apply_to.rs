@@ -0,0 +1,105 @@
+use globset::GlobBuilder;
+
+use super::{
+    model::AssetKind,
+    state::{AssetView, DomainState},
+};
+
+/// An instruction whose `applyTo` patterns match a file.
+#[derive(Debug, Clone)]
+pub struct ApplyToMatch<'a> {
+    pub instruction: &'a AssetView,
+    /// The patterns that matched, as written in the front matter.
+    pub patterns: Vec<&'a str>,
+}
+
+/// Every instruction, enabled or not, whose `applyTo` globs match `file`, a
+/// path relative to the workspace root. Patterns may be comma separated
+/// like in VS Code, and `*` does not cross directory separators. Invalid
+/// patterns never match.
+pub fn matching_instructions<'a>(state: &'a DomainState, file: &str) -> Vec<ApplyToMatch<'a>> {
+    let file = normalize(file);
+    if file.is_empty() {
+        return Vec::new();
+    }
+    state
+        .assets(AssetKind::Instruction)
+        .iter()
+        .filter_map(|instruction| {
+            let patterns: Vec<&str> = instruction
+                .apply_to
+                .iter()
+                .flat_map(|value| value.split(','))
+                .map(str::trim)
+                .filter(|pattern| matches(pattern, &file))
+                .collect();
+            (!patterns.is_empty()).then_some(ApplyToMatch {
+                instruction,
+                patterns,
+            })
+        })
+        .collect()
+}
+
+fn normalize(file: &str) -> String {
+    let file = file.trim().replace('\\', "/");
+    file.trim_start_matches("./")
+        .trim_start_matches('/')
+        .to_string()
+}
+
+fn matches(pattern: &str, file: &str) -> bool {
+    if pattern.is_empty() {
+        return false;
+    }
+    GlobBuilder::new(pattern.trim_start_matches('/'))
+        .literal_separator(true)
+        .build()
+        .is_ok_and(|glob| glob.compile_matcher().is_match(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::{Catalog, EnablementFile, Instruction};
+
+    fn instruction(slug: &str, apply_to: &str) -> Instruction {
+        Instruction {
+            path: format!("instructions/{slug}.instructions.md"),
+            slug: slug.into(),
+            name: slug.into(),
+            description: String::new(),
+            apply_to: vec![apply_to.into()],
+            tags: Vec::new(),
+            sha256: String::new(),
+            requires: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn lists_instructions_whose_patterns_match() {
+        let catalog = Catalog {
+            instructions: vec![
+                instruction("rust", "**/*.rs"),
+                instruction("web", "**/*.ts, **/*.tsx"),
+                instruction("root", "*.rs"),
+                instruction("all", "**"),
+                instruction("broken", "src/[lib.rs"),
+            ],
+            ..Catalog::default()
+        };
+        let state = DomainState::new(catalog, EnablementFile::default());
+        let names = |file: &str| {
+            matching_instructions(&state, file)
+                .iter()
+                .map(|found| found.instruction.slug.clone().unwrap_or_default())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names("./src/lib.rs"), ["all", "rust"]);
+        assert_eq!(names("main.rs"), ["all", "root", "rust"]);
+        let web = matching_instructions(&state, "app\\ui\\view.tsx");
+        assert_eq!(web[1].patterns, ["**/*.tsx"]);
+        assert!(names("  ").is_empty());
+    }
+}
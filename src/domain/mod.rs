@@ -1,3 +1,6 @@
+pub mod apply_to;
 pub mod model;
+pub mod query;
+pub mod rules;
 pub mod state;
 pub mod toggle;
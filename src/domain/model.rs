@@ -1,17 +1,82 @@
 use chrono::{DateTime, Utc};
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Ord, PartialOrd)]
+/// Source id of the official `github/awesome-copilot` catalog. Assets from this
+/// source keep their plain upstream-relative paths.
+pub const PRIMARY_SOURCE: &str = "upstream";
+
+/// Source id of the repository's own assets under `.awesome-copilot-tui/local/`.
+pub const LOCAL_SOURCE: &str = "local";
+
+/// Separator between a secondary source id and the upstream-relative path,
+/// e.g. `acme:prompts/review.prompt.md`.
+const SOURCE_SEPARATOR: char = ':';
+
+/// Split a catalog path into its source id and the path within that source.
+pub fn split_source(path: &str) -> (&str, &str) {
+    match path.split_once(SOURCE_SEPARATOR) {
+        Some((source, rest)) if !source.contains('/') => (source, rest),
+        _ => (PRIMARY_SOURCE, path),
+    }
+}
+
+/// Build the catalog path for `relative` as provided by `source`.
+pub fn namespaced(source: &str, relative: &str) -> String {
+    if source == PRIMARY_SOURCE {
+        relative.to_string()
+    } else {
+        format!("{source}{SOURCE_SEPARATOR}{relative}")
+    }
+}
+
+fn namespace_all(source: &str, paths: &mut [String]) {
+    for path in paths {
+        *path = namespaced(source, path);
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Ord,
+    PartialOrd,
+    clap::ValueEnum,
+)]
 #[serde(rename_all = "camelCase")]
 pub enum AssetKind {
     Prompt,
     Instruction,
     ChatMode,
+    Agent,
+    McpServer,
     Collection,
 }
 
+impl AssetKind {
+    /// File name suffix of assets of this kind.
+    pub fn file_suffix(self) -> &'static str {
+        match self {
+            AssetKind::Prompt => ".prompt.md",
+            AssetKind::Instruction => ".instructions.md",
+            AssetKind::ChatMode => ".chatmode.md",
+            AssetKind::Agent => ".agent.md",
+            AssetKind::McpServer => ".mcp.json",
+            AssetKind::Collection => ".collection.yml",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Prompt {
     pub path: String,
@@ -21,6 +86,8 @@ pub struct Prompt {
     pub mode: String,
     pub tags: Vec<String>,
     pub sha256: String,
+    /// Catalog paths of assets this one needs enabled alongside it.
+    pub requires: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -32,6 +99,8 @@ pub struct Instruction {
     pub apply_to: Vec<String>,
     pub tags: Vec<String>,
     pub sha256: String,
+    /// Catalog paths of assets this one needs enabled alongside it.
+    pub requires: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -43,6 +112,32 @@ pub struct ChatMode {
     pub tools: Vec<String>,
     pub tags: Vec<String>,
     pub sha256: String,
+    /// Catalog paths of assets this one needs enabled alongside it.
+    pub requires: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Agent {
+    pub path: String,
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub tools: Vec<String>,
+    pub tags: Vec<String>,
+    pub sha256: String,
+    /// Catalog paths of assets this one needs enabled alongside it.
+    pub requires: Vec<String>,
+}
+
+/// An MCP server definition, registered in `.vscode/mcp.json` when enabled.
+#[derive(Debug, Clone)]
+pub struct McpServer {
+    pub path: String,
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub sha256: String,
 }
 
 #[derive(Debug, Clone)]
@@ -68,37 +163,188 @@ pub struct Catalog {
     pub prompts: Vec<Prompt>,
     pub instructions: Vec<Instruction>,
     pub chat_modes: Vec<ChatMode>,
+    pub agents: Vec<Agent>,
+    pub mcp_servers: Vec<McpServer>,
     pub collections: Vec<Collection>,
     pub prompt_index: HashSet<String>,
     pub instruction_index: HashSet<String>,
     pub chat_mode_index: HashSet<String>,
+    pub agent_index: HashSet<String>,
+    pub mcp_server_index: HashSet<String>,
     pub collection_index: HashSet<String>,
     pub collection_lookup: HashMap<String, Collection>,
     pub membership: HashMap<String, Vec<String>>, // asset path -> collection ids
 }
 
+/// Schema version written by this build; older files are migrated on load.
+pub const ENABLEMENT_VERSION: u32 = 2;
+
+/// A migration step applied to the enablement file, kept in its history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationRecord {
+    pub from: u32,
+    pub to: u32,
+    pub description: String,
+    pub applied_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(default)]
+#[serde(default, rename_all = "camelCase")]
 pub struct EnablementFile {
     pub version: u32,
     pub updated_at: Option<DateTime<Utc>>,
     pub prompts: BTreeMap<String, bool>,
     pub instructions: BTreeMap<String, bool>,
     pub chat_modes: BTreeMap<String, bool>,
+    pub agents: BTreeMap<String, bool>,
+    pub mcp_servers: BTreeMap<String, bool>,
     pub collections: BTreeMap<String, bool>,
-    pub overrides: serde_json::Map<String, serde_json::Value>,
+    pub overrides: Overrides,
+    /// Declarative defaults for assets without an explicit entry.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<EnablementRule>,
+    /// How an asset listed by several collections with explicit states
+    /// resolves its inherited state.
+    #[serde(skip_serializing_if = "Inheritance::is_default")]
+    pub inheritance: Inheritance,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub migrations: Vec<MigrationRecord>,
+}
+
+/// Which parent collection decides an asset's inherited state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Resolution {
+    /// The first collection in `priority` order, then by id.
+    #[default]
+    Priority,
+    /// On when any parent collection is on.
+    AnyOn,
+}
+
+impl Resolution {
+    pub fn label(self) -> &'static str {
+        match self {
+            Resolution::Priority => "priority order",
+            Resolution::AnyOn => "any-on wins",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Inheritance {
+    pub resolution: Resolution,
+    /// Collection ids, highest priority first. Collections not listed come
+    /// after, by id.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub priority: Vec<String>,
+}
+
+impl Inheritance {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Enables or disables every asset matching all of its conditions, unless
+/// the asset has an explicit entry or inherits a state from a collection.
+/// When several rules match, the last one wins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnablementRule {
+    /// A tag the asset must have, compared case-insensitively.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// A glob the asset path must match, e.g. `instructions/*rust*`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// The kind of asset the rule is limited to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<AssetKind>,
+    pub enabled: bool,
+}
+
+impl fmt::Display for EnablementRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut conditions = Vec::new();
+        if let Some(tag) = &self.tag {
+            conditions.push(format!("tag {tag}"));
+        }
+        if let Some(path) = &self.path {
+            conditions.push(format!("path {path}"));
+        }
+        if let Some(kind) = self.kind {
+            conditions.push(format!("kind {kind:?}"));
+        }
+        if conditions.is_empty() {
+            conditions.push("every asset".into());
+        }
+        write!(f, "{}", conditions.join(", "))
+    }
+}
+
+/// Per-asset customizations kept in the enablement file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Overrides {
+    /// Free-form notes by asset path.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub notes: BTreeMap<String, String>,
+    /// Changes applied on top of the upstream content by asset path.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub patches: BTreeMap<String, ContentPatch>,
+    /// Keys this version does not know, e.g. moved here by a migration.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A local customization of an asset's front matter, applied whenever the
+/// file is copied from upstream.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ContentPatch {
+    /// Replaces the `applyTo` glob of an instruction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apply_to: Option<String>,
+    /// Front matter keys to set, replacing upstream values.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub front_matter: BTreeMap<String, serde_json::Value>,
+}
+
+impl ContentPatch {
+    /// Every front matter key the patch sets with its value; `applyTo` wins
+    /// over the same key in `front_matter`.
+    pub fn fields(&self) -> Vec<(&str, serde_json::Value)> {
+        let mut fields: Vec<(&str, serde_json::Value)> = self
+            .front_matter
+            .iter()
+            .filter(|(key, _)| self.apply_to.is_none() || key.as_str() != "applyTo")
+            .map(|(key, value)| (key.as_str(), value.clone()))
+            .collect();
+        if let Some(apply_to) = &self.apply_to {
+            fields.insert(0, ("applyTo", apply_to.as_str().into()));
+        }
+        fields
+    }
 }
 
 impl Default for EnablementFile {
     fn default() -> Self {
         Self {
-            version: 1,
+            version: ENABLEMENT_VERSION,
             updated_at: None,
             prompts: BTreeMap::new(),
             instructions: BTreeMap::new(),
             chat_modes: BTreeMap::new(),
+            agents: BTreeMap::new(),
+            mcp_servers: BTreeMap::new(),
             collections: BTreeMap::new(),
-            overrides: serde_json::Map::new(),
+            overrides: Overrides::default(),
+            rules: Vec::new(),
+            inheritance: Inheritance::default(),
+            migrations: Vec::new(),
         }
     }
 }
@@ -109,6 +355,8 @@ impl EnablementFile {
             AssetKind::Prompt => &self.prompts,
             AssetKind::Instruction => &self.instructions,
             AssetKind::ChatMode => &self.chat_modes,
+            AssetKind::Agent => &self.agents,
+            AssetKind::McpServer => &self.mcp_servers,
             AssetKind::Collection => &self.collections,
         }
     }
@@ -118,6 +366,8 @@ impl EnablementFile {
             AssetKind::Prompt => &mut self.prompts,
             AssetKind::Instruction => &mut self.instructions,
             AssetKind::ChatMode => &mut self.chat_modes,
+            AssetKind::Agent => &mut self.agents,
+            AssetKind::McpServer => &mut self.mcp_servers,
             AssetKind::Collection => &mut self.collections,
         }
     }
@@ -129,6 +379,65 @@ impl EnablementFile {
     pub fn set(&mut self, kind: AssetKind, path: &str, value: bool) {
         self.map_for_mut(kind).insert(path.to_string(), value);
     }
+
+    /// The note attached to the asset at `path`, if any.
+    pub fn note(&self, path: &str) -> Option<&str> {
+        self.overrides.notes.get(path).map(String::as_str)
+    }
+
+    /// Attach `note` to `path`, or drop the note when it is blank. Returns
+    /// whether anything changed.
+    pub fn set_note(&mut self, path: &str, note: &str) -> bool {
+        let note = note.trim();
+        if self.note(path).unwrap_or_default() == note {
+            return false;
+        }
+        if note.is_empty() {
+            self.overrides.notes.remove(path);
+        } else {
+            self.overrides
+                .notes
+                .insert(path.to_string(), note.to_string());
+        }
+        true
+    }
+
+    /// Entries added, removed or flipped relative to `before`.
+    pub fn changes_from(&self, before: &EnablementFile) -> Vec<EnablementChange> {
+        let mut changes = Vec::new();
+        for kind in [
+            AssetKind::Prompt,
+            AssetKind::Instruction,
+            AssetKind::ChatMode,
+            AssetKind::Agent,
+            AssetKind::McpServer,
+            AssetKind::Collection,
+        ] {
+            let (old, new) = (before.map_for(kind), self.map_for(kind));
+            let paths: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+            for path in paths {
+                let (before, after) = (old.get(path).copied(), new.get(path).copied());
+                if before != after {
+                    changes.push(EnablementChange {
+                        kind,
+                        path: path.clone(),
+                        before,
+                        after,
+                    });
+                }
+            }
+        }
+        changes
+    }
+}
+
+/// One enablement entry that differs between two versions of the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnablementChange {
+    pub kind: AssetKind,
+    pub path: String,
+    pub before: Option<bool>,
+    pub after: Option<bool>,
 }
 
 impl Catalog {
@@ -136,6 +445,8 @@ impl Catalog {
         self.prompt_index = self.prompts.iter().map(|p| p.path.clone()).collect();
         self.instruction_index = self.instructions.iter().map(|i| i.path.clone()).collect();
         self.chat_mode_index = self.chat_modes.iter().map(|c| c.path.clone()).collect();
+        self.agent_index = self.agents.iter().map(|a| a.path.clone()).collect();
+        self.mcp_server_index = self.mcp_servers.iter().map(|m| m.path.clone()).collect();
         self.collection_index = self.collections.iter().map(|c| c.path.clone()).collect();
         self.collection_lookup = self
             .collections
@@ -164,10 +475,45 @@ impl Catalog {
             AssetKind::Prompt => self.prompt_index.contains(path),
             AssetKind::Instruction => self.instruction_index.contains(path),
             AssetKind::ChatMode => self.chat_mode_index.contains(path),
+            AssetKind::Agent => self.agent_index.contains(path),
+            AssetKind::McpServer => self.mcp_server_index.contains(path),
             AssetKind::Collection => self.collection_index.contains(path),
         }
     }
 
+    /// Upstream checksum of a catalog entry.
+    pub fn sha256(&self, kind: AssetKind, path: &str) -> Option<&str> {
+        let sha = match kind {
+            AssetKind::Prompt => self
+                .prompts
+                .iter()
+                .find(|a| a.path == path)
+                .map(|a| &a.sha256),
+            AssetKind::Instruction => self
+                .instructions
+                .iter()
+                .find(|a| a.path == path)
+                .map(|a| &a.sha256),
+            AssetKind::ChatMode => self
+                .chat_modes
+                .iter()
+                .find(|a| a.path == path)
+                .map(|a| &a.sha256),
+            AssetKind::Agent => self
+                .agents
+                .iter()
+                .find(|a| a.path == path)
+                .map(|a| &a.sha256),
+            AssetKind::McpServer => self
+                .mcp_servers
+                .iter()
+                .find(|a| a.path == path)
+                .map(|a| &a.sha256),
+            AssetKind::Collection => self.collection_by_path(path).map(|c| &c.sha256),
+        };
+        sha.map(String::as_str)
+    }
+
     pub fn collection_by_id(&self, id: &str) -> Option<&Collection> {
         self.collections.iter().find(|c| c.id == id)
     }
@@ -176,6 +522,44 @@ impl Catalog {
         self.collection_lookup.get(path)
     }
 
+    /// Merge the catalog of a secondary source into this one, namespacing
+    /// every path and collection id with the source id so entries from
+    /// different sources never collide.
+    pub fn merge(&mut self, other: Catalog, source: &str) {
+        for mut prompt in other.prompts {
+            prompt.path = namespaced(source, &prompt.path);
+            namespace_all(source, &mut prompt.requires);
+            self.prompts.push(prompt);
+        }
+        for mut instruction in other.instructions {
+            instruction.path = namespaced(source, &instruction.path);
+            namespace_all(source, &mut instruction.requires);
+            self.instructions.push(instruction);
+        }
+        for mut mode in other.chat_modes {
+            mode.path = namespaced(source, &mode.path);
+            namespace_all(source, &mut mode.requires);
+            self.chat_modes.push(mode);
+        }
+        for mut agent in other.agents {
+            agent.path = namespaced(source, &agent.path);
+            namespace_all(source, &mut agent.requires);
+            self.agents.push(agent);
+        }
+        for mut server in other.mcp_servers {
+            server.path = namespaced(source, &server.path);
+            self.mcp_servers.push(server);
+        }
+        for mut collection in other.collections {
+            collection.path = namespaced(source, &collection.path);
+            collection.id = namespaced(source, &collection.id);
+            for item in &mut collection.items {
+                item.path = namespaced(source, &item.path);
+            }
+            self.collections.push(collection);
+        }
+    }
+
     pub fn memberships(&self, asset_path: &str) -> &[String] {
         self.membership
             .get(asset_path)
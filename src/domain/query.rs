@@ -0,0 +1,182 @@
+use super::state::AssetView;
+
+/// A parsed table search: groups separated by `OR`, each a list of terms
+/// that must all match. `AND` between terms is optional.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    groups: Vec<Vec<Term>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Term {
+    /// Written with a leading `-`: the asset must not match.
+    negated: bool,
+    field: Field,
+    /// Lowercase.
+    text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    /// Names, paths, slugs, descriptions, tags, `applyTo` globs and
+    /// collections, plus the file text under full-text search.
+    Any,
+    /// `tag:` compares whole tags.
+    Tag,
+    Path,
+    Name,
+}
+
+impl Query {
+    /// Parse `input`. Words are split on whitespace unless quoted, so
+    /// `"state locking"` matches the phrase.
+    pub fn parse(input: &str) -> Self {
+        let mut groups = vec![Vec::new()];
+        for word in words(input) {
+            match word.as_str() {
+                "OR" => groups.push(Vec::new()),
+                "AND" => {}
+                _ => {
+                    if let Some(term) = Term::parse(&word) {
+                        groups.last_mut().expect("never empty").push(term);
+                    }
+                }
+            }
+        }
+        groups.retain(|group| !group.is_empty());
+        Self { groups }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Whether `asset` satisfies the query. `body` answers whether the
+    /// asset's file contains a lowercase text, for full-text search.
+    pub fn matches(&self, asset: &AssetView, body: impl Fn(&str) -> bool) -> bool {
+        self.is_empty()
+            || self.groups.iter().any(|group| {
+                group
+                    .iter()
+                    .all(|term| term.matches(asset, &body) != term.negated)
+            })
+    }
+}
+
+impl Term {
+    fn parse(word: &str) -> Option<Self> {
+        let (negated, rest) = match word.strip_prefix('-') {
+            Some(rest) if !rest.is_empty() => (true, rest),
+            _ => (false, word),
+        };
+        let (field, text) = match rest.split_once(':') {
+            Some(("tag", text)) => (Field::Tag, text),
+            Some(("path", text)) => (Field::Path, text),
+            Some(("name", text)) => (Field::Name, text),
+            _ => (Field::Any, rest),
+        };
+        (!text.is_empty()).then(|| Term {
+            negated,
+            field,
+            text: text.to_lowercase(),
+        })
+    }
+
+    fn matches(&self, asset: &AssetView, body: &impl Fn(&str) -> bool) -> bool {
+        let contains = |value: &str| value.to_lowercase().contains(&self.text);
+        match self.field {
+            Field::Tag => asset.tags.iter().any(|tag| tag.to_lowercase() == self.text),
+            Field::Path => contains(&asset.path),
+            Field::Name => contains(&asset.name),
+            Field::Any => {
+                [
+                    asset.name.as_str(),
+                    asset.path.as_str(),
+                    asset.slug.as_deref().unwrap_or(""),
+                    &asset.description,
+                ]
+                .into_iter()
+                .chain(asset.tags.iter().map(String::as_str))
+                .chain(asset.apply_to.iter().map(String::as_str))
+                .chain(
+                    asset
+                        .collections
+                        .iter()
+                        .flat_map(|c| [c.id.as_str(), c.name.as_str()]),
+                )
+                .any(contains)
+                    || body(&self.text)
+            }
+        }
+    }
+}
+
+/// Split on whitespace, keeping double-quoted runs together without their
+/// quotes. `-"old api"` stays one negated word.
+fn words(input: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for ch in input.chars() {
+        match ch {
+            '"' => quoted = !quoted,
+            ch if ch.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+            }
+            ch => current.push(ch),
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        model::{Catalog, EnablementFile, Instruction},
+        state::DomainState,
+    };
+
+    #[test]
+    fn combines_terms_with_or_and_negation() {
+        let mut catalog = Catalog::default();
+        for (slug, tags) in [
+            ("rust", vec!["rust"]),
+            ("rust-legacy", vec!["rust"]),
+            ("python", vec!["python"]),
+            ("django", vec!["django", "python-web"]),
+        ] {
+            catalog.instructions.push(Instruction {
+                path: format!("instructions/{slug}.instructions.md"),
+                slug: slug.into(),
+                name: slug.into(),
+                description: format!("{slug} state locking"),
+                apply_to: Vec::new(),
+                tags: tags.into_iter().map(Into::into).collect(),
+                sha256: String::new(),
+                requires: Vec::new(),
+            });
+        }
+        let state = DomainState::new(catalog, EnablementFile::default());
+        let found = |query: &str| {
+            let query = Query::parse(query);
+            state
+                .all_assets()
+                .filter(|asset| query.matches(asset, |_| false))
+                .map(|asset| asset.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(found("rust -legacy"), ["rust"]);
+        assert_eq!(found("tag:python OR tag:django"), ["django", "python"]);
+        assert_eq!(found("RUST AND \"state locking\""), ["rust", "rust-legacy"]);
+        assert_eq!(found("\"locking state\""), Vec::<String>::new());
+        assert_eq!(found("-path:rust OR"), ["django", "python"]);
+        assert!(Query::parse("  OR AND ").is_empty());
+    }
+}
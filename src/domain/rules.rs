@@ -0,0 +1,132 @@
+use globset::{GlobBuilder, GlobMatcher};
+use serde::Serialize;
+
+use super::model::{split_source, AssetKind, EnablementRule};
+
+/// The rule that set an asset's state when nothing more specific did.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleMatch {
+    /// The rule's conditions, as shown to the user.
+    pub rule: String,
+    pub value: bool,
+}
+
+/// The `rules` of the enablement file, with their globs compiled.
+#[derive(Debug, Default, Clone)]
+pub struct RuleSet {
+    rules: Vec<(EnablementRule, Option<GlobMatcher>)>,
+}
+
+impl RuleSet {
+    /// Compile `rules`. A rule with an invalid path glob never matches; see
+    /// [`invalid_globs`] to report it.
+    pub fn new(rules: &[EnablementRule]) -> Self {
+        Self {
+            rules: rules
+                .iter()
+                .filter_map(|rule| {
+                    let matcher = match &rule.path {
+                        Some(pattern) => Some(compile(pattern)?),
+                        None => None,
+                    };
+                    Some((rule.clone(), matcher))
+                })
+                .collect(),
+        }
+    }
+
+    /// State of the last rule matching an asset, if any. Collections are
+    /// never matched, since their state cascades to their members. Path
+    /// globs match the path within the asset's source, so one rule covers
+    /// the same asset of every source.
+    pub fn evaluate(&self, kind: AssetKind, path: &str, tags: &[String]) -> Option<RuleMatch> {
+        if kind == AssetKind::Collection {
+            return None;
+        }
+        let (_, path) = split_source(path);
+        self.rules
+            .iter()
+            .rev()
+            .find(|(rule, matcher)| {
+                rule.kind.is_none_or(|k| k == kind)
+                    && rule
+                        .tag
+                        .as_ref()
+                        .is_none_or(|tag| tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+                    && matcher.as_ref().is_none_or(|m| m.is_match(path))
+            })
+            .map(|(rule, _)| RuleMatch {
+                rule: rule.to_string(),
+                value: rule.enabled,
+            })
+    }
+}
+
+/// Path globs of `rules` that don't compile.
+pub fn invalid_globs(rules: &[EnablementRule]) -> Vec<&str> {
+    rules
+        .iter()
+        .filter_map(|rule| rule.path.as_deref())
+        .filter(|pattern| compile(pattern).is_none())
+        .collect()
+}
+
+/// `*` stays within one directory, like `applyTo` globs.
+fn compile(pattern: &str) -> Option<GlobMatcher> {
+    GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .ok()
+        .map(|glob| glob.compile_matcher())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(tag: Option<&str>, path: Option<&str>, enabled: bool) -> EnablementRule {
+        EnablementRule {
+            tag: tag.map(Into::into),
+            path: path.map(Into::into),
+            kind: None,
+            enabled,
+        }
+    }
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let rules = RuleSet::new(&[
+            rule(Some("Rust"), None, true),
+            rule(None, Some("instructions/*legacy*"), false),
+            rule(None, Some("[invalid"), true),
+        ]);
+        let tags = vec!["rust".to_string()];
+        let state = |path: &str, tags: &[String]| {
+            rules
+                .evaluate(AssetKind::Instruction, path, tags)
+                .map(|m| m.value)
+        };
+
+        assert_eq!(
+            state("instructions/rust.instructions.md", &tags),
+            Some(true)
+        );
+        assert_eq!(
+            state("instructions/rust-legacy.instructions.md", &tags),
+            Some(false)
+        );
+        assert_eq!(
+            state("acme:instructions/old-legacy.instructions.md", &[]),
+            Some(false)
+        );
+        assert_eq!(state("instructions/go.instructions.md", &[]), None);
+        assert!(rules
+            .evaluate(
+                AssetKind::Collection,
+                "collections/rust.collection.yml",
+                &tags
+            )
+            .is_none());
+    }
+}
@@ -1,25 +1,59 @@
-use std::collections::BTreeMap;
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BTreeMap, HashMap},
+};
 
-use super::model::{AssetKind, Catalog, ChatMode, Collection, EnablementFile, Instruction, Prompt};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    model::{
+        split_source, Agent, AssetKind, Catalog, ChatMode, Collection, EnablementFile, Instruction,
+        McpServer, Prompt, Resolution,
+    },
+    rules::{RuleMatch, RuleSet},
+};
 use crate::io::sync::LocalStatus;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CollectionRef {
     pub id: String,
     pub name: String,
     pub path: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct InheritedState {
+    /// The collection that decided the state.
     pub collection: CollectionRef,
     pub value: bool,
+    /// Every collection listing the asset with an explicit state, in
+    /// priority order.
+    pub parents: Vec<ParentState>,
 }
 
-#[derive(Debug, Clone)]
+impl InheritedState {
+    /// Whether the parent collections disagree.
+    pub fn is_conflict(&self) -> bool {
+        self.parents.iter().any(|parent| parent.value)
+            && self.parents.iter().any(|parent| !parent.value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParentState {
+    pub collection: CollectionRef,
+    pub value: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct AssetView {
     pub kind: AssetKind,
     pub path: String,
+    pub source: String,
     pub slug: Option<String>,
     pub name: String,
     pub description: String,
@@ -28,18 +62,185 @@ pub struct AssetView {
     pub mode: Option<String>,
     pub tools: Vec<String>,
     pub collections: Vec<CollectionRef>,
+    /// Paths from the asset's `requires:` front matter.
+    pub requires: Vec<String>,
     pub member_count: usize,
+    /// Collections only: members that are effectively enabled.
+    pub enabled_members: usize,
     pub explicit: Option<bool>,
     pub inherited: Option<InheritedState>,
+    /// The enablement rule that applies when nothing more specific does.
+    pub rule: Option<RuleMatch>,
     pub effective: bool,
     pub local: LocalStatus,
 }
 
+impl AssetView {
+    /// A collection with some, but not all, of its members enabled, e.g.
+    /// after picking members individually.
+    pub fn is_partial(&self) -> bool {
+        self.kind == AssetKind::Collection
+            && self.enabled_members > 0
+            && self.enabled_members < self.member_count
+    }
+
+    /// An asset without an explicit entry whose parent collections disagree.
+    pub fn is_mixed(&self) -> bool {
+        self.explicit.is_none()
+            && self
+                .inherited
+                .as_ref()
+                .is_some_and(InheritedState::is_conflict)
+    }
+}
+
+/// One step of an asset's `requires:` chain.
+#[derive(Debug, Clone)]
+pub struct Dependency<'a> {
+    /// 1 for direct requirements, 2 for what those require, and so on.
+    pub depth: usize,
+    pub path: &'a str,
+    /// `None` when no asset in the catalog has this path.
+    pub asset: Option<&'a AssetView>,
+}
+
+/// Ordering of the asset table, cycled with `o`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortMode {
+    /// Catalog order (by name, as built by `recompute`).
+    #[default]
+    Name,
+    Path,
+    EnabledFirst,
+    LocalStatus,
+    Kind,
+    Favorites,
+}
+
+impl SortMode {
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::Name => SortMode::Path,
+            SortMode::Path => SortMode::EnabledFirst,
+            SortMode::EnabledFirst => SortMode::LocalStatus,
+            SortMode::LocalStatus => SortMode::Kind,
+            SortMode::Kind => SortMode::Favorites,
+            SortMode::Favorites => SortMode::Name,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Name => "name",
+            SortMode::Path => "path",
+            SortMode::EnabledFirst => "enabled first",
+            SortMode::LocalStatus => "local status",
+            SortMode::Kind => "kind",
+            SortMode::Favorites => "favorites first",
+        }
+    }
+}
+
+/// Which enablement states the asset table shows, cycled with `F`. Applied
+/// on top of the search query and chosen tags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StateFilter {
+    #[default]
+    All,
+    Enabled,
+    Disabled,
+    /// Assets with an entry of their own in the enablement file.
+    Explicit,
+    /// Assets without an entry that follow a parent collection.
+    Inherited,
+    /// Enabled assets whose local file is missing or differs from upstream.
+    OutOfSync,
+}
+
+impl StateFilter {
+    pub fn next(self) -> Self {
+        match self {
+            StateFilter::All => StateFilter::Enabled,
+            StateFilter::Enabled => StateFilter::Disabled,
+            StateFilter::Disabled => StateFilter::Explicit,
+            StateFilter::Explicit => StateFilter::Inherited,
+            StateFilter::Inherited => StateFilter::OutOfSync,
+            StateFilter::OutOfSync => StateFilter::All,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StateFilter::All => "all",
+            StateFilter::Enabled => "enabled",
+            StateFilter::Disabled => "disabled",
+            StateFilter::Explicit => "explicit only",
+            StateFilter::Inherited => "inherited only",
+            StateFilter::OutOfSync => "out of sync",
+        }
+    }
+
+    pub fn matches(self, asset: &AssetView) -> bool {
+        match self {
+            StateFilter::All => true,
+            StateFilter::Enabled => asset.effective,
+            StateFilter::Disabled => !asset.effective,
+            StateFilter::Explicit => asset.explicit.is_some(),
+            StateFilter::Inherited => asset.explicit.is_none() && asset.inherited.is_some(),
+            StateFilter::OutOfSync => {
+                asset.effective && matches!(asset.local, LocalStatus::Missing | LocalStatus::Diff)
+            }
+        }
+    }
+}
+
+/// Sort `assets` by `mode`, breaking ties by name. `local` supplies the local
+/// status and `favorite` whether an asset is starred, which the domain state
+/// does not track itself.
+pub fn sort_assets(
+    assets: &mut [&AssetView],
+    mode: SortMode,
+    local: impl Fn(&AssetView) -> LocalStatus,
+    favorite: impl Fn(&AssetView) -> bool,
+) {
+    let by_name = |a: &AssetView, b: &AssetView| {
+        a.name
+            .to_lowercase()
+            .cmp(&b.name.to_lowercase())
+            .then_with(|| a.path.cmp(&b.path))
+    };
+    assets.sort_by(|a, b| {
+        let primary = match mode {
+            SortMode::Name => Ordering::Equal,
+            SortMode::Path => a.path.cmp(&b.path),
+            SortMode::EnabledFirst => b.effective.cmp(&a.effective),
+            SortMode::LocalStatus => local_rank(local(a)).cmp(&local_rank(local(b))),
+            SortMode::Kind => a.kind.cmp(&b.kind),
+            SortMode::Favorites => favorite(b).cmp(&favorite(a)),
+        };
+        primary.then_with(|| by_name(a, b))
+    });
+}
+
+/// Files needing attention first.
+fn local_rank(status: LocalStatus) -> u8 {
+    match status {
+        LocalStatus::Update => 0,
+        LocalStatus::Diff => 1,
+        LocalStatus::Missing => 2,
+        LocalStatus::Same => 3,
+        LocalStatus::Unmanaged | LocalStatus::NA => 4,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct OrphanEntry {
     pub kind: AssetKind,
     pub path: String,
     pub value: bool,
+    /// Catalog path the entry most likely refers to now.
+    pub successor: Option<String>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -48,6 +249,13 @@ pub struct DomainState {
     pub enablement: EnablementFile,
     assets: BTreeMap<AssetKind, Vec<AssetView>>,
     orphans: Vec<OrphanEntry>,
+    /// SHA-256 that catalog paths had when last seen, so an orphan can be
+    /// matched to an asset that kept its content under a new path.
+    known_hashes: BTreeMap<String, String>,
+    /// Last computed local status per asset path, kept across `recompute`.
+    local_statuses: HashMap<String, LocalStatus>,
+    /// The enablement file's rules, compiled by `recompute`.
+    rules: RuleSet,
 }
 
 impl DomainState {
@@ -58,6 +266,9 @@ impl DomainState {
             enablement,
             assets: BTreeMap::new(),
             orphans: Vec::new(),
+            known_hashes: BTreeMap::new(),
+            local_statuses: HashMap::new(),
+            rules: RuleSet::default(),
         };
         state.recompute();
         state
@@ -67,12 +278,152 @@ impl DomainState {
         self.assets.get(&kind).map(|v| v.as_slice()).unwrap_or(&[])
     }
 
+    /// Every asset of every kind, in kind order.
+    pub fn all_assets(&self) -> impl Iterator<Item = &AssetView> {
+        self.assets.values().flatten()
+    }
+
+    /// An enabled asset of another source that is applied to the same local
+    /// file as `path`: sources share `.github/`, so only one of them can own
+    /// it at a time.
+    pub fn local_file_owner(&self, kind: AssetKind, path: &str) -> Option<&AssetView> {
+        if matches!(kind, AssetKind::Collection | AssetKind::McpServer) {
+            return None;
+        }
+        let (_, relative) = split_source(path);
+        self.assets(kind).iter().find(|asset| {
+            asset.effective && asset.path != path && split_source(&asset.path).1 == relative
+        })
+    }
+
     pub fn orphans(&self) -> &[OrphanEntry] {
         &self.orphans
     }
 
+    /// Remember the content hashes paths had before, and suggest successors
+    /// for orphans by content from now on.
+    pub fn set_known_hashes(&mut self, hashes: BTreeMap<String, String>) {
+        self.known_hashes = hashes;
+        self.orphans = self.collect_orphans();
+    }
+
+    /// Store freshly computed local statuses and show them on the views.
+    /// Assets missing from `statuses` read as not applicable.
+    pub fn set_local_statuses(&mut self, statuses: HashMap<String, LocalStatus>) {
+        self.local_statuses = statuses;
+        self.apply_local_statuses();
+    }
+
+    fn apply_local_statuses(&mut self) {
+        for view in self.assets.values_mut().flatten() {
+            view.local = self
+                .local_statuses
+                .get(&view.path)
+                .copied()
+                .unwrap_or(LocalStatus::NA);
+        }
+    }
+
+    /// Views of the collection's members in collection order; members missing
+    /// from the catalog are skipped.
+    pub fn collection_members(&self, collection_path: &str) -> Vec<&AssetView> {
+        let Some(collection) = self.catalog.collection_by_path(collection_path) else {
+            return Vec::new();
+        };
+        collection
+            .items
+            .iter()
+            .filter_map(|item| {
+                self.assets(item.kind)
+                    .iter()
+                    .find(|view| view.path == item.path)
+            })
+            .collect()
+    }
+
+    /// The view of the asset at `path`, whatever its kind.
+    pub fn asset_by_path(&self, path: &str) -> Option<&AssetView> {
+        self.assets
+            .values()
+            .flatten()
+            .find(|view| view.path == path)
+    }
+
+    /// Everything `path` requires, directly or through other requirements,
+    /// depth first. Each path is listed once, which also cuts cycles.
+    pub fn dependency_chain(&self, path: &str) -> Vec<Dependency<'_>> {
+        let mut chain = Vec::new();
+        if let Some(view) = self.asset_by_path(path) {
+            let mut seen = vec![view.path.as_str()];
+            self.walk_dependencies(view, 1, &mut seen, &mut chain);
+        }
+        chain
+    }
+
+    fn walk_dependencies<'a>(
+        &'a self,
+        view: &'a AssetView,
+        depth: usize,
+        seen: &mut Vec<&'a str>,
+        chain: &mut Vec<Dependency<'a>>,
+    ) {
+        for required in &view.requires {
+            if seen.contains(&required.as_str()) {
+                continue;
+            }
+            seen.push(required);
+            let asset = self.asset_by_path(required);
+            chain.push(Dependency {
+                depth,
+                path: required,
+                asset,
+            });
+            if let Some(asset) = asset {
+                self.walk_dependencies(asset, depth + 1, seen, chain);
+            }
+        }
+    }
+
+    /// Enabled assets that list `path` under `requires:`.
+    pub fn required_by(&self, path: &str) -> Vec<&AssetView> {
+        self.assets
+            .values()
+            .flatten()
+            .filter(|view| view.effective && view.requires.iter().any(|r| r == path))
+            .collect()
+    }
+
+    /// Effectively enabled and total assets of `kind`.
+    pub fn counts(&self, kind: AssetKind) -> (usize, usize) {
+        let assets = self.assets(kind);
+        let enabled = assets.iter().filter(|asset| asset.effective).count();
+        (enabled, assets.len())
+    }
+
+    /// Every tag used by assets of `kind` with the number of assets carrying
+    /// it, most used first.
+    pub fn tag_counts(&self, kind: AssetKind) -> Vec<(String, usize)> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for asset in self.assets(kind) {
+            let mut tags: Vec<&str> = asset.tags.iter().map(String::as_str).collect();
+            tags.sort_unstable();
+            tags.dedup();
+            for tag in tags {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<_> = counts
+            .into_iter()
+            .map(|(tag, count)| (tag.to_string(), count))
+            .collect();
+        // Stable, so equally used tags stay alphabetical.
+        counts.sort_by_key(|(_, count)| Reverse(*count));
+        counts
+    }
+
     pub fn recompute(&mut self) {
         self.assets.clear();
+        self.rules = RuleSet::new(&self.enablement.rules);
 
         let mut prompts: Vec<_> = self
             .catalog
@@ -101,6 +452,24 @@ impl DomainState {
         chat_modes.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
         self.assets.insert(AssetKind::ChatMode, chat_modes);
 
+        let mut agents: Vec<_> = self
+            .catalog
+            .agents
+            .iter()
+            .map(|a| self.build_agent_view(a))
+            .collect();
+        agents.sort_by_key(|a| a.name.to_lowercase());
+        self.assets.insert(AssetKind::Agent, agents);
+
+        let mut mcp_servers: Vec<_> = self
+            .catalog
+            .mcp_servers
+            .iter()
+            .map(|m| self.build_mcp_server_view(m))
+            .collect();
+        mcp_servers.sort_by_key(|a| a.name.to_lowercase());
+        self.assets.insert(AssetKind::McpServer, mcp_servers);
+
         let mut collections: Vec<_> = self
             .catalog
             .collections
@@ -111,16 +480,20 @@ impl DomainState {
         self.assets.insert(AssetKind::Collection, collections);
 
         self.orphans = self.collect_orphans();
+        self.apply_local_statuses();
     }
 
     fn build_prompt_view(&self, prompt: &Prompt) -> AssetView {
         let explicit = self.explicit_state(AssetKind::Prompt, &prompt.path);
         let inherited = self.inherited_state(&prompt.path);
-        let effective =
-            explicit.unwrap_or_else(|| inherited.as_ref().map(|s| s.value).unwrap_or(false));
+        let rule = self
+            .rules
+            .evaluate(AssetKind::Prompt, &prompt.path, &prompt.tags);
+        let effective = resolve(explicit, inherited.as_ref(), rule.as_ref());
         AssetView {
             kind: AssetKind::Prompt,
             path: prompt.path.clone(),
+            source: source_of(&prompt.path),
             slug: Some(prompt.slug.clone()),
             name: prompt.name.clone(),
             description: prompt.description.clone(),
@@ -133,9 +506,12 @@ impl DomainState {
             },
             tools: Vec::new(),
             collections: self.collections_for(&prompt.path),
+            requires: prompt.requires.clone(),
             member_count: 0,
+            enabled_members: 0,
             explicit,
             inherited,
+            rule,
             effective,
             local: LocalStatus::NA,
         }
@@ -144,11 +520,14 @@ impl DomainState {
     fn build_instruction_view(&self, instruction: &Instruction) -> AssetView {
         let explicit = self.explicit_state(AssetKind::Instruction, &instruction.path);
         let inherited = self.inherited_state(&instruction.path);
-        let effective =
-            explicit.unwrap_or_else(|| inherited.as_ref().map(|s| s.value).unwrap_or(false));
+        let rule =
+            self.rules
+                .evaluate(AssetKind::Instruction, &instruction.path, &instruction.tags);
+        let effective = resolve(explicit, inherited.as_ref(), rule.as_ref());
         AssetView {
             kind: AssetKind::Instruction,
             path: instruction.path.clone(),
+            source: source_of(&instruction.path),
             slug: Some(instruction.slug.clone()),
             name: instruction.name.clone(),
             description: instruction.description.clone(),
@@ -157,9 +536,12 @@ impl DomainState {
             mode: None,
             tools: Vec::new(),
             collections: self.collections_for(&instruction.path),
+            requires: instruction.requires.clone(),
             member_count: 0,
+            enabled_members: 0,
             explicit,
             inherited,
+            rule,
             effective,
             local: LocalStatus::NA,
         }
@@ -168,11 +550,14 @@ impl DomainState {
     fn build_chat_mode_view(&self, mode: &ChatMode) -> AssetView {
         let explicit = self.explicit_state(AssetKind::ChatMode, &mode.path);
         let inherited = self.inherited_state(&mode.path);
-        let effective =
-            explicit.unwrap_or_else(|| inherited.as_ref().map(|s| s.value).unwrap_or(false));
+        let rule = self
+            .rules
+            .evaluate(AssetKind::ChatMode, &mode.path, &mode.tags);
+        let effective = resolve(explicit, inherited.as_ref(), rule.as_ref());
         AssetView {
             kind: AssetKind::ChatMode,
             path: mode.path.clone(),
+            source: source_of(&mode.path),
             slug: Some(mode.slug.clone()),
             name: mode.name.clone(),
             description: mode.description.clone(),
@@ -181,9 +566,72 @@ impl DomainState {
             mode: None,
             tools: mode.tools.clone(),
             collections: self.collections_for(&mode.path),
+            requires: mode.requires.clone(),
+            member_count: 0,
+            enabled_members: 0,
+            explicit,
+            inherited,
+            rule,
+            effective,
+            local: LocalStatus::NA,
+        }
+    }
+
+    fn build_agent_view(&self, agent: &Agent) -> AssetView {
+        let explicit = self.explicit_state(AssetKind::Agent, &agent.path);
+        let inherited = self.inherited_state(&agent.path);
+        let rule = self
+            .rules
+            .evaluate(AssetKind::Agent, &agent.path, &agent.tags);
+        let effective = resolve(explicit, inherited.as_ref(), rule.as_ref());
+        AssetView {
+            kind: AssetKind::Agent,
+            path: agent.path.clone(),
+            source: source_of(&agent.path),
+            slug: Some(agent.slug.clone()),
+            name: agent.name.clone(),
+            description: agent.description.clone(),
+            tags: agent.tags.clone(),
+            apply_to: Vec::new(),
+            mode: None,
+            tools: agent.tools.clone(),
+            collections: self.collections_for(&agent.path),
+            requires: agent.requires.clone(),
+            member_count: 0,
+            enabled_members: 0,
+            explicit,
+            inherited,
+            rule,
+            effective,
+            local: LocalStatus::NA,
+        }
+    }
+
+    fn build_mcp_server_view(&self, server: &McpServer) -> AssetView {
+        let explicit = self.explicit_state(AssetKind::McpServer, &server.path);
+        let inherited = self.inherited_state(&server.path);
+        let rule = self
+            .rules
+            .evaluate(AssetKind::McpServer, &server.path, &server.tags);
+        let effective = resolve(explicit, inherited.as_ref(), rule.as_ref());
+        AssetView {
+            kind: AssetKind::McpServer,
+            path: server.path.clone(),
+            source: source_of(&server.path),
+            slug: Some(server.slug.clone()),
+            name: server.name.clone(),
+            description: server.description.clone(),
+            tags: server.tags.clone(),
+            apply_to: Vec::new(),
+            mode: None,
+            tools: Vec::new(),
+            collections: self.collections_for(&server.path),
+            requires: Vec::new(),
             member_count: 0,
+            enabled_members: 0,
             explicit,
             inherited,
+            rule,
             effective,
             local: LocalStatus::NA,
         }
@@ -195,6 +643,7 @@ impl DomainState {
         AssetView {
             kind: AssetKind::Collection,
             path: collection.path.clone(),
+            source: source_of(&collection.path),
             slug: Some(collection.id.clone()),
             name: collection.name.clone(),
             description: collection.description.clone(),
@@ -203,9 +652,16 @@ impl DomainState {
             mode: None,
             tools: Vec::new(),
             collections: Vec::new(),
+            requires: Vec::new(),
             member_count: collection.items.len(),
+            enabled_members: self
+                .collection_members(&collection.path)
+                .iter()
+                .filter(|member| member.effective)
+                .count(),
             explicit,
             inherited: None,
+            rule: None,
             effective,
             local: LocalStatus::NA,
         }
@@ -217,7 +673,7 @@ impl DomainState {
 
     fn inherited_state(&self, path: &str) -> Option<InheritedState> {
         let memberships = self.catalog.memberships(path);
-        let mut candidates: Vec<(CollectionRef, bool)> = Vec::new();
+        let mut parents: Vec<ParentState> = Vec::new();
         for collection_id in memberships {
             if let Some(collection) = self.catalog.collection_by_id(collection_id) {
                 if let Some(value) = self
@@ -225,22 +681,43 @@ impl DomainState {
                     .map_for(AssetKind::Collection)
                     .get(&collection.path)
                 {
-                    candidates.push((
-                        CollectionRef {
+                    parents.push(ParentState {
+                        collection: CollectionRef {
                             id: collection.id.clone(),
                             name: collection.name.clone(),
                             path: collection.path.clone(),
                         },
-                        *value,
-                    ));
+                        value: *value,
+                    });
                 }
             }
         }
-        candidates.sort_by(|a, b| a.0.id.cmp(&b.0.id));
-        candidates
-            .into_iter()
-            .next()
-            .map(|(collection, value)| InheritedState { collection, value })
+        let inheritance = &self.enablement.inheritance;
+        let rank = |id: &str| {
+            inheritance
+                .priority
+                .iter()
+                .position(|listed| listed == id)
+                .unwrap_or(usize::MAX)
+        };
+        parents.sort_by(|a, b| {
+            rank(&a.collection.id)
+                .cmp(&rank(&b.collection.id))
+                .then_with(|| a.collection.id.cmp(&b.collection.id))
+        });
+        let decider = match inheritance.resolution {
+            Resolution::Priority => parents.first(),
+            Resolution::AnyOn => parents
+                .iter()
+                .find(|parent| parent.value)
+                .or(parents.first()),
+        }?
+        .clone();
+        Some(InheritedState {
+            collection: decider.collection,
+            value: decider.value,
+            parents,
+        })
     }
 
     fn collections_for(&self, path: &str) -> Vec<CollectionRef> {
@@ -258,44 +735,135 @@ impl DomainState {
 
     fn collect_orphans(&self) -> Vec<OrphanEntry> {
         let mut result = Vec::new();
-        for (path, value) in &self.enablement.prompts {
-            if !self.catalog.contains(AssetKind::Prompt, path) {
-                result.push(OrphanEntry {
-                    kind: AssetKind::Prompt,
-                    path: path.clone(),
-                    value: *value,
-                });
+        for kind in [
+            AssetKind::Prompt,
+            AssetKind::Instruction,
+            AssetKind::ChatMode,
+            AssetKind::Agent,
+            AssetKind::McpServer,
+            AssetKind::Collection,
+        ] {
+            for (path, value) in self.enablement.map_for(kind) {
+                if !self.catalog.contains(kind, path) {
+                    result.push(OrphanEntry {
+                        kind,
+                        path: path.clone(),
+                        value: *value,
+                        successor: self.likely_successor(kind, path),
+                    });
+                }
             }
         }
-        for (path, value) in &self.enablement.instructions {
-            if !self.catalog.contains(AssetKind::Instruction, path) {
-                result.push(OrphanEntry {
-                    kind: AssetKind::Instruction,
-                    path: path.clone(),
-                    value: *value,
-                });
+        result.sort_by(|a, b| a.path.cmp(&b.path));
+        result
+    }
+
+    /// Guess where an orphaned entry moved: a catalog asset of the same kind
+    /// with the content the entry's path last had, or failing that the same
+    /// file name, or the same slug.
+    fn likely_successor(&self, kind: AssetKind, path: &str) -> Option<String> {
+        let candidates: Vec<(&str, &str)> = match kind {
+            AssetKind::Prompt => self
+                .catalog
+                .prompts
+                .iter()
+                .map(|a| (a.path.as_str(), a.sha256.as_str()))
+                .collect(),
+            AssetKind::Instruction => self
+                .catalog
+                .instructions
+                .iter()
+                .map(|a| (a.path.as_str(), a.sha256.as_str()))
+                .collect(),
+            AssetKind::ChatMode => self
+                .catalog
+                .chat_modes
+                .iter()
+                .map(|a| (a.path.as_str(), a.sha256.as_str()))
+                .collect(),
+            AssetKind::Agent => self
+                .catalog
+                .agents
+                .iter()
+                .map(|a| (a.path.as_str(), a.sha256.as_str()))
+                .collect(),
+            AssetKind::McpServer => self
+                .catalog
+                .mcp_servers
+                .iter()
+                .map(|a| (a.path.as_str(), a.sha256.as_str()))
+                .collect(),
+            AssetKind::Collection => self
+                .catalog
+                .collections
+                .iter()
+                .map(|c| (c.path.as_str(), c.sha256.as_str()))
+                .collect(),
+        };
+        if let Some(sha256) = self.known_hashes.get(path) {
+            if let Some((found, _)) = candidates.iter().find(|(_, sha)| sha == sha256) {
+                return Some(found.to_string());
             }
         }
-        for (path, value) in &self.enablement.chat_modes {
-            if !self.catalog.contains(AssetKind::ChatMode, path) {
-                result.push(OrphanEntry {
-                    kind: AssetKind::ChatMode,
-                    path: path.clone(),
-                    value: *value,
-                });
-            }
+        let name = file_name(path);
+        if let Some((found, _)) = candidates.iter().find(|(c, _)| file_name(c) == name) {
+            return Some(found.to_string());
+        }
+        let slug = slug_of(path);
+        candidates
+            .iter()
+            .find(|(c, _)| slug_of(c) == slug)
+            .map(|(c, _)| c.to_string())
+    }
+
+    /// Drop the enablement entry of a single orphan.
+    pub fn remove_orphan(&mut self, kind: AssetKind, path: &str) -> bool {
+        if self.enablement.map_for_mut(kind).remove(path).is_none() {
+            return false;
         }
-        for (path, value) in &self.enablement.collections {
-            if !self.catalog.contains(AssetKind::Collection, path) {
-                result.push(OrphanEntry {
-                    kind: AssetKind::Collection,
-                    path: path.clone(),
-                    value: *value,
-                });
+        self.recompute();
+        true
+    }
+
+    /// Move an orphan's stored value to `target`, replacing any explicit value
+    /// the target already had.
+    pub fn remap_orphan(&mut self, kind: AssetKind, path: &str, target: &str) -> bool {
+        if !self.catalog.contains(kind, target) {
+            return false;
+        }
+        let Some(value) = self.enablement.map_for_mut(kind).remove(path) else {
+            return false;
+        };
+        self.enablement
+            .map_for_mut(kind)
+            .insert(target.to_string(), value);
+        self.recompute();
+        true
+    }
+
+    /// Non-collection assets whose effective state differs from `before`,
+    /// with their new state.
+    pub fn effective_changes(&self, before: &DomainState) -> Vec<(AssetKind, String, bool)> {
+        let mut changes = Vec::new();
+        for kind in [
+            AssetKind::Prompt,
+            AssetKind::Instruction,
+            AssetKind::ChatMode,
+            AssetKind::Agent,
+            AssetKind::McpServer,
+        ] {
+            for asset in self.assets(kind) {
+                let previous = before
+                    .assets(kind)
+                    .iter()
+                    .find(|a| a.path == asset.path)
+                    .map(|a| a.effective);
+                if previous != Some(asset.effective) {
+                    changes.push((kind, asset.path.clone(), asset.effective));
+                }
             }
         }
-        result.sort_by(|a, b| a.path.cmp(&b.path));
-        result
+        changes
     }
 
     pub fn cleanup_orphans(&mut self) -> usize {
@@ -310,6 +878,71 @@ impl DomainState {
         self.recompute();
         removed
     }
+
+    /// Assets whose file already exists under `.github/` although nothing
+    /// enables them: no enablement entry and no enabled collection, as in a
+    /// repository that copied files by hand before using this tool.
+    pub fn adoptable(&self) -> Vec<&AssetView> {
+        [
+            AssetKind::Prompt,
+            AssetKind::Instruction,
+            AssetKind::ChatMode,
+            AssetKind::Agent,
+        ]
+        .into_iter()
+        .flat_map(|kind| self.assets(kind))
+        .filter(|view| {
+            view.explicit.is_none()
+                && !view.effective
+                && !matches!(view.local, LocalStatus::Missing | LocalStatus::NA)
+        })
+        .collect()
+    }
+
+    /// Enable every adoptable asset explicitly, leaving its file as it is.
+    /// Returns how many were adopted.
+    pub fn adopt(&mut self) -> usize {
+        let adopted: Vec<(AssetKind, String)> = self
+            .adoptable()
+            .into_iter()
+            .map(|view| (view.kind, view.path.clone()))
+            .collect();
+        for (kind, path) in &adopted {
+            self.enablement.set(*kind, path, true);
+        }
+        if !adopted.is_empty() {
+            self.recompute();
+        }
+        adopted.len()
+    }
+}
+
+/// An explicit entry wins over a collection's state, which wins over a rule;
+/// assets nothing applies to are off.
+fn resolve(
+    explicit: Option<bool>,
+    inherited: Option<&InheritedState>,
+    rule: Option<&RuleMatch>,
+) -> bool {
+    explicit
+        .or(inherited.map(|state| state.value))
+        .or(rule.map(|rule| rule.value))
+        .unwrap_or(false)
+}
+
+fn source_of(path: &str) -> String {
+    split_source(path).0.to_string()
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// File name without its kind suffix, normalized for comparison.
+fn slug_of(path: &str) -> String {
+    let name = file_name(path);
+    let stem = name.split('.').next().unwrap_or(name);
+    stem.to_ascii_lowercase().replace('_', "-")
 }
 
 #[cfg(test)]
@@ -317,7 +950,8 @@ mod tests {
     use super::*;
     use crate::domain::{
         model::{
-            AssetKind, Catalog, Collection, CollectionItem, EnablementFile, Instruction, Prompt,
+            AssetKind, Catalog, Collection, CollectionItem, EnablementFile, EnablementRule,
+            Instruction, Prompt, Resolution,
         },
         toggle,
     };
@@ -333,6 +967,7 @@ mod tests {
             apply_to: vec!["**/*.rs".into()],
             tags: vec!["test".into()],
             sha256: "test-sha256".into(),
+            requires: Vec::new(),
         };
         let collection = Collection {
             path: collection_path,
@@ -371,6 +1006,7 @@ mod tests {
             apply_to: vec!["**/*.rs".into()],
             tags: vec!["test".into()],
             sha256: "test-sha256".into(),
+            requires: Vec::new(),
         };
         let prompt = Prompt {
             path: prompt_path.clone(),
@@ -380,6 +1016,7 @@ mod tests {
             mode: String::new(),
             tags: vec!["test".into()],
             sha256: "test-sha256".into(),
+            requires: Vec::new(),
         };
         let collection = Collection {
             path: collection_path,
@@ -463,6 +1100,118 @@ mod tests {
         assert_eq!(prm.inherited.as_ref().unwrap().value, true);
     }
 
+    #[test]
+    fn counts_report_enabled_and_total_per_kind() {
+        let mut catalog = multi_catalog();
+        let mut other = catalog.prompts[0].clone();
+        other.path = "prompts/other.prompt.md".into();
+        catalog.prompts.push(other);
+        let mut enablement = EnablementFile::default();
+        enablement
+            .collections
+            .insert(catalog.collections[0].path.clone(), true);
+        let state = DomainState::new(catalog.finalize(), enablement);
+
+        assert_eq!(state.counts(AssetKind::Prompt), (1, 2));
+        assert_eq!(state.counts(AssetKind::Instruction), (1, 1));
+        assert_eq!(state.counts(AssetKind::ChatMode), (0, 0));
+    }
+
+    #[test]
+    fn picking_one_member_marks_collection_partial() {
+        let catalog = multi_catalog();
+        let collection_path = catalog.collections[0].path.clone();
+        let mut state = DomainState::new(catalog, EnablementFile::default());
+        let prompt_path = state.assets(AssetKind::Prompt)[0].path.clone();
+
+        toggle::set_asset(&mut state, AssetKind::Prompt, &prompt_path, true).unwrap();
+
+        let collection = &state.assets(AssetKind::Collection)[0];
+        assert!(!collection.effective);
+        assert_eq!(collection.enabled_members, 1);
+        assert!(collection.is_partial());
+        let members: Vec<_> = state
+            .collection_members(&collection_path)
+            .iter()
+            .map(|member| (member.kind, member.effective))
+            .collect();
+        assert_eq!(
+            members,
+            [(AssetKind::Instruction, false), (AssetKind::Prompt, true)]
+        );
+    }
+
+    #[test]
+    fn existing_files_without_entries_are_adopted() {
+        let catalog = multi_catalog();
+        let prompt_path = catalog.prompts[0].path.clone();
+        let instruction_path = catalog.instructions[0].path.clone();
+        let mut enablement = EnablementFile::default();
+        enablement
+            .instructions
+            .insert(instruction_path.clone(), false);
+        let mut state = DomainState::new(catalog, enablement);
+        assert!(state.adoptable().is_empty());
+
+        state.set_local_statuses(HashMap::from([
+            (prompt_path.clone(), LocalStatus::Diff),
+            (instruction_path.clone(), LocalStatus::Same),
+        ]));
+        let adoptable: Vec<_> = state.adoptable().iter().map(|view| &view.path).collect();
+        assert_eq!(adoptable, [&prompt_path]);
+
+        assert_eq!(state.adopt(), 1);
+        assert_eq!(state.enablement.prompts.get(&prompt_path), Some(&true));
+        assert_eq!(
+            state.enablement.instructions.get(&instruction_path),
+            Some(&false)
+        );
+        assert!(state.adoptable().is_empty());
+    }
+
+    #[test]
+    fn enabling_an_asset_enables_its_requirements() {
+        let mut catalog = multi_catalog();
+        let prompt_path = catalog.prompts[0].path.clone();
+        let instruction_path = catalog.instructions[0].path.clone();
+        catalog.prompts[0].requires = vec![instruction_path.clone(), "agents/gone.agent.md".into()];
+        // A cycle back to the prompt must not loop.
+        catalog.instructions[0].requires = vec![prompt_path.clone()];
+        let mut state = DomainState::new(catalog, EnablementFile::default());
+
+        let chain: Vec<_> = state
+            .dependency_chain(&prompt_path)
+            .iter()
+            .map(|dependency| {
+                (
+                    dependency.depth,
+                    dependency.path,
+                    dependency.asset.is_some(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            chain,
+            [
+                (1, instruction_path.as_str(), true),
+                (1, "agents/gone.agent.md", false)
+            ]
+        );
+
+        let result = toggle::set_asset(&mut state, AssetKind::Prompt, &prompt_path, true).unwrap();
+        assert_eq!(
+            result.enabled_dependencies,
+            [(AssetKind::Instruction, instruction_path.clone())]
+        );
+        assert_eq!(result.missing_dependencies, ["agents/gone.agent.md"]);
+        assert!(state.asset_by_path(&instruction_path).unwrap().effective);
+
+        let result =
+            toggle::set_asset(&mut state, AssetKind::Instruction, &instruction_path, false)
+                .unwrap();
+        assert_eq!(result.required_by, ["Sample Prompt"]);
+    }
+
     #[test]
     fn clean_project_assets_disabled_by_default() {
         let catalog = multi_catalog();
@@ -504,6 +1253,42 @@ mod tests {
         assert_eq!(prm.inherited.as_ref().unwrap().value, true);
     }
 
+    #[test]
+    fn state_filters_split_explicit_and_inherited_assets() {
+        let catalog = multi_catalog();
+        let collection_path = catalog.collections[0].path.clone();
+        let instruction_path = catalog.instructions[0].path.clone();
+        let prompt_path = catalog.prompts[0].path.clone();
+        let mut enablement = EnablementFile::default();
+        enablement.collections.insert(collection_path, true);
+        enablement.prompts.insert(prompt_path.clone(), false);
+        let mut state = DomainState::new(catalog, enablement);
+        state.set_local_statuses(HashMap::from([
+            (instruction_path, LocalStatus::Missing),
+            (prompt_path, LocalStatus::Missing),
+        ]));
+
+        let shown = |filter: StateFilter| {
+            state
+                .all_assets()
+                .filter(|asset| filter.matches(asset))
+                .map(|asset| asset.kind)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(shown(StateFilter::All).len(), 3);
+        assert_eq!(
+            shown(StateFilter::Enabled),
+            [AssetKind::Instruction, AssetKind::Collection]
+        );
+        assert_eq!(shown(StateFilter::Disabled), [AssetKind::Prompt]);
+        assert_eq!(
+            shown(StateFilter::Explicit),
+            [AssetKind::Prompt, AssetKind::Collection]
+        );
+        assert_eq!(shown(StateFilter::Inherited), [AssetKind::Instruction]);
+        assert_eq!(shown(StateFilter::OutOfSync), [AssetKind::Instruction]);
+    }
+
     #[test]
     fn instruction_inherits_collection_disable() {
         let catalog = sample_catalog();
@@ -551,6 +1336,214 @@ mod tests {
         );
     }
 
+    #[test]
+    fn overlapping_collections_resolve_by_strategy() {
+        let mut catalog = multi_catalog();
+        let mut second = catalog.collections[0].clone();
+        second.path = "collections/alpha.collection.yml".into();
+        second.id = "alpha".into();
+        catalog.collections.push(second);
+        let catalog = catalog.finalize();
+        let prompt_path = catalog.prompts[0].path.clone();
+        let mut enablement = EnablementFile::default();
+        enablement
+            .collections
+            .insert("collections/alpha.collection.yml".into(), false);
+        enablement
+            .collections
+            .insert("collections/sample.collection.yml".into(), true);
+        let mut state = DomainState::new(catalog, enablement);
+
+        let inherited = |state: &DomainState| {
+            state
+                .asset_by_path(&prompt_path)
+                .and_then(|asset| asset.inherited.clone())
+                .unwrap()
+        };
+        let by_id = inherited(&state);
+        assert!(!by_id.value);
+        assert_eq!(by_id.collection.id, "alpha");
+        assert_eq!(by_id.parents.len(), 2);
+        assert!(by_id.is_conflict());
+        assert!(state.asset_by_path(&prompt_path).unwrap().is_mixed());
+
+        state.enablement.inheritance.priority = vec!["bundle".into()];
+        state.recompute();
+        assert!(inherited(&state).value);
+
+        state.enablement.inheritance.priority.clear();
+        state.enablement.inheritance.resolution = Resolution::AnyOn;
+        state.recompute();
+        assert_eq!(inherited(&state).collection.id, "bundle");
+    }
+
+    #[test]
+    fn rules_enable_matching_assets_below_explicit_entries() {
+        let catalog = multi_catalog();
+        let prompt_path = catalog.prompts[0].path.clone();
+        let instruction_path = catalog.instructions[0].path.clone();
+        let mut enablement = EnablementFile::default();
+        enablement.rules.push(EnablementRule {
+            tag: Some("test".into()),
+            path: None,
+            kind: None,
+            enabled: true,
+        });
+        enablement
+            .instructions
+            .insert(instruction_path.clone(), false);
+        let mut state = DomainState::new(catalog, enablement);
+
+        let prompt = state.asset_by_path(&prompt_path).unwrap();
+        assert!(prompt.effective);
+        assert!(prompt.rule.is_some());
+        assert!(!state.asset_by_path(&instruction_path).unwrap().effective);
+
+        // Turning off a rule-enabled asset records an explicit entry.
+        toggle::toggle_asset(&mut state, AssetKind::Prompt, &prompt_path).expect("toggle succeeds");
+        assert_eq!(
+            state.enablement.prompts.get(&prompt_path).copied(),
+            Some(false)
+        );
+        toggle::toggle_asset(&mut state, AssetKind::Prompt, &prompt_path).expect("toggle succeeds");
+        assert!(!state.enablement.prompts.contains_key(&prompt_path));
+    }
+
+    #[test]
+    fn merged_source_assets_are_namespaced() {
+        let mut catalog = Catalog::default();
+        catalog.merge(sample_catalog(), crate::domain::model::PRIMARY_SOURCE);
+        catalog.merge(sample_catalog(), "acme");
+        let mut enablement = EnablementFile::default();
+        enablement
+            .collections
+            .insert("acme:collections/sample.collection.yml".into(), true);
+        let state = DomainState::new(catalog, enablement);
+
+        let instructions = state.assets(AssetKind::Instruction);
+        assert_eq!(instructions.len(), 2);
+        let upstream = instructions
+            .iter()
+            .find(|a| a.path == "instructions/sample.instructions.md")
+            .expect("primary instruction present");
+        assert_eq!(upstream.source, "upstream");
+        assert!(!upstream.effective);
+
+        let fork = instructions
+            .iter()
+            .find(|a| a.path == "acme:instructions/sample.instructions.md")
+            .expect("namespaced instruction present");
+        assert_eq!(fork.source, "acme");
+        assert!(fork.effective);
+        assert_eq!(fork.collections[0].id, "acme:sample");
+    }
+
+    #[test]
+    fn orphans_suggest_moved_successor() {
+        let mut enablement = EnablementFile::default();
+        enablement
+            .instructions
+            .insert("instructions/old/sample.instructions.md".into(), true);
+        let mut state = DomainState::new(sample_catalog(), enablement);
+        let orphan = state.orphans()[0].clone();
+        assert_eq!(
+            orphan.successor.as_deref(),
+            Some("instructions/sample.instructions.md")
+        );
+
+        assert!(state.remap_orphan(
+            orphan.kind,
+            &orphan.path,
+            orphan.successor.as_deref().unwrap()
+        ));
+        assert!(state.orphans().is_empty());
+        assert_eq!(
+            state
+                .enablement
+                .instructions
+                .get("instructions/sample.instructions.md"),
+            Some(&true)
+        );
+    }
+
+    #[test]
+    fn orphans_suggest_successor_with_the_same_content() {
+        let mut enablement = EnablementFile::default();
+        enablement
+            .instructions
+            .insert("instructions/legacy-rules.instructions.md".into(), true);
+        let mut state = DomainState::new(sample_catalog(), enablement);
+        assert_eq!(state.orphans()[0].successor, None);
+
+        state.set_known_hashes(
+            [(
+                "instructions/legacy-rules.instructions.md".to_string(),
+                "test-sha256".to_string(),
+            )]
+            .into(),
+        );
+        assert_eq!(
+            state.orphans()[0].successor.as_deref(),
+            Some("instructions/sample.instructions.md")
+        );
+    }
+
+    #[test]
+    fn sort_modes_reorder_assets() {
+        let mut enablement = EnablementFile::default();
+        enablement
+            .instructions
+            .insert("instructions/sample.instructions.md".into(), true);
+        let state = DomainState::new(sample_catalog(), enablement);
+        let mut assets: Vec<&AssetView> = state
+            .assets(AssetKind::Instruction)
+            .iter()
+            .chain(state.assets(AssetKind::Collection))
+            .collect();
+
+        sort_assets(&mut assets, SortMode::Kind, |a| a.local, |_| false);
+        assert_eq!(assets[0].kind, AssetKind::Instruction);
+
+        sort_assets(
+            &mut assets,
+            SortMode::LocalStatus,
+            |a| {
+                if a.kind == AssetKind::Collection {
+                    LocalStatus::Diff
+                } else {
+                    LocalStatus::Same
+                }
+            },
+            |_| false,
+        );
+        assert_eq!(assets[0].kind, AssetKind::Collection);
+
+        sort_assets(
+            &mut assets,
+            SortMode::Favorites,
+            |a| a.local,
+            |a| a.kind == AssetKind::Instruction,
+        );
+        assert_eq!(assets[0].kind, AssetKind::Instruction);
+        assert_eq!(SortMode::Favorites.next(), SortMode::Name);
+    }
+
+    #[test]
+    fn tag_counts_list_most_used_first() {
+        let mut catalog = sample_catalog();
+        let mut rust = catalog.instructions[0].clone();
+        rust.path = "instructions/rust.instructions.md".into();
+        rust.tags = vec!["rust".into(), "test".into(), "rust".into()];
+        catalog.instructions.push(rust);
+        let state = DomainState::new(catalog, EnablementFile::default());
+
+        assert_eq!(
+            state.tag_counts(AssetKind::Instruction),
+            [("test".to_string(), 2), ("rust".to_string(), 1)]
+        );
+        assert!(state.tag_counts(AssetKind::Prompt).is_empty());
+    }
+
     #[test]
     fn cleanup_removes_orphans() {
         let catalog = sample_catalog();
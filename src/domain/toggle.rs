@@ -1,4 +1,4 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 
 use super::{
     model::AssetKind,
@@ -8,22 +8,117 @@ use super::{
 #[derive(Debug, Clone)]
 pub struct ToggleResult {
     pub asset: AssetView,
+    /// Requirements that were off and got enabled along with the asset.
+    pub enabled_dependencies: Vec<(AssetKind, String)>,
+    /// Requirements that match no asset in the catalog.
+    pub missing_dependencies: Vec<String>,
+    /// Names of enabled assets that still require a disabled one.
+    pub required_by: Vec<String>,
 }
 
 pub fn toggle_asset(state: &mut DomainState, kind: AssetKind, path: &str) -> Result<ToggleResult> {
-    let (current_effective, inherited_value) = {
-        let assets = state.assets(kind);
-        let asset = assets
-            .iter()
-            .find(|a| a.path == path)
-            .ok_or_else(|| anyhow!("Asset not found for toggle: {}", path))?;
-        (
-            asset.effective,
-            asset.inherited.as_ref().map(|inherit| inherit.value),
-        )
-    };
+    let current_effective = state
+        .assets(kind)
+        .iter()
+        .find(|a| a.path == path)
+        .map(|asset| asset.effective)
+        .ok_or_else(|| anyhow!("Asset not found for toggle: {}", path))?;
+    set_asset(state, kind, path, !current_effective)
+}
+
+/// Make `path` effectively enabled or disabled, keeping an explicit entry
+/// only when the desired state differs from what its collections imply.
+/// Enabling also enables everything the asset (or, for a collection, its
+/// members) requires.
+pub fn set_asset(
+    state: &mut DomainState,
+    kind: AssetKind,
+    path: &str,
+    desired: bool,
+) -> Result<ToggleResult> {
+    if desired {
+        if let Some(owner) = state.local_file_owner(kind, path) {
+            bail!(
+                "{} is applied to the same file as the enabled {}; disable that one first",
+                path,
+                owner.path
+            );
+        }
+    }
+    set_explicit(state, kind, path, desired)?;
+
+    let mut enabled_dependencies = Vec::new();
+    let mut missing_dependencies = Vec::new();
+    let mut required_by = Vec::new();
+    if desired {
+        let roots = if kind == AssetKind::Collection {
+            state
+                .collection_members(path)
+                .iter()
+                .map(|member| member.path.clone())
+                .collect()
+        } else {
+            vec![path.to_string()]
+        };
+        for root in roots {
+            for dependency in state.dependency_chain(&root) {
+                match dependency.asset {
+                    None => missing_dependencies.push(dependency.path.to_string()),
+                    Some(asset) if !asset.effective => {
+                        enabled_dependencies.push((asset.kind, asset.path.clone()))
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        // Members of one collection may share requirements.
+        missing_dependencies.sort();
+        missing_dependencies.dedup();
+        enabled_dependencies.sort();
+        enabled_dependencies.dedup();
+        for (kind, path) in &enabled_dependencies {
+            set_explicit(state, *kind, path, true)?;
+        }
+    } else {
+        required_by = state
+            .required_by(path)
+            .into_iter()
+            .map(|view| view.name.clone())
+            .collect();
+    }
+
+    let updated_asset = state
+        .assets(kind)
+        .iter()
+        .find(|a| a.path == path)
+        .cloned()
+        .ok_or_else(|| anyhow!("Asset missing after toggle recompute: {}", path))?;
+
+    Ok(ToggleResult {
+        asset: updated_asset,
+        enabled_dependencies,
+        missing_dependencies,
+        required_by,
+    })
+}
+
+fn set_explicit(
+    state: &mut DomainState,
+    kind: AssetKind,
+    path: &str,
+    desired: bool,
+) -> Result<()> {
+    let asset = state
+        .assets(kind)
+        .iter()
+        .find(|a| a.path == path)
+        .ok_or_else(|| anyhow!("Asset not found for toggle: {}", path))?;
+    let inherited_value = asset
+        .inherited
+        .as_ref()
+        .map(|inherit| inherit.value)
+        .or(asset.rule.as_ref().map(|rule| rule.value));
 
-    let desired = !current_effective;
     let baseline = inherited_value.unwrap_or(false);
 
     let new_explicit = if desired == baseline {
@@ -40,82 +135,90 @@ pub fn toggle_asset(state: &mut DomainState, kind: AssetKind, path: &str) -> Res
     }
 
     state.recompute();
-
-    let updated_asset = state
-        .assets(kind)
-        .iter()
-        .find(|a| a.path == path)
-        .cloned()
-        .ok_or_else(|| anyhow!("Asset missing after toggle recompute: {}", path))?;
-
-    Ok(ToggleResult {
-        asset: updated_asset,
-    })
+    Ok(())
 }
 
-/// Analyze the impact of toggling a collection to help with user confirmation
-pub fn analyze_collection_toggle_impact(state: &DomainState, collection_path: &str) -> Result<CollectionToggleImpact> {
-    let collection = state.catalog.collections.iter()
+/// Analyze the impact of toggling a collection to help with user confirmation.
+/// The toggle runs on a copy of the state, so the prediction follows other
+/// parent collections, rules and requirements the way the real toggle does.
+pub fn analyze_collection_toggle_impact(
+    state: &DomainState,
+    collection_path: &str,
+) -> Result<CollectionToggleImpact> {
+    let collection = state
+        .catalog
+        .collections
+        .iter()
         .find(|c| c.path == collection_path)
         .ok_or_else(|| anyhow!("Collection not found: {}", collection_path))?;
-    
-    let collection_assets = state.assets(AssetKind::Collection);
-    let collection_view = collection_assets.iter()
+    let collection_view = state
+        .assets(AssetKind::Collection)
+        .iter()
         .find(|a| a.path == collection_path)
         .ok_or_else(|| anyhow!("Collection view not found: {}", collection_path))?;
-    
+
     let will_enable = !collection_view.effective;
-    let mut enable_count = 0;
-    let mut disable_count = 0;
-    let mut unchanged_count = 0;
-    let mut affected_members = Vec::new();
-    
-    for item in &collection.items {
-        let member_assets = state.assets(item.kind);
-        if let Some(member) = member_assets.iter().find(|a| a.path == item.path) {
-            let current_effective = member.effective;
-            let member_explicit = member.explicit;
-            
-            // Determine what the member's new effective state would be
-            let new_effective = if member_explicit.is_some() {
-                // If member has explicit setting, it won't change
-                current_effective
-            } else {
-                // Member will inherit the new collection state
-                will_enable
-            };
-            
-            let impact = if current_effective == new_effective {
-                unchanged_count += 1;
-                MemberToggleImpact::Unchanged
-            } else if new_effective {
-                enable_count += 1;
-                MemberToggleImpact::WillEnable
-            } else {
-                disable_count += 1;
-                MemberToggleImpact::WillDisable
-            };
-            
-            affected_members.push(MemberImpact {
-                path: item.path.clone(),
-                name: member.name.clone(),
-                kind: item.kind,
-                current_effective,
-                new_effective,
-                impact,
-            });
-        }
-    }
-    
-    Ok(CollectionToggleImpact {
+    let mut toggled = state.clone();
+    set_asset(
+        &mut toggled,
+        AssetKind::Collection,
+        collection_path,
+        will_enable,
+    )?;
+    let effective_after = |kind: AssetKind, path: &str| {
+        toggled
+            .assets(kind)
+            .iter()
+            .find(|a| a.path == path)
+            .map(|a| a.effective)
+    };
+
+    let mut impact = CollectionToggleImpact {
         collection_name: collection.name.clone(),
         collection_will_enable: will_enable,
         total_members: collection.items.len(),
-        enable_count,
-        disable_count,
-        unchanged_count,
-        affected_members,
-    })
+        enable_count: 0,
+        disable_count: 0,
+        unchanged_count: 0,
+        affected_members: Vec::new(),
+        dependencies: Vec::new(),
+    };
+    for item in &collection.items {
+        let Some(member) = state.assets(item.kind).iter().find(|a| a.path == item.path) else {
+            continue;
+        };
+        let new_effective = effective_after(item.kind, &item.path).unwrap_or(member.effective);
+        let change = MemberToggleImpact::between(member.effective, new_effective);
+        match change {
+            MemberToggleImpact::WillEnable => impact.enable_count += 1,
+            MemberToggleImpact::WillDisable => impact.disable_count += 1,
+            MemberToggleImpact::Unchanged => impact.unchanged_count += 1,
+        }
+        impact.affected_members.push(MemberImpact::new(member, change));
+    }
+
+    for kind in [
+        AssetKind::Prompt,
+        AssetKind::Instruction,
+        AssetKind::ChatMode,
+        AssetKind::Agent,
+        AssetKind::McpServer,
+    ] {
+        for asset in state.assets(kind) {
+            let member = collection
+                .items
+                .iter()
+                .any(|item| item.kind == kind && item.path == asset.path);
+            let change = MemberToggleImpact::between(
+                asset.effective,
+                effective_after(kind, &asset.path).unwrap_or(asset.effective),
+            );
+            if !member && change != MemberToggleImpact::Unchanged {
+                impact.dependencies.push(MemberImpact::new(asset, change));
+            }
+        }
+    }
+    Ok(impact)
 }
 
 #[derive(Debug, Clone)]
@@ -127,6 +230,9 @@ pub struct CollectionToggleImpact {
     pub disable_count: usize,
     pub unchanged_count: usize,
     pub affected_members: Vec<MemberImpact>,
+    /// Assets outside the collection whose state changes with it: the
+    /// members' requirements that get enabled too.
+    pub dependencies: Vec<MemberImpact>,
 }
 
 #[derive(Debug, Clone)]
@@ -134,11 +240,20 @@ pub struct MemberImpact {
     pub path: String,
     pub name: String,
     pub kind: AssetKind,
-    pub current_effective: bool,
-    pub new_effective: bool,
     pub impact: MemberToggleImpact,
 }
 
+impl MemberImpact {
+    fn new(asset: &AssetView, impact: MemberToggleImpact) -> Self {
+        Self {
+            path: asset.path.clone(),
+            name: asset.name.clone(),
+            kind: asset.kind,
+            impact,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum MemberToggleImpact {
     WillEnable,
@@ -146,11 +261,23 @@ pub enum MemberToggleImpact {
     Unchanged,
 }
 
+impl MemberToggleImpact {
+    fn between(current: bool, new: bool) -> Self {
+        match (current, new) {
+            (false, true) => MemberToggleImpact::WillEnable,
+            (true, false) => MemberToggleImpact::WillDisable,
+            _ => MemberToggleImpact::Unchanged,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::domain::{
-        model::{Catalog, Collection, CollectionItem, EnablementFile, Instruction, Prompt},
+        model::{
+            Catalog, Collection, CollectionItem, EnablementFile, Instruction, Prompt, Resolution,
+        },
         state::DomainState,
     };
 
@@ -163,6 +290,7 @@ mod tests {
             apply_to: vec!["language:rust".into()],
             tags: vec!["test".into()],
             sha256: "test-sha256".into(),
+            requires: Vec::new(),
         };
 
         let prompt = Prompt {
@@ -173,6 +301,7 @@ mod tests {
             mode: "chat".into(),
             tags: vec!["test".into()],
             sha256: "test-sha256".into(),
+            requires: Vec::new(),
         };
 
         let collection = Collection {
@@ -226,8 +355,6 @@ mod tests {
         assert_eq!(impact.affected_members.len(), 2);
         
         for member in &impact.affected_members {
-            assert!(!member.current_effective);
-            assert!(member.new_effective);
             assert_eq!(member.impact, MemberToggleImpact::WillEnable);
         }
     }
@@ -253,8 +380,6 @@ mod tests {
         assert_eq!(impact.affected_members.len(), 2);
         
         for member in &impact.affected_members {
-            assert!(member.current_effective);
-            assert!(!member.new_effective);
             assert_eq!(member.impact, MemberToggleImpact::WillDisable);
         }
     }
@@ -282,14 +407,67 @@ mod tests {
         
         let instruction_impact = impact.affected_members.iter()
             .find(|m| m.kind == AssetKind::Instruction).unwrap();
-        assert!(!instruction_impact.current_effective);
-        assert!(!instruction_impact.new_effective);
         assert_eq!(instruction_impact.impact, MemberToggleImpact::Unchanged);
         
         let prompt_impact = impact.affected_members.iter()
             .find(|m| m.kind == AssetKind::Prompt).unwrap();
-        assert!(prompt_impact.current_effective);
-        assert!(!prompt_impact.new_effective);
         assert_eq!(prompt_impact.impact, MemberToggleImpact::WillDisable);
     }
+
+    #[test]
+    fn impact_follows_other_parents_and_requirements() {
+        let mut catalog = multi_catalog();
+        let mut other = catalog.collections[0].clone();
+        other.path = "collections/other.collection.md".into();
+        other.id = "other-collection".into();
+        other.items.truncate(1);
+        catalog.collections.push(other);
+        let mut helper = catalog.prompts[0].clone();
+        helper.path = "prompts/helper.prompt.md".into();
+        helper.name = "Helper".into();
+        catalog.prompts[0].requires = vec![helper.path.clone()];
+        catalog.prompts.push(helper);
+        let catalog = catalog.finalize();
+        let collection_path = catalog.collections[0].path.clone();
+        let mut enablement = EnablementFile::default();
+        enablement.inheritance.resolution = Resolution::AnyOn;
+        enablement
+            .collections
+            .insert("collections/other.collection.md".into(), true);
+        let mut state = DomainState::new(catalog, enablement);
+
+        let impact = analyze_collection_toggle_impact(&state, &collection_path).unwrap();
+        // The instruction is already on through the other collection.
+        assert_eq!((impact.enable_count, impact.unchanged_count), (1, 1));
+        assert_eq!(impact.dependencies.len(), 1);
+        assert_eq!(impact.dependencies[0].name, "Helper");
+        assert_eq!(impact.dependencies[0].impact, MemberToggleImpact::WillEnable);
+
+        set_asset(&mut state, AssetKind::Collection, &collection_path, true).unwrap();
+        let impact = analyze_collection_toggle_impact(&state, &collection_path).unwrap();
+        // Any-on keeps the shared instruction on.
+        assert_eq!((impact.disable_count, impact.unchanged_count), (1, 1));
+        let instruction = impact
+            .affected_members
+            .iter()
+            .find(|m| m.kind == AssetKind::Instruction)
+            .unwrap();
+        assert_eq!(instruction.impact, MemberToggleImpact::Unchanged);
+    }
+
+    #[test]
+    fn refuses_to_enable_a_second_source_for_the_same_file() {
+        let mut catalog = multi_catalog();
+        let mut forked = catalog.prompts[0].clone();
+        forked.path = "fork:prompts/test.prompt.md".into();
+        catalog.prompts.push(forked);
+        let mut state = DomainState::new(catalog.finalize(), EnablementFile::default());
+
+        set_asset(&mut state, AssetKind::Prompt, "prompts/test.prompt.md", true).unwrap();
+        let forked = "fork:prompts/test.prompt.md";
+        assert!(set_asset(&mut state, AssetKind::Prompt, forked, true).is_err());
+
+        set_asset(&mut state, AssetKind::Prompt, "prompts/test.prompt.md", false).unwrap();
+        set_asset(&mut state, AssetKind::Prompt, forked, true).unwrap();
+    }
 }
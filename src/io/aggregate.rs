@@ -0,0 +1,114 @@
+use std::fmt::Write as _;
+
+use anyhow::Result;
+
+use crate::domain::{model::AssetKind, state::DomainState};
+
+use super::{
+    patch::split_front_matter, paths::RepoPaths, report, sync::upstream_content,
+    upstream::UpstreamRoots,
+};
+
+/// Markers around the part of `.github/copilot-instructions.md` that
+/// `[instructions] aggregate` maintains; text outside them is kept.
+pub const AGGREGATE_START: &str = "<!-- awesome-copilot-instructions:start -->";
+pub const AGGREGATE_END: &str = "<!-- awesome-copilot-instructions:end -->";
+
+/// The bodies of every effectively enabled instruction, front matter
+/// removed, each between markers naming its catalog path. `applyTo` globs
+/// are noted since the single file applies to everything.
+pub fn render(state: &DomainState, upstream: &UpstreamRoots) -> Result<String> {
+    let mut out = String::new();
+    for asset in state
+        .assets(AssetKind::Instruction)
+        .iter()
+        .filter(|asset| asset.effective)
+    {
+        let content = String::from_utf8_lossy(&upstream_content(upstream, &asset.path)?)
+            .replace("\r\n", "\n");
+        let body = split_front_matter(&content).map_or(content.as_str(), |(_, body)| body);
+        let _ = writeln!(out, "<!-- instruction:start {} -->", asset.path);
+        let _ = writeln!(out, "## {}\n", asset.name);
+        if !asset.apply_to.is_empty() {
+            let _ = writeln!(out, "_Applies to `{}`._\n", asset.apply_to.join("`, `"));
+        }
+        let _ = writeln!(out, "{}", body.trim());
+        let _ = writeln!(out, "<!-- instruction:end {} -->\n", asset.path);
+    }
+    Ok(out)
+}
+
+/// Rewrite the marked section of `.github/copilot-instructions.md` with
+/// `section`. Returns whether the file changed.
+pub fn write(paths: &RepoPaths, section: &str) -> Result<bool> {
+    report::inject_between(
+        &paths.copilot_instructions,
+        AGGREGATE_START,
+        AGGREGATE_END,
+        section,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::domain::model::{Catalog, EnablementFile, Instruction, PRIMARY_SOURCE};
+
+    #[test]
+    fn concatenates_enabled_instructions_between_markers() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = dir.path().join("upstream");
+        fs::create_dir_all(content.join("instructions")).unwrap();
+        let mut catalog = Catalog::default();
+        for (slug, ext, body) in [
+            ("rust", "rs", "Use clippy.\r\n"),
+            ("go", "go", "Use gofmt.\n"),
+        ] {
+            let path = format!("instructions/{slug}.instructions.md");
+            fs::write(
+                content.join(&path),
+                format!("---\r\napplyTo: '**/*.{ext}'\r\n---\r\n{body}"),
+            )
+            .unwrap();
+            catalog.instructions.push(Instruction {
+                path,
+                slug: slug.into(),
+                name: slug.to_uppercase(),
+                description: String::new(),
+                apply_to: vec![format!("**/*.{ext}")],
+                tags: Vec::new(),
+                sha256: String::new(),
+                requires: Vec::new(),
+            });
+        }
+        let mut enablement = EnablementFile::default();
+        enablement
+            .instructions
+            .insert("instructions/rust.instructions.md".into(), true);
+        let state = DomainState::new(catalog, enablement);
+        let mut upstream = UpstreamRoots::default();
+        upstream.insert(PRIMARY_SOURCE, content);
+
+        let section = render(&state, &upstream).unwrap();
+        assert_eq!(
+            section,
+            "<!-- instruction:start instructions/rust.instructions.md -->\n\
+             ## RUST\n\n\
+             _Applies to `**/*.rs`._\n\n\
+             Use clippy.\n\
+             <!-- instruction:end instructions/rust.instructions.md -->\n\n"
+        );
+
+        let paths = RepoPaths::new(dir.path().join("repo"));
+        fs::create_dir_all(&paths.github_dir).unwrap();
+        fs::write(&paths.copilot_instructions, "# House rules\n").unwrap();
+        assert!(write(&paths, &section).unwrap());
+        assert!(!write(&paths, &section).unwrap());
+        let written = fs::read_to_string(&paths.copilot_instructions).unwrap();
+        assert!(
+            written.starts_with("# House rules\n\n<!-- awesome-copilot-instructions:start -->\n")
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use walkdir::WalkDir;
+
+use super::{paths::RepoPaths, symlink, sync};
+
+/// A file saved under `.awesome-copilot-tui/backups/<timestamp>/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupEntry {
+    pub timestamp: String,
+    /// Path below `.github/` the backup was taken from.
+    pub relative: PathBuf,
+    pub path: PathBuf,
+}
+
+impl BackupEntry {
+    pub fn target(&self, paths: &RepoPaths) -> PathBuf {
+        paths.github_dir.join(&self.relative)
+    }
+}
+
+/// List every backup, newest first.
+pub fn list_backups(paths: &RepoPaths) -> Result<Vec<BackupEntry>> {
+    if !paths.backups_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut entries = Vec::new();
+    for entry in WalkDir::new(&paths.backups_dir)
+        .min_depth(2)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let below = entry
+            .path()
+            .strip_prefix(&paths.backups_dir)
+            .context("computing backup path")?;
+        let mut components = below.components();
+        let timestamp = components
+            .next()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .unwrap_or_default();
+        entries.push(BackupEntry {
+            timestamp,
+            relative: components.as_path().to_path_buf(),
+            path: entry.path().to_path_buf(),
+        });
+    }
+    entries.sort_by(|a, b| {
+        b.timestamp
+            .cmp(&a.timestamp)
+            .then_with(|| a.relative.cmp(&b.relative))
+    });
+    Ok(entries)
+}
+
+/// Unified diff turning the current file into the backup.
+pub fn diff_against_current(paths: &RepoPaths, entry: &BackupEntry) -> Result<String> {
+    let backup = read_text(&entry.path)?;
+    let target = entry.target(paths);
+    let current = if target.exists() {
+        read_text(&target)?
+    } else {
+        String::new()
+    };
+    if current == backup {
+        return Ok(String::new());
+    }
+    Ok(diffy::create_patch(&current, &backup).to_string())
+}
+
+/// Copy a backup back into `.github/`, backing up the file it replaces when
+/// that one holds different content.
+pub fn restore_backup(paths: &RepoPaths, entry: &BackupEntry) -> Result<PathBuf> {
+    let target = entry.target(paths);
+    if target.exists() && read_text(&target)? != read_text(&entry.path)? {
+        sync::backup_file(paths, &target)?;
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+    }
+    symlink::unlink(&target)?;
+    fs::copy(&entry.path, &target)
+        .with_context(|| format!("restoring {} -> {}", entry.path.display(), target.display()))?;
+    Ok(target)
+}
+
+fn read_text(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{domain::model::PRIMARY_SOURCE, io::upstream::UpstreamRoots};
+
+    #[test]
+    fn restoring_over_a_link_leaves_the_snapshot_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path().join("repo"));
+        let snapshot = dir.path().join("upstream");
+        fs::create_dir_all(snapshot.join("prompts")).unwrap();
+        fs::write(snapshot.join("prompts/sample.prompt.md"), "upstream\n").unwrap();
+        let mut upstream = UpstreamRoots::default();
+        upstream.insert(PRIMARY_SOURCE, snapshot.clone());
+        let local = paths.prompts_dir.join("sample.prompt.md");
+        symlink::link_asset(&paths, &upstream, &local, "prompts/sample.prompt.md").unwrap();
+        let saved = paths
+            .backups_dir
+            .join("20240101T000000.000Z/prompts/sample.prompt.md");
+        fs::create_dir_all(saved.parent().unwrap()).unwrap();
+        fs::write(&saved, "edited\n").unwrap();
+
+        let entry = list_backups(&paths).unwrap().remove(0);
+        assert_eq!(restore_backup(&paths, &entry).unwrap(), local);
+        assert!(!symlink::is_symlink(&local));
+        assert_eq!(fs::read_to_string(&local).unwrap(), "edited\n");
+        assert_eq!(
+            fs::read_to_string(snapshot.join("prompts/sample.prompt.md")).unwrap(),
+            "upstream\n"
+        );
+    }
+}
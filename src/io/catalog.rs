@@ -1,18 +1,21 @@
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use std::{collections::BTreeMap, fs, path::Path};
 
 use anyhow::{Context, Result};
 use serde::Deserialize;
-use sha2::{Digest, Sha256};
 use walkdir::WalkDir;
 
 use crate::domain::model::{
-    AssetKind, Catalog, ChatMode, Collection, CollectionItem, Instruction, Prompt,
+    namespaced, split_source, Agent, AssetKind, Catalog, ChatMode, Collection, CollectionItem,
+    Instruction, McpServer, Prompt, LOCAL_SOURCE,
 };
 
-use super::{paths::RepoPaths, upstream};
+use super::{
+    paths::RepoPaths,
+    sync,
+    upstream::{
+        self, FetchOptions, ProgressFn, Refresh, UpstreamRoots, UpstreamSnapshot, UpstreamSource,
+    },
+};
 
 #[derive(Debug, Deserialize)]
 struct FrontMatter {
@@ -24,8 +27,12 @@ struct FrontMatter {
     mode: String,
     #[serde(default)]
     tools: Vec<String>,
-    #[serde(default)]
+    #[serde(default, alias = "applyTo")]
     apply_to: String,
+    /// Paths of other assets, relative to the repository root, e.g.
+    /// `instructions/rust.instructions.md`.
+    #[serde(default)]
+    requires: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -42,6 +49,19 @@ struct CollectionYaml {
     items: Vec<CollectionItemYaml>,
 }
 
+/// An `mcp/*.mcp.json` asset: metadata plus the server definition that goes
+/// under `servers` in `.vscode/mcp.json`.
+#[derive(Debug, Deserialize)]
+pub struct McpServerFile {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub server: serde_json::Map<String, serde_json::Value>,
+}
+
 #[derive(Debug, Deserialize)]
 struct CollectionItemYaml {
     #[serde(default)]
@@ -52,30 +72,138 @@ struct CollectionItemYaml {
 
 pub struct CatalogLoad {
     pub catalog: Catalog,
+    /// Catalog files that could not be parsed.
     pub warnings: Vec<String>,
-    pub upstream_dir: PathBuf,
+    /// Snapshots served from the cache and sources that were skipped.
+    pub upstream_warnings: Vec<String>,
+    pub upstream: UpstreamRoots,
 }
 
-pub fn load_catalog(paths: &RepoPaths) -> Result<CatalogLoad> {
+/// Load and merge the catalogs of every source, then the repository's local
+/// assets. The first source is the primary one and must be available;
+/// secondary sources that fail to load only produce warnings.
+pub fn load_catalog(
+    paths: &RepoPaths,
+    sources: &[UpstreamSource],
+    fetch: FetchOptions,
+    progress: ProgressFn<'_>,
+) -> Result<CatalogLoad> {
     let mut warnings = Vec::new();
+    let mut upstream_warnings = Vec::new();
+    let mut catalog = Catalog::default();
+    let mut upstream = UpstreamRoots::default();
+
+    for source in sources {
+        match load_source(paths, source, fetch, progress, &mut warnings) {
+            Ok((source_catalog, mut snapshot)) => {
+                upstream_warnings.append(&mut snapshot.warnings);
+                catalog.merge(source_catalog, &source.id);
+                upstream.record_snapshot(&source.id, &snapshot);
+                upstream.insert(&source.id, snapshot.content_dir);
+            }
+            Err(err) if source.is_primary() => return Err(err),
+            Err(err) => upstream_warnings.push(format!(
+                "Skipping source '{}' ({source}): {err:#}",
+                source.id
+            )),
+        }
+    }
 
-    // Ensure upstream snapshot is available
-    let snapshot =
-        upstream::ensure_snapshot(paths, false).context("failed to obtain upstream snapshot")?;
-
-    warnings.extend(snapshot.warnings);
-
-    // Build catalog from upstream snapshot
-    let catalog = build_catalog_from_snapshot(&snapshot.content_dir, &mut warnings)
-        .context("failed to build catalog from upstream snapshot")?;
+    if paths.local_dir.is_dir() {
+        match build_catalog_from_snapshot(&paths.local_dir, &mut warnings) {
+            Ok(local) => {
+                catalog.merge(local, LOCAL_SOURCE);
+                upstream.insert(LOCAL_SOURCE, paths.local_dir.clone());
+            }
+            Err(err) => warnings.push(format!("Skipping local assets: {err:#}")),
+        }
+    }
 
+    warnings.extend(shared_file_warnings(&catalog));
     Ok(CatalogLoad {
         catalog: catalog.finalize(),
         warnings,
-        upstream_dir: snapshot.content_dir,
+        upstream_warnings,
+        upstream,
     })
 }
 
+/// One warning per local file that assets of several sources apply to, since
+/// only one of them can be enabled at a time.
+fn shared_file_warnings(catalog: &Catalog) -> Vec<String> {
+    let mut by_file: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    let paths = catalog
+        .prompts
+        .iter()
+        .map(|a| &a.path)
+        .chain(catalog.instructions.iter().map(|a| &a.path))
+        .chain(catalog.chat_modes.iter().map(|a| &a.path))
+        .chain(catalog.agents.iter().map(|a| &a.path));
+    for path in paths {
+        by_file.entry(split_source(path).1).or_default().push(path);
+    }
+    by_file
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(file, paths)| {
+            format!(
+                "{} all apply to {file}; only one of them can be enabled",
+                paths.join(", ")
+            )
+        })
+        .collect()
+}
+
+/// Parse one file of the local catalog and add it to `catalog` under the
+/// `local` source, returning its catalog path. The caller re-finalizes the
+/// catalog.
+pub fn add_local_asset(
+    catalog: &mut Catalog,
+    paths: &RepoPaths,
+    kind: AssetKind,
+    relative: &str,
+) -> Result<String> {
+    let file = paths.local_dir.join(relative);
+    let mut local = Catalog::default();
+    match kind {
+        AssetKind::Prompt => local.prompts.push(parse_prompt(&file, &paths.local_dir)?),
+        AssetKind::Instruction => local
+            .instructions
+            .push(parse_instruction(&file, &paths.local_dir)?),
+        AssetKind::ChatMode => local
+            .chat_modes
+            .push(parse_chat_mode(&file, &paths.local_dir)?),
+        AssetKind::Agent => local.agents.push(parse_agent(&file, &paths.local_dir)?),
+        AssetKind::McpServer => local
+            .mcp_servers
+            .push(parse_mcp_server(&file, &paths.local_dir)?),
+        AssetKind::Collection => local
+            .collections
+            .push(parse_collection(&file, &paths.local_dir)?),
+    }
+    catalog.merge(local, LOCAL_SOURCE);
+    Ok(namespaced(LOCAL_SOURCE, relative))
+}
+
+fn load_source(
+    paths: &RepoPaths,
+    source: &UpstreamSource,
+    fetch: FetchOptions,
+    progress: ProgressFn<'_>,
+    warnings: &mut Vec<String>,
+) -> Result<(Catalog, UpstreamSnapshot)> {
+    // Ensure upstream snapshot is available
+    let force = fetch.refresh == Refresh::Force;
+    let snapshot = upstream::ensure_snapshot(paths, source, force, fetch, progress)
+        .with_context(|| format!("failed to obtain snapshot for {source}"))?;
+
+    // Build catalog from upstream snapshot
+    let catalog = build_catalog_from_snapshot(&snapshot.content_dir, warnings)
+        .with_context(|| format!("failed to build catalog from {source}"))?;
+
+    Ok((catalog, snapshot))
+}
+
 fn build_catalog_from_snapshot(content_dir: &Path, warnings: &mut Vec<String>) -> Result<Catalog> {
     let mut catalog = Catalog::default();
 
@@ -88,6 +216,12 @@ fn build_catalog_from_snapshot(content_dir: &Path, warnings: &mut Vec<String>) -
     // Collect chat modes
     catalog.chat_modes = collect_chat_modes(content_dir, warnings)?;
 
+    // Collect agents
+    catalog.agents = collect_agents(content_dir, warnings)?;
+
+    // Collect MCP servers
+    catalog.mcp_servers = collect_mcp_servers(content_dir, warnings)?;
+
     // Collect collections
     catalog.collections = collect_collections(content_dir, warnings)?;
 
@@ -194,6 +328,66 @@ fn collect_chat_modes(content_dir: &Path, warnings: &mut Vec<String>) -> Result<
     Ok(chat_modes)
 }
 
+fn collect_agents(content_dir: &Path, warnings: &mut Vec<String>) -> Result<Vec<Agent>> {
+    let agents_dir = content_dir.join("agents");
+    if !agents_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut agents = Vec::new();
+
+    for entry in WalkDir::new(&agents_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.file_name().to_string_lossy().ends_with(".agent.md"))
+    {
+        match parse_agent(entry.path(), content_dir) {
+            Ok(agent) => agents.push(agent),
+            Err(err) => {
+                warnings.push(format!(
+                    "Failed to parse agent {}: {}",
+                    entry.path().display(),
+                    err
+                ));
+            }
+        }
+    }
+
+    agents.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(agents)
+}
+
+fn collect_mcp_servers(content_dir: &Path, warnings: &mut Vec<String>) -> Result<Vec<McpServer>> {
+    let mcp_dir = content_dir.join("mcp");
+    if !mcp_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut servers = Vec::new();
+
+    for entry in WalkDir::new(&mcp_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.file_name().to_string_lossy().ends_with(".mcp.json"))
+    {
+        match parse_mcp_server(entry.path(), content_dir) {
+            Ok(server) => servers.push(server),
+            Err(err) => {
+                warnings.push(format!(
+                    "Failed to parse MCP server {}: {}",
+                    entry.path().display(),
+                    err
+                ));
+            }
+        }
+    }
+
+    servers.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(servers)
+}
+
 fn collect_collections(content_dir: &Path, warnings: &mut Vec<String>) -> Result<Vec<Collection>> {
     let collections_dir = content_dir.join("collections");
     if !collections_dir.exists() {
@@ -257,6 +451,7 @@ fn parse_prompt(file_path: &Path, content_dir: &Path) -> Result<Prompt> {
         mode: front_matter.mode,
         tags: front_matter.tags,
         sha256,
+        requires: front_matter.requires,
     })
 }
 
@@ -294,6 +489,7 @@ fn parse_instruction(file_path: &Path, content_dir: &Path) -> Result<Instruction
         apply_to,
         tags: front_matter.tags,
         sha256,
+        requires: front_matter.requires,
     })
 }
 
@@ -325,6 +521,83 @@ fn parse_chat_mode(file_path: &Path, content_dir: &Path) -> Result<ChatMode> {
         tools: front_matter.tools,
         tags: front_matter.tags,
         sha256,
+        requires: front_matter.requires,
+    })
+}
+
+fn parse_agent(file_path: &Path, content_dir: &Path) -> Result<Agent> {
+    let content = fs::read_to_string(file_path)
+        .with_context(|| format!("reading agent file {}", file_path.display()))?;
+
+    let relative_path = file_path
+        .strip_prefix(content_dir)
+        .with_context(|| format!("computing relative path for {}", file_path.display()))?
+        .to_string_lossy()
+        .to_string();
+
+    let slug = file_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .replace(".agent", "");
+
+    let front_matter = parse_front_matter(&content)?;
+    let name = extract_title(&content).unwrap_or_else(|| slug_to_title(&slug));
+    let sha256 = compute_sha256(&content);
+
+    Ok(Agent {
+        path: relative_path,
+        slug,
+        name,
+        description: front_matter.description,
+        tools: front_matter.tools,
+        tags: front_matter.tags,
+        sha256,
+        requires: front_matter.requires,
+    })
+}
+
+/// Read an MCP server asset file.
+pub fn read_mcp_server(file_path: &Path) -> Result<McpServerFile> {
+    let content = fs::read_to_string(file_path)
+        .with_context(|| format!("reading MCP server file {}", file_path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("parsing JSON in {}", file_path.display()))
+}
+
+fn parse_mcp_server(file_path: &Path, content_dir: &Path) -> Result<McpServer> {
+    let content = fs::read_to_string(file_path)
+        .with_context(|| format!("reading MCP server file {}", file_path.display()))?;
+
+    let relative_path = file_path
+        .strip_prefix(content_dir)
+        .with_context(|| format!("computing relative path for {}", file_path.display()))?
+        .to_string_lossy()
+        .to_string();
+
+    let server: McpServerFile = serde_json::from_str(&content)
+        .with_context(|| format!("parsing JSON in {}", file_path.display()))?;
+
+    let slug = file_path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .trim_end_matches(".mcp.json")
+        .to_string();
+
+    let name = if server.name.is_empty() {
+        slug_to_title(&slug)
+    } else {
+        server.name
+    };
+
+    Ok(McpServer {
+        path: relative_path,
+        slug,
+        name,
+        description: server.description,
+        tags: server.tags,
+        sha256: compute_sha256(&content),
     })
 }
 
@@ -367,6 +640,8 @@ fn parse_collection(file_path: &Path, content_dir: &Path) -> Result<Collection>
                 "prompt" => AssetKind::Prompt,
                 "instruction" => AssetKind::Instruction,
                 "chatmode" | "chat_mode" => AssetKind::ChatMode,
+                "agent" => AssetKind::Agent,
+                "mcp" | "mcp-server" => AssetKind::McpServer,
                 "collection" => AssetKind::Collection,
                 _ => return None,
             };
@@ -414,6 +689,7 @@ impl Default for FrontMatter {
             mode: String::new(),
             tools: Vec::new(),
             apply_to: String::new(),
+            requires: Vec::new(),
         }
     }
 }
@@ -441,7 +717,5 @@ fn slug_to_title(slug: &str) -> String {
 }
 
 fn compute_sha256(content: &str) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(content.as_bytes());
-    hex::encode(hasher.finalize())
+    sync::hash_bytes(content.as_bytes())
 }
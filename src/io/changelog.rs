@@ -0,0 +1,303 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::model::{AssetKind, Catalog};
+
+use super::{paths::RepoPaths, upstream::UpstreamRoots};
+
+/// What an asset looked like in one snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestEntry {
+    pub kind: AssetKind,
+    pub path: String,
+    pub name: String,
+    pub sha256: String,
+}
+
+/// The catalog of one set of snapshot commits, reduced to what is needed to
+/// tell what changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CatalogDigest {
+    /// Snapshot commit per source id.
+    pub commits: BTreeMap<String, String>,
+    pub assets: Vec<DigestEntry>,
+}
+
+impl CatalogDigest {
+    pub fn new(catalog: &Catalog, upstream: &UpstreamRoots) -> Self {
+        let entry = |kind, path: &str, name: &str, sha256: &str| DigestEntry {
+            kind,
+            path: path.to_string(),
+            name: name.to_string(),
+            sha256: sha256.to_string(),
+        };
+        let mut assets = Vec::new();
+        for prompt in &catalog.prompts {
+            assets.push(entry(
+                AssetKind::Prompt,
+                &prompt.path,
+                &prompt.name,
+                &prompt.sha256,
+            ));
+        }
+        for instruction in &catalog.instructions {
+            assets.push(entry(
+                AssetKind::Instruction,
+                &instruction.path,
+                &instruction.name,
+                &instruction.sha256,
+            ));
+        }
+        for mode in &catalog.chat_modes {
+            assets.push(entry(
+                AssetKind::ChatMode,
+                &mode.path,
+                &mode.name,
+                &mode.sha256,
+            ));
+        }
+        for agent in &catalog.agents {
+            assets.push(entry(
+                AssetKind::Agent,
+                &agent.path,
+                &agent.name,
+                &agent.sha256,
+            ));
+        }
+        for server in &catalog.mcp_servers {
+            assets.push(entry(
+                AssetKind::McpServer,
+                &server.path,
+                &server.name,
+                &server.sha256,
+            ));
+        }
+        for collection in &catalog.collections {
+            assets.push(entry(
+                AssetKind::Collection,
+                &collection.path,
+                &collection.name,
+                &collection.sha256,
+            ));
+        }
+        Self {
+            commits: upstream.commits().clone(),
+            assets,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CatalogChange {
+    Added(DigestEntry),
+    Removed(DigestEntry),
+    /// Same content under a new path.
+    Renamed {
+        from: DigestEntry,
+        to: DigestEntry,
+    },
+    /// Same path, different content.
+    Changed(DigestEntry),
+}
+
+impl CatalogChange {
+    pub fn entry(&self) -> &DigestEntry {
+        match self {
+            CatalogChange::Added(entry)
+            | CatalogChange::Removed(entry)
+            | CatalogChange::Changed(entry) => entry,
+            CatalogChange::Renamed { to, .. } => to,
+        }
+    }
+}
+
+/// Upstream changes between the previous and the current snapshots.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changelog {
+    pub from: BTreeMap<String, String>,
+    pub to: BTreeMap<String, String>,
+    pub changes: Vec<CatalogChange>,
+}
+
+/// Persisted in the cache so the changelog survives restarts until the next
+/// upstream change.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct History {
+    previous: Option<CatalogDigest>,
+    current: CatalogDigest,
+}
+
+/// Compare two digests. An asset that disappeared while another with the
+/// same kind and content appeared counts as a rename.
+pub fn diff(old: &CatalogDigest, new: &CatalogDigest) -> Vec<CatalogChange> {
+    let old_paths: BTreeMap<&str, &DigestEntry> = old
+        .assets
+        .iter()
+        .map(|entry| (entry.path.as_str(), entry))
+        .collect();
+    let new_paths: BTreeSet<&str> = new.assets.iter().map(|entry| entry.path.as_str()).collect();
+    let mut removed: Vec<&DigestEntry> = old
+        .assets
+        .iter()
+        .filter(|entry| !new_paths.contains(entry.path.as_str()))
+        .collect();
+
+    let mut changes = Vec::new();
+    for entry in &new.assets {
+        match old_paths.get(entry.path.as_str()) {
+            Some(before) if before.sha256 != entry.sha256 => {
+                changes.push(CatalogChange::Changed(entry.clone()));
+            }
+            Some(_) => {}
+            None => match removed
+                .iter()
+                .position(|gone| gone.kind == entry.kind && gone.sha256 == entry.sha256)
+            {
+                Some(index) => changes.push(CatalogChange::Renamed {
+                    from: removed.remove(index).clone(),
+                    to: entry.clone(),
+                }),
+                None => changes.push(CatalogChange::Added(entry.clone())),
+            },
+        }
+    }
+    changes.extend(
+        removed
+            .into_iter()
+            .map(|entry| CatalogChange::Removed(entry.clone())),
+    );
+    changes
+}
+
+/// Record `digest` as the current catalog and return the changes since the
+/// previous snapshots. Loading the same commits again keeps the last
+/// changelog.
+pub fn record(paths: &RepoPaths, digest: CatalogDigest) -> Result<Changelog> {
+    let mut history = read_history(paths)?;
+    if advance(&mut history, digest) {
+        if let Some(parent) = paths.catalog_history.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+        }
+        fs::write(&paths.catalog_history, serde_json::to_string(&history)?)
+            .with_context(|| format!("writing {}", paths.catalog_history.display()))?;
+    }
+    Ok(changelog(&history))
+}
+
+/// The changelog [`record`] would return for `digest`, leaving the history
+/// file untouched.
+pub fn peek(paths: &RepoPaths, digest: CatalogDigest) -> Result<Changelog> {
+    let mut history = read_history(paths)?;
+    advance(&mut history, digest);
+    Ok(changelog(&history))
+}
+
+fn read_history(paths: &RepoPaths) -> Result<History> {
+    match fs::read_to_string(&paths.catalog_history) {
+        // A corrupt cache file just restarts the history.
+        Ok(content) => Ok(serde_json::from_str(&content).unwrap_or_default()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(History::default()),
+        Err(err) => {
+            Err(err).with_context(|| format!("reading {}", paths.catalog_history.display()))
+        }
+    }
+}
+
+/// Make `digest` the current catalog; false when its commits already are.
+fn advance(history: &mut History, digest: CatalogDigest) -> bool {
+    if history.current.commits == digest.commits {
+        return false;
+    }
+    let first_load = history.current.commits.is_empty();
+    history.previous = (!first_load).then(|| std::mem::take(&mut history.current));
+    history.current = digest;
+    true
+}
+
+fn changelog(history: &History) -> Changelog {
+    match &history.previous {
+        Some(previous) => Changelog {
+            from: previous.commits.clone(),
+            to: history.current.commits.clone(),
+            changes: diff(previous, &history.current),
+        },
+        None => Changelog::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, sha256: &str) -> DigestEntry {
+        DigestEntry {
+            kind: AssetKind::Prompt,
+            path: path.to_string(),
+            name: path.to_string(),
+            sha256: sha256.to_string(),
+        }
+    }
+
+    fn digest(commit: &str, assets: Vec<DigestEntry>) -> CatalogDigest {
+        CatalogDigest {
+            commits: [("upstream".to_string(), commit.to_string())].into(),
+            assets,
+        }
+    }
+
+    #[test]
+    fn detects_added_removed_renamed_and_changed_assets() {
+        let old = digest(
+            "a",
+            vec![
+                entry("kept", "1"),
+                entry("edited", "2"),
+                entry("old-name", "3"),
+                entry("gone", "4"),
+            ],
+        );
+        let new = digest(
+            "b",
+            vec![
+                entry("kept", "1"),
+                entry("edited", "9"),
+                entry("new-name", "3"),
+                entry("fresh", "5"),
+            ],
+        );
+        assert_eq!(
+            diff(&old, &new),
+            [
+                CatalogChange::Changed(entry("edited", "9")),
+                CatalogChange::Renamed {
+                    from: entry("old-name", "3"),
+                    to: entry("new-name", "3"),
+                },
+                CatalogChange::Added(entry("fresh", "5")),
+                CatalogChange::Removed(entry("gone", "4")),
+            ]
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path());
+        assert!(record(&paths, old.clone()).unwrap().changes.is_empty());
+        let recorded = fs::read_to_string(&paths.catalog_history).unwrap();
+        // Peeking reports the change without recording it.
+        assert_eq!(peek(&paths, new.clone()).unwrap().changes.len(), 4);
+        assert_eq!(
+            fs::read_to_string(&paths.catalog_history).unwrap(),
+            recorded
+        );
+        assert_eq!(record(&paths, new.clone()).unwrap().changes.len(), 4);
+        // Reloading the same snapshot keeps the changelog.
+        assert_eq!(record(&paths, new).unwrap().changes.len(), 4);
+    }
+}
@@ -0,0 +1,121 @@
+use std::{collections::BTreeMap, fmt};
+
+use anyhow::{Context, Result};
+use walkdir::WalkDir;
+
+use crate::domain::model::AssetKind;
+
+use super::{paths::RepoPaths, sync};
+
+const FILE_KINDS: [AssetKind; 4] = [
+    AssetKind::Prompt,
+    AssetKind::Instruction,
+    AssetKind::ChatMode,
+    AssetKind::Agent,
+];
+
+/// Content hashes of the managed folders under `.github/`, keyed by path
+/// relative to the repository root.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocalFiles {
+    hashes: BTreeMap<String, String>,
+}
+
+impl LocalFiles {
+    pub fn capture(paths: &RepoPaths) -> Result<Self> {
+        let mut hashes = BTreeMap::new();
+        for kind in FILE_KINDS {
+            let root = paths.asset_root(kind);
+            if !root.exists() {
+                continue;
+            }
+            for entry in WalkDir::new(root) {
+                let entry = entry.with_context(|| format!("walking {}", root.display()))?;
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+                let relative = entry
+                    .path()
+                    .strip_prefix(&paths.root)
+                    .unwrap_or(entry.path());
+                hashes.insert(
+                    relative.to_string_lossy().replace('\\', "/"),
+                    sync::hash_file(entry.path())?,
+                );
+            }
+        }
+        Ok(Self { hashes })
+    }
+
+    /// Files that appeared, changed or disappeared since `before`.
+    pub fn changes_since(&self, before: &LocalFiles) -> FileChanges {
+        let mut changes = FileChanges::default();
+        for (path, hash) in &self.hashes {
+            match before.hashes.get(path) {
+                None => changes.created.push(path.clone()),
+                Some(old) if old != hash => changes.updated.push(path.clone()),
+                Some(_) => {}
+            }
+        }
+        changes.deleted = before
+            .hashes
+            .keys()
+            .filter(|path| !self.hashes.contains_key(*path))
+            .cloned()
+            .collect();
+        changes
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileChanges {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
+impl FileChanges {
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.updated.is_empty() && self.deleted.is_empty()
+    }
+}
+
+impl fmt::Display for FileChanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} copied, {} updated, {} deleted",
+            self.created.len(),
+            self.updated.len(),
+            self.deleted.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn reports_created_updated_and_deleted_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path());
+        fs::create_dir_all(&paths.prompts_dir).unwrap();
+        fs::write(paths.prompts_dir.join("kept.prompt.md"), "one").unwrap();
+        fs::write(paths.prompts_dir.join("edited.prompt.md"), "one").unwrap();
+        fs::write(paths.prompts_dir.join("gone.prompt.md"), "one").unwrap();
+        let before = LocalFiles::capture(&paths).unwrap();
+
+        fs::write(paths.prompts_dir.join("edited.prompt.md"), "two").unwrap();
+        fs::remove_file(paths.prompts_dir.join("gone.prompt.md")).unwrap();
+        fs::write(paths.prompts_dir.join("new.prompt.md"), "one").unwrap();
+        let changes = LocalFiles::capture(&paths).unwrap().changes_since(&before);
+
+        assert_eq!(changes.created, [".github/prompts/new.prompt.md"]);
+        assert_eq!(changes.updated, [".github/prompts/edited.prompt.md"]);
+        assert_eq!(changes.deleted, [".github/prompts/gone.prompt.md"]);
+        assert_eq!(changes.to_string(), "1 copied, 1 updated, 1 deleted");
+    }
+}
@@ -0,0 +1,136 @@
+use anyhow::{Context, Result};
+use globset::{Glob, GlobMatcher};
+
+use crate::domain::state::DomainState;
+
+use super::config::PolicyConfig;
+
+/// What `check` verifies. Each class fails with its own exit code so CI can
+/// tell them apart; when several fail, the first in this order wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckFailure {
+    /// The enablement file can't be parsed or breaks the schema.
+    Enablement,
+    /// An enabled asset's local file is missing or differs from upstream.
+    LocalFiles,
+    /// The selection breaks a `[policy]` rule.
+    Policy,
+}
+
+impl CheckFailure {
+    pub fn exit_code(self) -> u8 {
+        match self {
+            CheckFailure::Enablement => 3,
+            CheckFailure::LocalFiles => 4,
+            CheckFailure::Policy => 5,
+        }
+    }
+}
+
+/// `check` found problems; the process exits with `failure`'s code.
+#[derive(Debug, thiserror::Error)]
+#[error("{summary}")]
+pub struct CheckFailed {
+    pub failure: CheckFailure,
+    pub summary: String,
+}
+
+/// The `[policy]` rules of `config.toml`, with their globs compiled.
+pub struct Policy {
+    require: Vec<(String, GlobMatcher)>,
+    forbid: Vec<(String, GlobMatcher)>,
+}
+
+impl Policy {
+    pub fn new(config: &PolicyConfig) -> Result<Self> {
+        let compile = |patterns: &[String], key: &str| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    let matcher = Glob::new(pattern)
+                        .with_context(|| format!("invalid glob '{pattern}' in [policy] {key}"))?
+                        .compile_matcher();
+                    Ok((pattern.clone(), matcher))
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(Self {
+            require: compile(&config.require, "require")?,
+            forbid: compile(&config.forbid, "forbid")?,
+        })
+    }
+
+    /// One line per broken rule: a required asset that is disabled, a
+    /// `require` pattern that matches no catalog asset, or an enabled asset
+    /// matching a `forbid` pattern.
+    pub fn violations(&self, state: &DomainState) -> Vec<String> {
+        let assets: Vec<_> = state.all_assets().collect();
+        let mut violations = Vec::new();
+        for (pattern, matcher) in &self.require {
+            let matching: Vec<_> = assets
+                .iter()
+                .filter(|asset| matcher.is_match(&asset.path))
+                .collect();
+            if matching.is_empty() {
+                violations.push(format!("require '{pattern}' matches no catalog asset"));
+            }
+            for asset in matching.into_iter().filter(|asset| !asset.effective) {
+                violations.push(format!("{} is required but disabled", asset.path));
+            }
+        }
+        for (pattern, matcher) in &self.forbid {
+            for asset in assets
+                .iter()
+                .filter(|asset| asset.effective && matcher.is_match(&asset.path))
+            {
+                violations.push(format!(
+                    "{} is enabled but forbidden by '{pattern}'",
+                    asset.path
+                ));
+            }
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::{Catalog, EnablementFile, Prompt};
+
+    #[test]
+    fn policy_reports_required_and_forbidden_assets() {
+        let mut catalog = Catalog::default();
+        for slug in ["review", "legacy"] {
+            catalog.prompts.push(Prompt {
+                path: format!("prompts/{slug}.prompt.md"),
+                slug: slug.into(),
+                name: slug.into(),
+                description: String::new(),
+                mode: String::new(),
+                tags: Vec::new(),
+                sha256: String::new(),
+                requires: Vec::new(),
+            });
+        }
+        let mut enablement = EnablementFile::default();
+        enablement
+            .prompts
+            .insert("prompts/legacy.prompt.md".into(), true);
+        let state = DomainState::new(catalog, enablement);
+
+        let policy = Policy::new(&PolicyConfig {
+            require: vec!["prompts/review.prompt.md".into(), "agents/*".into()],
+            forbid: vec!["prompts/legacy*".into()],
+        })
+        .unwrap();
+        assert_eq!(
+            policy.violations(&state),
+            [
+                "prompts/review.prompt.md is required but disabled",
+                "require 'agents/*' matches no catalog asset",
+                "prompts/legacy.prompt.md is enabled but forbidden by 'prompts/legacy*'",
+            ]
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use std::io::{self, Write};
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+/// Put `text` on the system clipboard through the terminal (OSC 52), which
+/// also works over SSH. Terminals that don't support it ignore the request.
+pub fn copy(text: &str) -> Result<()> {
+    let inside_tmux = std::env::var_os("TMUX").is_some();
+    let mut stdout = io::stdout();
+    stdout
+        .write_all(osc52(text, inside_tmux).as_bytes())
+        .and_then(|()| stdout.flush())
+        .context("writing to the terminal")
+}
+
+/// The escape sequence that sets the clipboard to `text`. tmux only passes
+/// it on to the outer terminal when wrapped in a DCS passthrough.
+fn osc52(text: &str, inside_tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", STANDARD.encode(text));
+    if inside_tmux {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_text_as_osc52() {
+        assert_eq!(osc52("hi", false), "\x1b]52;c;aGk=\x07");
+        assert_eq!(osc52("hi", true), "\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\");
+    }
+}
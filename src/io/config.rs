@@ -0,0 +1,447 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{de::DeserializeOwned, Deserialize};
+
+use super::{
+    paths::RepoPaths,
+    retry::RetryPolicy,
+    sync::{ApplyMode, LineEndings},
+    upstream::{FetchOptions, FetchStrategy, Freshness, Refresh, UpstreamSource},
+};
+
+/// Name of the per-user directory holding `config.toml`, `keymap.toml` and
+/// `theme.toml`.
+const APP_DIR: &str = "awesome-copilot-tui";
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Defaults from `config.toml`. The user file is read first and the
+/// repository's `.awesome-copilot-tui/config.toml` layered on top; command
+/// line flags win over both.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// UI tick rate in milliseconds.
+    pub tick: Option<u64>,
+    pub apply_mode: Option<ApplyMode>,
+    /// Convert applied files' line endings; unset keeps upstream's bytes.
+    pub line_endings: Option<LineEndings>,
+    pub theme: Option<String>,
+    /// Draw with ASCII characters only.
+    pub ascii: Option<bool>,
+    pub keymap: Option<String>,
+    /// Save the enablement file shortly after every change instead of
+    /// waiting for an explicit save.
+    pub auto_save: Option<bool>,
+    /// Queue the file changes of toggles and run them on save instead of
+    /// right away.
+    pub defer_apply: Option<bool>,
+    /// Extra upstream sources as `ID=OWNER/REPO[@REF]`.
+    pub sources: Vec<String>,
+    pub confirm: ConfirmConfig,
+    pub git: GitConfig,
+    pub vscode: VscodeConfig,
+    pub network: NetworkConfig,
+    pub history: HistoryConfig,
+    pub log: LogConfig,
+    pub report: ReportConfig,
+    pub instructions: InstructionsConfig,
+    pub monorepo: MonorepoConfig,
+    pub policy: PolicyConfig,
+    pub diff: DiffConfig,
+    pub search: SearchConfig,
+}
+
+/// Which actions ask before running; unset entries keep the default (ask).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ConfirmConfig {
+    /// Quitting with unsaved changes.
+    pub quit: Option<bool>,
+    /// Reloading with unsaved changes.
+    pub reload: Option<bool>,
+    /// Toggling a collection, which cascades to its members.
+    pub collection_toggle: Option<bool>,
+    /// Toggling or applying every marked asset.
+    pub bulk: Option<bool>,
+    /// Importing a profile.
+    pub import: Option<bool>,
+}
+
+/// Optional git integration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct GitConfig {
+    /// Stage `.github/` and the enablement file after saving or applying.
+    pub stage: Option<bool>,
+}
+
+/// Optional VS Code integration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct VscodeConfig {
+    /// Keep `chat.modeFilesLocations` in `.vscode/settings.json` pointing at
+    /// `.github/chatmodes` while chat modes are enabled.
+    pub settings: Option<bool>,
+    /// VS Code user directory `--target user` applies to, e.g. for
+    /// Insiders or VSCodium [default: `Code/User` in the config directory].
+    pub user_dir: Option<PathBuf>,
+}
+
+/// The activity log of toggles, applies, resets and saves.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct HistoryConfig {
+    /// Keep the log in `.awesome-copilot-tui/history.jsonl` across sessions.
+    pub persist: Option<bool>,
+}
+
+/// The log of footer messages.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct LogConfig {
+    /// Also append every message to `.awesome-copilot-tui/messages.log`.
+    pub file: Option<bool>,
+}
+
+/// The markdown report of enabled assets.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ReportConfig {
+    /// File, relative to the repository root, whose
+    /// `<!-- copilot-assets:start/end -->` section is rewritten on every save.
+    pub inject: Option<PathBuf>,
+}
+
+/// Extra outputs for instructions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct InstructionsConfig {
+    /// Also concatenate the enabled instructions into a marked section of
+    /// `.github/copilot-instructions.md` on every save.
+    pub aggregate: Option<bool>,
+}
+
+/// Sub-projects of a monorepo that get their own assets.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct MonorepoConfig {
+    /// Directories relative to the repository root, e.g. `services/api`,
+    /// each with its own `.github/` and enablement file.
+    pub projects: Vec<String>,
+}
+
+impl MonorepoConfig {
+    /// Check that `project` is one of the configured sub-projects.
+    pub fn find(&self, project: &str) -> Result<String> {
+        let project = project.trim_matches('/');
+        if self.projects.iter().any(|p| p.trim_matches('/') == project) {
+            return Ok(project.to_string());
+        }
+        if self.projects.is_empty() {
+            bail!("no sub-projects are configured; list them in [monorepo] projects");
+        }
+        bail!(
+            "'{project}' is not a configured sub-project (expected one of: {})",
+            self.projects.join(", ")
+        )
+    }
+}
+
+/// The external diff tool (`D`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct DiffConfig {
+    /// Command run with the upstream and local file paths appended, e.g.
+    /// `"meld"` or `"nvim -d"`. `$DIFFTOOL` wins over it.
+    pub tool: Option<String>,
+}
+
+/// The table search (`/`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct SearchConfig {
+    /// Also match queries against the text of upstream files; `Ctrl+F` in
+    /// the search input switches it for the session.
+    pub full_text: Option<bool>,
+}
+
+/// Rules `check` enforces on the selection, as catalog path globs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct PolicyConfig {
+    /// Assets that must be enabled.
+    pub require: Vec<String>,
+    /// Assets that must not be enabled.
+    pub forbid: Vec<String>,
+}
+
+/// How snapshots are fetched from GitHub; unset entries keep the defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct NetworkConfig {
+    /// Download the whole archive or only the asset files.
+    pub fetch: Option<FetchStrategy>,
+    /// Tries per request before falling back to the cached snapshot.
+    pub attempts: Option<u32>,
+    /// Delay before the first retry in milliseconds, doubled for each
+    /// further one.
+    pub backoff_ms: Option<u64>,
+    /// Randomize retry delays.
+    pub jitter: Option<bool>,
+    /// Hours a cached snapshot is used before it is downloaded again, or
+    /// `never` to only refresh on request.
+    pub freshness: Option<Freshness>,
+    /// Cached snapshots kept per source.
+    pub keep_snapshots: Option<usize>,
+}
+
+impl NetworkConfig {
+    fn layer(self, over: NetworkConfig) -> Self {
+        Self {
+            fetch: over.fetch.or(self.fetch),
+            attempts: over.attempts.or(self.attempts),
+            backoff_ms: over.backoff_ms.or(self.backoff_ms),
+            jitter: over.jitter.or(self.jitter),
+            freshness: over.freshness.or(self.freshness),
+            keep_snapshots: over.keep_snapshots.or(self.keep_snapshots),
+        }
+    }
+
+    pub fn resolve(&self) -> FetchOptions {
+        let defaults = FetchOptions::default();
+        FetchOptions {
+            strategy: self.fetch.unwrap_or_default(),
+            retry: RetryPolicy {
+                attempts: self.attempts.unwrap_or(defaults.retry.attempts).max(1),
+                backoff: self
+                    .backoff_ms
+                    .map(Duration::from_millis)
+                    .unwrap_or(defaults.retry.backoff),
+                jitter: self.jitter.unwrap_or(defaults.retry.jitter),
+            },
+            freshness: self.freshness.unwrap_or(defaults.freshness),
+            keep_snapshots: self
+                .keep_snapshots
+                .unwrap_or(defaults.keep_snapshots)
+                .max(1),
+            refresh: Refresh::default(),
+        }
+    }
+}
+
+/// Resolved confirmation settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Confirmations {
+    pub quit: bool,
+    pub reload: bool,
+    pub collection_toggle: bool,
+    pub bulk: bool,
+    pub import: bool,
+}
+
+impl Default for Confirmations {
+    fn default() -> Self {
+        ConfirmConfig::default().resolve()
+    }
+}
+
+impl ConfirmConfig {
+    fn layer(self, over: ConfirmConfig) -> Self {
+        Self {
+            quit: over.quit.or(self.quit),
+            reload: over.reload.or(self.reload),
+            collection_toggle: over.collection_toggle.or(self.collection_toggle),
+            bulk: over.bulk.or(self.bulk),
+            import: over.import.or(self.import),
+        }
+    }
+
+    pub fn resolve(&self) -> Confirmations {
+        Confirmations {
+            quit: self.quit.unwrap_or(true),
+            reload: self.reload.unwrap_or(true),
+            collection_toggle: self.collection_toggle.unwrap_or(true),
+            bulk: self.bulk.unwrap_or(true),
+            import: self.import.unwrap_or(true),
+        }
+    }
+}
+
+impl Config {
+    /// `over`'s settings replace this layer's; a non-empty source, project or
+    /// policy list replaces the whole list.
+    pub fn layer(self, over: Config) -> Self {
+        Self {
+            tick: over.tick.or(self.tick),
+            apply_mode: over.apply_mode.or(self.apply_mode),
+            line_endings: over.line_endings.or(self.line_endings),
+            theme: over.theme.or(self.theme),
+            ascii: over.ascii.or(self.ascii),
+            keymap: over.keymap.or(self.keymap),
+            auto_save: over.auto_save.or(self.auto_save),
+            defer_apply: over.defer_apply.or(self.defer_apply),
+            sources: if over.sources.is_empty() {
+                self.sources
+            } else {
+                over.sources
+            },
+            confirm: self.confirm.layer(over.confirm),
+            git: GitConfig {
+                stage: over.git.stage.or(self.git.stage),
+            },
+            vscode: VscodeConfig {
+                settings: over.vscode.settings.or(self.vscode.settings),
+                user_dir: over.vscode.user_dir.or(self.vscode.user_dir),
+            },
+            network: self.network.layer(over.network),
+            history: HistoryConfig {
+                persist: over.history.persist.or(self.history.persist),
+            },
+            log: LogConfig {
+                file: over.log.file.or(self.log.file),
+            },
+            report: ReportConfig {
+                inject: over.report.inject.or(self.report.inject),
+            },
+            instructions: InstructionsConfig {
+                aggregate: over.instructions.aggregate.or(self.instructions.aggregate),
+            },
+            diff: DiffConfig {
+                tool: over.diff.tool.or(self.diff.tool),
+            },
+            search: SearchConfig {
+                full_text: over.search.full_text.or(self.search.full_text),
+            },
+            monorepo: MonorepoConfig {
+                projects: if over.monorepo.projects.is_empty() {
+                    self.monorepo.projects
+                } else {
+                    over.monorepo.projects
+                },
+            },
+            policy: PolicyConfig {
+                require: if over.policy.require.is_empty() {
+                    self.policy.require
+                } else {
+                    over.policy.require
+                },
+                forbid: if over.policy.forbid.is_empty() {
+                    self.policy.forbid
+                } else {
+                    over.policy.forbid
+                },
+            },
+        }
+    }
+
+    pub fn upstream_sources(&self) -> Result<Vec<UpstreamSource>> {
+        self.sources
+            .iter()
+            .map(|source| {
+                source
+                    .parse()
+                    .with_context(|| format!("invalid source '{source}' in {CONFIG_FILE}"))
+            })
+            .collect()
+    }
+}
+
+/// Load the user config and the repository override.
+pub fn load_config(paths: &RepoPaths) -> Result<Config> {
+    let (user, _) = read_user_file::<Config>(CONFIG_FILE)?;
+    let repo = read_toml::<Config>(paths.workspace_dir.join(CONFIG_FILE))?;
+    Ok(user.layer(repo))
+}
+
+/// Paths for `--target user`: the VS Code profile from `[vscode] user-dir`
+/// in the user config, or the default one, with the enablement state in
+/// `user/` of the user config directory.
+pub fn user_target_paths() -> Result<RepoPaths> {
+    let config_dir = dirs::config_dir().context("no user config directory on this platform")?;
+    let (config, _) = read_user_file::<Config>(CONFIG_FILE)?;
+    let vscode_dir = config
+        .vscode
+        .user_dir
+        .unwrap_or_else(|| config_dir.join("Code").join("User"));
+    Ok(RepoPaths::user(
+        config_dir.join(APP_DIR).join("user"),
+        vscode_dir,
+    ))
+}
+
+/// Paths for `--project`: the sub-project of the repository at `root`, which
+/// must be listed in `[monorepo] projects`.
+pub fn project_paths(root: &Path, project: &str) -> Result<RepoPaths> {
+    let config = load_config(&RepoPaths::new(root))?;
+    Ok(RepoPaths::project(root, &config.monorepo.find(project)?))
+}
+
+/// Parse `file` from the user config directory (e.g.
+/// `~/.config/awesome-copilot-tui/`), falling back to the default when it does
+/// not exist. Also returns the path for error messages.
+pub fn read_user_file<T: DeserializeOwned + Default>(file: &str) -> Result<(T, String)> {
+    let Some(path) = user_file(file) else {
+        return Ok((T::default(), file.to_string()));
+    };
+    let display = path.display().to_string();
+    Ok((read_toml(path)?, display))
+}
+
+/// Path of `file` in the user config directory, if the platform has one.
+pub fn user_file(file: &str) -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_DIR).join(file))
+}
+
+fn read_toml<T: DeserializeOwned + Default>(path: PathBuf) -> Result<T> {
+    match std::fs::read_to_string(&path) {
+        Ok(content) => {
+            toml::from_str(&content).with_context(|| format!("parsing {}", path.display()))
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        Err(err) => Err(err).with_context(|| format!("reading {}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repo_layer_overrides_user_layer() {
+        let user: Config = toml::from_str(
+            "tick = 100\ntheme = \"light\"\nsources = [\"acme=acme/prompts\"]\n[confirm]\nquit = false\nbulk = false\n",
+        )
+        .unwrap();
+        let repo: Config =
+            toml::from_str("apply-mode = \"merge\"\ntheme = \"dark\"\n[confirm]\nbulk = true\n")
+                .unwrap();
+        let config = user.layer(repo);
+
+        assert_eq!(config.tick, Some(100));
+        assert_eq!(config.theme.as_deref(), Some("dark"));
+        assert_eq!(config.apply_mode, Some(ApplyMode::Merge));
+        assert_eq!(config.upstream_sources().unwrap()[0].id, "acme");
+        let confirm = config.confirm.resolve();
+        assert!(!confirm.quit);
+        assert!(confirm.bulk);
+        assert!(confirm.import);
+    }
+
+    #[test]
+    fn freshness_takes_hours_or_never() {
+        let config: Config =
+            toml::from_str("[network]\nfreshness = \"never\"\nkeep-snapshots = 2\n").unwrap();
+        let fetch = config.network.resolve();
+        assert_eq!(fetch.freshness, Freshness::Never);
+        assert_eq!(fetch.keep_snapshots, 2);
+
+        let config: Config = toml::from_str("[network]\nfreshness = 48\n").unwrap();
+        assert_eq!(config.network.resolve().freshness, Freshness::Hours(48));
+        assert!(toml::from_str::<Config>("[network]\nfreshness = \"soon\"\n").is_err());
+    }
+}
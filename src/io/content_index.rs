@@ -0,0 +1,172 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::model::{namespaced, split_source};
+
+use super::upstream::UpstreamRoots;
+
+/// Kept in each snapshot directory, next to `snapshot.json`, so it goes away
+/// with the snapshot.
+const INDEX_FILE: &str = "content-index.json";
+
+/// Lowercased text of asset files in the loaded snapshots, for searching
+/// their bodies rather than only their metadata.
+#[derive(Debug, Default, Clone)]
+pub struct ContentIndex {
+    bodies: HashMap<String, String>,
+}
+
+/// The bodies of one snapshot as saved on disk, keyed by the path inside it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SavedIndex {
+    commit: String,
+    bodies: BTreeMap<String, String>,
+}
+
+impl ContentIndex {
+    /// Read the file of every catalog path in `paths`, starting from the
+    /// index saved in each source's snapshot directory for its commit and
+    /// saving it again when files had to be read. Sources without a commit,
+    /// such as local assets, are always read. Files that can't be read are
+    /// left out.
+    pub fn load_or_build<'a>(
+        upstream: &UpstreamRoots,
+        paths: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Self> {
+        let mut by_source: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for path in paths {
+            let (source, relative) = split_source(path);
+            by_source.entry(source).or_default().push(relative);
+        }
+        let mut bodies = HashMap::new();
+        for (source, relatives) in by_source {
+            let saved_at = upstream
+                .commits()
+                .get(source)
+                .and_then(|commit| Some((commit, upstream.root(source)?.parent()?)));
+            let Some((commit, snapshot_dir)) = saved_at else {
+                bodies.extend(relatives.into_iter().filter_map(|relative| {
+                    let path = namespaced(source, relative);
+                    let body = read_body(&upstream.resolve(&path).ok()?)?;
+                    Some((path, body))
+                }));
+                continue;
+            };
+            let index_path = snapshot_dir.join(INDEX_FILE);
+            let mut saved = read_saved(&index_path)
+                .filter(|saved| &saved.commit == commit)
+                .unwrap_or_else(|| SavedIndex {
+                    commit: commit.clone(),
+                    bodies: BTreeMap::new(),
+                });
+            let mut changed = false;
+            for relative in relatives {
+                let path = namespaced(source, relative);
+                if !saved.bodies.contains_key(relative) {
+                    let file = upstream.resolve(&path).ok();
+                    let Some(body) = file.and_then(|file| read_body(&file)) else {
+                        continue;
+                    };
+                    saved.bodies.insert(relative.to_string(), body);
+                    changed = true;
+                }
+                bodies.insert(path, saved.bodies[relative].clone());
+            }
+            if changed {
+                let json = serde_json::to_string(&saved).context("serializing content index")?;
+                fs::write(&index_path, json)
+                    .with_context(|| format!("writing {}", index_path.display()))?;
+            }
+        }
+        Ok(Self { bodies })
+    }
+
+    pub fn len(&self) -> usize {
+        self.bodies.len()
+    }
+
+    /// Whether the file of the asset at `path` contains the lowercase
+    /// `text`.
+    pub fn contains(&self, path: &str, text: &str) -> bool {
+        self.bodies
+            .get(path)
+            .is_some_and(|body| body.contains(text))
+    }
+}
+
+fn read_body(path: &Path) -> Option<String> {
+    Some(fs::read_to_string(path).ok()?.to_lowercase())
+}
+
+fn read_saved(path: &Path) -> Option<SavedIndex> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::{domain::model::PRIMARY_SOURCE, io::upstream::UpstreamSnapshot};
+
+    #[test]
+    fn reads_catalog_files_lowercased() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("prompts")).unwrap();
+        fs::write(
+            dir.path().join("prompts/infra.prompt.md"),
+            "---\ndescription: Infra\n---\nEnable State Locking for Terraform backends.\n",
+        )
+        .unwrap();
+        let mut upstream = UpstreamRoots::default();
+        upstream.insert(PRIMARY_SOURCE, dir.path().to_path_buf());
+
+        let index = ContentIndex::load_or_build(
+            &upstream,
+            ["prompts/infra.prompt.md", "prompts/missing.prompt.md"],
+        )
+        .unwrap();
+        assert_eq!(index.len(), 1);
+        assert!(index.contains("prompts/infra.prompt.md", "state locking"));
+        assert!(!index.contains("prompts/infra.prompt.md", "drift"));
+        assert!(!index.contains("prompts/missing.prompt.md", "state"));
+    }
+
+    #[test]
+    fn reuses_the_index_saved_for_the_snapshot_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        let content_dir = dir.path().join("abc123/content");
+        fs::create_dir_all(content_dir.join("prompts")).unwrap();
+        let file = content_dir.join("prompts/infra.prompt.md");
+        fs::write(&file, "Terraform state locking\n").unwrap();
+        let upstream = |commit: &str| {
+            let mut upstream = UpstreamRoots::default();
+            upstream.insert(PRIMARY_SOURCE, content_dir.clone());
+            upstream.record_snapshot(
+                PRIMARY_SOURCE,
+                &UpstreamSnapshot {
+                    commit: commit.into(),
+                    fetched_at: Utc::now(),
+                    content_dir: content_dir.clone(),
+                    warnings: Vec::new(),
+                },
+            );
+            upstream
+        };
+        let paths = ["prompts/infra.prompt.md"];
+
+        ContentIndex::load_or_build(&upstream("abc123"), paths).unwrap();
+        fs::write(&file, "Kubernetes\n").unwrap();
+        let saved = ContentIndex::load_or_build(&upstream("abc123"), paths).unwrap();
+        assert!(saved.contains(paths[0], "terraform"));
+
+        let rebuilt = ContentIndex::load_or_build(&upstream("def456"), paths).unwrap();
+        assert!(rebuilt.contains(paths[0], "kubernetes"));
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus},
+};
+
+use anyhow::{bail, Context, Result};
+use tempfile::TempDir;
+
+use crate::domain::model::split_source;
+
+use super::{sync, upstream::UpstreamRoots};
+
+/// Environment variable naming the diff command; it wins over `[diff] tool`.
+pub const DIFFTOOL_ENV: &str = "DIFFTOOL";
+
+/// The diff command to run: `$DIFFTOOL` when set, otherwise `configured`.
+pub fn resolve(configured: Option<&str>) -> Option<String> {
+    std::env::var(DIFFTOOL_ENV)
+        .ok()
+        .or_else(|| configured.map(str::to_string))
+        .filter(|tool| !tool.trim().is_empty())
+}
+
+/// The upstream side of a comparison: what applying the asset writes, with
+/// its patch, variables and line endings, rather than the raw snapshot file.
+/// It is removed when dropped.
+pub struct UpstreamCopy {
+    _dir: TempDir,
+    pub path: PathBuf,
+}
+
+/// Write the applied content of `asset_path` to a read-only temp file named
+/// like the asset, so the tool picks its syntax and edits can't go there.
+pub fn upstream_copy(upstream: &UpstreamRoots, asset_path: &str) -> Result<UpstreamCopy> {
+    let content = sync::upstream_content(upstream, asset_path)?;
+    let dir = tempfile::Builder::new()
+        .prefix("upstream-")
+        .tempdir()
+        .context("creating temp dir for the upstream file")?;
+    let (_, relative) = split_source(asset_path);
+    let name = Path::new(relative)
+        .file_name()
+        .unwrap_or(OsStr::new(relative));
+    let path = dir.path().join(name);
+    fs::write(&path, content).with_context(|| format!("writing {}", path.display()))?;
+    let mut permissions = fs::metadata(&path)
+        .with_context(|| format!("reading {}", path.display()))?
+        .permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(&path, permissions)
+        .with_context(|| format!("making {} read-only", path.display()))?;
+    Ok(UpstreamCopy { _dir: dir, path })
+}
+
+/// Run `tool` on the upstream and local files and wait for it to exit.
+/// `tool` is split on whitespace, so it may carry its own arguments, and the
+/// two paths are appended in that order.
+pub fn run(tool: &str, upstream: &Path, local: &Path) -> Result<ExitStatus> {
+    let command = command(tool, upstream, local)?;
+    Command::new(command[0])
+        .args(&command[1..])
+        .status()
+        .with_context(|| format!("running diff tool '{tool}'"))
+}
+
+fn command<'a>(tool: &'a str, upstream: &'a Path, local: &'a Path) -> Result<Vec<&'a OsStr>> {
+    let mut parts: Vec<&OsStr> = tool.split_whitespace().map(OsStr::new).collect();
+    if parts.is_empty() {
+        bail!("the diff tool command is empty");
+    }
+    parts.push(upstream.as_os_str());
+    parts.push(local.as_os_str());
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_upstream_and_local_paths() {
+        let parts = command(
+            "nvim -d",
+            Path::new("/cache/review.prompt.md"),
+            Path::new(".github/prompts/review.prompt.md"),
+        )
+        .unwrap();
+        assert_eq!(
+            parts,
+            [
+                "nvim",
+                "-d",
+                "/cache/review.prompt.md",
+                ".github/prompts/review.prompt.md"
+            ]
+        );
+        assert!(command("  ", Path::new("a"), Path::new("b")).is_err());
+    }
+
+    #[test]
+    fn upstream_copy_holds_the_applied_content() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("prompts")).unwrap();
+        fs::write(
+            dir.path().join("prompts/review.prompt.md"),
+            "Review {{project_name}}\n",
+        )
+        .unwrap();
+        let mut upstream = UpstreamRoots::default();
+        upstream.insert("acme", dir.path().to_path_buf());
+        upstream.set_variables([("project_name".to_string(), "api".to_string())].into());
+
+        let copy = upstream_copy(&upstream, "acme:prompts/review.prompt.md").unwrap();
+        assert!(copy.path.ends_with("review.prompt.md"));
+        assert_eq!(fs::read_to_string(&copy.path).unwrap(), "Review api\n");
+        assert!(fs::metadata(&copy.path).unwrap().permissions().readonly());
+        let path = copy.path.clone();
+        drop(copy);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+
+use crate::domain::{model::AssetKind, rules, state::DomainState};
+
+use super::{
+    enablement::{self, EnablementWarning},
+    paths::RepoPaths,
+    upstream::{self, UpstreamSource},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Something is broken and needs fixing.
+    Problem,
+    /// Worth a look, but nothing fails because of it.
+    Warning,
+}
+
+/// One thing `doctor` noticed, with what to do about it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub summary: String,
+    pub fix: String,
+}
+
+impl Finding {
+    pub fn problem(summary: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Problem,
+            summary: summary.into(),
+            fix: fix.into(),
+        }
+    }
+
+    pub fn warning(summary: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            summary: summary.into(),
+            fix: fix.into(),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mark = match self.severity {
+            Severity::Problem => "✗",
+            Severity::Warning => "!",
+        };
+        write!(f, "{mark} {}\n    fix: {}", self.summary, self.fix)
+    }
+}
+
+/// Checks that need no catalog: the directory layout, the workspace
+/// directory, cached snapshots and the enablement file. Run these before
+/// loading, which creates missing directories.
+pub fn check_files(paths: &RepoPaths, sources: &[UpstreamSource]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for kind in [
+        AssetKind::Prompt,
+        AssetKind::Instruction,
+        AssetKind::ChatMode,
+        AssetKind::Agent,
+    ] {
+        let dir = paths.asset_root(kind);
+        if !dir.is_dir() {
+            let shown = dir.strip_prefix(&paths.root).unwrap_or(dir).display();
+            findings.push(Finding::warning(
+                format!("{shown} does not exist"),
+                format!("run `mkdir -p {shown}`, or open the TUI once to create it"),
+            ));
+        }
+    }
+
+    let writable_dir = if paths.workspace_dir.is_dir() {
+        &paths.workspace_dir
+    } else {
+        &paths.root
+    };
+    if let Err(err) = tempfile::tempfile_in(writable_dir) {
+        findings.push(Finding::problem(
+            format!("{} is not writable: {err}", writable_dir.display()),
+            "fix the directory's permissions or ownership so saves and applies can write there",
+        ));
+    }
+
+    for source in sources {
+        for (dir, reason) in upstream::broken_snapshots(paths, source) {
+            findings.push(Finding::problem(
+                format!("cached snapshot {} is corrupt: {reason}", dir.display()),
+                format!(
+                    "delete {} and it is downloaded again on the next run",
+                    dir.display()
+                ),
+            ));
+        }
+    }
+
+    let (enablement_path, _) = enablement::enablement_location(paths);
+    let shown = enablement_path
+        .strip_prefix(&paths.root)
+        .unwrap_or(&enablement_path)
+        .display();
+    match enablement::load_enablement(paths) {
+        Ok(load) => {
+            for warning in load.warnings {
+                findings.push(match warning {
+                    EnablementWarning::MissingFile => Finding::warning(
+                        format!("{shown} does not exist yet"),
+                        "enable assets in the TUI and save to create it",
+                    ),
+                    EnablementWarning::ParseError(err) => Finding::problem(
+                        format!("{shown} is not valid: {err}"),
+                        format!(
+                            "fix the syntax, or restore it with `git checkout -- {shown}`; \
+                             until then every asset reads as disabled"
+                        ),
+                    ),
+                    EnablementWarning::SchemaValidation(errors) => Finding::problem(
+                        format!("{shown} does not match its schema: {}", errors.join(", ")),
+                        "correct the listed fields, or open the TUI and save to rewrite it",
+                    ),
+                    EnablementWarning::Migrated(steps) => Finding::warning(
+                        format!("{shown} uses an older format ({})", steps.join(", ")),
+                        "open the TUI and save to write the current format",
+                    ),
+                });
+            }
+        }
+        Err(err) => findings.push(Finding::problem(
+            format!("{shown} cannot be read: {err:#}"),
+            "check the file's permissions",
+        )),
+    }
+    findings
+}
+
+/// Checks against the loaded catalog: enablement entries for assets it no
+/// longer has, collection items pointing at missing assets, and rules whose
+/// path glob doesn't compile.
+pub fn check_state(state: &DomainState) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for orphan in state.orphans() {
+        let fix = match &orphan.successor {
+            Some(successor) => format!("remap it to {successor} in the orphans view (O)"),
+            None => "remove it with `c` in the TUI, or delete the entry by hand".to_string(),
+        };
+        findings.push(Finding::warning(
+            format!("enablement entry {} matches no catalog asset", orphan.path),
+            fix,
+        ));
+    }
+    for collection in &state.catalog.collections {
+        for item in &collection.items {
+            if !state.catalog.contains(item.kind, &item.path) {
+                findings.push(Finding::warning(
+                    format!(
+                        "collection {} lists {}, which is not in the catalog",
+                        collection.path, item.path
+                    ),
+                    "report it to the collection's source; enabling the collection skips the item",
+                ));
+            }
+        }
+    }
+    for pattern in rules::invalid_globs(&state.enablement.rules) {
+        findings.push(Finding::warning(
+            format!("enablement rule path '{pattern}' is not a valid glob"),
+            "fix the pattern in the enablement file; the rule matches nothing until then",
+        ));
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::domain::model::{Catalog, Collection, CollectionItem, EnablementFile};
+
+    #[test]
+    fn reports_layout_enablement_and_catalog_problems() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path());
+        fs::create_dir_all(&paths.prompts_dir).unwrap();
+        fs::create_dir_all(&paths.instructions_dir).unwrap();
+        fs::create_dir_all(&paths.workspace_dir).unwrap();
+        fs::write(&paths.enablement, "{ not json").unwrap();
+
+        let findings = check_files(&paths, &[]);
+        let summaries: Vec<_> = findings.iter().map(|f| f.summary.as_str()).collect();
+        assert_eq!(findings.len(), 3, "{summaries:?}");
+        assert!(summaries[0].starts_with(".github/chatmodes"));
+        assert!(summaries[1].starts_with(".github/agents"));
+        assert_eq!(findings[2].severity, Severity::Problem);
+        assert!(summaries[2].contains("is not valid"));
+
+        let catalog = Catalog {
+            collections: vec![Collection {
+                path: "collections/set.collection.yml".into(),
+                id: "set".into(),
+                slug: "set".into(),
+                name: "Set".into(),
+                description: String::new(),
+                tags: Vec::new(),
+                items: vec![CollectionItem {
+                    path: "prompts/gone.prompt.md".into(),
+                    kind: AssetKind::Prompt,
+                }],
+                sha256: String::new(),
+            }],
+            ..Default::default()
+        }
+        .finalize();
+        let mut enablement = EnablementFile::default();
+        enablement
+            .prompts
+            .insert("prompts/old.prompt.md".into(), true);
+        let findings = check_state(&DomainState::new(catalog, enablement));
+        assert_eq!(findings.len(), 2);
+        assert!(findings[0].summary.contains("prompts/old.prompt.md"));
+        assert!(findings[1].summary.contains("prompts/gone.prompt.md"));
+    }
+}
@@ -4,12 +4,14 @@ use jsonschema::{paths::JSONPointer, JSONSchema, ValidationError};
 use once_cell::sync::OnceCell;
 use serde::Deserialize;
 use serde_json::Value;
-use std::{fmt, fs, io::Write};
+use std::{fmt, fs, io::Write, path::PathBuf};
 
 use crate::domain::model::EnablementFile;
 
 use super::paths::RepoPaths;
 
+mod migrations;
+
 const SCHEMA_JSON: &str = include_str!("../../docs/schemas/enablement.schema.json");
 
 #[derive(Debug, Clone, Deserialize)]
@@ -17,6 +19,7 @@ pub enum EnablementWarning {
     MissingFile,
     ParseError(String),
     SchemaValidation(Vec<String>),
+    Migrated(Vec<String>),
 }
 
 impl fmt::Display for EnablementWarning {
@@ -38,6 +41,13 @@ impl fmt::Display for EnablementWarning {
                     errors.join(", ")
                 )
             }
+            EnablementWarning::Migrated(steps) => {
+                write!(
+                    f,
+                    "Enablement file migrated ({}); save to persist the upgrade",
+                    steps.join(", ")
+                )
+            }
         }
     }
 }
@@ -48,33 +58,63 @@ pub struct EnablementLoad {
     pub warnings: Vec<EnablementWarning>,
 }
 
+/// On-disk encoding of the enablement file. Both are validated against the
+/// same JSON schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnablementFormat {
+    Json,
+    Yaml,
+}
+
+/// Locate the enablement file. `enablement.json` wins when both exist; a lone
+/// `enablement.yaml` keeps being used so saving preserves the format.
+pub fn enablement_location(paths: &RepoPaths) -> (PathBuf, EnablementFormat) {
+    if !paths.enablement.exists() && paths.enablement_yaml.exists() {
+        (paths.enablement_yaml.clone(), EnablementFormat::Yaml)
+    } else {
+        (paths.enablement.clone(), EnablementFormat::Json)
+    }
+}
+
 pub fn load_enablement(paths: &RepoPaths) -> Result<EnablementLoad> {
-    match fs::read_to_string(&paths.enablement) {
-        Ok(content) => parse_enablement(&content),
+    let (path, format) = enablement_location(paths);
+    match fs::read_to_string(&path) {
+        Ok(content) => parse_enablement(&content, format),
         Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(EnablementLoad {
             file: EnablementFile::default(),
             warnings: vec![EnablementWarning::MissingFile],
         }),
-        Err(err) => Err(err).with_context(|| {
-            format!(
-                "Failed to read enablement file from {}",
-                paths.enablement.display()
-            )
-        }),
+        Err(err) => Err(err)
+            .with_context(|| format!("Failed to read enablement file from {}", path.display())),
     }
 }
 
-fn parse_enablement(content: &str) -> Result<EnablementLoad> {
-    let value: Value = match serde_json::from_str(content) {
+pub fn parse_enablement(content: &str, format: EnablementFormat) -> Result<EnablementLoad> {
+    let parsed = match format {
+        EnablementFormat::Json => serde_json::from_str::<Value>(content).map_err(|e| e.to_string()),
+        EnablementFormat::Yaml => serde_yaml::from_str::<Value>(content).map_err(|e| e.to_string()),
+    };
+    let mut value = match parsed {
         Ok(value) => value,
         Err(err) => {
             return Ok(EnablementLoad {
                 file: EnablementFile::default(),
-                warnings: vec![EnablementWarning::ParseError(err.to_string())],
+                warnings: vec![EnablementWarning::ParseError(err)],
             })
         }
     };
 
+    let mut warnings = Vec::new();
+    let applied = migrations::migrate(&mut value);
+    if !applied.is_empty() {
+        warnings.push(EnablementWarning::Migrated(
+            applied
+                .iter()
+                .map(|record| format!("v{} → v{}", record.from, record.to))
+                .collect(),
+        ));
+    }
+
     let schema = schema();
     let mut validation_errors = Vec::new();
     if let Err(errors) = schema.validate(&value) {
@@ -84,23 +124,26 @@ fn parse_enablement(content: &str) -> Result<EnablementLoad> {
         }
     }
 
-    if !validation_errors.is_empty() {
-        return Ok(EnablementLoad {
-            file: EnablementFile::default(),
-            warnings: vec![EnablementWarning::SchemaValidation(validation_errors)],
-        });
-    }
-
-    let mut file: EnablementFile = serde_json::from_value(value)
-        .context("Failed to deserialize enablement file into struct")?;
-    if file.version == 0 {
-        file.version = 1;
-    }
+    // Keep whatever still deserializes rather than dropping the user's
+    // selection; saving rewrites it in a schema-valid shape.
+    let file = if validation_errors.is_empty() {
+        serde_json::from_value(value)
+            .context("Failed to deserialize enablement file into struct")?
+    } else {
+        let reads = |value: &Value| serde_json::from_value::<EnablementFile>(value.clone()).is_ok();
+        let mut dropped = Vec::new();
+        let kept = salvage(&value, &reads, "", &mut dropped);
+        validation_errors.extend(
+            dropped
+                .into_iter()
+                .map(|pointer| format!("{pointer}: unreadable, dropped")),
+        );
+        warnings.push(EnablementWarning::SchemaValidation(validation_errors));
+        kept.and_then(|kept| serde_json::from_value(kept).ok())
+            .unwrap_or_default()
+    };
 
-    Ok(EnablementLoad {
-        file,
-        warnings: Vec::new(),
-    })
+    Ok(EnablementLoad { file, warnings })
 }
 
 pub fn save_enablement(paths: &RepoPaths, file: &mut EnablementFile) -> Result<()> {
@@ -113,19 +156,29 @@ pub fn save_enablement(paths: &RepoPaths, file: &mut EnablementFile) -> Result<(
         )));
     }
 
-    let json =
-        serde_json::to_string_pretty(&value).context("Failed to stringify enablement JSON")?;
-    let parent_dir = paths
-        .enablement
+    let (path, format) = enablement_location(paths);
+    let rendered = match format {
+        EnablementFormat::Json => {
+            let mut json = serde_json::to_string_pretty(&value)
+                .context("Failed to stringify enablement JSON")?;
+            json.push('\n');
+            json
+        }
+        EnablementFormat::Yaml => {
+            serde_yaml::to_string(&value).context("Failed to stringify enablement YAML")?
+        }
+    };
+    let parent_dir = path
         .parent()
         .map(|p| p.to_path_buf())
         .unwrap_or_else(|| paths.root.clone());
+    fs::create_dir_all(&parent_dir)
+        .with_context(|| format!("creating {}", parent_dir.display()))?;
     let mut temp = tempfile::NamedTempFile::new_in(parent_dir)
         .context("Failed to create temporary file for enablement write")?;
-    temp.write_all(json.as_bytes())
-        .context("Failed to write enablement JSON")?;
-    temp.write_all(b"\n").ok();
-    temp.persist(&paths.enablement)
+    temp.write_all(rendered.as_bytes())
+        .context("Failed to write enablement file")?;
+    temp.persist(&path)
         .context("Failed to persist enablement file")?;
     Ok(())
 }
@@ -144,6 +197,59 @@ fn schema() -> &'static JSONSchema {
     })
 }
 
+/// The parts of `value` that `reads` accepts, found by trying each object
+/// entry and array element on its own. The JSON pointers of the parts left
+/// out are added to `dropped`.
+fn salvage(
+    value: &Value,
+    reads: &dyn Fn(&Value) -> bool,
+    pointer: &str,
+    dropped: &mut Vec<String>,
+) -> Option<Value> {
+    if reads(value) {
+        return Some(value.clone());
+    }
+    let kept = match value {
+        Value::Object(entries) => {
+            let mut kept = serde_json::Map::new();
+            for (key, entry) in entries {
+                let reads_entry = |entry: &Value| {
+                    reads(&Value::Object(
+                        [(key.clone(), entry.clone())].into_iter().collect(),
+                    ))
+                };
+                let escaped = key.replace('~', "~0").replace('/', "~1");
+                let entry_pointer = format!("{pointer}/{escaped}");
+                if let Some(entry) = salvage(entry, &reads_entry, &entry_pointer, dropped) {
+                    kept.insert(key.clone(), entry);
+                }
+            }
+            Some(Value::Object(kept))
+        }
+        Value::Array(elements) => {
+            let reads_element = |element: &Value| reads(&Value::Array(vec![element.clone()]));
+            let kept = elements
+                .iter()
+                .enumerate()
+                .filter_map(|(i, element)| {
+                    salvage(element, &reads_element, &format!("{pointer}/{i}"), dropped)
+                })
+                .collect();
+            Some(Value::Array(kept))
+        }
+        _ => None,
+    };
+    let kept = kept.filter(|kept| reads(kept));
+    if kept.is_none() {
+        dropped.push(if pointer.is_empty() {
+            "<root>".into()
+        } else {
+            pointer.into()
+        });
+    }
+    kept
+}
+
 fn format_pointer(pointer: &JSONPointer) -> String {
     let rendered = pointer.to_string();
     if rendered.is_empty() {
@@ -161,3 +267,125 @@ fn format_validation_errors<'a>(errors: impl IntoIterator<Item = ValidationError
     }
     parts.join("; ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths() -> (tempfile::TempDir, RepoPaths) {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path());
+        fs::create_dir_all(&paths.workspace_dir).unwrap();
+        (dir, paths)
+    }
+
+    #[test]
+    fn yaml_enablement_round_trips_in_yaml() {
+        let (_dir, paths) = paths();
+        fs::write(
+            &paths.enablement_yaml,
+            "version: 2\nprompts:\n  prompts/a.prompt.md: true\ninstructions: {}\nchatModes:\n  chatmodes/b.chatmode.md: false\ncollections: {}\n",
+        )
+        .unwrap();
+
+        let mut load = load_enablement(&paths).unwrap();
+        assert!(load.warnings.is_empty(), "{:?}", load.warnings);
+        assert_eq!(
+            load.file.chat_modes.get("chatmodes/b.chatmode.md"),
+            Some(&false)
+        );
+
+        load.file
+            .instructions
+            .insert("instructions/c.instructions.md".into(), true);
+        save_enablement(&paths, &mut load.file).unwrap();
+        assert!(!paths.enablement.exists());
+        let reloaded = load_enablement(&paths).unwrap();
+        assert_eq!(reloaded.file.instructions.len(), 1);
+        assert!(reloaded.file.updated_at.is_some());
+    }
+
+    #[test]
+    fn notes_are_kept_in_overrides() {
+        let (_dir, paths) = paths();
+        let mut file = EnablementFile::default();
+        file.overrides.other.insert("legacy".into(), true.into());
+        assert!(file.set_note("prompts/a.prompt.md", "  asked for by the docs team "));
+        assert!(!file.set_note("prompts/a.prompt.md", "asked for by the docs team"));
+        save_enablement(&paths, &mut file).unwrap();
+
+        let mut load = load_enablement(&paths).unwrap();
+        assert!(load.warnings.is_empty(), "{:?}", load.warnings);
+        assert_eq!(
+            load.file.note("prompts/a.prompt.md"),
+            Some("asked for by the docs team")
+        );
+
+        assert!(load.file.set_note("prompts/a.prompt.md", " "));
+        assert_eq!(load.file.note("prompts/a.prompt.md"), None);
+        assert!(load.file.overrides.notes.is_empty());
+        assert_eq!(load.file.overrides.other.len(), 1);
+    }
+
+    #[test]
+    fn yaml_enablement_is_schema_validated() {
+        let (_dir, paths) = paths();
+        fs::write(
+            &paths.enablement_yaml,
+            "version: 2\nprompts: {}\ninstructions: {}\nchatModes: {}\ncollections: {}\ntheme: dark\n",
+        )
+        .unwrap();
+        let load = load_enablement(&paths).unwrap();
+        assert!(matches!(
+            load.warnings.as_slice(),
+            [EnablementWarning::SchemaValidation(_)]
+        ));
+    }
+
+    #[test]
+    fn unreadable_entries_are_dropped_one_by_one() {
+        let load = parse_enablement(
+            r#"{
+                "version": 2,
+                "prompts": {"prompts/a.prompt.md": true, "prompts/b.prompt.md": "yes"},
+                "instructions": {"instructions/c.instructions.md": false},
+                "rules": [{"tag": "rust", "enabled": true}, {"tag": "go", "enabled": 1}]
+            }"#,
+            EnablementFormat::Json,
+        )
+        .unwrap();
+
+        assert_eq!(load.file.prompts.len(), 1);
+        assert_eq!(load.file.prompts.get("prompts/a.prompt.md"), Some(&true));
+        assert_eq!(load.file.instructions.len(), 1);
+        assert_eq!(load.file.rules.len(), 1);
+        let [EnablementWarning::SchemaValidation(errors)] = load.warnings.as_slice() else {
+            panic!("{:?}", load.warnings);
+        };
+        assert!(errors.contains(&"/prompts/prompts~1b.prompt.md: unreadable, dropped".into()));
+        assert!(errors.contains(&"/rules/1: unreadable, dropped".into()));
+    }
+
+    #[test]
+    fn old_files_are_migrated_and_history_is_saved() {
+        let (_dir, paths) = paths();
+        fs::write(
+            &paths.enablement,
+            r#"{"version": 1, "prompts": {"prompts/a.prompt.md": true}, "chat_modes": {}}"#,
+        )
+        .unwrap();
+
+        let mut load = load_enablement(&paths).unwrap();
+        assert!(matches!(
+            load.warnings.as_slice(),
+            [EnablementWarning::Migrated(_)]
+        ));
+        assert_eq!(load.file.version, crate::domain::model::ENABLEMENT_VERSION);
+        assert_eq!(load.file.prompts.len(), 1);
+        save_enablement(&paths, &mut load.file).unwrap();
+
+        let reloaded = load_enablement(&paths).unwrap();
+        assert!(reloaded.warnings.is_empty());
+        assert_eq!(reloaded.file.migrations.len(), 1);
+    }
+}
@@ -0,0 +1,157 @@
+use chrono::Utc;
+use serde_json::{Map, Value};
+
+use crate::domain::model::{MigrationRecord, ENABLEMENT_VERSION};
+
+/// One upgrade step from `from` to `from + 1`.
+struct Migration {
+    from: u32,
+    description: &'static str,
+    apply: fn(&mut Map<String, Value>),
+}
+
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: 1,
+    description: "camelCase keys, list-valued maps, unknown keys moved to overrides",
+    apply: v1_to_v2,
+}];
+
+const ASSET_MAPS: [&str; 4] = ["prompts", "instructions", "chatModes", "collections"];
+
+const KNOWN_KEYS: [&str; 8] = [
+    "version",
+    "updatedAt",
+    "prompts",
+    "instructions",
+    "chatModes",
+    "collections",
+    "overrides",
+    "migrations",
+];
+
+/// Upgrade a raw enablement document to `ENABLEMENT_VERSION`, returning the
+/// steps that ran. Documents that are not objects or are already current are
+/// left untouched.
+pub(super) fn migrate(value: &mut Value) -> Vec<MigrationRecord> {
+    let Some(object) = value.as_object_mut() else {
+        return Vec::new();
+    };
+    let mut version = object
+        .get("version")
+        .and_then(Value::as_u64)
+        .map(|v| v.max(1) as u32)
+        .unwrap_or(1);
+    let mut applied = Vec::new();
+    while version < ENABLEMENT_VERSION {
+        let Some(migration) = MIGRATIONS.iter().find(|m| m.from == version) else {
+            break;
+        };
+        (migration.apply)(object);
+        applied.push(MigrationRecord {
+            from: version,
+            to: version + 1,
+            description: migration.description.to_string(),
+            applied_at: Utc::now(),
+        });
+        version += 1;
+        object.insert("version".into(), Value::from(version));
+    }
+    if !applied.is_empty() {
+        let history = object
+            .entry("migrations")
+            .or_insert_with(|| Value::Array(Vec::new()));
+        if let Value::Array(history) = history {
+            history.extend(
+                applied
+                    .iter()
+                    .filter_map(|record| serde_json::to_value(record).ok()),
+            );
+        }
+    }
+    applied
+}
+
+/// Version 1 files were written with snake_case keys by early builds and
+/// some hand-written ones list enabled paths instead of mapping them.
+fn v1_to_v2(object: &mut Map<String, Value>) {
+    for (old, new) in [("chat_modes", "chatModes"), ("updated_at", "updatedAt")] {
+        if let Some(value) = object.remove(old) {
+            object.entry(new).or_insert(value);
+        }
+    }
+    for key in ASSET_MAPS {
+        match object.get_mut(key) {
+            Some(Value::Array(paths)) => {
+                let map: Map<String, Value> = paths
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(|path| (path.to_string(), Value::Bool(true)))
+                    .collect();
+                object.insert(key.into(), Value::Object(map));
+            }
+            Some(Value::Null) | None => {
+                object.insert(key.into(), Value::Object(Map::new()));
+            }
+            Some(_) => {}
+        }
+    }
+    let unknown: Vec<String> = object
+        .keys()
+        .filter(|key| !KNOWN_KEYS.contains(&key.as_str()))
+        .cloned()
+        .collect();
+    if unknown.is_empty() {
+        return;
+    }
+    let mut moved = Map::new();
+    for key in unknown {
+        if let Some(value) = object.remove(&key) {
+            moved.insert(key, value);
+        }
+    }
+    let overrides = object
+        .entry("overrides")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Value::Object(overrides) = overrides {
+        for (key, value) in moved {
+            overrides.entry(key).or_insert(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn v1_file_is_upgraded_without_losing_data() {
+        let mut value = json!({
+            "version": 1,
+            "updated_at": "2024-01-01T00:00:00Z",
+            "prompts": ["prompts/a.prompt.md"],
+            "chat_modes": {"chatmodes/b.chatmode.md": false},
+            "theme": "dark"
+        });
+        let applied = migrate(&mut value);
+        assert_eq!(applied.len(), 1);
+        assert_eq!(value["version"], json!(2));
+        assert_eq!(value["prompts"], json!({"prompts/a.prompt.md": true}));
+        assert_eq!(
+            value["chatModes"],
+            json!({"chatmodes/b.chatmode.md": false})
+        );
+        assert_eq!(value["instructions"], json!({}));
+        assert_eq!(value["updatedAt"], json!("2024-01-01T00:00:00Z"));
+        assert_eq!(value["overrides"], json!({"theme": "dark"}));
+        assert_eq!(value["migrations"][0]["from"], json!(1));
+    }
+
+    #[test]
+    fn current_file_is_left_alone() {
+        let mut value = json!({"version": ENABLEMENT_VERSION, "prompts": {}});
+        let before = value.clone();
+        assert!(migrate(&mut value).is_empty());
+        assert_eq!(value, before);
+    }
+}
@@ -0,0 +1,251 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{bail, Context, Result};
+
+use crate::domain::model::{EnablementChange, EnablementFile};
+
+use super::{enablement, paths::RepoPaths};
+
+/// Whether `git` is available and the repository root is inside a work tree.
+pub fn is_repository(paths: &RepoPaths) -> bool {
+    run(paths, &["rev-parse", "--is-inside-work-tree"]).is_ok_and(|output| output.trim() == "true")
+}
+
+/// Files the tool manages: `.github/` and the enablement file, relative to
+/// the repository root.
+fn managed_paths(paths: &RepoPaths) -> Vec<PathBuf> {
+    let (enablement, _) = enablement::enablement_location(paths);
+    [paths.github_dir.clone(), enablement]
+        .into_iter()
+        .filter(|path| path.exists())
+        .map(|path| relative(paths, &path))
+        .collect()
+}
+
+fn relative(paths: &RepoPaths, path: &Path) -> PathBuf {
+    path.strip_prefix(&paths.root).unwrap_or(path).to_path_buf()
+}
+
+/// Stage additions, edits and deletions under the managed paths.
+pub fn stage(paths: &RepoPaths) -> Result<()> {
+    let managed = managed_paths(paths);
+    if managed.is_empty() {
+        return Ok(());
+    }
+    let mut args: Vec<String> = vec!["add".into(), "--all".into(), "--".into()];
+    args.extend(managed.iter().map(|path| path.display().to_string()));
+    run(paths, &args)?;
+    Ok(())
+}
+
+/// The enablement file as of `HEAD`, or the default when it isn't committed
+/// yet.
+pub fn committed_enablement(paths: &RepoPaths) -> EnablementFile {
+    let (path, format) = enablement::enablement_location(paths);
+    let spec = format!("HEAD:{}", relative(paths, &path).display()).replace('\\', "/");
+    run(paths, &["show", spec.as_str()])
+        .ok()
+        .and_then(|content| enablement::parse_enablement(&content, format).ok())
+        .map(|load| load.file)
+        .unwrap_or_default()
+}
+
+/// Stage the managed paths and commit only them, leaving anything else the
+/// user staged alone. Returns the new commit's short hash.
+pub fn commit(paths: &RepoPaths, message: &str) -> Result<String> {
+    stage(paths)?;
+    let managed = managed_paths(paths);
+    let mut args: Vec<String> = vec![
+        "diff".into(),
+        "--cached".into(),
+        "--quiet".into(),
+        "--".into(),
+    ];
+    args.extend(managed.iter().map(|path| path.display().to_string()));
+    if run(paths, &args).is_ok() {
+        bail!("nothing to commit under .github or the enablement file");
+    }
+    let mut args: Vec<String> = vec![
+        "commit".into(),
+        "-m".into(),
+        message.to_string(),
+        "--".into(),
+    ];
+    args.extend(managed.iter().map(|path| path.display().to_string()));
+    run(paths, &args)?;
+    Ok(run(paths, &["rev-parse", "--short", "HEAD"])?
+        .trim()
+        .to_string())
+}
+
+/// Files under `.github/` whose content differs from `HEAD`: modified,
+/// staged or untracked. Empty outside a git repository.
+pub fn uncommitted(paths: &RepoPaths) -> Result<BTreeSet<PathBuf>> {
+    if !paths.github_dir.exists() || !is_repository(paths) {
+        return Ok(BTreeSet::new());
+    }
+    let github = relative(paths, &paths.github_dir).display().to_string();
+    let output = run(
+        paths,
+        &[
+            "status",
+            "--porcelain=v1",
+            "-z",
+            "--untracked-files=all",
+            "--",
+            github.as_str(),
+        ],
+    )?;
+    Ok(parse_status(&output)
+        .into_iter()
+        .map(|path| paths.root.join(path))
+        .collect())
+}
+
+/// Whether `path` differs from `HEAD` (modified, staged or untracked), or
+/// `None` outside a git repository.
+pub fn has_changes(paths: &RepoPaths, path: &Path) -> Result<Option<bool>> {
+    if !is_repository(paths) {
+        return Ok(None);
+    }
+    let relative = relative(paths, path).display().to_string();
+    let output = run(
+        paths,
+        &[
+            "status",
+            "--porcelain=v1",
+            "--untracked-files=all",
+            "--",
+            relative.as_str(),
+        ],
+    )?;
+    Ok(Some(!output.trim().is_empty()))
+}
+
+/// Paths from `git status --porcelain -z`, skipping deletions (nothing left
+/// to overwrite) and the source side of renames.
+fn parse_status(output: &str) -> Vec<String> {
+    let mut entries = output.split('\0').filter(|entry| !entry.is_empty());
+    let mut files = Vec::new();
+    while let Some(entry) = entries.next() {
+        let (status, path) = entry.split_at(3.min(entry.len()));
+        if status.starts_with('R') || status.starts_with('C') {
+            entries.next();
+        }
+        if !status.contains('D') {
+            files.push(path.to_string());
+        }
+    }
+    files
+}
+
+/// Commit message listing the assets enabled and disabled since `HEAD`.
+pub fn commit_message(changes: &[EnablementChange]) -> String {
+    let enabled: Vec<&str> = changes
+        .iter()
+        .filter(|change| change.after == Some(true))
+        .map(|change| change.path.as_str())
+        .collect();
+    let disabled: Vec<&str> = changes
+        .iter()
+        .filter(|change| change.after != Some(true))
+        .map(|change| change.path.as_str())
+        .collect();
+    let mut message = match (enabled.len(), disabled.len()) {
+        (0, 0) => "Update Copilot assets from upstream".to_string(),
+        (on, 0) => format!("Enable {on} Copilot asset{}", plural(on)),
+        (0, off) => format!("Disable {off} Copilot asset{}", plural(off)),
+        (on, off) => format!("Enable {on} and disable {off} Copilot assets"),
+    };
+    for (heading, paths) in [("Enabled", &enabled), ("Disabled", &disabled)] {
+        if !paths.is_empty() {
+            message.push_str(&format!("\n\n{heading}:"));
+            for path in paths.iter() {
+                message.push_str(&format!("\n- {path}"));
+            }
+        }
+    }
+    message
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+fn run<S: AsRef<std::ffi::OsStr>>(paths: &RepoPaths, args: &[S]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(&paths.root)
+        .args(args)
+        .output()
+        .context("running git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.first()
+                .map(|arg| arg.as_ref().to_string_lossy())
+                .unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::AssetKind;
+
+    fn change(path: &str, after: Option<bool>) -> EnablementChange {
+        EnablementChange {
+            kind: AssetKind::Prompt,
+            path: path.to_string(),
+            before: None,
+            after,
+        }
+    }
+
+    #[test]
+    fn message_lists_enabled_and_disabled_assets() {
+        let message = commit_message(&[
+            change("prompts/a.prompt.md", Some(true)),
+            change("prompts/b.prompt.md", Some(false)),
+            change("prompts/c.prompt.md", None),
+        ]);
+        assert_eq!(
+            message,
+            "Enable 1 and disable 2 Copilot assets\n\n\
+             Enabled:\n- prompts/a.prompt.md\n\n\
+             Disabled:\n- prompts/b.prompt.md\n- prompts/c.prompt.md"
+        );
+        assert_eq!(
+            commit_message(&[change("prompts/a.prompt.md", Some(true))])
+                .lines()
+                .next(),
+            Some("Enable 1 Copilot asset")
+        );
+    }
+
+    #[test]
+    fn status_skips_deletions_and_rename_sources() {
+        let output = " M .github/prompts/a.prompt.md\0?? .github/prompts/new.prompt.md\0\
+                      D  .github/prompts/gone.prompt.md\0R  .github/prompts/to.prompt.md\0\
+                      .github/prompts/from.prompt.md\0";
+        assert_eq!(
+            parse_status(output),
+            [
+                ".github/prompts/a.prompt.md",
+                ".github/prompts/new.prompt.md",
+                ".github/prompts/to.prompt.md",
+            ]
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::paths::RepoPaths;
+
+/// Entries kept in `history.jsonl`; older ones are dropped when it is read.
+const MAX_ENTRIES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HistoryEvent {
+    Toggle,
+    Apply,
+    Reset,
+    Save,
+}
+
+impl HistoryEvent {
+    pub fn label(self) -> &'static str {
+        match self {
+            HistoryEvent::Toggle => "toggle",
+            HistoryEvent::Apply => "apply",
+            HistoryEvent::Reset => "reset",
+            HistoryEvent::Save => "save",
+        }
+    }
+}
+
+/// One change recorded in the activity log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub at: DateTime<Utc>,
+    pub event: HistoryEvent,
+    pub summary: String,
+}
+
+impl HistoryEntry {
+    pub fn now(event: HistoryEvent, summary: impl Into<String>) -> Self {
+        Self {
+            at: Utc::now(),
+            event,
+            summary: summary.into(),
+        }
+    }
+}
+
+/// The persisted log, oldest first. Unreadable lines are skipped and the
+/// file is cut back to the newest entries once it grows past the limit.
+pub fn load_history(paths: &RepoPaths) -> Result<Vec<HistoryEntry>> {
+    let content = match fs::read_to_string(&paths.history) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("reading {}", paths.history.display()))
+        }
+    };
+    let mut entries: Vec<HistoryEntry> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    if entries.len() > MAX_ENTRIES {
+        entries.drain(..entries.len() - MAX_ENTRIES);
+        let mut trimmed = String::new();
+        for entry in &entries {
+            trimmed.push_str(&serde_json::to_string(entry)?);
+            trimmed.push('\n');
+        }
+        fs::write(&paths.history, trimmed)
+            .with_context(|| format!("writing {}", paths.history.display()))?;
+    }
+    Ok(entries)
+}
+
+/// Add `entry` to the end of the persisted log.
+pub fn append_history(paths: &RepoPaths, entry: &HistoryEntry) -> Result<()> {
+    fs::create_dir_all(&paths.workspace_dir)
+        .with_context(|| format!("creating {}", paths.workspace_dir.display()))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&paths.history)
+        .with_context(|| format!("opening {}", paths.history.display()))?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)
+        .with_context(|| format!("writing {}", paths.history.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_and_trims_the_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path());
+        assert!(load_history(&paths).unwrap().is_empty());
+
+        for index in 0..MAX_ENTRIES + 2 {
+            let entry = HistoryEntry::now(HistoryEvent::Toggle, format!("entry {index}"));
+            append_history(&paths, &entry).unwrap();
+        }
+        fs::write(
+            &paths.history,
+            fs::read_to_string(&paths.history).unwrap() + "not json\n",
+        )
+        .unwrap();
+
+        let entries = load_history(&paths).unwrap();
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0].summary, "entry 2");
+        assert_eq!(
+            fs::read_to_string(&paths.history).unwrap().lines().count(),
+            MAX_ENTRIES
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+
+use super::{
+    catalog::{self, CatalogLoad},
+    changelog::{self, CatalogChange, CatalogDigest, Changelog},
+    enablement::{self, EnablementLoad},
+    manifest,
+    paths::RepoPaths,
+    symlink,
+    upstream::{FetchOptions, SnapshotProgress, UpstreamSource},
+    variables,
+};
+
+/// Everything needed to (re)build the domain state.
+pub struct LoadedState {
+    pub catalog: CatalogLoad,
+    pub enablement: EnablementLoad,
+    /// Upstream changes since the previous snapshots.
+    pub changelog: Changelog,
+    /// SHA-256 of catalog paths as last seen, to match orphans to renamed
+    /// assets.
+    pub known_hashes: BTreeMap<String, String>,
+}
+
+/// Load catalog and enablement state synchronously; the TUI runs this on its
+/// worker thread. Linked assets are pointed at the new snapshots and the
+/// catalog history is updated, so callers must hold the instance lock.
+pub fn load_state(
+    paths: &RepoPaths,
+    sources: &[UpstreamSource],
+    fetch: FetchOptions,
+    progress: &dyn Fn(SnapshotProgress),
+) -> Result<LoadedState> {
+    let (mut catalog, enablement) = load_sources(paths, sources, fetch, progress)?;
+    if let Err(err) = symlink::refresh_links(paths, &catalog.upstream) {
+        catalog.warnings.push(format!(
+            "Could not point linked assets at the new snapshot: {err:#}"
+        ));
+    }
+    let digest = CatalogDigest::new(&catalog.catalog, &catalog.upstream);
+    let changelog = changelog::record(paths, digest).unwrap_or_else(|err| {
+        catalog
+            .warnings
+            .push(format!("Could not update the catalog history: {err:#}"));
+        Changelog::default()
+    });
+    let known_hashes = known_hashes(paths, &changelog);
+    Ok(LoadedState {
+        catalog,
+        enablement,
+        changelog,
+        known_hashes,
+    })
+}
+
+/// [`load_state`] without writing to the repository, for commands that only
+/// read it and so run without the instance lock: links are left as they
+/// are and the catalog history is only read.
+pub fn read_state(
+    paths: &RepoPaths,
+    sources: &[UpstreamSource],
+    fetch: FetchOptions,
+) -> Result<LoadedState> {
+    let (mut catalog, enablement) = load_sources(paths, sources, fetch, &|_| {})?;
+    let digest = CatalogDigest::new(&catalog.catalog, &catalog.upstream);
+    let changelog = changelog::peek(paths, digest).unwrap_or_else(|err| {
+        catalog
+            .warnings
+            .push(format!("Could not read the catalog history: {err:#}"));
+        Changelog::default()
+    });
+    let known_hashes = known_hashes(paths, &changelog);
+    Ok(LoadedState {
+        catalog,
+        enablement,
+        changelog,
+        known_hashes,
+    })
+}
+
+fn load_sources(
+    paths: &RepoPaths,
+    sources: &[UpstreamSource],
+    fetch: FetchOptions,
+    progress: &dyn Fn(SnapshotProgress),
+) -> Result<(CatalogLoad, EnablementLoad)> {
+    let mut catalog = catalog::load_catalog(paths, sources, fetch, progress)?;
+    let enablement = enablement::load_enablement(paths)?;
+    catalog
+        .upstream
+        .set_patches(enablement.file.overrides.patches.clone());
+    catalog
+        .upstream
+        .set_variables(variables::load_variables(paths)?);
+    Ok((catalog, enablement))
+}
+
+/// What applied assets hashed to, overridden by the previous snapshot for
+/// assets that left the catalog with the last upstream change.
+fn known_hashes(paths: &RepoPaths, changelog: &Changelog) -> BTreeMap<String, String> {
+    let mut hashes: BTreeMap<_, _> = manifest::load_manifest(paths)
+        .map(|manifest| manifest.files)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(path, entry)| (path, entry.sha256))
+        .collect();
+    for change in &changelog.changes {
+        if let CatalogChange::Removed(gone) | CatalogChange::Renamed { from: gone, .. } = change {
+            hashes.insert(gone.path.clone(), gone.sha256.clone());
+        }
+    }
+    hashes
+}
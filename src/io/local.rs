@@ -0,0 +1,159 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use crate::domain::model::AssetKind;
+
+use super::paths::RepoPaths;
+
+/// A new in-house asset to scaffold under `.awesome-copilot-tui/local/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewAsset {
+    pub kind: AssetKind,
+    pub title: String,
+    pub description: String,
+    /// Prompts only: `agent`, `ask` or `edit`.
+    pub mode: String,
+    /// Instructions only: glob of the files the instructions apply to.
+    pub apply_to: String,
+    /// Prompts, chat modes and agents.
+    pub tools: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Header<'a> {
+    description: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    mode: &'a str,
+    #[serde(rename = "applyTo", skip_serializing_if = "str::is_empty")]
+    apply_to: &'a str,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    tools: &'a [String],
+}
+
+impl NewAsset {
+    /// File name stem derived from the title, e.g. `deploy-checklist`.
+    pub fn slug(&self) -> String {
+        self.title
+            .to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// Markdown with the front matter the catalog reads.
+    pub fn render(&self) -> Result<String> {
+        let header = serde_yaml::to_string(&Header {
+            description: &self.description,
+            mode: &self.mode,
+            apply_to: &self.apply_to,
+            tools: &self.tools,
+        })?;
+        Ok(format!(
+            "---\n{header}---\n\n# {}\n\n{}\n",
+            self.title, self.description
+        ))
+    }
+}
+
+/// Write `asset` into the local catalog, returning the catalog-relative path
+/// (e.g. `prompts/deploy-checklist.prompt.md`). Existing files are never
+/// overwritten.
+pub fn scaffold(paths: &RepoPaths, asset: &NewAsset) -> Result<String> {
+    let kind = asset.kind;
+    if matches!(kind, AssetKind::McpServer | AssetKind::Collection) {
+        bail!("only prompts, instructions, chat modes and agents can be created");
+    }
+    let slug = asset.slug();
+    if slug.is_empty() {
+        bail!("the title needs at least one letter or digit");
+    }
+    let relative = format!("{}/{slug}{}", kind_dir(kind), kind.file_suffix());
+    let path: PathBuf = paths.local_dir.join(&relative);
+    if path.exists() {
+        bail!("{} already exists", path.display());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+    }
+    fs::write(&path, asset.render()?).with_context(|| format!("writing {}", path.display()))?;
+    Ok(relative)
+}
+
+/// Copy an unmanaged file from `.github/` into the local catalog so it
+/// becomes a local asset, returning its catalog-relative path. The copy maps
+/// back to the same file under `.github/`.
+pub fn adopt_file(paths: &RepoPaths, kind: AssetKind, file: &Path) -> Result<String> {
+    let below = file
+        .strip_prefix(paths.asset_root(kind))
+        .with_context(|| format!("{} is not under .github", file.display()))?;
+    let below: Vec<_> = below.iter().map(|part| part.to_string_lossy()).collect();
+    let relative = format!("{}/{}", kind_dir(kind), below.join("/"));
+    let path = paths.local_dir.join(&relative);
+    if path.exists() {
+        bail!("{} already exists", path.display());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+    }
+    fs::copy(file, &path)
+        .with_context(|| format!("copying {} -> {}", file.display(), path.display()))?;
+    Ok(relative)
+}
+
+fn kind_dir(kind: AssetKind) -> &'static str {
+    match kind {
+        AssetKind::Prompt => "prompts",
+        AssetKind::Instruction => "instructions",
+        AssetKind::ChatMode => "chatmodes",
+        AssetKind::Agent => "agents",
+        AssetKind::McpServer => "mcp",
+        AssetKind::Collection => "collections",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{domain::model::Catalog, io::catalog};
+
+    #[test]
+    fn scaffolds_front_matter_and_refuses_to_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path());
+        let asset = NewAsset {
+            kind: AssetKind::Instruction,
+            title: "Rust: Error handling".into(),
+            description: "Use anyhow: add context".into(),
+            mode: String::new(),
+            apply_to: "**/*.rs".into(),
+            tools: Vec::new(),
+        };
+
+        let relative = scaffold(&paths, &asset).unwrap();
+        assert_eq!(relative, "instructions/rust-error-handling.instructions.md");
+        let content = fs::read_to_string(paths.local_dir.join(&relative)).unwrap();
+        assert_eq!(
+            content,
+            "---\ndescription: 'Use anyhow: add context'\napplyTo: '**/*.rs'\n---\n\n\
+             # Rust: Error handling\n\nUse anyhow: add context\n"
+        );
+        assert!(scaffold(&paths, &asset).is_err());
+
+        let mut catalog = Catalog::default();
+        let path =
+            catalog::add_local_asset(&mut catalog, &paths, AssetKind::Instruction, &relative)
+                .unwrap();
+        assert_eq!(
+            path,
+            "local:instructions/rust-error-handling.instructions.md"
+        );
+        assert_eq!(catalog.instructions[0].apply_to, ["**/*.rs"]);
+        assert_eq!(catalog.instructions[0].name, "Rust: Error handling");
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+};
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use fs2::FileExt;
+
+use super::paths::RepoPaths;
+
+/// Exclusive lock on `.awesome-copilot-tui/lock`, held while a session may
+/// write the enablement file or `.github/`. The OS releases it when the
+/// process exits, so a crash never leaves a stale lock behind.
+pub struct InstanceLock {
+    file: File,
+}
+
+impl InstanceLock {
+    /// Take the lock, failing with the holder's details when another session
+    /// already has it.
+    pub fn acquire(paths: &RepoPaths) -> Result<Self> {
+        fs::create_dir_all(&paths.workspace_dir)
+            .with_context(|| format!("creating {}", paths.workspace_dir.display()))?;
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&paths.lock)
+            .with_context(|| format!("opening {}", paths.lock.display()))?;
+        if file.try_lock_exclusive().is_err() {
+            let mut holder = String::new();
+            let _ = file.read_to_string(&mut holder);
+            bail!(
+                "another awesome-copilot-tui session is using {} ({}); close it first",
+                paths.root.display(),
+                holder.trim()
+            );
+        }
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        write!(
+            file,
+            "pid {} since {}",
+            std::process::id(),
+            Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+        )
+        .with_context(|| format!("writing {}", paths.lock.display()))?;
+        Ok(Self { file })
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_session_is_refused_until_first_ends() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path());
+
+        let first = InstanceLock::acquire(&paths).unwrap();
+        let err = InstanceLock::acquire(&paths).err().unwrap().to_string();
+        assert!(err.contains("another awesome-copilot-tui session"));
+        assert!(err.contains(&format!("pid {}", std::process::id())));
+
+        drop(first);
+        assert!(InstanceLock::acquire(&paths).is_ok());
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+/// Size at which `--log-file` rolls over to a fresh file.
+pub const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// An append-only log file that, once it grows past `max_bytes`, is renamed
+/// to `<path>.1` (replacing the previous one) and started afresh.
+pub struct RollingFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+}
+
+impl RollingFile {
+    pub fn open(path: &Path, max_bytes: u64) -> Result<Self> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+        }
+        let file = append(path).with_context(|| format!("opening {}", path.display()))?;
+        let written = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            written,
+            max_bytes,
+        })
+    }
+
+    fn roll(&mut self) -> io::Result<()> {
+        let mut rolled = self.path.clone().into_os_string();
+        rolled.push(".1");
+        fs::rename(&self.path, rolled)?;
+        self.file = append(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+fn append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+impl Write for RollingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.roll()?;
+        }
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolls_over_past_the_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs/tui.log");
+        let mut log = RollingFile::open(&path, 10).unwrap();
+        log.write_all(b"first\n").unwrap();
+        log.write_all(b"second\n").unwrap();
+        log.write_all(b"third\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "third\n");
+        assert_eq!(
+            fs::read_to_string(dir.path().join("logs/tui.log.1")).unwrap(),
+            "second\n"
+        );
+        // Reopening continues the current file.
+        let mut log = RollingFile::open(&path, 10).unwrap();
+        log.write_all(b"x\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "third\nx\n");
+    }
+}
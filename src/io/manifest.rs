@@ -0,0 +1,138 @@
+use std::{collections::BTreeMap, fs, io::Write};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::model::split_source;
+
+use super::paths::RepoPaths;
+
+/// What was written when one asset was last applied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedEntry {
+    /// Snapshot commit of the asset's source. Local assets have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// SHA-256 of the content that was applied: the upstream file with its
+    /// content patch, variables and line endings applied, as
+    /// `sync::upstream_content` returns it.
+    pub sha256: String,
+    pub applied_at: DateTime<Utc>,
+}
+
+/// `.awesome-copilot-tui/applied.json`: the applied files, keyed by catalog
+/// path.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppliedManifest {
+    #[serde(default)]
+    pub files: BTreeMap<String, AppliedEntry>,
+}
+
+impl AppliedManifest {
+    /// Record that `asset_path` was just applied from content hashing to
+    /// `sha256`.
+    pub fn record(&mut self, asset_path: &str, commit: Option<&str>, sha256: String) {
+        self.files.insert(
+            asset_path.to_string(),
+            AppliedEntry {
+                commit: commit.map(str::to_string),
+                sha256,
+                applied_at: Utc::now(),
+            },
+        );
+    }
+
+    /// Drop `asset_path` after its file was removed.
+    pub fn forget(&mut self, asset_path: &str) {
+        self.files.remove(asset_path);
+    }
+
+    /// The asset of another source recorded for the same local file as
+    /// `asset_path`, since every source applies below the same `.github/`.
+    pub fn other_owner(&self, asset_path: &str) -> Option<&str> {
+        let (_, relative) = split_source(asset_path);
+        self.files
+            .keys()
+            .find(|path| path.as_str() != asset_path && split_source(path).1 == relative)
+            .map(String::as_str)
+    }
+}
+
+pub fn load_manifest(paths: &RepoPaths) -> Result<AppliedManifest> {
+    match fs::read_to_string(&paths.applied_manifest) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| format!("parsing {}", paths.applied_manifest.display())),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(AppliedManifest::default()),
+        Err(err) => {
+            Err(err).with_context(|| format!("reading {}", paths.applied_manifest.display()))
+        }
+    }
+}
+
+fn save_manifest(paths: &RepoPaths, manifest: &AppliedManifest) -> Result<()> {
+    // A sub-project keeps its manifest below the workspace directory.
+    let dir = paths
+        .applied_manifest
+        .parent()
+        .unwrap_or(&paths.workspace_dir);
+    fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    let json = serde_json::to_string_pretty(manifest).context("serializing applied manifest")?;
+    let mut temp = tempfile::NamedTempFile::new_in(dir)
+        .context("creating temporary file for the applied manifest")?;
+    temp.write_all(json.as_bytes())
+        .and_then(|()| temp.write_all(b"\n"))
+        .context("writing applied manifest")?;
+    temp.persist(&paths.applied_manifest)
+        .with_context(|| format!("writing {}", paths.applied_manifest.display()))?;
+    Ok(())
+}
+
+/// Run `batch` of applies and removals against the manifest, read once, and
+/// save it once afterwards if it changed. It is saved even when the batch
+/// fails partway, so the files written before the failure stay recorded.
+pub fn with_manifest<T>(
+    paths: &RepoPaths,
+    batch: impl FnOnce(&mut AppliedManifest) -> Result<T>,
+) -> Result<T> {
+    let mut manifest = load_manifest(paths)?;
+    let loaded = manifest.clone();
+    let result = batch(&mut manifest);
+    if manifest != loaded {
+        save_manifest(paths, &manifest)?;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_and_forgets_applied_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path());
+        assert_eq!(load_manifest(&paths).unwrap(), AppliedManifest::default());
+
+        with_manifest(&paths, |manifest| {
+            manifest.record("prompts/a.prompt.md", Some("abc123"), "1".into());
+            manifest.record("local:prompts/b.prompt.md", None, "2".into());
+            Ok(())
+        })
+        .unwrap();
+        let failed: Result<()> = with_manifest(&paths, |manifest| {
+            manifest.record("prompts/a.prompt.md", Some("def456"), "3".into());
+            manifest.forget("local:prompts/b.prompt.md");
+            manifest.forget("prompts/missing.prompt.md");
+            anyhow::bail!("the next file failed")
+        });
+        assert!(failed.is_err());
+
+        let manifest = load_manifest(&paths).unwrap();
+        assert_eq!(manifest.files.len(), 1);
+        let entry = &manifest.files["prompts/a.prompt.md"];
+        assert_eq!(entry.commit.as_deref(), Some("def456"));
+        assert_eq!(entry.sha256, "3");
+    }
+}
@@ -0,0 +1,82 @@
+use std::{fs::OpenOptions, io::Write};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+
+use super::paths::RepoPaths;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageLevel {
+    Info,
+    Error,
+}
+
+impl MessageLevel {
+    pub fn label(self) -> &'static str {
+        match self {
+            MessageLevel::Info => "info",
+            MessageLevel::Error => "error",
+        }
+    }
+}
+
+/// A footer message as it was shown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageEntry {
+    pub at: DateTime<Utc>,
+    pub level: MessageLevel,
+    pub text: String,
+}
+
+impl MessageEntry {
+    pub fn now(level: MessageLevel, text: impl Into<String>) -> Self {
+        Self {
+            at: Utc::now(),
+            level,
+            text: text.into(),
+        }
+    }
+}
+
+/// Add `entry` to `messages.log` as one `<RFC 3339 time> <level> <text>`
+/// line.
+pub fn append_message(paths: &RepoPaths, entry: &MessageEntry) -> Result<()> {
+    std::fs::create_dir_all(&paths.workspace_dir)
+        .with_context(|| format!("creating {}", paths.workspace_dir.display()))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&paths.message_log)
+        .with_context(|| format!("opening {}", paths.message_log.display()))?;
+    writeln!(
+        file,
+        "{} {:<5} {}",
+        entry.at.to_rfc3339(),
+        entry.level.label(),
+        entry.text.replace('\n', " ")
+    )
+    .with_context(|| format!("writing {}", paths.message_log.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_one_line_per_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path());
+        append_message(&paths, &MessageEntry::now(MessageLevel::Info, "Saved")).unwrap();
+        append_message(
+            &paths,
+            &MessageEntry::now(MessageLevel::Error, "Failed:\nboom"),
+        )
+        .unwrap();
+
+        let content = std::fs::read_to_string(&paths.message_log).unwrap();
+        let lines: Vec<_> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with(" info  Saved"));
+        assert!(lines[1].ends_with(" error Failed: boom"));
+    }
+}
@@ -1,5 +1,37 @@
+pub mod aggregate;
+pub mod backups;
 pub mod catalog;
+pub mod changelog;
+pub mod changes;
+pub mod check;
+pub mod clipboard;
+pub mod config;
+pub mod content_index;
+pub mod difftool;
+pub mod doctor;
 pub mod enablement;
+pub mod git;
+pub mod history;
+pub mod loader;
+pub mod local;
+pub mod lock;
+pub mod log_file;
+pub mod manifest;
+pub mod message_log;
+pub mod patch;
 pub mod paths;
+pub mod plan;
+pub mod profile;
+pub mod prune;
+pub mod report;
+pub mod repos;
+pub mod retry;
+pub mod script;
+pub mod symlink;
 pub mod sync;
 pub mod upstream;
+pub mod variables;
+pub mod vscode;
+pub mod watcher;
+pub mod worker;
+pub mod workspace;
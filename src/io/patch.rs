@@ -0,0 +1,87 @@
+use crate::domain::model::ContentPatch;
+
+/// `content` with the front matter keys of `patch` set. Existing keys are
+/// replaced along with their indented continuation lines, missing keys are
+/// added at the end of the front matter, and a file without front matter
+/// gets one. Values are written as JSON, which YAML reads as flow values.
+pub fn patch_content(content: &str, patch: &ContentPatch) -> String {
+    let fields = patch.fields();
+    if fields.is_empty() {
+        return content.to_string();
+    }
+    let (front, body) = split_front_matter(content).unwrap_or(("", content));
+
+    let mut out = String::from("---\n");
+    let mut pending: Vec<&str> = fields.iter().map(|(key, _)| *key).collect();
+    let mut replacing = false;
+    for line in front.lines() {
+        let continuation = line.starts_with([' ', '\t']) || line.starts_with("- ");
+        if replacing && continuation {
+            continue;
+        }
+        replacing = false;
+        if let Some((key, value)) = line
+            .split_once(':')
+            .and_then(|(key, _)| fields.iter().find(|(field, _)| *field == key.trim_end()))
+        {
+            out.push_str(&field_line(key, value));
+            pending.retain(|field| field != key);
+            replacing = true;
+            continue;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    for (key, value) in &fields {
+        if pending.contains(key) {
+            out.push_str(&field_line(key, value));
+        }
+    }
+    out.push_str("---\n");
+    out.push_str(body);
+    out
+}
+
+/// The front matter lines and the body after the closing `---`.
+pub fn split_front_matter(content: &str) -> Option<(&str, &str)> {
+    let rest = content.strip_prefix("---\n")?;
+    if let Some(body) = rest.strip_prefix("---\n") {
+        return Some(("", body));
+    }
+    match rest.find("\n---\n") {
+        Some(end) => Some((&rest[..=end], &rest[end + 5..])),
+        None => rest.strip_suffix("\n---").map(|front| (front, "")),
+    }
+}
+
+fn field_line(key: &str, value: &serde_json::Value) -> String {
+    format!("{key}: {value}\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn replaces_and_adds_front_matter_keys() {
+        let patch = ContentPatch {
+            apply_to: Some("src/**/*.rs".into()),
+            front_matter: BTreeMap::from([
+                ("applyTo".into(), "ignored".into()),
+                ("tools".into(), serde_json::json!(["search"])),
+            ]),
+        };
+        let content = "---\ndescription: Rust\napplyTo: '**/*.rs'\ntools:\n  - edit\n---\n# Rust\n";
+        assert_eq!(
+            patch_content(content, &patch),
+            "---\ndescription: Rust\napplyTo: \"src/**/*.rs\"\ntools: [\"search\"]\n---\n# Rust\n"
+        );
+        assert_eq!(
+            patch_content("# Plain\n", &patch),
+            "---\napplyTo: \"src/**/*.rs\"\ntools: [\"search\"]\n---\n# Plain\n"
+        );
+        assert_eq!(patch_content(content, &ContentPatch::default()), content);
+    }
+}
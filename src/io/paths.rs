@@ -1,37 +1,183 @@
 use std::path::{Path, PathBuf};
 
+/// Where enabled assets are applied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Target {
+    /// The repository's `.github/` and `.vscode/`.
+    #[default]
+    Repo,
+    /// The VS Code user profile, for every workspace, with its own
+    /// enablement state in the user config directory.
+    User,
+}
+
 #[derive(Debug, Clone)]
 pub struct RepoPaths {
+    pub target: Target,
+    /// Monorepo sub-project the assets are applied to, relative to `root`,
+    /// e.g. `services/api`; `None` for the repository itself.
+    pub project: Option<String>,
     pub root: PathBuf,
+    /// Directory the asset files are applied below: `.github/`, or the
+    /// profile's `prompts/` folder for the user target.
     pub github_dir: PathBuf,
     pub instructions_dir: PathBuf,
     pub prompts_dir: PathBuf,
     pub chatmodes_dir: PathBuf,
+    pub agents_dir: PathBuf,
     pub collections_dir: PathBuf,
+    /// Repository-wide instructions, where `[instructions] aggregate` keeps
+    /// the enabled instructions.
+    pub copilot_instructions: PathBuf,
     pub workspace_dir: PathBuf,
     pub cache_dir: PathBuf,
+    /// Digests of the current and previous catalogs, for the changelog.
+    pub catalog_history: PathBuf,
     pub backups_dir: PathBuf,
+    /// Activity log, written when `[history] persist` is set.
+    pub history: PathBuf,
+    /// Footer messages, written when `[log] file` is set.
+    pub message_log: PathBuf,
+    /// What each applied file was copied from and when.
+    pub applied_manifest: PathBuf,
+    pub base_dir: PathBuf,
+    /// Local edits kept by the patch apply mode, as unified diffs against
+    /// the merge base.
+    pub patches_dir: PathBuf,
+    pub sources_cache_dir: PathBuf,
+    /// In-house assets, laid out like an upstream snapshot.
+    pub local_dir: PathBuf,
+    /// One symlink per source to its loaded snapshot, which files applied
+    /// in symlink mode point through.
+    pub links_dir: PathBuf,
     pub enablement: PathBuf,
+    pub enablement_yaml: PathBuf,
+    /// Values for placeholders in applied files.
+    pub variables: PathBuf,
+    pub sources: PathBuf,
+    pub workspace_state: PathBuf,
+    pub lock: PathBuf,
+    pub vscode_dir: PathBuf,
+    pub vscode_settings: PathBuf,
+    /// Workspace MCP server configuration, where MCP server assets are
+    /// registered.
+    pub vscode_mcp: PathBuf,
 }
 
 impl RepoPaths {
     pub fn new(root: impl AsRef<Path>) -> Self {
         let root = root.as_ref().to_path_buf();
-        let github_dir = root.join(".github");
-        let workspace_dir = root.join(".awesome-copilot-tui");
+        Self::layout(
+            root.join(".github"),
+            root.join(".awesome-copilot-tui"),
+            root.join(".vscode"),
+            root,
+        )
+    }
+
+    /// Paths for the user target: VS Code reads prompts, instructions, chat
+    /// modes and agents from one flat `prompts/` folder of the profile in
+    /// `vscode_dir` (e.g. `~/.config/Code/User`), and MCP servers from its
+    /// `mcp.json`. The enablement file, cache and backups live in
+    /// `state_dir`.
+    pub fn user(state_dir: impl AsRef<Path>, vscode_dir: impl AsRef<Path>) -> Self {
+        let state_dir = state_dir.as_ref().to_path_buf();
+        let vscode_dir = vscode_dir.as_ref().to_path_buf();
+        let prompts_dir = vscode_dir.join("prompts");
+        let mut paths = Self::layout(
+            prompts_dir.clone(),
+            state_dir.clone(),
+            vscode_dir,
+            state_dir,
+        );
+        paths.target = Target::User;
+        paths.instructions_dir = prompts_dir.clone();
+        paths.chatmodes_dir = prompts_dir.clone();
+        paths.agents_dir = prompts_dir.clone();
+        paths.prompts_dir = prompts_dir;
+        paths
+    }
+
+    /// Paths for a monorepo sub-project such as `services/api`: assets are
+    /// applied to its own `.github/` and `.vscode/`, while its enablement
+    /// file, variables, applied-file records and backups live in
+    /// `.awesome-copilot-tui/projects/<project>/`. The cache, sources and
+    /// config stay shared with the repository.
+    pub fn project(root: impl AsRef<Path>, project: &str) -> Self {
+        let root = root.as_ref().to_path_buf();
+        let project_dir = root.join(project);
+        let mut paths = Self::layout(
+            project_dir.join(".github"),
+            root.join(".awesome-copilot-tui"),
+            project_dir.join(".vscode"),
+            root,
+        );
+        let state_dir = paths.workspace_dir.join("projects").join(project);
+        paths.enablement = state_dir.join("enablement.json");
+        paths.enablement_yaml = state_dir.join("enablement.yaml");
+        paths.variables = state_dir.join("variables.toml");
+        paths.applied_manifest = state_dir.join("applied.json");
+        paths.base_dir = state_dir.join("base");
+        paths.patches_dir = state_dir.join("patches");
+        paths.backups_dir = state_dir.join("backups");
+        paths.project = Some(project.to_string());
+        paths
+    }
+
+    fn layout(
+        github_dir: PathBuf,
+        workspace_dir: PathBuf,
+        vscode_dir: PathBuf,
+        root: PathBuf,
+    ) -> Self {
         let cache_dir = workspace_dir.join("cache");
         let backups_dir = workspace_dir.join("backups");
+        let catalog_history = cache_dir.join("catalog-history.json");
+        let history = workspace_dir.join("history.jsonl");
+        let message_log = workspace_dir.join("messages.log");
+        let applied_manifest = workspace_dir.join("applied.json");
+        let base_dir = workspace_dir.join("base");
+        let patches_dir = workspace_dir.join("patches");
+        let sources_cache_dir = workspace_dir.join("sources");
+        let local_dir = workspace_dir.join("local");
+        let links_dir = workspace_dir.join("links");
         let enablement = workspace_dir.join("enablement.json");
+        let enablement_yaml = workspace_dir.join("enablement.yaml");
+        let variables = workspace_dir.join("variables.toml");
+        let sources = workspace_dir.join("sources.json");
+        let workspace_state = workspace_dir.join("workspace.json");
+        let lock = workspace_dir.join("lock");
         Self {
+            target: Target::Repo,
+            project: None,
             github_dir: github_dir.clone(),
             instructions_dir: github_dir.join("instructions"),
             prompts_dir: github_dir.join("prompts"),
             chatmodes_dir: github_dir.join("chatmodes"),
+            agents_dir: github_dir.join("agents"),
             collections_dir: github_dir.join("collections"),
+            copilot_instructions: github_dir.join("copilot-instructions.md"),
             workspace_dir,
             cache_dir,
+            catalog_history,
             backups_dir,
+            history,
+            message_log,
+            applied_manifest,
+            base_dir,
+            patches_dir,
+            sources_cache_dir,
+            local_dir,
+            links_dir,
             enablement,
+            enablement_yaml,
+            variables,
+            sources,
+            workspace_state,
+            lock,
+            vscode_settings: vscode_dir.join("settings.json"),
+            vscode_mcp: vscode_dir.join("mcp.json"),
+            vscode_dir,
             root,
         }
     }
@@ -41,6 +187,7 @@ impl RepoPaths {
         std::fs::create_dir_all(&self.instructions_dir)?;
         std::fs::create_dir_all(&self.prompts_dir)?;
         std::fs::create_dir_all(&self.chatmodes_dir)?;
+        std::fs::create_dir_all(&self.agents_dir)?;
         // Do not create collections directory under .github: collections are a logical grouping only.
         std::fs::create_dir_all(&self.cache_dir)?;
         std::fs::create_dir_all(&self.backups_dir)?;
@@ -52,7 +199,53 @@ impl RepoPaths {
             crate::domain::model::AssetKind::Prompt => &self.prompts_dir,
             crate::domain::model::AssetKind::Instruction => &self.instructions_dir,
             crate::domain::model::AssetKind::ChatMode => &self.chatmodes_dir,
+            crate::domain::model::AssetKind::Agent => &self.agents_dir,
+            crate::domain::model::AssetKind::McpServer => &self.vscode_dir,
             crate::domain::model::AssetKind::Collection => &self.collections_dir,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{domain::model::AssetKind, io::sync};
+
+    #[test]
+    fn user_target_applies_into_the_profile_prompts_folder() {
+        let paths = RepoPaths::user("/state", "/home/me/.config/Code/User");
+        let prompts = Path::new("/home/me/.config/Code/User/prompts");
+
+        assert_eq!(
+            sync::local_path(&paths, AssetKind::Prompt, "prompts/review.prompt.md"),
+            prompts.join("review.prompt.md")
+        );
+        assert_eq!(
+            sync::local_path(&paths, AssetKind::ChatMode, "chatmodes/plan.chatmode.md"),
+            prompts.join("plan.chatmode.md")
+        );
+        assert_eq!(
+            sync::local_path(&paths, AssetKind::McpServer, "mcp/github.mcp.json"),
+            Path::new("/home/me/.config/Code/User/mcp.json")
+        );
+        assert_eq!(paths.enablement, Path::new("/state/enablement.json"));
+    }
+
+    #[test]
+    fn sub_project_shares_the_cache_but_not_the_enablement_file() {
+        let repo = RepoPaths::new("/mono");
+        let api = RepoPaths::project("/mono", "services/api");
+
+        assert_eq!(
+            sync::local_path(&api, AssetKind::Prompt, "prompts/review.prompt.md"),
+            Path::new("/mono/services/api/.github/prompts/review.prompt.md")
+        );
+        assert_eq!(
+            api.enablement,
+            Path::new("/mono/.awesome-copilot-tui/projects/services/api/enablement.json")
+        );
+        assert_ne!(api.applied_manifest, repo.applied_manifest);
+        assert_eq!(api.cache_dir, repo.cache_dir);
+        assert_eq!(api.lock, repo.lock);
+    }
+}
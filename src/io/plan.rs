@@ -0,0 +1,209 @@
+use std::fmt;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::domain::model::AssetKind;
+
+use super::{
+    manifest::{self, AppliedManifest},
+    paths::RepoPaths,
+    sync::{self, ApplyMode, LocalStatus},
+    upstream::UpstreamRoots,
+    vscode,
+};
+
+/// What a bulk operation would do to one local file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlannedChange {
+    Create,
+    Overwrite,
+    Merge,
+    Remove,
+}
+
+impl PlannedChange {
+    /// Terraform-style marker shown before the path.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            PlannedChange::Create => "+",
+            PlannedChange::Overwrite | PlannedChange::Merge => "~",
+            PlannedChange::Remove => "-",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PlannedChange::Create => "create",
+            PlannedChange::Overwrite => "overwrite",
+            PlannedChange::Merge => "merge",
+            PlannedChange::Remove => "remove",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlanEntry {
+    pub kind: AssetKind,
+    /// Upstream-relative asset path.
+    pub path: String,
+    pub change: PlannedChange,
+}
+
+impl fmt::Display for PlanEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:<9} {}",
+            self.change.symbol(),
+            self.change.label(),
+            self.path
+        )
+    }
+}
+
+/// Every file a bulk operation would touch, computed from the current local
+/// statuses before anything is written.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncPlan {
+    pub entries: Vec<PlanEntry>,
+}
+
+impl SyncPlan {
+    /// Record what applying `kind`/`path` from upstream would do. Files that
+    /// already match upstream are left out.
+    pub fn add_apply(
+        &mut self,
+        paths: &RepoPaths,
+        upstream: &UpstreamRoots,
+        manifest: &AppliedManifest,
+        kind: AssetKind,
+        path: &str,
+        mode: ApplyMode,
+    ) -> Result<()> {
+        let change = match sync::compute_local_status(paths, upstream, manifest, kind, path)? {
+            LocalStatus::Missing => PlannedChange::Create,
+            LocalStatus::Diff | LocalStatus::Update
+                if matches!(mode, ApplyMode::Merge | ApplyMode::Patch) =>
+            {
+                PlannedChange::Merge
+            }
+            LocalStatus::Diff | LocalStatus::Update => PlannedChange::Overwrite,
+            LocalStatus::Same | LocalStatus::Unmanaged | LocalStatus::NA => return Ok(()),
+        };
+        self.push(kind, path, change);
+        Ok(())
+    }
+
+    /// Record the removal of `kind`/`path`'s local file, if there is one.
+    pub fn add_remove(&mut self, paths: &RepoPaths, kind: AssetKind, path: &str) {
+        let present = match kind {
+            AssetKind::Collection => false,
+            AssetKind::McpServer => vscode::has_mcp_server(paths, path),
+            _ => sync::local_path(paths, kind, path).exists(),
+        };
+        if present {
+            self.push(kind, path, PlannedChange::Remove);
+        }
+    }
+
+    /// Plan for applying every asset in `assets` from upstream.
+    pub fn for_apply<'a>(
+        paths: &RepoPaths,
+        upstream: &UpstreamRoots,
+        assets: impl IntoIterator<Item = (AssetKind, &'a str)>,
+        mode: ApplyMode,
+    ) -> Result<Self> {
+        let manifest = manifest::load_manifest(paths)?;
+        let mut plan = Self::default();
+        for (kind, path) in assets {
+            plan.add_apply(paths, upstream, &manifest, kind, path, mode)?;
+        }
+        Ok(plan)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn count(&self, change: PlannedChange) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.change == change)
+            .count()
+    }
+
+    fn push(&mut self, kind: AssetKind, path: &str, change: PlannedChange) {
+        // Collections can reach the same file twice; the first plan wins.
+        if !self.entries.iter().any(|entry| entry.path == path) {
+            self.entries.push(PlanEntry {
+                kind,
+                path: path.to_string(),
+                change,
+            });
+        }
+    }
+}
+
+impl fmt::Display for SyncPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} to create, {} to overwrite, {} to remove",
+            self.count(PlannedChange::Create),
+            self.count(PlannedChange::Overwrite) + self.count(PlannedChange::Merge),
+            self.count(PlannedChange::Remove)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::domain::model::PRIMARY_SOURCE;
+
+    #[test]
+    fn plan_lists_creates_overwrites_and_removals() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path().join("repo"));
+        let mut upstream = UpstreamRoots::default();
+        upstream.insert(PRIMARY_SOURCE, dir.path().join("upstream"));
+        fs::create_dir_all(dir.path().join("upstream/prompts")).unwrap();
+        fs::create_dir_all(&paths.prompts_dir).unwrap();
+        let assets = ["new", "changed", "same"].map(|name| format!("prompts/{name}.prompt.md"));
+        for path in &assets {
+            fs::write(upstream.resolve(path).unwrap(), "upstream").unwrap();
+        }
+        fs::write(paths.prompts_dir.join("changed.prompt.md"), "local edit").unwrap();
+        fs::write(paths.prompts_dir.join("same.prompt.md"), "upstream").unwrap();
+        fs::write(paths.prompts_dir.join("old.prompt.md"), "stale").unwrap();
+
+        let mut plan = SyncPlan::for_apply(
+            &paths,
+            &upstream,
+            assets.iter().map(|path| (AssetKind::Prompt, path.as_str())),
+            ApplyMode::Overwrite,
+        )
+        .unwrap();
+        plan.add_remove(&paths, AssetKind::Prompt, "prompts/old.prompt.md");
+        plan.add_remove(&paths, AssetKind::Prompt, "prompts/absent.prompt.md");
+
+        let changes: Vec<_> = plan
+            .entries
+            .iter()
+            .map(|entry| (entry.path.as_str(), entry.change))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ("prompts/new.prompt.md", PlannedChange::Create),
+                ("prompts/changed.prompt.md", PlannedChange::Overwrite),
+                ("prompts/old.prompt.md", PlannedChange::Remove),
+            ]
+        );
+        assert_eq!(plan.to_string(), "1 to create, 1 to overwrite, 1 to remove");
+    }
+}
@@ -0,0 +1,406 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    model::{AssetKind, Catalog, EnablementFile, EnablementRule},
+    state::DomainState,
+};
+
+use super::{
+    enablement::{self, EnablementFormat, EnablementWarning},
+    paths::RepoPaths,
+    upstream::{UpstreamRoots, USER_AGENT},
+};
+
+const ASSET_KINDS: [AssetKind; 6] = [
+    AssetKind::Prompt,
+    AssetKind::Instruction,
+    AssetKind::ChatMode,
+    AssetKind::Agent,
+    AssetKind::McpServer,
+    AssetKind::Collection,
+];
+
+pub const PROFILE_VERSION: u32 = 1;
+
+/// File name used when exporting a profile without an explicit path.
+pub const DEFAULT_PROFILE_FILE: &str = "awesome-copilot.profile.json";
+
+/// A portable copy of an enablement selection. Each entry carries the
+/// upstream checksum it was exported against so an importer can tell
+/// whether it resolves to the same file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Snapshot commit of every source the catalog was built from.
+    #[serde(default)]
+    pub sources: BTreeMap<String, String>,
+    #[serde(default)]
+    pub entries: Vec<ProfileEntry>,
+    /// The enablement file's rules, which decide assets without an entry.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<EnablementRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileEntry {
+    pub kind: AssetKind,
+    pub path: String,
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileFormat {
+    Json,
+    Yaml,
+}
+
+impl ProfileFormat {
+    /// `.yaml`/`.yml` files are YAML, everything else is JSON.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml") => {
+                ProfileFormat::Yaml
+            }
+            _ => ProfileFormat::Json,
+        }
+    }
+}
+
+/// Capture every explicit enablement entry and rule of `state`.
+pub fn build_profile(state: &DomainState, upstream: &UpstreamRoots) -> Profile {
+    let mut entries = Vec::new();
+    for kind in ASSET_KINDS {
+        for (path, enabled) in state.enablement.map_for(kind) {
+            entries.push(ProfileEntry {
+                kind,
+                path: path.clone(),
+                enabled: *enabled,
+                sha256: state.catalog.sha256(kind, path).map(str::to_string),
+            });
+        }
+    }
+    Profile {
+        version: PROFILE_VERSION,
+        exported_at: Utc::now(),
+        sources: upstream.commits().clone(),
+        entries,
+        rules: state.enablement.rules.clone(),
+    }
+}
+
+/// Write `profile` to `path`, picking JSON or YAML from the extension.
+pub fn write_profile(path: &Path, profile: &Profile) -> Result<()> {
+    let rendered = match ProfileFormat::from_path(path) {
+        ProfileFormat::Json => {
+            let mut json =
+                serde_json::to_string_pretty(profile).context("Failed to serialize profile")?;
+            json.push('\n');
+            json
+        }
+        ProfileFormat::Yaml => {
+            serde_yaml::to_string(profile).context("Failed to serialize profile as YAML")?
+        }
+    };
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    fs::create_dir_all(&parent).with_context(|| format!("creating {}", parent.display()))?;
+    let mut temp = tempfile::NamedTempFile::new_in(&parent)
+        .context("Failed to create temporary file for profile write")?;
+    temp.write_all(rendered.as_bytes())
+        .context("Failed to write profile")?;
+    temp.persist(path)
+        .with_context(|| format!("Failed to persist profile to {}", path.display()))?;
+    Ok(())
+}
+
+/// How imported entries combine with the local enablement file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportMode {
+    /// Keep local entries and let imported ones win on conflicts.
+    #[default]
+    Merge,
+    /// Drop every local entry and rule before importing.
+    Replace,
+}
+
+/// Imported entries checked against the current catalog.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportPlan {
+    /// Entries that resolve to a catalog asset.
+    pub entries: Vec<ProfileEntry>,
+    /// Entries with no matching catalog asset; these are not imported.
+    pub orphans: Vec<ProfileEntry>,
+    /// Imported entries whose upstream file changed since the export.
+    pub drifted: Vec<ProfileEntry>,
+    pub rules: Vec<EnablementRule>,
+}
+
+impl ImportPlan {
+    pub fn summary(&self) -> String {
+        format!(
+            "{} entries, {} rules, {} orphaned, {} changed upstream since export",
+            self.entries.len(),
+            self.rules.len(),
+            self.orphans.len(),
+            self.drifted.len()
+        )
+    }
+}
+
+/// Read a profile or a plain enablement file from a path or an http(s) URL.
+/// A directory is treated as another repository and its enablement file is
+/// used.
+pub fn read_import(location: &str) -> Result<Profile> {
+    let content = if location.starts_with("http://") || location.starts_with("https://") {
+        fetch_text(location)?
+    } else {
+        let mut path = PathBuf::from(location);
+        if path.is_dir() {
+            path = enablement::enablement_location(&RepoPaths::new(&path)).0;
+        }
+        fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?
+    };
+    parse_import(&content).with_context(|| format!("parsing {location}"))
+}
+
+fn fetch_text(url: &str) -> Result<String> {
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(Duration::from_secs(30))
+        .build()
+        .context("building HTTP client")?;
+    client
+        .get(url)
+        .send()
+        .with_context(|| format!("fetching {url}"))?
+        .error_for_status()
+        .with_context(|| format!("fetching {url}"))?
+        .text()
+        .with_context(|| format!("reading response from {url}"))
+}
+
+/// Profiles and enablement files may be JSON or YAML; a document with an
+/// `entries` list is a profile, anything else an enablement file. Those are
+/// read like the local one: older versions are migrated and unreadable
+/// entries dropped.
+fn parse_import(content: &str) -> Result<Profile> {
+    let value: serde_yaml::Value = serde_yaml::from_str(content)?;
+    if value.get("entries").is_some() {
+        return Ok(serde_yaml::from_value(value)?);
+    }
+    // YAML is a superset of JSON, so this reads either.
+    let load = enablement::parse_enablement(content, EnablementFormat::Yaml)?;
+    for warning in load.warnings {
+        match warning {
+            EnablementWarning::ParseError(err) => bail!(err),
+            warning => tracing::warn!(%warning, "imported enablement file"),
+        }
+    }
+    let file = load.file;
+    let mut entries = Vec::new();
+    for kind in ASSET_KINDS {
+        for (path, enabled) in file.map_for(kind) {
+            entries.push(ProfileEntry {
+                kind,
+                path: path.clone(),
+                enabled: *enabled,
+                sha256: None,
+            });
+        }
+    }
+    Ok(Profile {
+        version: PROFILE_VERSION,
+        exported_at: file.updated_at.unwrap_or_else(Utc::now),
+        sources: BTreeMap::new(),
+        entries,
+        rules: file.rules,
+    })
+}
+
+pub fn plan_import(profile: Profile, catalog: &Catalog) -> ImportPlan {
+    let mut plan = ImportPlan {
+        rules: profile.rules,
+        ..ImportPlan::default()
+    };
+    for entry in profile.entries {
+        if !catalog.contains(entry.kind, &entry.path) {
+            plan.orphans.push(entry);
+            continue;
+        }
+        let current = catalog.sha256(entry.kind, &entry.path);
+        if entry.sha256.is_some() && entry.sha256.as_deref() != current {
+            plan.drifted.push(entry.clone());
+        }
+        plan.entries.push(entry);
+    }
+    plan
+}
+
+/// Imported rules go after the local ones, so they win where both match;
+/// rules already present locally are not added twice.
+pub fn apply_import(enablement: &mut EnablementFile, plan: &ImportPlan, mode: ImportMode) {
+    if mode == ImportMode::Replace {
+        for kind in ASSET_KINDS {
+            enablement.map_for_mut(kind).clear();
+        }
+        enablement.rules.clear();
+    }
+    for rule in &plan.rules {
+        if !enablement.rules.contains(rule) {
+            enablement.rules.push(rule.clone());
+        }
+    }
+    for entry in &plan.entries {
+        enablement
+            .map_for_mut(entry.kind)
+            .insert(entry.path.clone(), entry.enabled);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::{Catalog, EnablementFile, Prompt};
+
+    fn state() -> DomainState {
+        let mut catalog = Catalog::default();
+        catalog.prompts.push(Prompt {
+            path: "prompts/review.prompt.md".into(),
+            slug: "review".into(),
+            name: "Review".into(),
+            description: String::new(),
+            mode: String::new(),
+            tags: Vec::new(),
+            sha256: "abc123".into(),
+            requires: Vec::new(),
+        });
+        let mut enablement = EnablementFile::default();
+        enablement
+            .prompts
+            .insert("prompts/review.prompt.md".into(), true);
+        enablement
+            .instructions
+            .insert("instructions/gone.instructions.md".into(), false);
+        enablement.rules.push(rule("rust", true));
+        DomainState::new(catalog, enablement)
+    }
+
+    fn rule(tag: &str, enabled: bool) -> EnablementRule {
+        EnablementRule {
+            tag: Some(tag.into()),
+            path: None,
+            kind: None,
+            enabled,
+        }
+    }
+
+    #[test]
+    fn profile_records_explicit_entries_with_hashes() {
+        let profile = build_profile(&state(), &UpstreamRoots::default());
+        assert_eq!(
+            profile.entries,
+            vec![
+                ProfileEntry {
+                    kind: AssetKind::Prompt,
+                    path: "prompts/review.prompt.md".into(),
+                    enabled: true,
+                    sha256: Some("abc123".into()),
+                },
+                ProfileEntry {
+                    kind: AssetKind::Instruction,
+                    path: "instructions/gone.instructions.md".into(),
+                    enabled: false,
+                    sha256: None,
+                },
+            ]
+        );
+        assert_eq!(profile.rules, [rule("rust", true)]);
+    }
+
+    #[test]
+    fn import_plan_separates_orphans_and_drift() {
+        let mut profile = build_profile(&state(), &UpstreamRoots::default());
+        profile.entries[0].sha256 = Some("older".into());
+        let plan = plan_import(profile, &state().catalog);
+        assert_eq!(plan.entries.len(), 1);
+        assert_eq!(plan.drifted.len(), 1);
+        assert_eq!(plan.orphans[0].path, "instructions/gone.instructions.md");
+    }
+
+    #[test]
+    fn replace_drops_local_entries_and_merge_keeps_them() {
+        let plan = plan_import(
+            parse_import(
+                r#"{"version": 2, "prompts": {"prompts/review.prompt.md": false},
+                    "rules": [{"tag": "rust", "enabled": true}, {"tag": "go", "enabled": false}]}"#,
+            )
+            .unwrap(),
+            &state().catalog,
+        );
+        let mut merged = state().enablement;
+        apply_import(&mut merged, &plan, ImportMode::Merge);
+        assert_eq!(merged.prompts.get("prompts/review.prompt.md"), Some(&false));
+        assert_eq!(merged.instructions.len(), 1);
+        assert_eq!(merged.rules, [rule("rust", true), rule("go", false)]);
+
+        let mut replaced = state().enablement;
+        replaced.rules = vec![rule("python", true)];
+        apply_import(&mut replaced, &plan, ImportMode::Replace);
+        assert!(replaced.instructions.is_empty());
+        assert_eq!(replaced.rules, [rule("rust", true), rule("go", false)]);
+    }
+
+    #[test]
+    fn older_enablement_files_are_migrated_on_import() {
+        let profile = parse_import(
+            "version: 1\nprompts: [prompts/review.prompt.md]\nchat_modes: {chatmodes/plan.chatmode.md: true}\n",
+        )
+        .unwrap();
+        let paths: Vec<_> = profile
+            .entries
+            .iter()
+            .map(|entry| (entry.kind, entry.path.as_str(), entry.enabled))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                (AssetKind::Prompt, "prompts/review.prompt.md", true),
+                (AssetKind::ChatMode, "chatmodes/plan.chatmode.md", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn profile_format_follows_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile = build_profile(&state(), &UpstreamRoots::default());
+        for name in ["team.yaml", "team.json"] {
+            let path = dir.path().join(name);
+            write_profile(&path, &profile).unwrap();
+            let content = fs::read_to_string(&path).unwrap();
+            let parsed: Profile = if name.ends_with(".yaml") {
+                serde_yaml::from_str(&content).unwrap()
+            } else {
+                serde_json::from_str(&content).unwrap()
+            };
+            assert_eq!(parsed, profile);
+        }
+    }
+}
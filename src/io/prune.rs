@@ -0,0 +1,220 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use walkdir::WalkDir;
+
+use crate::domain::{model::AssetKind, state::DomainState};
+
+use super::{paths::RepoPaths, sync};
+
+/// Why a local file no longer belongs in `.github/`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruneReason {
+    /// The catalog asset behind the file is disabled.
+    Disabled,
+    /// No catalog asset maps to the file.
+    Unknown,
+}
+
+impl PruneReason {
+    pub fn label(self) -> &'static str {
+        match self {
+            PruneReason::Disabled => "disabled",
+            PruneReason::Unknown => "not in catalog",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PruneCandidate {
+    pub kind: AssetKind,
+    pub local_path: PathBuf,
+    pub reason: PruneReason,
+}
+
+impl PruneCandidate {
+    /// Path relative to the repository root, for display.
+    pub fn display_path(&self, paths: &RepoPaths) -> String {
+        self.local_path
+            .strip_prefix(&paths.root)
+            .unwrap_or(&self.local_path)
+            .display()
+            .to_string()
+    }
+}
+
+const PRUNED_KINDS: [AssetKind; 4] = [
+    AssetKind::Prompt,
+    AssetKind::Instruction,
+    AssetKind::ChatMode,
+    AssetKind::Agent,
+];
+
+/// Asset files under `.github/prompts|instructions|chatmodes|agents` whose assets are
+/// disabled or unknown to the catalog. Files that don't look like assets
+/// (READMEs, …) are left alone, as are unknown files listed in `ignored` by
+/// their repository-relative path.
+pub fn find_candidates(
+    paths: &RepoPaths,
+    state: &DomainState,
+    ignored: &BTreeSet<String>,
+) -> Result<Vec<PruneCandidate>> {
+    let mut candidates = Vec::new();
+    for kind in PRUNED_KINDS {
+        // Several sources can map to the same file; keep it if any is enabled.
+        let mut expected: HashMap<PathBuf, bool> = HashMap::new();
+        for asset in state.assets(kind) {
+            *expected
+                .entry(sync::local_path(paths, kind, &asset.path))
+                .or_default() |= asset.effective;
+        }
+        let root = paths.asset_root(kind);
+        if !root.exists() {
+            continue;
+        }
+        for entry in WalkDir::new(root).sort_by_file_name() {
+            let entry = entry.with_context(|| format!("walking {}", root.display()))?;
+            let name = entry.file_name().to_string_lossy();
+            if !entry.file_type().is_file() || !name.ends_with(kind.file_suffix()) {
+                continue;
+            }
+            let reason = match expected.get(entry.path()) {
+                Some(true) => continue,
+                Some(false) => PruneReason::Disabled,
+                None => PruneReason::Unknown,
+            };
+            let candidate = PruneCandidate {
+                kind,
+                local_path: entry.into_path(),
+                reason,
+            };
+            if reason == PruneReason::Unknown && ignored.contains(&candidate.display_path(paths)) {
+                continue;
+            }
+            candidates.push(candidate);
+        }
+    }
+    Ok(candidates)
+}
+
+/// Asset files no catalog asset maps to, except the ignored ones: the
+/// files shown with [`LocalStatus::Unmanaged`](sync::LocalStatus::Unmanaged).
+pub fn find_unmanaged(
+    paths: &RepoPaths,
+    state: &DomainState,
+    ignored: &BTreeSet<String>,
+) -> Result<Vec<PruneCandidate>> {
+    Ok(find_candidates(paths, state, ignored)?
+        .into_iter()
+        .filter(|candidate| candidate.reason == PruneReason::Unknown)
+        .collect())
+}
+
+/// Back up and delete every candidate, returning how many were removed.
+pub fn prune(paths: &RepoPaths, candidates: &[PruneCandidate]) -> Result<usize> {
+    let mut removed = 0;
+    for candidate in candidates {
+        if !candidate.local_path.exists() {
+            continue;
+        }
+        sync::backup_file(paths, &candidate.local_path)?;
+        fs::remove_file(&candidate.local_path)
+            .with_context(|| format!("removing {}", candidate.local_path.display()))?;
+        remove_empty_parents(&candidate.local_path, paths.asset_root(candidate.kind));
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+/// Drop directories emptied by a removal, stopping at the kind root.
+fn remove_empty_parents(file: &Path, root: &Path) {
+    let mut dir = file.parent();
+    while let Some(current) = dir {
+        if current == root || fs::remove_dir(current).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::{Catalog, EnablementFile, Prompt};
+
+    fn prompt(path: &str) -> Prompt {
+        Prompt {
+            path: path.to_string(),
+            slug: String::new(),
+            name: path.to_string(),
+            description: String::new(),
+            mode: String::new(),
+            tags: Vec::new(),
+            sha256: String::new(),
+            requires: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn finds_disabled_and_unknown_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path());
+        fs::create_dir_all(&paths.prompts_dir).unwrap();
+        for name in [
+            "on.prompt.md",
+            "off.prompt.md",
+            "stray.prompt.md",
+            "README.md",
+        ] {
+            fs::write(paths.prompts_dir.join(name), "x").unwrap();
+        }
+        let catalog = Catalog {
+            prompts: vec![
+                prompt("prompts/on.prompt.md"),
+                prompt("prompts/off.prompt.md"),
+            ],
+            ..Default::default()
+        };
+        let mut enablement = EnablementFile::default();
+        enablement
+            .prompts
+            .insert("prompts/on.prompt.md".into(), true);
+        let state = DomainState::new(catalog, enablement);
+
+        let ignored = BTreeSet::from([".github/prompts/stray.prompt.md".to_string()]);
+        assert_eq!(find_candidates(&paths, &state, &ignored).unwrap().len(), 1);
+        let unmanaged = find_unmanaged(&paths, &state, &BTreeSet::new()).unwrap();
+        assert_eq!(
+            unmanaged[0].display_path(&paths),
+            ".github/prompts/stray.prompt.md"
+        );
+
+        let candidates = find_candidates(&paths, &state, &BTreeSet::new()).unwrap();
+        let found: Vec<_> = candidates
+            .iter()
+            .map(|c| (c.display_path(&paths), c.reason))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    ".github/prompts/off.prompt.md".to_string(),
+                    PruneReason::Disabled
+                ),
+                (
+                    ".github/prompts/stray.prompt.md".to_string(),
+                    PruneReason::Unknown
+                ),
+            ]
+        );
+
+        assert_eq!(prune(&paths, &candidates).unwrap(), 2);
+        assert!(paths.prompts_dir.join("on.prompt.md").exists());
+        assert!(paths.prompts_dir.join("README.md").exists());
+        assert!(!paths.prompts_dir.join("off.prompt.md").exists());
+    }
+}
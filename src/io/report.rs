@@ -0,0 +1,249 @@
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    domain::{
+        model::{split_source, AssetKind, LOCAL_SOURCE},
+        state::{AssetView, DomainState},
+    },
+    io::upstream::{UpstreamRoots, UpstreamSource},
+};
+
+/// Where the TUI writes the report, relative to the repository root.
+pub const DEFAULT_REPORT_FILE: &str = "copilot-assets.md";
+
+/// Markers around the section `inject` maintains in another file.
+pub const SECTION_START: &str = "<!-- copilot-assets:start -->";
+pub const SECTION_END: &str = "<!-- copilot-assets:end -->";
+
+const REPORT_KINDS: [(AssetKind, &str); 6] = [
+    (AssetKind::Prompt, "Prompts"),
+    (AssetKind::Instruction, "Instructions"),
+    (AssetKind::ChatMode, "Chat modes"),
+    (AssetKind::Agent, "Agents"),
+    (AssetKind::McpServer, "MCP servers"),
+    (AssetKind::Collection, "Collections"),
+];
+
+/// A markdown document listing every effectively enabled asset by kind,
+/// with its description and a link to the upstream file at the loaded
+/// snapshot. Kinds with nothing enabled are left out.
+pub fn render(state: &DomainState, upstream: &UpstreamRoots, sources: &[UpstreamSource]) -> String {
+    format!(
+        "# Copilot assets\n\n{}",
+        body(state, upstream, sources, "##")
+    )
+}
+
+/// The report without its title and with smaller headings, for embedding in
+/// a document such as the README.
+pub fn section(
+    state: &DomainState,
+    upstream: &UpstreamRoots,
+    sources: &[UpstreamSource],
+) -> String {
+    body(state, upstream, sources, "###")
+}
+
+fn body(
+    state: &DomainState,
+    upstream: &UpstreamRoots,
+    sources: &[UpstreamSource],
+    heading_level: &str,
+) -> String {
+    let mut out = String::new();
+    let snapshots: Vec<_> = sources
+        .iter()
+        .filter_map(|source| {
+            let commit = upstream.commits().get(&source.id)?;
+            Some(format!(
+                "[{}/{}]({}) at `{}`",
+                source.owner,
+                source.repo,
+                source.tree_url(commit),
+                short(commit)
+            ))
+        })
+        .collect();
+    out.push_str("Enabled in this repository with awesome-copilot-tui");
+    if !snapshots.is_empty() {
+        let _ = write!(out, ", from {}", snapshots.join(", "));
+    }
+    out.push_str(".\n");
+
+    let mut any = false;
+    for (kind, heading) in REPORT_KINDS {
+        let enabled: Vec<_> = state
+            .assets(kind)
+            .iter()
+            .filter(|asset| asset.effective)
+            .collect();
+        if enabled.is_empty() {
+            continue;
+        }
+        any = true;
+        let _ = write!(out, "\n{heading_level} {heading}\n\n");
+        for asset in enabled {
+            out.push_str(&entry(asset, upstream, sources));
+        }
+    }
+    if !any {
+        out.push_str("\nNo assets are enabled.\n");
+    }
+    out
+}
+
+/// Write `report` to `path` atomically.
+pub fn write(path: &Path, report: &str) -> Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+    let mut temp = tempfile::NamedTempFile::new_in(parent)
+        .context("Failed to create temporary file for the report")?;
+    temp.write_all(report.as_bytes())
+        .context("Failed to write the report")?;
+    temp.persist(path)
+        .with_context(|| format!("Failed to persist the report to {}", path.display()))?;
+    Ok(())
+}
+
+/// Rewrite the marked section of `path` with `section`, appending the
+/// markers when the file has none. Returns whether the file changed.
+pub fn inject(path: &Path, section: &str) -> Result<bool> {
+    inject_between(path, SECTION_START, SECTION_END, section)
+}
+
+/// Like [`inject`], with the section between the `start` and `end` markers.
+pub fn inject_between(path: &Path, start: &str, end: &str, section: &str) -> Result<bool> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err).with_context(|| format!("reading {}", path.display())),
+    };
+    let updated = replace_section(&content, start, end, section);
+    if updated == content {
+        return Ok(false);
+    }
+    write(path, &updated)?;
+    Ok(true)
+}
+
+fn replace_section(content: &str, start_marker: &str, end_marker: &str, section: &str) -> String {
+    let block = format!("{start_marker}\n{section}{end_marker}");
+    if let Some(start) = content.find(start_marker) {
+        if let Some(end) = content[start..].find(end_marker) {
+            let end = start + end + end_marker.len();
+            return format!("{}{block}{}", &content[..start], &content[end..]);
+        }
+    }
+    let mut out = content.to_string();
+    if !out.is_empty() {
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out.push_str(&block);
+    out.push('\n');
+    out
+}
+
+fn entry(asset: &AssetView, upstream: &UpstreamRoots, sources: &[UpstreamSource]) -> String {
+    let (source_id, relative) = split_source(&asset.path);
+    let name = asset.name.replace(['[', ']'], "");
+    let link = sources
+        .iter()
+        .find(|source| source.id == source_id && source_id != LOCAL_SOURCE)
+        .map(|source| {
+            let commit = upstream
+                .commits()
+                .get(source_id)
+                .unwrap_or(&source.reference);
+            source.file_url(commit, relative)
+        });
+    let mut line = match link {
+        Some(url) => format!("- [{name}]({url})"),
+        None => format!("- {name} (`{}`)", asset.path),
+    };
+    let description = asset.description.split_whitespace().collect::<Vec<_>>();
+    if !description.is_empty() {
+        let _ = write!(line, " — {}", description.join(" "));
+    }
+    line.push('\n');
+    line
+}
+
+fn short(commit: &str) -> &str {
+    commit.get(..7).unwrap_or(commit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::{Catalog, EnablementFile, Instruction, Prompt};
+
+    #[test]
+    fn lists_enabled_assets_by_kind_with_links() {
+        let mut catalog = Catalog::default();
+        catalog.prompts.push(Prompt {
+            path: "prompts/review.prompt.md".into(),
+            slug: "review".into(),
+            name: "Review".into(),
+            description: "Review the\n  current diff".into(),
+            mode: String::new(),
+            tags: Vec::new(),
+            sha256: String::new(),
+            requires: Vec::new(),
+        });
+        catalog.instructions.push(Instruction {
+            path: "instructions/rust.instructions.md".into(),
+            slug: "rust".into(),
+            name: "Rust".into(),
+            description: String::new(),
+            apply_to: Vec::new(),
+            tags: Vec::new(),
+            sha256: String::new(),
+            requires: Vec::new(),
+        });
+        let mut enablement = EnablementFile::default();
+        enablement
+            .prompts
+            .insert("prompts/review.prompt.md".into(), true);
+        let state = DomainState::new(catalog, enablement);
+
+        let report = render(
+            &state,
+            &UpstreamRoots::default(),
+            &[UpstreamSource::official()],
+        );
+        assert_eq!(
+            report,
+            "# Copilot assets\n\n\
+             Enabled in this repository with awesome-copilot-tui.\n\n\
+             ## Prompts\n\n\
+             - [Review](https://github.com/github/awesome-copilot/blob/main/prompts/review.prompt.md) \
+             — Review the current diff\n"
+        );
+    }
+
+    #[test]
+    fn replaces_only_the_marked_section() {
+        let readme = format!("# Repo\n\n{SECTION_START}\nold\n{SECTION_END}\n\nMore.\n");
+        assert_eq!(
+            replace_section(&readme, SECTION_START, SECTION_END, "new\n"),
+            format!("# Repo\n\n{SECTION_START}\nnew\n{SECTION_END}\n\nMore.\n")
+        );
+        assert_eq!(
+            replace_section("# Repo", SECTION_START, SECTION_END, "new\n"),
+            format!("# Repo\n\n{SECTION_START}\nnew\n{SECTION_END}\n")
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::config;
+
+/// Kept in the user config directory, next to `config.toml`, so every
+/// session can switch to the repositories opened before.
+pub const REGISTRY_FILE: &str = "repos.json";
+
+/// Repositories opened in the TUI, most recently opened first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RepoRegistry {
+    pub repos: Vec<RegisteredRepo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredRepo {
+    pub path: PathBuf,
+    pub opened_at: DateTime<Utc>,
+}
+
+impl RepoRegistry {
+    /// Read the registry at `file`; a missing file is an empty registry.
+    pub fn load(file: &Path) -> Result<Self> {
+        match fs::read_to_string(file) {
+            Ok(content) => serde_json::from_str(&content)
+                .with_context(|| format!("parsing {}", file.display())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err).with_context(|| format!("reading {}", file.display())),
+        }
+    }
+
+    pub fn save(&self, file: &Path) -> Result<()> {
+        let dir = file.parent().context("registry file has no directory")?;
+        fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        let json = serde_json::to_string_pretty(self).context("serializing repositories")?;
+        let mut temp = tempfile::NamedTempFile::new_in(dir)
+            .with_context(|| format!("creating a temporary file in {}", dir.display()))?;
+        temp.write_all(json.as_bytes())
+            .with_context(|| format!("writing {}", file.display()))?;
+        temp.write_all(b"\n").ok();
+        temp.persist(file)
+            .with_context(|| format!("writing {}", file.display()))?;
+        Ok(())
+    }
+
+    /// Move `path` to the front, adding it when it is new.
+    pub fn record(&mut self, path: &Path, at: DateTime<Utc>) {
+        self.remove(path);
+        self.repos.insert(
+            0,
+            RegisteredRepo {
+                path: path.to_path_buf(),
+                opened_at: at,
+            },
+        );
+    }
+
+    /// Forget `path`, returning whether it was registered.
+    pub fn remove(&mut self, path: &Path) -> bool {
+        let before = self.repos.len();
+        self.repos.retain(|repo| repo.path != path);
+        self.repos.len() != before
+    }
+}
+
+/// Where the registry lives, if the platform has a user config directory.
+pub fn registry_file() -> Option<PathBuf> {
+    config::user_file(REGISTRY_FILE)
+}
+
+/// Register `root` as opened now. Paths are stored absolute so the list
+/// works from any directory.
+pub fn record_opened(root: &Path) -> Result<()> {
+    let Some(file) = registry_file() else {
+        return Ok(());
+    };
+    let root = fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    let mut registry = RepoRegistry::load(&file)?;
+    registry.record(&root, Utc::now());
+    registry.save(&file)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn keeps_the_most_recently_opened_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("config").join(REGISTRY_FILE);
+        let start = Utc::now();
+        let mut registry = RepoRegistry::load(&file).unwrap();
+        registry.record(Path::new("/src/infra"), start);
+        registry.record(Path::new("/src/web"), start + Duration::minutes(1));
+        registry.record(Path::new("/src/infra"), start + Duration::minutes(2));
+        registry.save(&file).unwrap();
+
+        let mut registry = RepoRegistry::load(&file).unwrap();
+        let paths: Vec<_> = registry.repos.iter().map(|r| r.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [PathBuf::from("/src/infra"), PathBuf::from("/src/web")]
+        );
+        assert!(registry.remove(Path::new("/src/web")));
+        assert!(!registry.remove(Path::new("/src/web")));
+        assert_eq!(registry.repos.len(), 1);
+    }
+}
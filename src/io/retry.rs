@@ -0,0 +1,211 @@
+use std::{io, thread, time::Duration};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use reqwest::{blocking::Response, StatusCode};
+
+/// Longest we are willing to wait before a retry, including a rate limit
+/// reset announced by GitHub. Longer waits fail right away so the caller can
+/// fall back to the cache.
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// How requests to GitHub are repeated after transient failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Tries per request, including the first one.
+    pub attempts: u32,
+    /// Delay before the first retry; doubled for every further one.
+    pub backoff: Duration,
+    /// Spread each delay randomly between half and all of its value.
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff: Duration::from_millis(500),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (1-based).
+    fn delay(&self, retry: u32) -> Duration {
+        let delay = self
+            .backoff
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(MAX_DELAY);
+        if self.jitter {
+            delay.mul_f64(0.5 + fastrand::f64() / 2.0)
+        } else {
+            delay
+        }
+    }
+
+    /// Run `op` until it succeeds, fails permanently, or the attempts are
+    /// used up. `what` names the request in the log.
+    pub fn run<T>(&self, what: &str, mut op: impl FnMut() -> Result<T>) -> Result<T> {
+        let mut retry = 0;
+        loop {
+            let err = match op() {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+            retry += 1;
+            let wait = match classify(&err) {
+                Failure::Permanent => return Err(err),
+                Failure::Transient(_) if retry >= self.attempts => return Err(err),
+                Failure::Transient(Some(wait)) => wait,
+                Failure::Transient(None) => self.delay(retry),
+            };
+            if wait > MAX_DELAY {
+                return Err(err);
+            }
+            tracing::warn!(
+                request = what,
+                retry,
+                wait_ms = wait.as_millis() as u64,
+                error = %format!("{err:#}"),
+                "retrying after transient failure"
+            );
+            thread::sleep(wait);
+        }
+    }
+}
+
+/// GitHub refused the request because the API rate limit is used up.
+#[derive(Debug, thiserror::Error)]
+#[error("GitHub rate limit exceeded{}", reset_note(.reset))]
+pub struct RateLimited {
+    /// When requests are accepted again, if GitHub said so.
+    pub reset: Option<DateTime<Utc>>,
+}
+
+fn reset_note(reset: &Option<DateTime<Utc>>) -> String {
+    reset
+        .map(|reset| format!(" until {}", reset.format("%H:%M:%S UTC")))
+        .unwrap_or_default()
+}
+
+/// Turn a rate-limited response (403 or 429 with no requests remaining, or
+/// 429 with `Retry-After`) into a `RateLimited` error; other responses pass
+/// through.
+pub fn check_rate_limit(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return Ok(response);
+    }
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<i64>().ok())
+    };
+    let exhausted = header("x-ratelimit-remaining") == Some(0);
+    let retry_after = header("retry-after");
+    if !exhausted && retry_after.is_none() && status == StatusCode::FORBIDDEN {
+        return Ok(response);
+    }
+    let reset = match retry_after {
+        Some(seconds) => Some(Utc::now() + chrono::Duration::seconds(seconds)),
+        None => header("x-ratelimit-reset").and_then(|epoch| DateTime::from_timestamp(epoch, 0)),
+    };
+    Err(RateLimited { reset }.into())
+}
+
+enum Failure {
+    /// Worth another try, after the given wait if the server named one.
+    Transient(Option<Duration>),
+    Permanent,
+}
+
+fn classify(err: &anyhow::Error) -> Failure {
+    for cause in err.chain() {
+        if let Some(limited) = cause.downcast_ref::<RateLimited>() {
+            let wait = limited
+                .reset
+                .map(|reset| (reset - Utc::now()).to_std().unwrap_or_default());
+            return Failure::Transient(Some(wait.unwrap_or(Duration::MAX)));
+        }
+        if let Some(err) = cause.downcast_ref::<reqwest::Error>() {
+            let transient = err.is_timeout()
+                || err.is_connect()
+                || err.is_body()
+                || err.status().is_some_and(|status| status.is_server_error());
+            return if transient {
+                Failure::Transient(None)
+            } else {
+                Failure::Permanent
+            };
+        }
+        if let Some(err) = cause.downcast_ref::<io::Error>() {
+            // Connection trouble while streaming a download surfaces as I/O.
+            if matches!(
+                err.kind(),
+                io::ErrorKind::TimedOut
+                    | io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::UnexpectedEof
+                    | io::ErrorKind::Interrupted
+            ) {
+                return Failure::Transient(None);
+            }
+        }
+    }
+    Failure::Permanent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_transient_failures_only() {
+        let policy = RetryPolicy {
+            attempts: 3,
+            backoff: Duration::ZERO,
+            jitter: false,
+        };
+        let mut calls = 0;
+        let result = policy.run("test", || {
+            calls += 1;
+            if calls < 3 {
+                Err(io::Error::from(io::ErrorKind::ConnectionReset).into())
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        calls = 0;
+        let result: Result<()> = policy.run("test", || {
+            calls += 1;
+            Err(anyhow::anyhow!("404 Not Found"))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+
+        // A rate limit far in the future is not waited out.
+        calls = 0;
+        let result: Result<()> = policy.run("test", || {
+            calls += 1;
+            Err(RateLimited {
+                reset: Some(Utc::now() + chrono::Duration::hours(1)),
+            }
+            .into())
+        });
+        assert!(result.unwrap_err().to_string().contains("until"));
+        assert_eq!(calls, 1);
+
+        let policy = RetryPolicy {
+            backoff: Duration::from_secs(1),
+            ..policy
+        };
+        assert_eq!(policy.delay(1), Duration::from_secs(1));
+        assert_eq!(policy.delay(3), Duration::from_secs(4));
+        assert_eq!(policy.delay(10), MAX_DELAY);
+    }
+}
@@ -0,0 +1,113 @@
+use std::{fmt, fs, path::Path};
+
+use anyhow::{anyhow, bail, Context, Result};
+use globset::{Glob, GlobMatcher};
+
+/// What one line of an apply script does to the assets it matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptAction {
+    Enable,
+    Disable,
+    /// Copy the file from upstream without changing enablement.
+    Apply,
+}
+
+impl fmt::Display for ScriptAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ScriptAction::Enable => "enable",
+            ScriptAction::Disable => "disable",
+            ScriptAction::Apply => "apply",
+        })
+    }
+}
+
+/// One operation: an action and the catalog paths it applies to.
+#[derive(Debug, Clone)]
+pub struct ScriptOp {
+    /// 1-based line in the script, for error messages.
+    pub line: usize,
+    pub action: ScriptAction,
+    pub pattern: String,
+    matcher: GlobMatcher,
+}
+
+impl ScriptOp {
+    /// Whether the catalog path (e.g. `prompts/review.prompt.md` or
+    /// `acme:prompts/review.prompt.md`) is selected by this operation.
+    pub fn matches(&self, path: &str) -> bool {
+        self.matcher.is_match(path)
+    }
+}
+
+/// Parse a script of `enable|disable|apply <path-or-glob>` lines. Blank lines
+/// and `#` comments are skipped.
+pub fn parse_script(content: &str) -> Result<Vec<ScriptOp>> {
+    let mut ops = Vec::new();
+    for (index, raw) in content.lines().enumerate() {
+        let line = index + 1;
+        let text = raw.split('#').next().unwrap_or_default().trim();
+        if text.is_empty() {
+            continue;
+        }
+        let (verb, pattern) = text
+            .split_once(char::is_whitespace)
+            .map(|(verb, pattern)| (verb, pattern.trim()))
+            .ok_or_else(|| anyhow!("line {line}: expected `<action> <path-or-glob>`"))?;
+        let action = match verb {
+            "enable" => ScriptAction::Enable,
+            "disable" => ScriptAction::Disable,
+            "apply" => ScriptAction::Apply,
+            other => bail!("line {line}: unknown action '{other}' (enable, disable or apply)"),
+        };
+        let matcher = Glob::new(pattern)
+            .with_context(|| format!("line {line}: invalid glob '{pattern}'"))?
+            .compile_matcher();
+        ops.push(ScriptOp {
+            line,
+            action,
+            pattern: pattern.to_string(),
+            matcher,
+        });
+    }
+    Ok(ops)
+}
+
+pub fn read_script(path: &Path) -> Result<Vec<ScriptOp>> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    parse_script(&content).with_context(|| format!("parsing {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_actions_and_globs() {
+        let ops = parse_script(
+            "# team setup\n\
+             enable prompts/*.prompt.md\n\
+             \n\
+             disable prompts/legacy-*   # retired\n\
+             apply chatmodes/plan.chatmode.md\n",
+        )
+        .unwrap();
+        let summary: Vec<_> = ops.iter().map(|op| (op.line, op.action)).collect();
+        assert_eq!(
+            summary,
+            [
+                (2, ScriptAction::Enable),
+                (4, ScriptAction::Disable),
+                (5, ScriptAction::Apply)
+            ]
+        );
+        assert!(ops[0].matches("prompts/review.prompt.md"));
+        assert!(!ops[0].matches("acme:prompts/review.prompt.md"));
+        assert!(ops[1].matches("prompts/legacy-review.prompt.md"));
+
+        let err = parse_script("enable\n").unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+        assert!(parse_script("toggle prompts/a.prompt.md").is_err());
+    }
+}
@@ -0,0 +1,197 @@
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use crate::domain::model::split_source;
+
+use super::{paths::RepoPaths, upstream::UpstreamRoots};
+
+/// What a local asset path is, as far as links go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkState {
+    /// A regular file, or nothing at all.
+    NotLinked,
+    /// A symlink resolving to the asset's file in the loaded snapshot.
+    Current,
+    /// A symlink resolving anywhere else, such as an older snapshot.
+    Elsewhere,
+    /// A symlink whose target is gone.
+    Dangling,
+}
+
+/// Classify `local_path` against `upstream_file`, the asset's file in the
+/// loaded snapshot.
+pub fn link_state(local_path: &Path, upstream_file: &Path) -> LinkState {
+    if !is_symlink(local_path) {
+        return LinkState::NotLinked;
+    }
+    match (
+        fs::canonicalize(local_path),
+        fs::canonicalize(upstream_file),
+    ) {
+        (Err(_), _) => LinkState::Dangling,
+        (Ok(target), Ok(upstream)) if target == upstream => LinkState::Current,
+        _ => LinkState::Elsewhere,
+    }
+}
+
+pub fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_symlink())
+}
+
+/// Remove `path` if it is a symlink, returning whether it was one. Writing
+/// through a link would change the snapshot it points into.
+pub fn unlink(path: &Path) -> Result<bool> {
+    if !is_symlink(path) {
+        return Ok(false);
+    }
+    fs::remove_file(path).with_context(|| format!("removing link {}", path.display()))?;
+    Ok(true)
+}
+
+/// Replace `local_path` with a relative symlink to the asset through
+/// `links/<source>`, so refreshing the snapshot updates it without another
+/// apply.
+pub fn link_asset(
+    paths: &RepoPaths,
+    upstream: &UpstreamRoots,
+    local_path: &Path,
+    asset_path: &str,
+) -> Result<()> {
+    let (source, relative) = split_source(asset_path);
+    let root = upstream
+        .root(source)
+        .with_context(|| format!("no snapshot is loaded for source '{source}'"))?;
+    let source_link = paths.links_dir.join(source);
+    point(&source_link, root, true)?;
+    point(local_path, &source_link.join(relative), false)
+}
+
+/// Point every existing `links/<source>` at the source's loaded snapshot.
+/// Sources nothing was linked from are left alone.
+pub fn refresh_links(paths: &RepoPaths, upstream: &UpstreamRoots) -> Result<()> {
+    for (source, root) in upstream.roots() {
+        let link = paths.links_dir.join(source);
+        if is_symlink(&link) {
+            point(&link, root, true)?;
+        }
+    }
+    Ok(())
+}
+
+/// Make `link` a symlink to `target`, relative to the link's directory.
+fn point(link: &Path, target: &Path, dir: bool) -> Result<()> {
+    let parent = link.parent().context("link has no parent directory")?;
+    fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+    let relative = relative_to(parent, target)?;
+    if fs::read_link(link).is_ok_and(|current| current == relative) {
+        return Ok(());
+    }
+    match fs::remove_file(link) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err).with_context(|| format!("removing {}", link.display())),
+    }
+    symlink(&relative, link, dir)
+        .with_context(|| format!("linking {} -> {}", link.display(), relative.display()))
+}
+
+/// `target` as seen from `dir`, e.g. `../../.awesome-copilot-tui/links/...`.
+fn relative_to(dir: &Path, target: &Path) -> Result<PathBuf> {
+    let dir = normalize(&std::path::absolute(dir).context("resolving link directory")?);
+    let target = normalize(&std::path::absolute(target).context("resolving link target")?);
+    let common = dir
+        .components()
+        .zip(target.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut relative: PathBuf = dir.components().skip(common).map(|_| "..").collect();
+    relative.extend(target.components().skip(common));
+    Ok(relative)
+}
+
+/// Drop `.` and resolve `..` without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path, _dir: bool) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path, dir: bool) -> io::Result<()> {
+    if dir {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::domain::model::PRIMARY_SOURCE;
+
+    #[test]
+    fn links_follow_the_loaded_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path());
+        let snapshot = |commit: &str, body: &str| {
+            let root = paths.cache_dir.join(commit).join("content");
+            fs::create_dir_all(root.join("prompts")).unwrap();
+            fs::write(root.join("prompts/review.prompt.md"), body).unwrap();
+            let mut upstream = UpstreamRoots::default();
+            upstream.insert(PRIMARY_SOURCE, root);
+            upstream
+        };
+        let local = paths.prompts_dir.join("review.prompt.md");
+        let asset = "prompts/review.prompt.md";
+
+        let old = snapshot("abc", "old");
+        link_asset(&paths, &old, &local, asset).unwrap();
+        assert_eq!(fs::read_to_string(&local).unwrap(), "old");
+        assert!(fs::read_link(&local).unwrap().is_relative());
+        assert_eq!(
+            link_state(&local, &old.resolve(asset).unwrap()),
+            LinkState::Current
+        );
+
+        let new = snapshot("def", "new");
+        assert_eq!(
+            link_state(&local, &new.resolve(asset).unwrap()),
+            LinkState::Elsewhere
+        );
+        refresh_links(&paths, &new).unwrap();
+        assert_eq!(fs::read_to_string(&local).unwrap(), "new");
+        assert_eq!(
+            link_state(&local, &new.resolve(asset).unwrap()),
+            LinkState::Current
+        );
+
+        fs::remove_dir_all(paths.cache_dir.join("def")).unwrap();
+        assert_eq!(
+            link_state(&local, &new.resolve(asset).unwrap()),
+            LinkState::Dangling
+        );
+        assert!(unlink(&local).unwrap());
+        assert_eq!(
+            link_state(&local, &new.resolve(asset).unwrap()),
+            LinkState::NotLinked
+        );
+    }
+}
@@ -1,23 +1,106 @@
 use std::{
-    fs, io,
+    fmt, fs, io,
     path::{Path, PathBuf},
 };
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
 use sha2::{Digest, Sha256};
 
-use crate::domain::model::AssetKind;
+use crate::domain::model::{split_source, AssetKind};
 
-use super::paths::RepoPaths;
+use super::{
+    manifest::AppliedManifest,
+    patch,
+    paths::RepoPaths,
+    symlink::{self, LinkState},
+    upstream::UpstreamRoots,
+    variables, vscode,
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Directory name format of a backup set, e.g. `20240131T120000.123Z`.
+pub const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum LocalStatus {
     Missing,
     Same,
     Diff,
+    /// The local file still matches what was last applied, but upstream has
+    /// changed since: an update is waiting to be applied.
+    Update,
+    /// An asset file under `.github/` that no catalog asset maps to. Only
+    /// reported for such files, never for catalog assets.
+    Unmanaged,
     NA, // Not applicable (e.g., collections)
 }
 
+/// How `apply_from_upstream` treats a local file that already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApplyMode {
+    /// Replace the local file with the upstream content.
+    #[default]
+    Overwrite,
+    /// Three-way merge local edits with upstream changes, using the content
+    /// recorded at the previous apply as the common base.
+    Merge,
+    /// Keep local edits as a patch in the workspace directory and re-apply it
+    /// on top of every upstream update.
+    Patch,
+    /// Link the local file to the cached snapshot instead of copying it, so
+    /// refreshed snapshots show up without applying again. Assets with a
+    /// content patch are copied, as is everything when `line-endings` is
+    /// set.
+    Symlink,
+}
+
+/// Line endings of files written by `apply_from_upstream`, to follow the
+/// repository's `.gitattributes` rather than upstream's bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LineEndings {
+    /// CRLF on Windows, LF elsewhere.
+    Native,
+    Lf,
+    Crlf,
+}
+
+impl LineEndings {
+    /// `content` with every line ending, CRLF or LF, written this way.
+    pub fn convert(self, content: &[u8]) -> Vec<u8> {
+        let crlf = match self {
+            LineEndings::Native => cfg!(windows),
+            LineEndings::Lf => false,
+            LineEndings::Crlf => true,
+        };
+        let mut converted = Vec::with_capacity(content.len());
+        for (i, &byte) in content.iter().enumerate() {
+            if byte == b'\r' && content.get(i + 1) == Some(&b'\n') {
+                continue;
+            }
+            if byte == b'\n' && crlf {
+                converted.push(b'\r');
+            }
+            converted.push(byte);
+        }
+        converted
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApplyOutcome {
+    /// The local file was created or replaced with upstream content.
+    Written,
+    /// The local file already matched upstream.
+    Unchanged,
+    /// Local edits and upstream changes were merged cleanly.
+    Merged,
+    /// The merge produced conflict markers that need manual resolution.
+    Conflicted,
+}
+
 #[derive(Debug, Clone)]
 pub struct DiffEntry {
     pub kind: AssetKind,
@@ -25,66 +108,407 @@ pub struct DiffEntry {
     pub status: LocalStatus,
 }
 
+/// Compare the local file of an asset with upstream. `manifest` is the
+/// applied manifest, loaded once for a batch of assets.
 pub fn compute_local_status(
     paths: &RepoPaths,
-    upstream_root: &Path,
+    upstream: &UpstreamRoots,
+    manifest: &AppliedManifest,
     kind: AssetKind,
     relative_path: &str,
 ) -> Result<LocalStatus> {
-    if kind == AssetKind::Collection {
-        return Ok(LocalStatus::NA);
+    match kind {
+        AssetKind::Collection => return Ok(LocalStatus::NA),
+        AssetKind::McpServer => {
+            return vscode::mcp_server_status(paths, upstream, relative_path);
+        }
+        _ => {}
+    }
+    let local_path = local_path(paths, kind, relative_path);
+    match symlink::link_state(&local_path, &upstream.resolve(relative_path)?) {
+        LinkState::Dangling => return Ok(LocalStatus::Missing),
+        LinkState::Current if !upstream.rewrites(relative_path) => return Ok(LocalStatus::Same),
+        // Links elsewhere are compared by content like files.
+        _ => {}
     }
-    let upstream_path = upstream_root.join(relative_path);
-    let local_path = paths
-        .asset_root(kind)
-        .join(relative_path_for_kind(kind, relative_path));
     if !local_path.exists() {
         return Ok(LocalStatus::Missing);
     }
-    let upstream_hash = hash_file(&upstream_path).context("hashing upstream file")?;
+    let content = upstream_content(upstream, relative_path)?;
+    let upstream_hash = hash_bytes(&content);
     let local_hash = hash_file(&local_path).context("hashing local file")?;
     if upstream_hash == local_hash {
-        Ok(LocalStatus::Same)
-    } else {
-        Ok(LocalStatus::Diff)
+        return Ok(LocalStatus::Same);
     }
+    // A file carrying exactly its kept local patch counts as up to date.
+    let patch = read_local_patch(paths, relative_path)?;
+    let patched_hash = |content: &[u8]| {
+        let text = String::from_utf8_lossy(content);
+        patch
+            .as_deref()
+            .and_then(|patch| apply_patch(&text, patch))
+            .map(|patched| hash_bytes(patched.as_bytes()))
+    };
+    if patched_hash(&content).is_some_and(|hash| hash == local_hash) {
+        return Ok(LocalStatus::Same);
+    }
+    // The manifest tells an untouched file with a newer upstream apart from
+    // a local edit of content upstream still has.
+    if let Some(entry) = manifest.files.get(relative_path) {
+        if entry.sha256 == local_hash {
+            return Ok(LocalStatus::Update);
+        }
+        if entry.sha256 == upstream_hash {
+            return Ok(LocalStatus::Diff);
+        }
+    }
+    // Files applied before the manifest existed only have their merge base.
+    let base_path = base_path_for(paths, relative_path);
+    if base_path.exists() {
+        let base =
+            fs::read(&base_path).with_context(|| format!("reading {}", base_path.display()))?;
+        if hash_bytes(&base) == local_hash
+            || patched_hash(&base).is_some_and(|hash| hash == local_hash)
+        {
+            return Ok(LocalStatus::Update);
+        }
+    }
+    Ok(LocalStatus::Diff)
 }
 
 pub fn apply_from_upstream(
     paths: &RepoPaths,
-    upstream_root: &Path,
+    upstream: &UpstreamRoots,
+    manifest: &mut AppliedManifest,
     kind: AssetKind,
     relative_path: &str,
-) -> Result<PathBuf> {
-    if kind == AssetKind::Collection {
+    mode: ApplyMode,
+) -> Result<ApplyOutcome> {
+    match kind {
         // No-op: collections are not copied locally
-        return Ok(paths.asset_root(kind).to_path_buf());
+        AssetKind::Collection => return Ok(ApplyOutcome::Unchanged),
+        AssetKind::McpServer => {
+            let changed = vscode::install_mcp_server(paths, upstream, relative_path)?;
+            return Ok(if changed {
+                ApplyOutcome::Written
+            } else {
+                ApplyOutcome::Unchanged
+            });
+        }
+        _ => {}
+    }
+    let local_path = local_path(paths, kind, relative_path);
+    if local_path.exists() || symlink::is_symlink(&local_path) {
+        if let Some(owner) = manifest.other_owner(relative_path) {
+            bail!(
+                "{} was applied from {owner}; disable that asset before applying {relative_path}",
+                local_path.display()
+            );
+        }
+    }
+    let content = upstream_content(upstream, relative_path)?;
+    let base_path = base_path_for(paths, relative_path);
+
+    // A link holds no local edits, and writing through it would change the
+    // snapshot.
+    symlink::unlink(&local_path)?;
+    if local_path.exists() && hash_file(&local_path)? != hash_bytes(&content) {
+        backup_file(paths, &local_path)?;
     }
-    let upstream_path = upstream_root.join(relative_path);
-    let local_relative = relative_path_for_kind(kind, relative_path);
-    let local_path = paths.asset_root(kind).join(&local_relative);
+
+    let outcome = match mode {
+        ApplyMode::Symlink if !upstream.rewrites(relative_path) => {
+            symlink::link_asset(paths, upstream, &local_path, relative_path)?;
+            ApplyOutcome::Written
+        }
+        ApplyMode::Merge if local_path.exists() => {
+            merge_into_local(&String::from_utf8_lossy(&content), &local_path, &base_path)?
+        }
+        ApplyMode::Patch => patch_into_local(
+            paths,
+            relative_path,
+            &String::from_utf8_lossy(&content),
+            &local_path,
+            &base_path,
+        )?,
+        _ => {
+            write_local(&local_path, &content)?;
+            ApplyOutcome::Written
+        }
+    };
+
+    record_applied(upstream, manifest, relative_path, &content, &base_path)?;
+    Ok(outcome)
+}
+
+fn write_local(local_path: &Path, content: &[u8]) -> Result<()> {
     if let Some(parent) = local_path.parent() {
         fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
     }
-    fs::copy(&upstream_path, &local_path).with_context(|| {
-        format!(
-            "copying {} -> {}",
-            upstream_path.display(),
-            local_path.display()
-        )
-    })?;
-    Ok(local_path)
+    fs::write(local_path, content).with_context(|| format!("writing {}", local_path.display()))
+}
+
+/// The upstream file of `relative_path` with its content patch applied, if
+/// it has one, its placeholders filled in and its line endings converted, if
+/// configured: what applying the asset writes locally.
+pub fn upstream_content(upstream: &UpstreamRoots, relative_path: &str) -> Result<Vec<u8>> {
+    let upstream_path = upstream.resolve(relative_path)?;
+    let content =
+        fs::read(&upstream_path).with_context(|| format!("reading {}", upstream_path.display()))?;
+    let content = match upstream.patch(relative_path) {
+        Some(patch) => patch::patch_content(&String::from_utf8_lossy(&content), patch).into_bytes(),
+        None => content,
+    };
+    let content = if upstream.variables().is_empty() {
+        content
+    } else {
+        variables::substitute(&String::from_utf8_lossy(&content), upstream.variables()).into_bytes()
+    };
+    Ok(match upstream.line_endings() {
+        Some(line_endings) => line_endings.convert(&content),
+        None => content,
+    })
+}
+
+/// Record the merge base and the manifest entry of content just applied.
+fn record_applied(
+    upstream: &UpstreamRoots,
+    manifest: &mut AppliedManifest,
+    relative_path: &str,
+    content: &[u8],
+    base_path: &Path,
+) -> Result<()> {
+    record_base(content, base_path)?;
+    let (source, _) = split_source(relative_path);
+    let commit = upstream.commits().get(source).map(String::as_str);
+    manifest.record(relative_path, commit, hash_bytes(content));
+    Ok(())
 }
 
-pub fn remove_local(paths: &RepoPaths, kind: AssetKind, relative_path: &str) -> Result<bool> {
-    if kind == AssetKind::Collection {
+/// Remember what upstream looked like so the next merge has a base.
+fn record_base(content: &[u8], base_path: &Path) -> Result<()> {
+    if let Some(parent) = base_path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+    }
+    fs::write(base_path, content)
+        .with_context(|| format!("recording merge base {}", base_path.display()))?;
+    Ok(())
+}
+
+/// Counts from applying many assets at once.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct SyncSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub merged: usize,
+    pub unchanged: usize,
+    /// Assets left with conflict markers.
+    pub conflicted: Vec<String>,
+}
+
+impl fmt::Display for SyncSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} created, {} updated, {} already in sync",
+            self.created, self.updated, self.unchanged
+        )?;
+        if self.merged > 0 {
+            write!(f, ", {} merged", self.merged)?;
+        }
+        if !self.conflicted.is_empty() {
+            write!(f, ", {} with conflicts", self.conflicted.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// Apply every asset in `assets`, leaving files that already match upstream
+/// untouched.
+pub fn sync_assets<'a>(
+    paths: &RepoPaths,
+    upstream: &UpstreamRoots,
+    manifest: &mut AppliedManifest,
+    assets: impl IntoIterator<Item = (AssetKind, &'a str)>,
+    mode: ApplyMode,
+) -> Result<SyncSummary> {
+    let mut summary = SyncSummary::default();
+    for (kind, path) in assets {
+        match compute_local_status(paths, upstream, manifest, kind, path)? {
+            LocalStatus::Unmanaged | LocalStatus::NA => continue,
+            LocalStatus::Same => {
+                let base_path = base_path_for(paths, path);
+                if kind != AssetKind::McpServer && !base_path.exists() {
+                    let content = upstream_content(upstream, path)?;
+                    record_applied(upstream, manifest, path, &content, &base_path)?;
+                }
+                summary.unchanged += 1;
+            }
+            LocalStatus::Missing => {
+                apply_from_upstream(paths, upstream, manifest, kind, path, mode)?;
+                summary.created += 1;
+            }
+            LocalStatus::Diff | LocalStatus::Update => {
+                match apply_from_upstream(paths, upstream, manifest, kind, path, mode)? {
+                    ApplyOutcome::Written => summary.updated += 1,
+                    ApplyOutcome::Merged => summary.merged += 1,
+                    ApplyOutcome::Unchanged => summary.unchanged += 1,
+                    ApplyOutcome::Conflicted => summary.conflicted.push(path.to_string()),
+                }
+            }
+        }
+    }
+    Ok(summary)
+}
+
+fn merge_into_local(theirs: &str, local_path: &Path, base_path: &Path) -> Result<ApplyOutcome> {
+    let ours = fs::read_to_string(local_path)
+        .with_context(|| format!("reading {}", local_path.display()))?;
+    if ours == theirs {
+        return Ok(ApplyOutcome::Unchanged);
+    }
+    // Without a recorded base every differing line is treated as a conflict.
+    let base = match fs::read_to_string(base_path) {
+        Ok(base) => base,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err).with_context(|| format!("reading {}", base_path.display())),
+    };
+    if ours == base {
+        fs::write(local_path, theirs)
+            .with_context(|| format!("writing {}", local_path.display()))?;
+        return Ok(ApplyOutcome::Written);
+    }
+    let (content, outcome) = match diffy::merge(&base, &ours, theirs) {
+        Ok(merged) => (merged, ApplyOutcome::Merged),
+        Err(conflicted) => (relabel_conflicts(&conflicted), ApplyOutcome::Conflicted),
+    };
+    fs::write(local_path, content).with_context(|| format!("writing {}", local_path.display()))?;
+    Ok(outcome)
+}
+
+/// Carry local edits over an upstream update: they are three-way merged
+/// like in merge mode, taken from the local file or, when it is gone, from
+/// the kept patch. The result's difference from upstream is kept as the new
+/// patch, so the edits survive even an overwrite. On conflicts the previous
+/// patch is left in place until the markers are resolved.
+fn patch_into_local(
+    paths: &RepoPaths,
+    relative_path: &str,
+    theirs: &str,
+    local_path: &Path,
+    base_path: &Path,
+) -> Result<ApplyOutcome> {
+    let base = read_if_exists(base_path)?.unwrap_or_else(|| theirs.to_string());
+    let ours = match read_if_exists(local_path)? {
+        Some(local) => local,
+        None => read_local_patch(paths, relative_path)?
+            .and_then(|patch| apply_patch(&base, &patch))
+            .unwrap_or_else(|| base.clone()),
+    };
+    let (content, outcome) = if ours == base {
+        (theirs.to_string(), ApplyOutcome::Written)
+    } else {
+        match diffy::merge(&base, &ours, theirs) {
+            Ok(merged) => (merged, ApplyOutcome::Merged),
+            Err(conflicted) => {
+                write_local(local_path, relabel_conflicts(&conflicted).as_bytes())?;
+                return Ok(ApplyOutcome::Conflicted);
+            }
+        }
+    };
+    write_local(local_path, content.as_bytes())?;
+    let patch_path = local_patch_path(paths, relative_path);
+    if content == theirs {
+        if patch_path.exists() {
+            fs::remove_file(&patch_path)
+                .with_context(|| format!("removing {}", patch_path.display()))?;
+        }
+    } else {
+        let patch = diffy::create_patch(theirs, &content).to_string();
+        write_local(&patch_path, patch.as_bytes())?;
+    }
+    Ok(outcome)
+}
+
+fn read_if_exists(path: &Path) -> Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("reading {}", path.display())),
+    }
+}
+
+/// The local edits kept for `relative_path` by the patch apply mode, as a
+/// diff against the merge base.
+fn read_local_patch(paths: &RepoPaths, relative_path: &str) -> Result<Option<String>> {
+    read_if_exists(&local_patch_path(paths, relative_path))
+}
+
+/// `content` with `patch` applied, or `None` when the patch does not parse
+/// or its context no longer matches.
+fn apply_patch(content: &str, patch: &str) -> Option<String> {
+    let patch = diffy::Patch::from_str(patch).ok()?;
+    diffy::apply(content, &patch).ok()
+}
+
+/// Name the conflict sides after where they come from instead of diffy's
+/// generic "ours"/"theirs".
+fn relabel_conflicts(content: &str) -> String {
+    content
+        .split_inclusive('\n')
+        .map(|line| match line.trim_end() {
+            "<<<<<<< ours" => line.replacen("ours", "local", 1),
+            ">>>>>>> theirs" => line.replacen("theirs", "upstream", 1),
+            _ => line.to_string(),
+        })
+        .collect()
+}
+
+fn base_path_for(paths: &RepoPaths, relative_path: &str) -> PathBuf {
+    let (source, relative) = split_source(relative_path);
+    paths.base_dir.join(source).join(relative)
+}
+
+fn local_patch_path(paths: &RepoPaths, relative_path: &str) -> PathBuf {
+    let (source, relative) = split_source(relative_path);
+    paths
+        .patches_dir
+        .join(source)
+        .join(format!("{relative}.patch"))
+}
+
+pub fn remove_local(
+    paths: &RepoPaths,
+    manifest: &mut AppliedManifest,
+    kind: AssetKind,
+    relative_path: &str,
+) -> Result<bool> {
+    match kind {
+        AssetKind::Collection => return Ok(false),
+        AssetKind::McpServer => return vscode::remove_mcp_server(paths, relative_path),
+        _ => {}
+    }
+    let local_path = local_path(paths, kind, relative_path);
+    // The file belongs to the same asset of another source.
+    if !manifest.files.contains_key(relative_path) && manifest.other_owner(relative_path).is_some()
+    {
         return Ok(false);
     }
-    let local_relative = relative_path_for_kind(kind, relative_path);
-    let local_path = paths.asset_root(kind).join(&local_relative);
+    if symlink::unlink(&local_path)? {
+        manifest.forget(relative_path);
+        return Ok(true);
+    }
     if local_path.exists() {
+        // Files still matching the content recorded at apply time can be
+        // restored from upstream; anything else is backed up first.
+        let base_path = base_path_for(paths, relative_path);
+        if !base_path.exists() || !same_content(&local_path, &base_path)? {
+            backup_file(paths, &local_path)?;
+        }
         std::fs::remove_file(&local_path)
             .with_context(|| format!("removing {}", local_path.display()))?;
+        let _ = std::fs::remove_file(base_path);
+        manifest.forget(relative_path);
         // Optionally clean up empty parent directories (best-effort)
         if let Some(parent) = local_path.parent() {
             let _ = std::fs::remove_dir(parent);
@@ -95,17 +519,442 @@ pub fn remove_local(paths: &RepoPaths, kind: AssetKind, relative_path: &str) ->
     }
 }
 
+/// Copy a file below `.github/` to `backups/<UTC timestamp>/<path below .github>`.
+/// An earlier backup of the same file with the same timestamp is never
+/// replaced: the set gets a `-2`, `-3`, … suffix instead.
+pub fn backup_file(paths: &RepoPaths, local_path: &Path) -> Result<PathBuf> {
+    let relative = local_path
+        .strip_prefix(&paths.github_dir)
+        .with_context(|| format!("{} is not under .github", local_path.display()))?;
+    let timestamp = Utc::now().format(BACKUP_TIMESTAMP_FORMAT).to_string();
+    let mut backup_path = paths.backups_dir.join(&timestamp).join(relative);
+    for suffix in 2.. {
+        if !backup_path.exists() {
+            break;
+        }
+        backup_path = paths
+            .backups_dir
+            .join(format!("{timestamp}-{suffix}"))
+            .join(relative);
+    }
+    if let Some(parent) = backup_path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+    }
+    fs::copy(local_path, &backup_path).with_context(|| {
+        format!(
+            "backing up {} -> {}",
+            local_path.display(),
+            backup_path.display()
+        )
+    })?;
+    tracing::info!(backup = %backup_path.display(), "backed up local file");
+    Ok(backup_path)
+}
+
+fn same_content(a: &Path, b: &Path) -> Result<bool> {
+    Ok(hash_file(a)? == hash_file(b)?)
+}
+
+/// Where the catalog asset at `relative_path` lives below `.github/`. MCP
+/// servers all live in `.vscode/mcp.json`.
+pub fn local_path(paths: &RepoPaths, kind: AssetKind, relative_path: &str) -> PathBuf {
+    if kind == AssetKind::McpServer {
+        return paths.vscode_mcp.clone();
+    }
+    paths
+        .asset_root(kind)
+        .join(relative_path_for_kind(kind, relative_path))
+}
+
 fn relative_path_for_kind(_kind: AssetKind, relative_path: &str) -> PathBuf {
-    // Upstream relative paths already start with prompts/, instructions/, chatmodes/, collections/
-    // Our local roots are .github/<kind>, so drop the first segment.
+    // Upstream relative paths already start with prompts/, instructions/, chatmodes/, agents/, collections/
+    // Our local roots are .github/<kind>, so drop the source prefix and the first segment.
+    let (_, relative_path) = split_source(relative_path);
     let mut comps = relative_path.split('/');
     comps.next(); // drop top-level kind dir
     PathBuf::from(comps.collect::<Vec<_>>().join("/"))
 }
 
-fn hash_file(path: &Path) -> Result<String> {
-    let mut file = fs::File::open(path).with_context(|| format!("opening {}", path.display()))?;
+pub fn hash_file(path: &Path) -> Result<String> {
+    let content = fs::read(path).with_context(|| format!("hashing {}", path.display()))?;
+    Ok(hash_bytes(&content))
+}
+
+/// SHA-256 of `content` with CRLF line endings read as LF, so a checkout
+/// where git converted line endings still matches upstream.
+pub fn hash_bytes(content: &[u8]) -> String {
     let mut hasher = Sha256::new();
-    io::copy(&mut file, &mut hasher).with_context(|| format!("hashing {}", path.display()))?;
-    Ok(hex::encode(hasher.finalize()))
+    let mut rest = content;
+    while let Some(cr) = rest.windows(2).position(|pair| pair == b"\r\n") {
+        hasher.update(&rest[..cr]);
+        rest = &rest[cr + 1..];
+    }
+    hasher.update(rest);
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{domain::model::PRIMARY_SOURCE, io::manifest};
+
+    const ASSET: &str = "prompts/sample.prompt.md";
+
+    fn setup() -> (tempfile::TempDir, RepoPaths, UpstreamRoots) {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let paths = RepoPaths::new(dir.path().join("repo"));
+        let upstream_dir = dir.path().join("upstream");
+        fs::create_dir_all(upstream_dir.join("prompts")).unwrap();
+        let mut upstream = UpstreamRoots::default();
+        upstream.insert(PRIMARY_SOURCE, upstream_dir);
+        (dir, paths, upstream)
+    }
+
+    fn write_upstream(upstream: &UpstreamRoots, content: &str) {
+        fs::write(upstream.resolve(ASSET).unwrap(), content).unwrap();
+    }
+
+    fn local_content(paths: &RepoPaths) -> String {
+        fs::read_to_string(paths.prompts_dir.join("sample.prompt.md")).unwrap()
+    }
+
+    fn apply_one(
+        paths: &RepoPaths,
+        upstream: &UpstreamRoots,
+        kind: AssetKind,
+        relative_path: &str,
+        mode: ApplyMode,
+    ) -> Result<ApplyOutcome> {
+        manifest::with_manifest(paths, |manifest| {
+            apply_from_upstream(paths, upstream, manifest, kind, relative_path, mode)
+        })
+    }
+
+    fn remove_one(paths: &RepoPaths, kind: AssetKind, relative_path: &str) -> Result<bool> {
+        manifest::with_manifest(paths, |manifest| {
+            remove_local(paths, manifest, kind, relative_path)
+        })
+    }
+
+    fn status_of(
+        paths: &RepoPaths,
+        upstream: &UpstreamRoots,
+        kind: AssetKind,
+        relative_path: &str,
+    ) -> Result<LocalStatus> {
+        let manifest = manifest::load_manifest(paths)?;
+        compute_local_status(paths, upstream, &manifest, kind, relative_path)
+    }
+
+    fn sync_all<'a>(
+        paths: &RepoPaths,
+        upstream: &UpstreamRoots,
+        assets: impl IntoIterator<Item = (AssetKind, &'a str)>,
+        mode: ApplyMode,
+    ) -> Result<SyncSummary> {
+        manifest::with_manifest(paths, |manifest| {
+            sync_assets(paths, upstream, manifest, assets, mode)
+        })
+    }
+
+    #[test]
+    fn merge_combines_local_and_upstream_edits() {
+        let (_dir, paths, upstream) = setup();
+        write_upstream(&upstream, "one\ntwo\nthree\n");
+        let outcome = apply_one(
+            &paths,
+            &upstream,
+            AssetKind::Prompt,
+            ASSET,
+            ApplyMode::Merge,
+        )
+        .unwrap();
+        assert_eq!(outcome, ApplyOutcome::Written);
+
+        fs::write(
+            paths.prompts_dir.join("sample.prompt.md"),
+            "ONE\ntwo\nthree\n",
+        )
+        .unwrap();
+        write_upstream(&upstream, "one\ntwo\nTHREE\n");
+        let outcome = apply_one(
+            &paths,
+            &upstream,
+            AssetKind::Prompt,
+            ASSET,
+            ApplyMode::Merge,
+        )
+        .unwrap();
+        assert_eq!(outcome, ApplyOutcome::Merged);
+        assert_eq!(local_content(&paths), "ONE\ntwo\nTHREE\n");
+    }
+
+    #[test]
+    fn patch_mode_keeps_local_edits_across_updates() {
+        let (_dir, paths, upstream) = setup();
+        let apply = |upstream: &UpstreamRoots| {
+            apply_one(&paths, upstream, AssetKind::Prompt, ASSET, ApplyMode::Patch).unwrap()
+        };
+        let status = |upstream: &UpstreamRoots| {
+            status_of(&paths, upstream, AssetKind::Prompt, ASSET).unwrap()
+        };
+        write_upstream(&upstream, "one\ntwo\nthree\nfour\n");
+        assert_eq!(apply(&upstream), ApplyOutcome::Written);
+
+        fs::write(
+            paths.prompts_dir.join("sample.prompt.md"),
+            "ONE\ntwo\nthree\nfour\n",
+        )
+        .unwrap();
+        write_upstream(&upstream, "one\ntwo\nthree\nFOUR\n");
+        assert_eq!(apply(&upstream), ApplyOutcome::Merged);
+        assert_eq!(local_content(&paths), "ONE\ntwo\nthree\nFOUR\n");
+        assert_eq!(status(&upstream), LocalStatus::Same);
+
+        write_upstream(&upstream, "one\ntwo\nthree\nfour!\n");
+        assert_eq!(status(&upstream), LocalStatus::Update);
+        assert_eq!(apply(&upstream), ApplyOutcome::Merged);
+        assert_eq!(local_content(&paths), "ONE\ntwo\nthree\nfour!\n");
+
+        fs::remove_file(paths.prompts_dir.join("sample.prompt.md")).unwrap();
+        assert_eq!(apply(&upstream), ApplyOutcome::Merged);
+        assert_eq!(local_content(&paths), "ONE\ntwo\nthree\nfour!\n");
+
+        fs::write(
+            paths.prompts_dir.join("sample.prompt.md"),
+            "one\ntwo\nthree\nfour!\n",
+        )
+        .unwrap();
+        assert_eq!(apply(&upstream), ApplyOutcome::Written);
+        assert!(read_local_patch(&paths, ASSET).unwrap().is_none());
+    }
+
+    #[test]
+    fn sources_never_overwrite_each_others_files() {
+        let (dir, paths, mut upstream) = setup();
+        write_upstream(&upstream, "upstream\n");
+        fs::create_dir_all(dir.path().join("fork/prompts")).unwrap();
+        upstream.insert("fork", dir.path().join("fork"));
+        let forked = "fork:prompts/sample.prompt.md";
+        fs::write(upstream.resolve(forked).unwrap(), "fork\n").unwrap();
+        let apply = |path| {
+            apply_one(
+                &paths,
+                &upstream,
+                AssetKind::Prompt,
+                path,
+                ApplyMode::Overwrite,
+            )
+        };
+
+        let unloaded = apply("gone:prompts/sample.prompt.md").unwrap_err();
+        assert!(unloaded.to_string().contains("not loaded"));
+        apply(ASSET).unwrap();
+        assert!(apply(forked).is_err());
+        assert!(!remove_one(&paths, AssetKind::Prompt, forked).unwrap());
+        assert_eq!(local_content(&paths), "upstream\n");
+
+        assert!(remove_one(&paths, AssetKind::Prompt, ASSET).unwrap());
+        apply(forked).unwrap();
+        assert_eq!(local_content(&paths), "fork\n");
+    }
+
+    #[test]
+    fn crlf_checkouts_match_lf_upstream() {
+        let (_dir, paths, upstream) = setup();
+        write_upstream(&upstream, "one\ntwo\n");
+        fs::create_dir_all(&paths.prompts_dir).unwrap();
+        fs::write(paths.prompts_dir.join("sample.prompt.md"), "one\r\ntwo\r\n").unwrap();
+        let status = status_of(&paths, &upstream, AssetKind::Prompt, ASSET).unwrap();
+        assert_eq!(status, LocalStatus::Same);
+        assert_ne!(hash_bytes(b"one\rtwo"), hash_bytes(b"one\ntwo"));
+    }
+
+    #[test]
+    fn applies_with_configured_line_endings() {
+        let (_dir, paths, mut upstream) = setup();
+        write_upstream(&upstream, "one\r\ntwo\nthree");
+        upstream.set_line_endings(Some(LineEndings::Crlf));
+        apply_one(
+            &paths,
+            &upstream,
+            AssetKind::Prompt,
+            ASSET,
+            ApplyMode::Symlink,
+        )
+        .unwrap();
+        assert!(!symlink::is_symlink(
+            &paths.prompts_dir.join("sample.prompt.md")
+        ));
+        assert_eq!(local_content(&paths), "one\r\ntwo\r\nthree");
+        let status = status_of(&paths, &upstream, AssetKind::Prompt, ASSET).unwrap();
+        assert_eq!(status, LocalStatus::Same);
+
+        assert_eq!(LineEndings::Lf.convert(b"one\r\ntwo\r"), b"one\ntwo\r");
+    }
+
+    #[test]
+    fn manifest_tells_updates_from_local_edits() {
+        let (_dir, paths, upstream) = setup();
+        let status = || status_of(&paths, &upstream, AssetKind::Prompt, ASSET).unwrap();
+        write_upstream(&upstream, "one\n");
+        apply_one(
+            &paths,
+            &upstream,
+            AssetKind::Prompt,
+            ASSET,
+            ApplyMode::Overwrite,
+        )
+        .unwrap();
+        let entry = &manifest::load_manifest(&paths).unwrap().files[ASSET];
+        assert_eq!(entry.sha256, hash_bytes(b"one\n"));
+        // Without merge bases the manifest alone decides.
+        fs::remove_dir_all(&paths.base_dir).unwrap();
+
+        write_upstream(&upstream, "two\n");
+        assert_eq!(status(), LocalStatus::Update);
+        write_upstream(&upstream, "one\n");
+        fs::write(paths.prompts_dir.join("sample.prompt.md"), "mine\n").unwrap();
+        assert_eq!(status(), LocalStatus::Diff);
+
+        remove_one(&paths, AssetKind::Prompt, ASSET).unwrap();
+        assert!(manifest::load_manifest(&paths).unwrap().files.is_empty());
+    }
+
+    #[test]
+    fn merge_writes_conflict_markers() {
+        let (_dir, paths, upstream) = setup();
+        write_upstream(&upstream, "one\ntwo\n");
+        apply_one(
+            &paths,
+            &upstream,
+            AssetKind::Prompt,
+            ASSET,
+            ApplyMode::Merge,
+        )
+        .unwrap();
+
+        fs::write(paths.prompts_dir.join("sample.prompt.md"), "mine\ntwo\n").unwrap();
+        write_upstream(&upstream, "theirs\ntwo\n");
+        let outcome = apply_one(
+            &paths,
+            &upstream,
+            AssetKind::Prompt,
+            ASSET,
+            ApplyMode::Merge,
+        )
+        .unwrap();
+        assert_eq!(outcome, ApplyOutcome::Conflicted);
+        let content = local_content(&paths);
+        assert!(content.contains("<<<<<<< local\nmine\n"));
+        assert!(content.contains("theirs\n>>>>>>> upstream\n"));
+    }
+
+    #[test]
+    fn sync_counts_created_updated_and_unchanged() {
+        let (_dir, paths, upstream) = setup();
+        let other = "prompts/other.prompt.md";
+        write_upstream(&upstream, "one\n");
+        fs::write(upstream.resolve(other).unwrap(), "other\n").unwrap();
+        let assets = [(AssetKind::Prompt, ASSET), (AssetKind::Prompt, other)];
+
+        let summary = sync_all(&paths, &upstream, assets, ApplyMode::Overwrite).unwrap();
+        assert_eq!(
+            (summary.created, summary.updated, summary.unchanged),
+            (2, 0, 0)
+        );
+
+        write_upstream(&upstream, "two\n");
+        assert_eq!(
+            status_of(&paths, &upstream, AssetKind::Prompt, ASSET).unwrap(),
+            LocalStatus::Update
+        );
+        let summary = sync_all(&paths, &upstream, assets, ApplyMode::Overwrite).unwrap();
+        assert_eq!(
+            (summary.created, summary.updated, summary.unchanged),
+            (0, 1, 1)
+        );
+        assert_eq!(local_content(&paths), "two\n");
+        assert_eq!(
+            summary.to_string(),
+            "0 created, 1 updated, 1 already in sync"
+        );
+    }
+
+    #[test]
+    fn overwriting_local_edits_creates_backup() {
+        let (_dir, paths, upstream) = setup();
+        write_upstream(&upstream, "one\n");
+        fs::create_dir_all(&paths.prompts_dir).unwrap();
+        fs::write(paths.prompts_dir.join("sample.prompt.md"), "edited\n").unwrap();
+        apply_one(
+            &paths,
+            &upstream,
+            AssetKind::Prompt,
+            ASSET,
+            ApplyMode::Overwrite,
+        )
+        .unwrap();
+
+        let backups: Vec<_> = walkdir::WalkDir::new(&paths.backups_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].path().ends_with("prompts/sample.prompt.md"));
+        assert_eq!(fs::read_to_string(backups[0].path()).unwrap(), "edited\n");
+
+        // Removing an unmodified applied file needs no backup.
+        remove_one(&paths, AssetKind::Prompt, ASSET).unwrap();
+        let count = walkdir::WalkDir::new(&paths.backups_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .count();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn backups_in_quick_succession_are_all_kept() {
+        let (_dir, paths, _upstream) = setup();
+        let local = paths.prompts_dir.join("sample.prompt.md");
+        fs::create_dir_all(&paths.prompts_dir).unwrap();
+        let backups: Vec<_> = ["first\n", "second\n", "third\n"]
+            .into_iter()
+            .map(|content| {
+                fs::write(&local, content).unwrap();
+                backup_file(&paths, &local).unwrap()
+            })
+            .collect();
+        let kept: Vec<_> = backups
+            .iter()
+            .map(|backup| fs::read_to_string(backup).unwrap())
+            .collect();
+        assert_eq!(kept, ["first\n", "second\n", "third\n"]);
+    }
+
+    #[test]
+    fn overwrite_replaces_local_edits() {
+        let (_dir, paths, upstream) = setup();
+        write_upstream(&upstream, "one\n");
+        apply_one(
+            &paths,
+            &upstream,
+            AssetKind::Prompt,
+            ASSET,
+            ApplyMode::Overwrite,
+        )
+        .unwrap();
+        fs::write(paths.prompts_dir.join("sample.prompt.md"), "edited\n").unwrap();
+        apply_one(
+            &paths,
+            &upstream,
+            AssetKind::Prompt,
+            ASSET,
+            ApplyMode::Overwrite,
+        )
+        .unwrap();
+        assert_eq!(local_content(&paths), "one\n");
+    }
 }
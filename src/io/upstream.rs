@@ -1,26 +1,272 @@
 use std::{
-    fs,
-    io::{self, copy},
+    collections::BTreeMap,
+    fmt, fs,
+    io::{self, copy, Read},
     path::{Path, PathBuf},
+    str::FromStr,
     time::Duration,
 };
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use chrono::{DateTime, Utc};
-use reqwest::blocking::Client;
+use reqwest::{
+    blocking::{Client, Response},
+    header::{ETAG, IF_NONE_MATCH},
+    StatusCode,
+};
 use serde::{Deserialize, Serialize};
 use tempfile::NamedTempFile;
 use zip::ZipArchive;
 
-use super::paths::RepoPaths;
+use crate::domain::model::{split_source, ContentPatch, LOCAL_SOURCE, PRIMARY_SOURCE};
+
+use super::{
+    paths::RepoPaths,
+    retry::{check_rate_limit, RetryPolicy},
+    sync::LineEndings,
+};
+
+mod cache;
+mod sparse;
+
+pub use cache::{cached_snapshots, remove_snapshot, CachedSnapshot};
 
-const USER_AGENT: &str = "awesome-copilot-tui (+https://github.com/astrosteveo/awesome-copilot)";
+pub(crate) const USER_AGENT: &str =
+    "awesome-copilot-tui (+https://github.com/astrosteveo/awesome-copilot)";
 const GITHUB_API: &str = "https://api.github.com";
 const OWNER: &str = "github";
 const REPO: &str = "awesome-copilot";
 const REF: &str = "main";
-const FRESHNESS_HOURS: i64 = 12;
-const MAX_CACHE_ENTRIES: usize = 5;
+const DEFAULT_FRESHNESS_HOURS: u32 = 12;
+const DEFAULT_KEEP_SNAPSHOTS: usize = 5;
+
+/// A GitHub repository that provides catalog assets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpstreamSource {
+    pub id: String,
+    pub owner: String,
+    pub repo: String,
+    #[serde(rename = "ref", default = "default_ref")]
+    pub reference: String,
+}
+
+fn default_ref() -> String {
+    REF.to_string()
+}
+
+impl UpstreamSource {
+    /// The official `github/awesome-copilot` repository.
+    pub fn official() -> Self {
+        Self {
+            id: PRIMARY_SOURCE.to_string(),
+            owner: OWNER.to_string(),
+            repo: REPO.to_string(),
+            reference: REF.to_string(),
+        }
+    }
+
+    pub fn is_primary(&self) -> bool {
+        self.id == PRIMARY_SOURCE
+    }
+
+    /// Link to the repository on GitHub as of `commit`.
+    pub fn tree_url(&self, commit: &str) -> String {
+        format!(
+            "https://github.com/{}/{}/tree/{commit}",
+            self.owner, self.repo
+        )
+    }
+
+    /// Link to `relative` on GitHub as of `commit`.
+    pub fn file_url(&self, commit: &str, relative: &str) -> String {
+        format!(
+            "https://github.com/{}/{}/blob/{commit}/{relative}",
+            self.owner, self.repo
+        )
+    }
+
+    fn cache_root(&self, paths: &RepoPaths) -> PathBuf {
+        if self.is_primary() {
+            paths.cache_dir.clone()
+        } else {
+            paths.sources_cache_dir.join(&self.id)
+        }
+    }
+}
+
+impl fmt::Display for UpstreamSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}@{}", self.owner, self.repo, self.reference)
+    }
+}
+
+impl FromStr for UpstreamSource {
+    type Err = anyhow::Error;
+
+    /// Parse `ID=OWNER/REPO[@REF]`.
+    fn from_str(value: &str) -> Result<Self> {
+        let (id, spec) = value
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("expected ID=OWNER/REPO[@REF], got '{value}'"))?;
+        let (repo_spec, reference) = match spec.split_once('@') {
+            Some((repo_spec, reference)) => (repo_spec, reference.to_string()),
+            None => (spec, default_ref()),
+        };
+        let (owner, repo) = repo_spec
+            .split_once('/')
+            .ok_or_else(|| anyhow::anyhow!("expected OWNER/REPO in '{spec}'"))?;
+        let source = Self {
+            id: id.trim().to_string(),
+            owner: owner.trim().to_string(),
+            repo: repo.trim().to_string(),
+            reference,
+        };
+        validate_source_id(&source.id)?;
+        Ok(source)
+    }
+}
+
+fn validate_source_id(id: &str) -> Result<()> {
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow::anyhow!(
+            "invalid source id '{id}': use letters, digits, '-' or '_'"
+        ));
+    }
+    if id == LOCAL_SOURCE {
+        return Err(anyhow::anyhow!(
+            "source id '{LOCAL_SOURCE}' is reserved for .awesome-copilot-tui/local"
+        ));
+    }
+    Ok(())
+}
+
+/// Load the sources declared in `.awesome-copilot-tui/sources.json` plus any
+/// passed on the command line. The official repository is always first and
+/// follows `pin` instead of `main` when one is set.
+pub fn load_sources(
+    paths: &RepoPaths,
+    extra: &[UpstreamSource],
+    pin: Option<&str>,
+) -> Result<Vec<UpstreamSource>> {
+    let mut declared: Vec<UpstreamSource> = match fs::read_to_string(&paths.sources) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| format!("parsing {}", paths.sources.display()))?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => {
+            return Err(err).with_context(|| format!("reading {}", paths.sources.display()))
+        }
+    };
+    declared.extend(extra.iter().cloned());
+
+    let mut official = UpstreamSource::official();
+    if let Some(pin) = pin {
+        official.reference = pin.to_string();
+    }
+    let mut sources = vec![official];
+    for source in declared {
+        validate_source_id(&source.id)?;
+        if let Some(existing) = sources.iter_mut().find(|s| s.id == source.id) {
+            *existing = source;
+        } else {
+            sources.push(source);
+        }
+    }
+    Ok(sources)
+}
+
+/// Extracted snapshot directories for every loaded source, and the content
+/// patches from the enablement file that are applied on top of them.
+#[derive(Debug, Clone, Default)]
+pub struct UpstreamRoots {
+    roots: BTreeMap<String, PathBuf>,
+    commits: BTreeMap<String, String>,
+    fetched_at: BTreeMap<String, DateTime<Utc>>,
+    patches: BTreeMap<String, ContentPatch>,
+    line_endings: Option<LineEndings>,
+    variables: BTreeMap<String, String>,
+}
+
+impl UpstreamRoots {
+    pub fn insert(&mut self, source: &str, content_dir: PathBuf) {
+        self.roots.insert(source.to_string(), content_dir);
+    }
+
+    pub fn record_snapshot(&mut self, source: &str, snapshot: &UpstreamSnapshot) {
+        self.commits
+            .insert(source.to_string(), snapshot.commit.clone());
+        self.fetched_at
+            .insert(source.to_string(), snapshot.fetched_at);
+    }
+
+    /// Snapshot commit of every loaded source, keyed by source id.
+    pub fn commits(&self) -> &BTreeMap<String, String> {
+        &self.commits
+    }
+
+    /// When each source's snapshot was downloaded, keyed by source id.
+    pub fn fetched_at(&self) -> &BTreeMap<String, DateTime<Utc>> {
+        &self.fetched_at
+    }
+
+    pub fn set_patches(&mut self, patches: BTreeMap<String, ContentPatch>) {
+        self.patches = patches;
+    }
+
+    /// The local patch of the asset at `asset_path`, if it has one.
+    pub fn patch(&self, asset_path: &str) -> Option<&ContentPatch> {
+        self.patches.get(asset_path)
+    }
+
+    pub fn set_line_endings(&mut self, line_endings: Option<LineEndings>) {
+        self.line_endings = line_endings;
+    }
+
+    /// How applied files' line endings are converted; `None` keeps upstream's.
+    pub fn line_endings(&self) -> Option<LineEndings> {
+        self.line_endings
+    }
+
+    pub fn set_variables(&mut self, variables: BTreeMap<String, String>) {
+        self.variables = variables;
+    }
+
+    /// Values substituted for `{{name}}` placeholders in applied files.
+    pub fn variables(&self) -> &BTreeMap<String, String> {
+        &self.variables
+    }
+
+    /// Whether applying the asset at `asset_path` writes something other
+    /// than the snapshot's bytes, so it can't be linked.
+    pub fn rewrites(&self, asset_path: &str) -> bool {
+        self.line_endings.is_some()
+            || !self.variables.is_empty()
+            || self.patch(asset_path).is_some()
+    }
+
+    /// Extracted content directory of every loaded source, keyed by source id.
+    pub fn roots(&self) -> &BTreeMap<String, PathBuf> {
+        &self.roots
+    }
+
+    /// Extracted content directory of `source`'s snapshot.
+    pub fn root(&self, source: &str) -> Option<&Path> {
+        self.roots.get(source).map(PathBuf::as_path)
+    }
+
+    /// Absolute path of a catalog asset inside its source snapshot. Fails
+    /// when the asset's source isn't loaded.
+    pub fn resolve(&self, asset_path: &str) -> Result<PathBuf> {
+        let (source, relative) = split_source(asset_path);
+        match self.roots.get(source) {
+            Some(root) => Ok(root.join(relative)),
+            None => bail!("source '{source}' of {asset_path} is not loaded"),
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct UpstreamSnapshot {
@@ -30,6 +276,88 @@ pub struct UpstreamSnapshot {
     pub warnings: Vec<String>,
 }
 
+/// Progress of a snapshot refresh, reported while `ensure_snapshot` runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotProgress {
+    Resolving {
+        source: String,
+    },
+    Downloading {
+        source: String,
+        received: u64,
+        total: Option<u64>,
+    },
+    Extracting {
+        source: String,
+        done: usize,
+        total: usize,
+    },
+    /// Sparse fetch: asset files downloaded or reused so far.
+    Fetching {
+        source: String,
+        done: usize,
+        total: usize,
+    },
+}
+
+impl SnapshotProgress {
+    /// Completed fraction of the current phase, when it is known.
+    pub fn ratio(&self) -> Option<f64> {
+        match self {
+            SnapshotProgress::Resolving { .. } => None,
+            SnapshotProgress::Downloading {
+                received, total, ..
+            } => total
+                .filter(|total| *total > 0)
+                .map(|total| (*received as f64 / total as f64).min(1.0)),
+            SnapshotProgress::Extracting { done, total, .. }
+            | SnapshotProgress::Fetching { done, total, .. } => {
+                (*total > 0).then(|| (*done as f64 / *total as f64).min(1.0))
+            }
+        }
+    }
+}
+
+impl fmt::Display for SnapshotProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotProgress::Resolving { source } => {
+                write!(f, "Resolving {source} snapshot…")
+            }
+            SnapshotProgress::Downloading {
+                source, received, ..
+            } => match self.ratio() {
+                Some(ratio) => write!(f, "Downloading {source} snapshot… {:.0}%", ratio * 100.0),
+                None => write!(
+                    f,
+                    "Downloading {source} snapshot… {:.1} MB",
+                    *received as f64 / (1024.0 * 1024.0)
+                ),
+            },
+            SnapshotProgress::Extracting {
+                source,
+                done,
+                total,
+            } => {
+                write!(f, "Extracting {source} snapshot… {done}/{total} files")
+            }
+            SnapshotProgress::Fetching {
+                source,
+                done,
+                total,
+            } => {
+                write!(f, "Fetching {source} assets… {done}/{total} files")
+            }
+        }
+    }
+}
+
+/// Callback receiving progress updates; use `&|_| {}` when nobody is watching.
+pub type ProgressFn<'a> = &'a dyn Fn(SnapshotProgress);
+
+/// Bytes between two download progress reports.
+const PROGRESS_STEP: u64 = 64 * 1024;
+
 #[derive(Debug, Deserialize)]
 struct CommitResponse {
     sha: String,
@@ -39,12 +367,141 @@ struct CommitResponse {
 struct SnapshotMetadata {
     commit: String,
     fetched_at: DateTime<Utc>,
+    /// ETag of the commit lookup that last resolved to this snapshot, sent
+    /// back as `If-None-Match` on the next refresh.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    commit_etag: Option<String>,
+    /// ETag of the downloaded archive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    archive_etag: Option<String>,
+    /// Git blob SHA per asset file, recorded by sparse fetches so the next
+    /// one can reuse unchanged files.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    blobs: BTreeMap<String, String>,
+}
+
+/// How a snapshot is downloaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FetchStrategy {
+    /// The zip archive of the whole repository.
+    #[default]
+    Archive,
+    /// Only the files in the asset directories, one request each; files whose
+    /// blob is unchanged since the previous snapshot are copied from it.
+    Sparse,
+}
+
+/// How long a cached snapshot is used before it is downloaded again, even
+/// if its commit hasn't moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "FreshnessSetting")]
+pub enum Freshness {
+    Hours(u32),
+    /// Cached snapshots never go stale, and GitHub isn't asked whether the
+    /// reference moved unless a refresh is requested.
+    Never,
+}
+
+impl Default for Freshness {
+    fn default() -> Self {
+        Freshness::Hours(DEFAULT_FRESHNESS_HOURS)
+    }
+}
+
+impl FromStr for Freshness {
+    type Err = anyhow::Error;
+
+    /// Parse a number of hours or `never`.
+    fn from_str(value: &str) -> Result<Self> {
+        if value.eq_ignore_ascii_case("never") {
+            return Ok(Freshness::Never);
+        }
+        value
+            .parse()
+            .map(Freshness::Hours)
+            .map_err(|_| anyhow::anyhow!("expected a number of hours or 'never', got '{value}'"))
+    }
+}
+
+/// `freshness = 12` or `freshness = "never"` in `config.toml`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FreshnessSetting {
+    Hours(u32),
+    Word(String),
+}
+
+impl TryFrom<FreshnessSetting> for Freshness {
+    type Error = anyhow::Error;
+
+    fn try_from(setting: FreshnessSetting) -> Result<Self> {
+        match setting {
+            FreshnessSetting::Hours(hours) => Ok(Freshness::Hours(hours)),
+            FreshnessSetting::Word(word) => word.parse(),
+        }
+    }
+}
+
+/// What a load does about cached snapshots.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Refresh {
+    /// Use them as far as `Freshness` allows.
+    #[default]
+    Cached,
+    /// The user asked for a refresh: check GitHub even with
+    /// `Freshness::Never`.
+    Check,
+    /// Download every snapshot again, pinned ones included.
+    Force,
+}
+
+/// Network settings for `ensure_snapshot`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchOptions {
+    pub strategy: FetchStrategy,
+    pub retry: RetryPolicy,
+    pub freshness: Freshness,
+    /// Snapshots kept per source; older ones are deleted after a download.
+    pub keep_snapshots: usize,
+    pub refresh: Refresh,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            strategy: FetchStrategy::default(),
+            retry: RetryPolicy::default(),
+            freshness: Freshness::default(),
+            keep_snapshots: DEFAULT_KEEP_SNAPSHOTS,
+            refresh: Refresh::Cached,
+        }
+    }
+}
+
+/// Answer to a conditional commit lookup.
+enum CommitLookup {
+    Latest {
+        sha: String,
+        etag: Option<String>,
+    },
+    /// The reference still points where it did when the ETag was issued.
+    NotModified,
 }
 
-pub fn ensure_snapshot(paths: &RepoPaths, force_refresh: bool) -> Result<UpstreamSnapshot> {
+pub fn ensure_snapshot(
+    paths: &RepoPaths,
+    source: &UpstreamSource,
+    force_refresh: bool,
+    fetch: FetchOptions,
+    progress: ProgressFn<'_>,
+) -> Result<UpstreamSnapshot> {
     paths
         .ensure_project_structure()
         .context("creating project directories")?;
+    let cache_dir = source.cache_root(paths);
+    fs::create_dir_all(&cache_dir)
+        .with_context(|| format!("creating cache directory {}", cache_dir.display()))?;
 
     let client = Client::builder()
         .user_agent(USER_AGENT)
@@ -54,18 +511,79 @@ pub fn ensure_snapshot(paths: &RepoPaths, force_refresh: bool) -> Result<Upstrea
 
     let mut warnings = Vec::new();
 
-    match fetch_latest_commit(&client) {
-        Ok(commit) => {
-            let snapshot_dir = paths.cache_dir.join(&commit);
-            if !force_refresh {
-                if let Some(snapshot) = try_load_snapshot(&snapshot_dir, false) {
+    // A snapshot pinned to an exact commit never changes, so reuse it without
+    // asking GitHub, regardless of its age.
+    if !force_refresh && is_commit_sha(&source.reference) {
+        if let Some(snapshot) =
+            try_load_snapshot(&cache_dir.join(&source.reference), Freshness::Never)
+        {
+            return Ok(snapshot);
+        }
+    }
+    if !force_refresh && fetch.refresh == Refresh::Cached && fetch.freshness == Freshness::Never {
+        if let Some(Ok(snapshot)) = load_latest_snapshot(&cache_dir) {
+            return Ok(snapshot);
+        }
+    }
+
+    progress(SnapshotProgress::Resolving {
+        source: source.id.clone(),
+    });
+    // Ask GitHub whether the reference moved since the newest cached snapshot
+    // was resolved; a 304 answer costs no rate limit.
+    let latest_dir = latest_snapshot_dir(&cache_dir);
+    let known_etag = latest_dir
+        .as_deref()
+        .filter(|_| !force_refresh)
+        .and_then(read_metadata)
+        .and_then(|metadata| metadata.commit_etag);
+    let lookup = fetch.retry.run("commit lookup", || {
+        fetch_latest_commit(&client, source, known_etag.as_deref())
+    });
+    match lookup {
+        Ok(CommitLookup::NotModified) => {
+            let latest_dir = latest_dir.context("no cached snapshot for the ETag")?;
+            revalidate_snapshot(&latest_dir, None)?.with_context(|| {
+                format!(
+                    "failed to load cached snapshot from {}",
+                    latest_dir.display()
+                )
+            })
+        }
+        Ok(CommitLookup::Latest { sha: commit, etag }) => {
+            let snapshot_dir = cache_dir.join(&commit);
+            if !force_refresh && try_load_snapshot(&snapshot_dir, fetch.freshness).is_some() {
+                if let Some(snapshot) = revalidate_snapshot(&snapshot_dir, etag.clone())? {
                     return Ok(snapshot);
                 }
             }
 
-            match download_snapshot(&client, &paths.cache_dir, &commit) {
+            let download = match fetch.strategy {
+                FetchStrategy::Archive => fetch.retry.run("archive download", || {
+                    download_snapshot(
+                        &client,
+                        source,
+                        &cache_dir,
+                        &commit,
+                        etag.clone(),
+                        force_refresh,
+                        progress,
+                    )
+                }),
+                FetchStrategy::Sparse => sparse::download_snapshot(
+                    &client,
+                    source,
+                    &cache_dir,
+                    &commit,
+                    etag.clone(),
+                    force_refresh,
+                    fetch.retry,
+                    progress,
+                ),
+            };
+            match download {
                 Ok(snapshot) => {
-                    prune_old_snapshots(&paths.cache_dir, MAX_CACHE_ENTRIES)?;
+                    prune_old_snapshots(&cache_dir, fetch.keep_snapshots.max(1))?;
                     return Ok(snapshot);
                 }
                 Err(download_err) => {
@@ -73,7 +591,7 @@ pub fn ensure_snapshot(paths: &RepoPaths, force_refresh: bool) -> Result<Upstrea
                         "Failed to refresh upstream archive ({}); falling back to cached snapshot if available",
                         download_err
                     ));
-                    if let Some(snapshot) = try_load_snapshot(&snapshot_dir, true) {
+                    if let Some(snapshot) = try_load_snapshot(&snapshot_dir, Freshness::Never) {
                         let mut snapshot = snapshot;
                         snapshot.warnings.extend(warnings);
                         return Ok(snapshot);
@@ -81,7 +599,7 @@ pub fn ensure_snapshot(paths: &RepoPaths, force_refresh: bool) -> Result<Upstrea
                 }
             }
 
-            if let Some(snapshot) = load_latest_snapshot(&paths.cache_dir) {
+            if let Some(snapshot) = load_latest_snapshot(&cache_dir) {
                 let mut snapshot = snapshot?;
                 snapshot.warnings.append(&mut warnings);
                 snapshot
@@ -98,7 +616,7 @@ pub fn ensure_snapshot(paths: &RepoPaths, force_refresh: bool) -> Result<Upstrea
             warnings.push(format!(
                 "Failed to query latest commit from GitHub API: {err}; attempting to use cached snapshot"
             ));
-            if let Some(snapshot_result) = load_latest_snapshot(&paths.cache_dir) {
+            if let Some(snapshot_result) = load_latest_snapshot(&cache_dir) {
                 let mut snapshot = snapshot_result?;
                 snapshot.warnings.extend(warnings);
                 return Ok(snapshot);
@@ -110,43 +628,104 @@ pub fn ensure_snapshot(paths: &RepoPaths, force_refresh: bool) -> Result<Upstrea
     }
 }
 
-fn fetch_latest_commit(client: &Client) -> Result<String> {
+fn is_commit_sha(reference: &str) -> bool {
+    reference.len() == 40 && reference.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Resolve the source's reference to a commit. With `etag`, the request is
+/// conditional and may come back as `NotModified`.
+fn fetch_latest_commit(
+    client: &Client,
+    source: &UpstreamSource,
+    etag: Option<&str>,
+) -> Result<CommitLookup> {
     let url = format!(
-        "{GITHUB_API}/repos/{OWNER}/{REPO}/commits/{REF}",
-        GITHUB_API = GITHUB_API,
-        OWNER = OWNER,
-        REPO = REPO,
-        REF = REF
+        "{GITHUB_API}/repos/{owner}/{repo}/commits/{reference}",
+        owner = source.owner,
+        repo = source.repo,
+        reference = source.reference
     );
-    let response = client
+    let mut request = client
         .get(url)
-        .header("Accept", "application/vnd.github+json")
-        .send()
-        .context("fetching latest commit")?
+        .header("Accept", "application/vnd.github+json");
+    if let Some(etag) = etag {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+    let response = check_rate_limit(request.send().context("fetching latest commit")?)?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(CommitLookup::NotModified);
+    }
+    let response = response
         .error_for_status()
         .context("GitHub commit request failed")?;
+    let etag = etag_of(&response);
     let commit: CommitResponse = response.json().context("parsing commit response")?;
-    Ok(commit.sha)
+    Ok(CommitLookup::Latest {
+        sha: commit.sha,
+        etag,
+    })
+}
+
+fn etag_of(response: &Response) -> Option<String> {
+    response
+        .headers()
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
 }
 
-fn download_snapshot(client: &Client, cache_dir: &Path, commit: &str) -> Result<UpstreamSnapshot> {
+/// Download and extract the archive of `commit`. A stale snapshot of the same
+/// commit is revalidated with its archive ETag instead of downloaded again,
+/// unless `force` is set.
+fn download_snapshot(
+    client: &Client,
+    source: &UpstreamSource,
+    cache_dir: &Path,
+    commit: &str,
+    commit_etag: Option<String>,
+    force: bool,
+    progress: ProgressFn<'_>,
+) -> Result<UpstreamSnapshot> {
+    let snapshot_dir = cache_dir.join(commit);
     let url = format!(
-        "https://codeload.github.com/{OWNER}/{REPO}/zip/refs/heads/{REF}",
-        OWNER = OWNER,
-        REPO = REPO,
-        REF = REF
+        "https://codeload.github.com/{owner}/{repo}/zip/{commit}",
+        owner = source.owner,
+        repo = source.repo,
     );
-    let mut response = client
-        .get(url)
-        .send()
-        .context("downloading upstream archive")?
+    let mut request = client.get(url);
+    let cached_etag = read_metadata(&snapshot_dir)
+        .filter(|_| !force)
+        .and_then(|metadata| metadata.archive_etag);
+    if let Some(etag) = &cached_etag {
+        request = request.header(IF_NONE_MATCH, etag.as_str());
+    }
+    let response = check_rate_limit(request.send().context("downloading upstream archive")?)?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        if let Some(snapshot) = revalidate_snapshot(&snapshot_dir, commit_etag.clone())? {
+            return Ok(snapshot);
+        }
+    }
+    let response = response
         .error_for_status()
         .context("GitHub archive request failed")?;
+    let archive_etag = etag_of(&response);
 
     let mut tmp = NamedTempFile::new_in(cache_dir).context("creating temp file for archive")?;
-    copy(&mut response, &mut tmp).context("writing archive to disk")?;
+    let total = response.content_length();
+    let mut reader = ProgressReader {
+        inner: response,
+        received: 0,
+        reported: 0,
+        report: &|received| {
+            progress(SnapshotProgress::Downloading {
+                source: source.id.clone(),
+                received,
+                total,
+            })
+        },
+    };
+    copy(&mut reader, &mut tmp).context("writing archive to disk")?;
 
-    let snapshot_dir = cache_dir.join(commit);
     if snapshot_dir.exists() {
         fs::remove_dir_all(&snapshot_dir)
             .with_context(|| format!("removing old snapshot at {}", snapshot_dir.display()))?;
@@ -157,7 +736,13 @@ fn download_snapshot(client: &Client, cache_dir: &Path, commit: &str) -> Result<
     let file = tmp.reopen().context("reopening archive temp file")?;
     let mut archive = ZipArchive::new(file).context("opening archive")?;
 
-    for i in 0..archive.len() {
+    let entry_count = archive.len();
+    for i in 0..entry_count {
+        progress(SnapshotProgress::Extracting {
+            source: source.id.clone(),
+            done: i,
+            total: entry_count,
+        });
         let mut entry = archive.by_index(i).context("reading archive entry")?;
         let outpath = snapshot_dir.join(entry.mangled_name());
         if entry.is_dir() {
@@ -180,12 +765,11 @@ fn download_snapshot(client: &Client, cache_dir: &Path, commit: &str) -> Result<
     let metadata = SnapshotMetadata {
         commit: commit.to_string(),
         fetched_at,
+        commit_etag,
+        archive_etag,
+        blobs: BTreeMap::new(),
     };
-    let metadata_path = snapshot_dir.join("snapshot.json");
-    let metadata_file = fs::File::create(&metadata_path)
-        .with_context(|| format!("writing metadata {}", metadata_path.display()))?;
-    serde_json::to_writer_pretty(metadata_file, &metadata)
-        .with_context(|| format!("serializing metadata {}", metadata_path.display()))?;
+    write_metadata(&snapshot_dir, &metadata)?;
 
     Ok(UpstreamSnapshot {
         commit: commit.to_string(),
@@ -195,6 +779,26 @@ fn download_snapshot(client: &Client, cache_dir: &Path, commit: &str) -> Result<
     })
 }
 
+/// Reader adapter reporting the number of bytes read every `PROGRESS_STEP`.
+struct ProgressReader<'a, R> {
+    inner: R,
+    received: u64,
+    reported: u64,
+    report: &'a dyn Fn(u64),
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.received += read as u64;
+        if read == 0 || self.received - self.reported >= PROGRESS_STEP {
+            self.reported = self.received;
+            (self.report)(self.received);
+        }
+        Ok(read)
+    }
+}
+
 fn find_content_dir(snapshot_dir: &Path) -> Result<PathBuf> {
     let mut entries = fs::read_dir(snapshot_dir)
         .with_context(|| format!("reading snapshot dir {}", snapshot_dir.display()))?;
@@ -210,19 +814,49 @@ fn find_content_dir(snapshot_dir: &Path) -> Result<PathBuf> {
     ))
 }
 
-fn try_load_snapshot(snapshot_dir: &Path, allow_stale: bool) -> Option<UpstreamSnapshot> {
+fn read_metadata(snapshot_dir: &Path) -> Option<SnapshotMetadata> {
+    let metadata_path = snapshot_dir.join("snapshot.json");
+    serde_json::from_reader(fs::File::open(metadata_path).ok()?).ok()
+}
+
+fn write_metadata(snapshot_dir: &Path, metadata: &SnapshotMetadata) -> Result<()> {
+    let metadata_path = snapshot_dir.join("snapshot.json");
+    let metadata_file = fs::File::create(&metadata_path)
+        .with_context(|| format!("writing metadata {}", metadata_path.display()))?;
+    serde_json::to_writer_pretty(metadata_file, metadata)
+        .with_context(|| format!("serializing metadata {}", metadata_path.display()))
+}
+
+/// Mark the cached snapshot as confirmed current just now, remembering a new
+/// commit ETag if there is one. `None` if the snapshot can't be loaded.
+fn revalidate_snapshot(
+    snapshot_dir: &Path,
+    commit_etag: Option<String>,
+) -> Result<Option<UpstreamSnapshot>> {
+    let Some(mut metadata) = read_metadata(snapshot_dir) else {
+        return Ok(None);
+    };
+    metadata.fetched_at = Utc::now();
+    if commit_etag.is_some() {
+        metadata.commit_etag = commit_etag;
+    }
+    write_metadata(snapshot_dir, &metadata)?;
+    Ok(try_load_snapshot(snapshot_dir, Freshness::Never))
+}
+
+fn try_load_snapshot(snapshot_dir: &Path, freshness: Freshness) -> Option<UpstreamSnapshot> {
     if !snapshot_dir.exists() {
         return None;
     }
-    let metadata_path = snapshot_dir.join("snapshot.json");
-    let metadata: SnapshotMetadata =
-        serde_json::from_reader(fs::File::open(&metadata_path).ok()?).ok()?;
+    let metadata = read_metadata(snapshot_dir)?;
     let content_dir = find_content_dir(snapshot_dir).ok()?;
     let age_hours = Utc::now()
         .signed_duration_since(metadata.fetched_at)
         .num_hours();
-    if !allow_stale && age_hours > FRESHNESS_HOURS {
-        return None;
+    if let Freshness::Hours(hours) = freshness {
+        if age_hours > i64::from(hours) {
+            return None;
+        }
     }
     Some(UpstreamSnapshot {
         commit: metadata.commit,
@@ -232,7 +866,36 @@ fn try_load_snapshot(snapshot_dir: &Path, allow_stale: bool) -> Option<UpstreamS
     })
 }
 
-fn load_latest_snapshot(cache_dir: &Path) -> Option<Result<UpstreamSnapshot>> {
+/// Cached snapshot directories of `source` that can't be loaded, with the
+/// reason.
+pub fn broken_snapshots(
+    paths: &RepoPaths,
+    source: &UpstreamSource,
+) -> Vec<(PathBuf, &'static str)> {
+    let Ok(entries) = fs::read_dir(source.cache_root(paths)) else {
+        return Vec::new();
+    };
+    let mut broken: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|snapshot_dir| {
+            let reason = if read_metadata(&snapshot_dir).is_none() {
+                "snapshot.json is missing or unreadable"
+            } else if find_content_dir(&snapshot_dir).is_err() {
+                "the extracted content is missing"
+            } else {
+                return None;
+            };
+            Some((snapshot_dir, reason))
+        })
+        .collect();
+    broken.sort();
+    broken
+}
+
+/// The most recently written snapshot directory in `cache_dir`.
+fn latest_snapshot_dir(cache_dir: &Path) -> Option<PathBuf> {
     let mut entries = match fs::read_dir(cache_dir) {
         Ok(entries) => entries
             .filter_map(|entry| entry.ok())
@@ -242,12 +905,13 @@ fn load_latest_snapshot(cache_dir: &Path) -> Option<Result<UpstreamSnapshot>> {
     };
     entries
         .sort_by_key(|entry| std::cmp::Reverse(entry.metadata().and_then(|m| m.modified()).ok()));
-    entries.into_iter().next().map(|entry| {
-        try_load_snapshot(entry.path().as_path(), true).ok_or_else(|| {
-            anyhow::anyhow!(
-                "failed to load cached snapshot from {}",
-                entry.path().display()
-            )
+    entries.into_iter().next().map(|entry| entry.path())
+}
+
+fn load_latest_snapshot(cache_dir: &Path) -> Option<Result<UpstreamSnapshot>> {
+    latest_snapshot_dir(cache_dir).map(|path| {
+        try_load_snapshot(&path, Freshness::Never).ok_or_else(|| {
+            anyhow::anyhow!("failed to load cached snapshot from {}", path.display())
         })
     })
 }
@@ -0,0 +1,102 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use walkdir::WalkDir;
+
+use super::{read_metadata, UpstreamSource};
+use crate::io::paths::RepoPaths;
+
+/// A snapshot directory in the cache of one source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedSnapshot {
+    pub dir: PathBuf,
+    /// From `snapshot.json`, or the directory name when that can't be read.
+    pub commit: String,
+    /// `None` when `snapshot.json` can't be read.
+    pub fetched_at: Option<DateTime<Utc>>,
+    /// Bytes on disk of every file in the directory.
+    pub size: u64,
+}
+
+/// The snapshots cached for `source`, most recently fetched first.
+pub fn cached_snapshots(paths: &RepoPaths, source: &UpstreamSource) -> Vec<CachedSnapshot> {
+    let Ok(entries) = fs::read_dir(source.cache_root(paths)) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .map(|dir| {
+            let metadata = read_metadata(&dir);
+            let name = dir.file_name().unwrap_or_default().to_string_lossy();
+            CachedSnapshot {
+                commit: metadata
+                    .as_ref()
+                    .map_or_else(|| name.into_owned(), |metadata| metadata.commit.clone()),
+                fetched_at: metadata.map(|metadata| metadata.fetched_at),
+                size: dir_size(&dir),
+                dir,
+            }
+        })
+        .collect();
+    snapshots.sort_by(|a, b| b.fetched_at.cmp(&a.fetched_at).then(a.dir.cmp(&b.dir)));
+    snapshots
+}
+
+/// Delete a cached snapshot; the next load downloads it again if needed.
+pub fn remove_snapshot(snapshot: &CachedSnapshot) -> Result<()> {
+    fs::remove_dir_all(&snapshot.dir)
+        .with_context(|| format!("removing snapshot {}", snapshot.dir.display()))
+}
+
+fn dir_size(dir: &std::path::Path) -> u64 {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_snapshots_newest_first_with_their_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path());
+        let source = UpstreamSource::official();
+        let snapshot = |commit: &str, fetched_at: &str| {
+            let snapshot_dir = paths.cache_dir.join(commit);
+            fs::create_dir_all(snapshot_dir.join("content/prompts")).unwrap();
+            fs::write(
+                snapshot_dir.join("content/prompts/a.prompt.md"),
+                "0123456789",
+            )
+            .unwrap();
+            fs::write(
+                snapshot_dir.join("snapshot.json"),
+                format!(r#"{{"commit":"{commit}","fetched_at":"{fetched_at}"}}"#),
+            )
+            .unwrap();
+        };
+        snapshot("old", "2024-01-01T00:00:00Z");
+        snapshot("new", "2024-02-01T00:00:00Z");
+        fs::create_dir_all(paths.cache_dir.join("broken")).unwrap();
+        fs::write(paths.cache_dir.join("catalog-history.json"), "{}").unwrap();
+
+        let snapshots = cached_snapshots(&paths, &source);
+        let commits: Vec<_> = snapshots.iter().map(|s| s.commit.as_str()).collect();
+        assert_eq!(commits, ["new", "old", "broken"]);
+        assert!(snapshots[0].size > 10);
+        assert_eq!(snapshots[2].fetched_at, None);
+        assert_eq!(snapshots[2].size, 0);
+
+        remove_snapshot(&snapshots[1]).unwrap();
+        assert_eq!(cached_snapshots(&paths, &source).len(), 2);
+    }
+}
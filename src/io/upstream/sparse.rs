@@ -0,0 +1,218 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use reqwest::{blocking::Client, Url};
+use serde::Deserialize;
+
+use super::{
+    check_rate_limit, latest_snapshot_dir, read_metadata, revalidate_snapshot, write_metadata,
+    ProgressFn, SnapshotMetadata, SnapshotProgress, UpstreamSnapshot, UpstreamSource, GITHUB_API,
+};
+use crate::io::retry::RetryPolicy;
+
+/// Top-level directories the catalog reads assets from.
+const ASSET_DIRS: &[&str] = &[
+    "prompts",
+    "instructions",
+    "chatmodes",
+    "agents",
+    "mcp",
+    "collections",
+];
+
+#[derive(Debug, Deserialize)]
+struct TreeResponse {
+    tree: Vec<TreeEntry>,
+    #[serde(default)]
+    truncated: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct TreeEntry {
+    path: String,
+    #[serde(rename = "type")]
+    kind: String,
+    sha: String,
+}
+
+/// Fetch the asset files of `commit` into `cache_dir/<commit>`, laid out like
+/// an extracted archive. Files whose blob SHA matches the newest cached
+/// snapshot are copied from it rather than downloaded. An existing snapshot of
+/// the same commit is reused unless `force` is set.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn download_snapshot(
+    client: &Client,
+    source: &UpstreamSource,
+    cache_dir: &Path,
+    commit: &str,
+    commit_etag: Option<String>,
+    force: bool,
+    retry: RetryPolicy,
+    progress: ProgressFn<'_>,
+) -> Result<UpstreamSnapshot> {
+    let snapshot_dir = cache_dir.join(commit);
+    if !force {
+        // A commit's files never change, so its snapshot only needs a new date.
+        if let Some(snapshot) = revalidate_snapshot(&snapshot_dir, commit_etag.clone())? {
+            return Ok(snapshot);
+        }
+    }
+
+    let blobs = retry.run("tree listing", || fetch_asset_blobs(client, source, commit))?;
+    let previous = latest_snapshot_dir(cache_dir)
+        .filter(|dir| !force || dir != &snapshot_dir)
+        .and_then(|dir| {
+            let metadata = read_metadata(&dir)?;
+            let content_dir = super::find_content_dir(&dir).ok()?;
+            Some((content_dir, metadata.blobs))
+        });
+
+    let partial_dir = cache_dir.join(format!("{commit}.partial"));
+    if partial_dir.exists() {
+        fs::remove_dir_all(&partial_dir)
+            .with_context(|| format!("removing {}", partial_dir.display()))?;
+    }
+    let content_dir = partial_dir.join(format!("{}-{commit}", source.repo));
+    let total = blobs.len();
+    for (done, (path, sha)) in blobs.iter().enumerate() {
+        progress(SnapshotProgress::Fetching {
+            source: source.id.clone(),
+            done,
+            total,
+        });
+        let target = content_dir.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("creating directory {}", parent.display()))?;
+        }
+        let reusable = previous
+            .as_ref()
+            .and_then(|(dir, known)| (known.get(path) == Some(sha)).then(|| dir.join(path)));
+        if let Some(cached) = reusable.filter(|cached| cached.is_file()) {
+            fs::copy(&cached, &target).with_context(|| format!("copying {}", cached.display()))?;
+            continue;
+        }
+        let bytes = retry.run("file download", || fetch_file(client, source, commit, path))?;
+        fs::write(&target, bytes).with_context(|| format!("writing file {}", target.display()))?;
+    }
+    fs::create_dir_all(&content_dir)
+        .with_context(|| format!("creating snapshot directory {}", content_dir.display()))?;
+
+    let fetched_at = Utc::now();
+    let metadata = SnapshotMetadata {
+        commit: commit.to_string(),
+        fetched_at,
+        commit_etag,
+        archive_etag: None,
+        blobs,
+    };
+    write_metadata(&partial_dir, &metadata)?;
+    if snapshot_dir.exists() {
+        fs::remove_dir_all(&snapshot_dir)
+            .with_context(|| format!("removing old snapshot at {}", snapshot_dir.display()))?;
+    }
+    fs::rename(&partial_dir, &snapshot_dir)
+        .with_context(|| format!("moving snapshot into {}", snapshot_dir.display()))?;
+
+    Ok(UpstreamSnapshot {
+        commit: commit.to_string(),
+        fetched_at,
+        content_dir: snapshot_dir.join(format!("{}-{commit}", source.repo)),
+        warnings: Vec::new(),
+    })
+}
+
+/// Blob SHA of every file under the asset directories of `commit`.
+fn fetch_asset_blobs(
+    client: &Client,
+    source: &UpstreamSource,
+    commit: &str,
+) -> Result<BTreeMap<String, String>> {
+    let url = format!(
+        "{GITHUB_API}/repos/{owner}/{repo}/git/trees/{commit}?recursive=1",
+        owner = source.owner,
+        repo = source.repo,
+    );
+    let response = client
+        .get(url)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .context("listing upstream files")?;
+    let tree: TreeResponse = check_rate_limit(response)?
+        .error_for_status()
+        .context("GitHub tree request failed")?
+        .json()
+        .context("parsing tree response")?;
+    if tree.truncated {
+        bail!("the repository tree is too large to list; use the archive fetch strategy");
+    }
+    Ok(asset_blobs(tree.tree))
+}
+
+fn asset_blobs(entries: Vec<TreeEntry>) -> BTreeMap<String, String> {
+    entries
+        .into_iter()
+        .filter(|entry| entry.kind == "blob" && is_asset_path(&entry.path))
+        .map(|entry| (entry.path, entry.sha))
+        .collect()
+}
+
+fn is_asset_path(path: &str) -> bool {
+    path.split_once('/')
+        .is_some_and(|(dir, rest)| ASSET_DIRS.contains(&dir) && !rest.is_empty())
+}
+
+fn fetch_file(
+    client: &Client,
+    source: &UpstreamSource,
+    commit: &str,
+    path: &str,
+) -> Result<Vec<u8>> {
+    let mut url = Url::parse("https://raw.githubusercontent.com").expect("valid base URL");
+    url.path_segments_mut()
+        .expect("base URL has a path")
+        .extend([source.owner.as_str(), source.repo.as_str(), commit])
+        .extend(path.split('/'));
+    let response = client
+        .get(url)
+        .send()
+        .with_context(|| format!("downloading {path}"))?;
+    let bytes = check_rate_limit(response)?
+        .error_for_status()
+        .with_context(|| format!("GitHub request for {path} failed"))?
+        .bytes()
+        .with_context(|| format!("reading {path}"))?;
+    Ok(bytes.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_files_under_asset_directories() {
+        let entry = |path: &str, kind: &str| TreeEntry {
+            path: path.to_string(),
+            kind: kind.to_string(),
+            sha: format!("sha-{path}"),
+        };
+        let blobs = asset_blobs(vec![
+            entry("README.md", "blob"),
+            entry("prompts", "tree"),
+            entry("prompts/review.prompt.md", "blob"),
+            entry("collections/web.collection.yml", "blob"),
+            entry("mcp/github.mcp.json", "blob"),
+            entry("docs/prompts/guide.md", "blob"),
+            entry("promptsx/a.md", "blob"),
+        ]);
+        assert_eq!(
+            blobs.keys().map(String::as_str).collect::<Vec<_>>(),
+            [
+                "collections/web.collection.yml",
+                "mcp/github.mcp.json",
+                "prompts/review.prompt.md"
+            ]
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use anyhow::{Context, Result};
+
+use super::paths::RepoPaths;
+
+const VARIABLES_FILE: &str = "variables.toml";
+
+/// Values for `{{name}}` placeholders in applied files, from the
+/// repository's `variables.toml` with a sub-project's own file layered on
+/// top.
+pub fn load_variables(paths: &RepoPaths) -> Result<BTreeMap<String, String>> {
+    let mut variables = read_variables(&paths.workspace_dir.join(VARIABLES_FILE))?;
+    if paths.project.is_some() {
+        variables.extend(read_variables(&paths.variables)?);
+    }
+    Ok(variables)
+}
+
+fn read_variables(path: &Path) -> Result<BTreeMap<String, String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => {
+            toml::from_str(&content).with_context(|| format!("parsing {}", path.display()))
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(err) => Err(err).with_context(|| format!("reading {}", path.display())),
+    }
+}
+
+/// `content` with every `{{name}}` or `{{ name }}` placeholder of a known
+/// variable replaced by its value. Unknown placeholders are left alone, so
+/// upstream text that happens to use braces survives.
+pub fn substitute(content: &str, variables: &BTreeMap<String, String>) -> String {
+    let mut substituted = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim();
+        let end = start + 2 + len + 2;
+        match variables.get(name) {
+            Some(value) => {
+                substituted.push_str(&rest[..start]);
+                substituted.push_str(value);
+            }
+            None => substituted.push_str(&rest[..end]),
+        }
+        rest = &rest[end..];
+    }
+    substituted.push_str(rest);
+    substituted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_values_override_the_repository_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::project(dir.path(), "services/api");
+        fs::create_dir_all(paths.variables.parent().unwrap()).unwrap();
+        fs::write(
+            paths.workspace_dir.join(VARIABLES_FILE),
+            "org = \"acme\"\nproject_name = \"monorepo\"\n",
+        )
+        .unwrap();
+        fs::write(&paths.variables, "project_name = \"api\"\n").unwrap();
+        let variables = load_variables(&paths).unwrap();
+
+        assert_eq!(
+            substitute(
+                "{{project_name}} by {{ org }}: {{unknown}} {x} {{open",
+                &variables
+            ),
+            "api by acme: {{unknown}} {x} {{open"
+        );
+    }
+}
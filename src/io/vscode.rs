@@ -0,0 +1,267 @@
+use std::{fs, io, io::Write, path::Path};
+
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::{json, ser::PrettyFormatter, Value};
+
+use crate::domain::model::split_source;
+
+use super::{catalog, paths::RepoPaths, sync::LocalStatus, upstream::UpstreamRoots};
+
+type Settings = IndexMap<String, Value>;
+
+/// VS Code setting listing extra folders to discover chat modes in.
+pub const MODE_LOCATIONS: &str = "chat.modeFilesLocations";
+/// Where applied chat modes live, relative to the workspace folder.
+const CHAT_MODES_DIR: &str = ".github/chatmodes";
+/// Object in `.vscode/mcp.json` holding the server definitions by name.
+const MCP_SERVERS: &str = "servers";
+
+/// Register `.github/chatmodes` under `chat.modeFilesLocations` in
+/// `.vscode/settings.json` while any chat mode is enabled, and drop the entry
+/// once none are. Other settings keep their order. Returns whether the file
+/// changed.
+pub fn sync_chat_modes(paths: &RepoPaths, enabled: bool) -> Result<bool> {
+    let path = &paths.vscode_settings;
+    let hint = format!("add \"{MODE_LOCATIONS}\": {{ \"{CHAT_MODES_DIR}\": true }} by hand");
+    let before = match read_settings(path, &hint)? {
+        Some(settings) => settings,
+        None if !enabled => return Ok(false),
+        None => Settings::new(),
+    };
+    let mut settings = before.clone();
+    let locations = settings
+        .entry(MODE_LOCATIONS.to_string())
+        .or_insert_with(|| json!({}));
+    let Some(locations) = locations.as_object_mut() else {
+        bail!("{MODE_LOCATIONS} in {} is not an object", path.display());
+    };
+    if enabled {
+        locations.insert(CHAT_MODES_DIR.to_string(), Value::Bool(true));
+    } else {
+        locations.remove(CHAT_MODES_DIR);
+    }
+    if locations.is_empty() {
+        settings.shift_remove(MODE_LOCATIONS);
+    }
+    write_if_changed(path, &before, settings)
+}
+
+/// Name an MCP server asset is registered under in `.vscode/mcp.json`: its
+/// file name without `.mcp.json`, e.g. `github` for `mcp/github.mcp.json`.
+pub fn mcp_server_name(relative_path: &str) -> &str {
+    let (_, relative) = split_source(relative_path);
+    let file = relative.rsplit('/').next().unwrap_or(relative);
+    file.strip_suffix(".mcp.json").unwrap_or(file)
+}
+
+/// Whether `.vscode/mcp.json` registers the server matching upstream
+/// (`Same`), a different definition under its name (`Diff`), or nothing
+/// (`Missing`).
+pub fn mcp_server_status(
+    paths: &RepoPaths,
+    upstream: &UpstreamRoots,
+    relative_path: &str,
+) -> Result<LocalStatus> {
+    let Some(registered) = registered_mcp_server(paths, relative_path)? else {
+        return Ok(LocalStatus::Missing);
+    };
+    let asset = catalog::read_mcp_server(&upstream.resolve(relative_path)?)?;
+    Ok(if registered == Value::Object(asset.server) {
+        LocalStatus::Same
+    } else {
+        LocalStatus::Diff
+    })
+}
+
+/// Whether `.vscode/mcp.json` has an entry under the asset's server name.
+/// Unreadable files count as not having one.
+pub fn has_mcp_server(paths: &RepoPaths, relative_path: &str) -> bool {
+    matches!(registered_mcp_server(paths, relative_path), Ok(Some(_)))
+}
+
+fn registered_mcp_server(paths: &RepoPaths, relative_path: &str) -> Result<Option<Value>> {
+    let hint = "fix the file or edit the server by hand";
+    let Some(config) = read_settings(&paths.vscode_mcp, hint)? else {
+        return Ok(None);
+    };
+    Ok(config
+        .get(MCP_SERVERS)
+        .and_then(|servers| servers.get(mcp_server_name(relative_path)))
+        .cloned())
+}
+
+/// Register the upstream definition of the MCP server at `relative_path` in
+/// `.vscode/mcp.json`, replacing an entry with the same name and keeping
+/// every other server. Returns whether the file changed.
+pub fn install_mcp_server(
+    paths: &RepoPaths,
+    upstream: &UpstreamRoots,
+    relative_path: &str,
+) -> Result<bool> {
+    let asset = catalog::read_mcp_server(&upstream.resolve(relative_path)?)?;
+    let path = &paths.vscode_mcp;
+    let name = mcp_server_name(relative_path);
+    let hint = format!("add the \"{name}\" server by hand");
+    let before = read_settings(path, &hint)?.unwrap_or_default();
+    let mut config = before.clone();
+    let servers = config
+        .entry(MCP_SERVERS.to_string())
+        .or_insert_with(|| json!({}));
+    let Some(servers) = servers.as_object_mut() else {
+        bail!("{MCP_SERVERS} in {} is not an object", path.display());
+    };
+    servers.insert(name.to_string(), Value::Object(asset.server));
+    write_if_changed(path, &before, config)
+}
+
+/// Drop the MCP server at `relative_path` from `.vscode/mcp.json`. Returns
+/// whether it was registered.
+pub fn remove_mcp_server(paths: &RepoPaths, relative_path: &str) -> Result<bool> {
+    let path = &paths.vscode_mcp;
+    let name = mcp_server_name(relative_path);
+    let hint = format!("remove the \"{name}\" server by hand");
+    let Some(before) = read_settings(path, &hint)? else {
+        return Ok(false);
+    };
+    let mut config = before.clone();
+    let removed = config
+        .get_mut(MCP_SERVERS)
+        .and_then(Value::as_object_mut)
+        .and_then(|servers| servers.remove(name))
+        .is_some();
+    if removed {
+        write_if_changed(path, &before, config)?;
+    }
+    Ok(removed)
+}
+
+/// Read a VS Code JSON file keeping its key order; `None` if it doesn't exist.
+/// `hint` tells the user what to do by hand when the file has comments.
+fn read_settings(path: &Path, hint: &str) -> Result<Option<Settings>> {
+    match fs::read_to_string(path) {
+        Ok(content) if content.trim().is_empty() => Ok(Some(Settings::new())),
+        Ok(content) => serde_json::from_str(&content).map(Some).with_context(|| {
+            format!(
+                "{} is not plain JSON (comments are not supported); {hint}",
+                path.display()
+            )
+        }),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("reading {}", path.display())),
+    }
+}
+
+fn write_if_changed(path: &Path, before: &Settings, settings: Settings) -> Result<bool> {
+    if &settings == before {
+        return Ok(false);
+    }
+
+    // VS Code writes settings with four-space indentation.
+    let mut rendered = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(
+        &mut rendered,
+        PrettyFormatter::with_indent(b"    "),
+    );
+    settings
+        .serialize(&mut serializer)
+        .context("serializing VS Code settings")?;
+    rendered.push(b'\n');
+    let dir = path.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    let mut temp = tempfile::NamedTempFile::new_in(dir)
+        .context("Failed to create temporary file for VS Code settings")?;
+    temp.write_all(&rendered)
+        .context("Failed to write VS Code settings")?;
+    temp.persist(path)
+        .with_context(|| format!("Failed to persist {}", path.display()))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_and_removes_chat_mode_location_keeping_other_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path());
+        assert!(!sync_chat_modes(&paths, false).unwrap());
+        assert!(!paths.vscode_settings.exists());
+
+        fs::create_dir_all(paths.vscode_settings.parent().unwrap()).unwrap();
+        fs::write(
+            &paths.vscode_settings,
+            r#"{"editor.tabSize": 2, "chat.modeFilesLocations": {"docs/modes": true}, "a.b": 1}"#,
+        )
+        .unwrap();
+        assert!(sync_chat_modes(&paths, true).unwrap());
+        assert!(!sync_chat_modes(&paths, true).unwrap());
+        let settings: IndexMap<String, Value> =
+            serde_json::from_str(&fs::read_to_string(&paths.vscode_settings).unwrap()).unwrap();
+        assert_eq!(
+            settings.keys().collect::<Vec<_>>(),
+            ["editor.tabSize", MODE_LOCATIONS, "a.b"]
+        );
+        assert_eq!(
+            settings[MODE_LOCATIONS],
+            json!({ "docs/modes": true, ".github/chatmodes": true })
+        );
+
+        assert!(sync_chat_modes(&paths, false).unwrap());
+        let settings: Value =
+            serde_json::from_str(&fs::read_to_string(&paths.vscode_settings).unwrap()).unwrap();
+        assert_eq!(settings[MODE_LOCATIONS], json!({ "docs/modes": true }));
+
+        fs::write(&paths.vscode_settings, "{\n  // comment\n}").unwrap();
+        assert!(sync_chat_modes(&paths, true).is_err());
+    }
+
+    #[test]
+    fn installs_and_removes_mcp_servers_alongside_hand_written_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path().join("repo"));
+        let content = dir.path().join("upstream");
+        fs::create_dir_all(content.join("mcp")).unwrap();
+        fs::write(
+            content.join("mcp/github.mcp.json"),
+            r#"{"name": "GitHub", "server": {"type": "http", "url": "https://example.com/mcp"}}"#,
+        )
+        .unwrap();
+        let mut upstream = UpstreamRoots::default();
+        upstream.insert(crate::domain::model::PRIMARY_SOURCE, content);
+        let asset = "mcp/github.mcp.json";
+        assert_eq!(mcp_server_name(asset), "github");
+        assert_eq!(
+            mcp_server_status(&paths, &upstream, asset).unwrap(),
+            LocalStatus::Missing
+        );
+
+        fs::create_dir_all(&paths.vscode_dir).unwrap();
+        fs::write(
+            &paths.vscode_mcp,
+            r#"{"servers": {"mine": {"command": "mine"}}, "inputs": []}"#,
+        )
+        .unwrap();
+        assert!(install_mcp_server(&paths, &upstream, asset).unwrap());
+        assert!(!install_mcp_server(&paths, &upstream, asset).unwrap());
+        assert_eq!(
+            mcp_server_status(&paths, &upstream, asset).unwrap(),
+            LocalStatus::Same
+        );
+        let config: Settings =
+            serde_json::from_str(&fs::read_to_string(&paths.vscode_mcp).unwrap()).unwrap();
+        assert_eq!(config.keys().collect::<Vec<_>>(), [MCP_SERVERS, "inputs"]);
+        assert_eq!(
+            config[MCP_SERVERS]["github"],
+            json!({"type": "http", "url": "https://example.com/mcp"})
+        );
+
+        assert!(remove_mcp_server(&paths, asset).unwrap());
+        assert!(!has_mcp_server(&paths, asset));
+        let config: Value =
+            serde_json::from_str(&fs::read_to_string(&paths.vscode_mcp).unwrap()).unwrap();
+        assert_eq!(config[MCP_SERVERS], json!({"mine": {"command": "mine"}}));
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    path::Path,
+    sync::mpsc::{self, Receiver},
+};
+
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::paths::RepoPaths;
+
+/// What an external edit touched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalChange {
+    /// Files under `.github/`.
+    LocalFiles,
+    /// The enablement file (JSON or YAML).
+    Enablement,
+}
+
+/// Watches `.github/` and the enablement file for edits made outside the TUI
+/// (editors, `git checkout`, …).
+pub struct RepoWatcher {
+    // Dropping the watcher stops it, so keep it alive alongside the receiver.
+    _watcher: RecommendedWatcher,
+    receiver: Receiver<notify::Result<Event>>,
+    paths: RepoPaths,
+}
+
+impl RepoWatcher {
+    pub fn spawn(paths: &RepoPaths) -> Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender).context("creating file watcher")?;
+        watcher
+            .watch(&paths.github_dir, RecursiveMode::Recursive)
+            .with_context(|| format!("watching {}", paths.github_dir.display()))?;
+        // The enablement file is replaced atomically on save, so watch its
+        // directory rather than the file itself. A sub-project's may not
+        // exist before its first save.
+        let enablement_dir = paths.enablement.parent().unwrap_or(&paths.workspace_dir);
+        std::fs::create_dir_all(enablement_dir)
+            .with_context(|| format!("creating {}", enablement_dir.display()))?;
+        watcher
+            .watch(enablement_dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("watching {}", enablement_dir.display()))?;
+        Ok(Self {
+            _watcher: watcher,
+            receiver,
+            paths: paths.clone(),
+        })
+    }
+
+    /// Drain pending events into the set of areas that changed.
+    pub fn poll(&self) -> Vec<ExternalChange> {
+        let mut changes = Vec::new();
+        while let Ok(event) = self.receiver.try_recv() {
+            let Ok(event) = event else {
+                continue;
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            for path in &event.paths {
+                if let Some(change) = self.classify(path) {
+                    if !changes.contains(&change) {
+                        changes.push(change);
+                    }
+                }
+            }
+        }
+        changes
+    }
+
+    fn classify(&self, path: &Path) -> Option<ExternalChange> {
+        if path == self.paths.enablement || path == self.paths.enablement_yaml {
+            Some(ExternalChange::Enablement)
+        } else if path.starts_with(&self.paths.github_dir) {
+            Some(ExternalChange::LocalFiles)
+        } else {
+            None
+        }
+    }
+}
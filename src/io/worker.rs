@@ -0,0 +1,281 @@
+use std::{
+    collections::HashMap,
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    thread,
+};
+
+use anyhow::{anyhow, Result};
+use rayon::prelude::*;
+
+use crate::domain::model::AssetKind;
+
+use super::{
+    content_index::ContentIndex,
+    loader::{self, LoadedState},
+    manifest,
+    paths::RepoPaths,
+    sync::{self, ApplyMode, ApplyOutcome, LocalStatus, SyncSummary},
+    upstream::{FetchOptions, SnapshotProgress, UpstreamRoots, UpstreamSource},
+};
+
+/// Work the UI hands off so it never blocks on disk or network.
+pub enum Job {
+    /// Download snapshots and load the catalog and enablement file.
+    Load {
+        sources: Vec<UpstreamSource>,
+        fetch: FetchOptions,
+    },
+    /// Hash the local copy of `assets`. Answers carry `generation` so the UI
+    /// can drop results that a later request has superseded.
+    Statuses {
+        generation: u64,
+        upstream: UpstreamRoots,
+        assets: Vec<(AssetKind, String)>,
+    },
+    /// Apply or remove local files, in order.
+    Sync {
+        upstream: UpstreamRoots,
+        ops: Vec<FileOp>,
+        mode: ApplyMode,
+        /// Report the outcome in the footer and stage the files.
+        announce: bool,
+    },
+    /// Bring every listed asset in line with upstream, skipping matches.
+    ApplyAll {
+        upstream: UpstreamRoots,
+        assets: Vec<(AssetKind, String)>,
+        mode: ApplyMode,
+    },
+    /// Load or build the full-text index of the catalog's files.
+    Index {
+        upstream: UpstreamRoots,
+        paths: Vec<String>,
+    },
+}
+
+/// One file change of a `Job::Sync`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileOp {
+    Apply(AssetKind, String),
+    Remove(AssetKind, String),
+}
+
+impl FileOp {
+    /// Apply when the asset is effectively enabled, remove otherwise.
+    pub fn for_state(kind: AssetKind, path: String, enabled: bool) -> Self {
+        if enabled {
+            FileOp::Apply(kind, path)
+        } else {
+            FileOp::Remove(kind, path)
+        }
+    }
+
+    /// The asset the change is for.
+    pub fn asset(&self) -> (AssetKind, &str) {
+        match self {
+            FileOp::Apply(kind, path) | FileOp::Remove(kind, path) => (*kind, path),
+        }
+    }
+}
+
+pub enum WorkerEvent {
+    Progress(SnapshotProgress),
+    Loaded(Box<Result<LoadedState>>),
+    Statuses {
+        generation: u64,
+        statuses: Result<HashMap<String, LocalStatus>>,
+    },
+    /// Outcome per applied asset path; removals are not listed.
+    Synced {
+        outcomes: Result<Vec<(String, ApplyOutcome)>>,
+        announce: bool,
+    },
+    AppliedAll(Result<SyncSummary>),
+    Indexed(Result<ContentIndex>),
+}
+
+/// A background thread running jobs one at a time, in the order they were
+/// sent, so file writes and the hashing that follows them never interleave.
+pub struct Worker {
+    jobs: Sender<Job>,
+    events: Receiver<WorkerEvent>,
+}
+
+impl Worker {
+    pub fn spawn(paths: RepoPaths) -> Self {
+        let (jobs, job_receiver) = mpsc::channel::<Job>();
+        let (sender, events) = mpsc::channel();
+        thread::spawn(move || {
+            // Ends once the UI drops its `Worker`.
+            for job in job_receiver {
+                let event = run_job(&paths, job, &sender);
+                if sender.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { jobs, events }
+    }
+
+    pub fn send(&self, job: Job) -> Result<()> {
+        self.jobs
+            .send(job)
+            .map_err(|_| anyhow!("background worker stopped unexpectedly"))
+    }
+
+    /// Events that arrived since the last call.
+    pub fn poll(&self) -> Result<Vec<WorkerEvent>> {
+        let mut events = Vec::new();
+        loop {
+            match self.events.try_recv() {
+                Ok(event) => events.push(event),
+                Err(TryRecvError::Empty) => return Ok(events),
+                Err(TryRecvError::Disconnected) => {
+                    return Err(anyhow!("background worker stopped unexpectedly"))
+                }
+            }
+        }
+    }
+}
+
+fn run_job(paths: &RepoPaths, job: Job, sender: &Sender<WorkerEvent>) -> WorkerEvent {
+    match job {
+        Job::Load { sources, fetch } => {
+            let report = |progress| {
+                let _ = sender.send(WorkerEvent::Progress(progress));
+            };
+            let result = loader::load_state(paths, &sources, fetch, &report);
+            WorkerEvent::Loaded(Box::new(result))
+        }
+        Job::Statuses {
+            generation,
+            upstream,
+            assets,
+        } => WorkerEvent::Statuses {
+            generation,
+            statuses: local_statuses(paths, &upstream, &assets),
+        },
+        Job::Sync {
+            upstream,
+            ops,
+            mode,
+            announce,
+        } => WorkerEvent::Synced {
+            outcomes: sync_files(paths, &upstream, ops, mode),
+            announce,
+        },
+        Job::ApplyAll {
+            upstream,
+            assets,
+            mode,
+        } => WorkerEvent::AppliedAll(manifest::with_manifest(paths, |manifest| {
+            sync::sync_assets(
+                paths,
+                &upstream,
+                manifest,
+                assets.iter().map(|(kind, path)| (*kind, path.as_str())),
+                mode,
+            )
+        })),
+        Job::Index { upstream, paths } => WorkerEvent::Indexed(ContentIndex::load_or_build(
+            &upstream,
+            paths.iter().map(String::as_str),
+        )),
+    }
+}
+
+/// Local status of every asset, keyed by path, hashed in parallel.
+pub fn local_statuses(
+    paths: &RepoPaths,
+    upstream: &UpstreamRoots,
+    assets: &[(AssetKind, String)],
+) -> Result<HashMap<String, LocalStatus>> {
+    let manifest = manifest::load_manifest(paths)?;
+    assets
+        .par_iter()
+        .map(|(kind, path)| {
+            let status = sync::compute_local_status(paths, upstream, &manifest, *kind, path)?;
+            Ok((path.clone(), status))
+        })
+        .collect()
+}
+
+fn sync_files(
+    paths: &RepoPaths,
+    upstream: &UpstreamRoots,
+    ops: Vec<FileOp>,
+    mode: ApplyMode,
+) -> Result<Vec<(String, ApplyOutcome)>> {
+    manifest::with_manifest(paths, |manifest| {
+        let mut outcomes = Vec::new();
+        for op in ops {
+            match op {
+                FileOp::Apply(kind, path) => {
+                    let outcome =
+                        sync::apply_from_upstream(paths, upstream, manifest, kind, &path, mode)?;
+                    outcomes.push((path, outcome));
+                }
+                FileOp::Remove(kind, path) => {
+                    sync::remove_local(paths, manifest, kind, &path)?;
+                }
+            }
+        }
+        Ok(outcomes)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, time::Duration};
+
+    use super::*;
+    use crate::domain::model::PRIMARY_SOURCE;
+
+    #[test]
+    fn runs_jobs_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = RepoPaths::new(dir.path().join("repo"));
+        let content = dir.path().join("upstream");
+        fs::create_dir_all(content.join("prompts")).unwrap();
+        fs::write(content.join("prompts/a.prompt.md"), "# A\n").unwrap();
+        let mut upstream = UpstreamRoots::default();
+        upstream.insert(PRIMARY_SOURCE, content);
+        let asset = (AssetKind::Prompt, "prompts/a.prompt.md".to_string());
+
+        let worker = Worker::spawn(paths.clone());
+        let statuses = |generation| Job::Statuses {
+            generation,
+            upstream: upstream.clone(),
+            assets: vec![asset.clone()],
+        };
+        worker.send(statuses(1)).unwrap();
+        worker
+            .send(Job::Sync {
+                upstream: upstream.clone(),
+                ops: vec![FileOp::Apply(asset.0, asset.1.clone())],
+                mode: ApplyMode::Overwrite,
+                announce: true,
+            })
+            .unwrap();
+        worker.send(statuses(2)).unwrap();
+
+        let mut events = Vec::new();
+        while events.len() < 3 {
+            events.extend(worker.poll().unwrap());
+            thread::sleep(Duration::from_millis(10));
+        }
+        let status = |event: &WorkerEvent| match event {
+            WorkerEvent::Statuses { statuses, .. } => {
+                statuses.as_ref().unwrap()["prompts/a.prompt.md"]
+            }
+            _ => panic!("expected statuses"),
+        };
+        assert_eq!(status(&events[0]), LocalStatus::Missing);
+        assert!(matches!(
+            &events[1],
+            WorkerEvent::Synced { outcomes: Ok(outcomes), announce: true }
+                if outcomes[0].1 == ApplyOutcome::Written
+        ));
+        assert_eq!(status(&events[2]), LocalStatus::Same);
+    }
+}
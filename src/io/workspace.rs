@@ -0,0 +1,71 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::Write,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{model::AssetKind, state::SortMode};
+
+use super::paths::RepoPaths;
+
+/// Per-repository settings persisted in `.awesome-copilot-tui/workspace.json`.
+/// Unlike the enablement file this is not schema-validated, so new fields only
+/// need a serde default.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WorkspaceState {
+    /// Commit SHA or tag the official snapshot is pinned to.
+    pub pinned_ref: Option<String>,
+    /// Ordering of the asset table.
+    pub sort: SortMode,
+    /// Width of the asset table in percent; the details pane gets the rest.
+    pub table_width: Option<u16>,
+    /// Paths of starred assets, shown with `*` and listed first by the
+    /// favorites sort.
+    pub favorites: BTreeSet<String>,
+    /// Unmanaged files under `.github/`, relative to the repository root,
+    /// that are neither listed as unmanaged nor pruned.
+    pub ignored_files: BTreeSet<String>,
+    /// Where the TUI was when it last quit.
+    pub session: SessionState,
+}
+
+/// The view to resume on the next launch, saved on quit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SessionState {
+    pub tab: Option<AssetKind>,
+    /// Path of the selected asset on each tab that had one.
+    pub selected: BTreeMap<AssetKind, String>,
+    /// The applied search query, if any.
+    pub query: String,
+}
+
+pub fn load_workspace(paths: &RepoPaths) -> Result<WorkspaceState> {
+    match fs::read_to_string(&paths.workspace_state) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| format!("parsing {}", paths.workspace_state.display())),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(WorkspaceState::default()),
+        Err(err) => {
+            Err(err).with_context(|| format!("reading {}", paths.workspace_state.display()))
+        }
+    }
+}
+
+pub fn save_workspace(paths: &RepoPaths, state: &WorkspaceState) -> Result<()> {
+    fs::create_dir_all(&paths.workspace_dir)
+        .with_context(|| format!("creating {}", paths.workspace_dir.display()))?;
+    let json =
+        serde_json::to_string_pretty(state).context("Failed to serialize workspace state")?;
+    let mut temp = tempfile::NamedTempFile::new_in(&paths.workspace_dir)
+        .context("Failed to create temporary file for workspace write")?;
+    temp.write_all(json.as_bytes())
+        .context("Failed to write workspace JSON")?;
+    temp.write_all(b"\n").ok();
+    temp.persist(&paths.workspace_state)
+        .context("Failed to persist workspace file")?;
+    Ok(())
+}
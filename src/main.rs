@@ -3,8 +3,20 @@ mod domain;
 mod io;
 mod ui;
 
-use anyhow::Result;
+use std::process::ExitCode;
 
-fn main() -> Result<()> {
-    app::run()
+use io::check::CheckFailed;
+
+fn main() -> ExitCode {
+    match app::run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err:?}");
+            // `check` tells its failure classes apart for CI.
+            let code = err
+                .downcast_ref::<CheckFailed>()
+                .map_or(1, |failed| failed.failure.exit_code());
+            ExitCode::from(code)
+        }
+    }
 }
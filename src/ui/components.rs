@@ -1,17 +1,29 @@
 use crate::domain::{
     model::AssetKind,
     state::AssetView,
+    toggle::{analyze_collection_toggle_impact, MemberToggleImpact},
 };
 use crate::io::sync::LocalStatus;
 
 pub struct UiState;
 
-pub fn state_badge(asset: &AssetView) -> String {
-    if asset.effective {
+/// Short state label for the table. `ascii` spells the source out instead
+/// of using a symbol.
+pub fn state_badge(asset: &AssetView, ascii: bool) -> String {
+    if ascii {
+        return ascii_badge(asset).to_string();
+    }
+    if asset.is_partial() {
+        "◐ Partial".to_string()
+    } else if asset.is_mixed() {
+        "⚠ Mixed".to_string()
+    } else if asset.effective {
         if asset.explicit == Some(true) {
             "✓ On".to_string()
         } else if asset.inherited.is_some() {
             "↳ On".to_string()
+        } else if asset.rule.is_some() {
+            "⚙ On".to_string()
         } else {
             "• On".to_string()
         }
@@ -20,12 +32,32 @@ pub fn state_badge(asset: &AssetView) -> String {
             "✗ Off".to_string()
         } else if asset.inherited.is_some() {
             "↳ Off".to_string()
+        } else if asset.rule.is_some() {
+            "⚙ Off".to_string()
         } else {
             "• Off".to_string()
         }
     }
 }
 
+fn ascii_badge(asset: &AssetView) -> &'static str {
+    if asset.is_partial() {
+        "partial"
+    } else if asset.is_mixed() {
+        "MIXED"
+    } else if asset.explicit == Some(asset.effective) {
+        if asset.effective { "ON" } else { "OFF" }
+    } else if asset.inherited.is_some() {
+        if asset.effective { "on(inh)" } else { "off(inh)" }
+    } else if asset.rule.is_some() {
+        if asset.effective { "on(rule)" } else { "off(rule)" }
+    } else if asset.effective {
+        "on"
+    } else {
+        "off"
+    }
+}
+
 pub fn tags_field(asset: &AssetView) -> String {
     match asset.kind {
         AssetKind::Instruction => {
@@ -37,7 +69,10 @@ pub fn tags_field(asset: &AssetView) -> String {
                 String::new()
             }
         }
-        AssetKind::Collection => format!("{} items", asset.member_count),
+        AssetKind::Collection => format!(
+            "enabled {}/{}",
+            asset.enabled_members, asset.member_count
+        ),
         _ => {
             if !asset.tags.is_empty() {
                 asset.tags.join(", ")
@@ -63,6 +98,9 @@ pub fn status_line(asset: &AssetView) -> String {
             inherited.value, inherited.collection.id
         ));
     }
+    if let Some(rule) = &asset.rule {
+        parts.push(format!("Rule: {} for {}", rule.value, rule.rule));
+    }
     parts.join(" | ")
 }
 
@@ -86,6 +124,8 @@ pub fn toggle_preview(asset: &AssetView) -> String {
         "explicit"
     } else if asset.inherited.is_some() {
         "inherited"
+    } else if asset.rule.is_some() {
+        "rule"
     } else {
         "default"
     };
@@ -107,72 +147,65 @@ pub fn collection_toggle_impact(asset: &AssetView, domain_state: &crate::domain:
     if asset.kind != crate::domain::model::AssetKind::Collection {
         return None;
     }
-    
-    // Find the collection to analyze its members
-    let collection = domain_state.catalog.collections.iter()
-        .find(|c| c.path == asset.path)?;
-    
-    let will_enable = !asset.effective;
-    let mut impact_lines = Vec::new();
-    let mut enable_count = 0;
-    let mut disable_count = 0;
-    let mut unchanged_count = 0;
-    
+    let impact = analyze_collection_toggle_impact(domain_state, &asset.path).ok()?;
+
     // Show up to 5 members with their state changes
-    for item in collection.items.iter().take(5) {
-        let member_assets = domain_state.assets(item.kind);
-        if let Some(member) = member_assets.iter().find(|a| a.path == item.path) {
-            let current_effective = member.effective;
-            let member_explicit = member.explicit;
-            
-            // Determine what the member's new effective state would be
-            let new_effective = if member_explicit.is_some() {
-                // If member has explicit setting, it won't change
-                current_effective
-            } else {
-                // Member will inherit the new collection state
-                will_enable
+    let impact_lines: Vec<_> = impact.affected_members.iter().take(5)
+        .map(|member| {
+            let change_desc = match member.impact {
+                MemberToggleImpact::WillEnable => "will enable",
+                MemberToggleImpact::WillDisable => "will disable",
+                MemberToggleImpact::Unchanged => "no change",
             };
-            
-            let change_desc = if current_effective == new_effective {
-                unchanged_count += 1;
-                "no change"
-            } else if new_effective {
-                enable_count += 1;
-                "will enable"
-            } else {
-                disable_count += 1;
-                "will disable"
-            };
-            
-            impact_lines.push(format!("  • {} ({})", member.name, change_desc));
-        }
-    }
-    
-    let total_members = collection.items.len();
+            format!("  • {} ({})", member.name, change_desc)
+        })
+        .collect();
+
+    let total_members = impact.total_members;
     let mut summary = format!("Impact on {} member{}:", 
         total_members, if total_members == 1 { "" } else { "s" });
     
     if total_members > 5 {
-        summary.push_str(&format!(" (showing first 5)"));
+        summary.push_str(" (showing first 5)");
     }
     
     summary.push('\n');
     summary.push_str(&impact_lines.join("\n"));
     
-    if enable_count > 0 || disable_count > 0 || unchanged_count > 0 {
+    if !impact.affected_members.is_empty() {
         summary.push_str(&format!("\nSummary: {} enable, {} disable, {} unchanged", 
-            enable_count, disable_count, unchanged_count));
+            impact.enable_count, impact.disable_count, impact.unchanged_count));
+    }
+    if !impact.dependencies.is_empty() {
+        summary.push_str(&format!("\nAlso enables {} requirement{}",
+            impact.dependencies.len(), if impact.dependencies.len() == 1 { "" } else { "s" }));
     }
     
     Some(summary)
 }
 
 pub fn local_status(asset: &AssetView) -> String {
-    match asset.local {
-        LocalStatus::Missing => "Missing".into(),
-        LocalStatus::Same => "Same".into(),
-        LocalStatus::Diff => "Diff".into(),
-        LocalStatus::NA => "N/A".into(),
+    local_status_label(asset.local).into()
+}
+
+pub fn local_status_label(status: LocalStatus) -> &'static str {
+    match status {
+        LocalStatus::Missing => "Missing",
+        LocalStatus::Same => "Same",
+        LocalStatus::Diff => "Diff",
+        LocalStatus::Update => "Update",
+        LocalStatus::Unmanaged => "Unmanaged",
+        LocalStatus::NA => "N/A",
+    }
+}
+
+/// `5m`, `3h` or `2d`.
+pub fn format_age(age: chrono::Duration) -> String {
+    if age.num_hours() >= 48 {
+        format!("{}d", age.num_days())
+    } else if age.num_minutes() >= 60 {
+        format!("{}h", age.num_hours())
+    } else {
+        format!("{}m", age.num_minutes().max(0))
     }
 }
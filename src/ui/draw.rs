@@ -1,29 +1,74 @@
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Tabs, Wrap},
+    widgets::{
+        Block, Borders, Cell, Clear, Gauge, List, ListItem, ListState, Paragraph, Row, Table,
+        TableState, Tabs, Wrap,
+    },
     Frame,
 };
 
 use crate::{
-    app::{App, PendingPrompt},
-    domain::model::AssetKind,
+    app::{apply_mode_label, App, PendingPrompt},
+    domain::{
+        model::AssetKind,
+        state::{AssetView, StateFilter},
+    },
+    io::{paths::Target, sync::LocalStatus},
 };
 
-use super::{components, input};
+use super::{components, input, markdown, overlay, theme::Theme};
 
 pub fn render(frame: &mut Frame<'_>, app: &App) {
+    render_screen(frame, app);
+    app.theme().degrade(frame.buffer_mut());
+}
+
+fn render_screen(frame: &mut Frame<'_>, app: &App) {
     let size = frame.size();
     if size.width < 50 || size.height < 20 {
         frame.render_widget(
             Paragraph::new("Terminal too small for UI (min 50x20)")
-                .style(Style::default().fg(Color::Red)),
+                .style(app.theme().fg(app.theme().error)),
             size,
         );
         return;
     }
 
+    let layout = screen_layout(size, app.table_width(), app.facets().is_open());
+
+    render_header(frame, layout.header, app);
+    render_tabs(frame, layout.tabs, app);
+    match app.overlay() {
+        Some(active) => overlay::render(frame, layout.body, app, active),
+        None => render_body(frame, &layout, app),
+    }
+    render_footer(frame, layout.footer, app);
+
+    if app.is_loading() {
+        render_loading(frame, layout.body, app);
+    }
+}
+
+/// Screen regions, shared with mouse hit-testing.
+pub struct ScreenLayout {
+    pub header: Rect,
+    pub tabs: Rect,
+    pub body: Rect,
+    /// The tag panel; empty while it is hidden.
+    pub facets: Rect,
+    pub table: Rect,
+    pub detail: Rect,
+    pub footer: Rect,
+}
+
+/// Width of the tag panel, borders included.
+const FACETS_WIDTH: u16 = 28;
+
+/// Regions for a terminal of `size`, with the table taking `table_width`
+/// percent of the body left of the tag panel, if shown.
+pub fn screen_layout(size: Rect, table_width: u16, facets: bool) -> ScreenLayout {
     let layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -33,117 +78,329 @@ pub fn render(frame: &mut Frame<'_>, app: &App) {
             Constraint::Length(2),
         ])
         .split(size);
+    let sidebar = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length(if facets { FACETS_WIDTH } else { 0 }),
+            Constraint::Min(0),
+        ])
+        .split(layout[2]);
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(table_width),
+            Constraint::Percentage(100 - table_width),
+        ])
+        .split(sidebar[1]);
+    ScreenLayout {
+        header: layout[0],
+        tabs: layout[1],
+        body: layout[2],
+        facets: sidebar[0],
+        table: body[0],
+        detail: body[1],
+        footer: layout[3],
+    }
+}
+
+/// The tab whose title is drawn at `column`.
+pub fn tab_at(area: Rect, app: &App, column: u16) -> Option<AssetKind> {
+    // Titles are padded by one cell on each side and separated by a divider.
+    let mut x = area.x + 1;
+    for kind in TABS {
+        let width = tab_label(app, kind).chars().count() as u16 + 2;
+        if column >= x && column < x + width {
+            return Some(kind);
+        }
+        x += width + 1;
+    }
+    None
+}
+
+/// Index of the table row drawn at `row`, given the selected index the table
+/// scrolls to keep visible.
+pub fn table_row_at(area: Rect, selected: usize, row: u16) -> Option<usize> {
+    // Border and header take the first two lines, the bottom border the last.
+    let first = area.y + 2;
+    let visible = area.height.saturating_sub(3) as usize;
+    if row < first || visible == 0 || row >= first + visible as u16 {
+        return None;
+    }
+    let offset = selected.saturating_sub(visible - 1);
+    Some(offset + (row - first) as usize)
+}
+
+/// Index of the tag panel row drawn at `row`, scrolled like the table.
+pub fn facet_row_at(area: Rect, selected: usize, row: u16) -> Option<usize> {
+    let first = area.y + 1;
+    let visible = area.height.saturating_sub(2) as usize;
+    if row < first || visible == 0 || row >= first + visible as u16 {
+        return None;
+    }
+    let offset = selected.saturating_sub(visible - 1);
+    Some(offset + (row - first) as usize)
+}
+
+fn render_loading(frame: &mut Frame<'_>, area: Rect, app: &App) {
+    let popup = centered_rect(area, 60, 3);
+    let (label, ratio) = match app.load_progress() {
+        Some(progress) => (progress.to_string(), progress.ratio().unwrap_or(0.0)),
+        None => ("Loading catalog…".to_string(), 0.0),
+    };
+    let gauge = Gauge::default()
+        .block(Block::default().borders(Borders::ALL).title("Upstream"))
+        .gauge_style(app.theme().fg(app.theme().accent))
+        .ratio(ratio)
+        .label(label);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(gauge, popup);
+}
 
-    render_header(frame, layout[0], app);
-    render_tabs(frame, layout[1], app);
-    render_body(frame, layout[2], app);
-    render_footer(frame, layout[3], app);
+/// A rectangle of `percent_x` width and `height` rows centered in `area`.
+fn centered_rect(area: Rect, percent_x: u16, height: u16) -> Rect {
+    let width = area.width * percent_x / 100;
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
 }
 
 fn render_header(frame: &mut Frame<'_>, area: Rect, app: &App) {
-    let repo = app.repo_root().display().to_string();
-    let dirty = if app.dirty() {
-        Span::styled(
-            "DIRTY",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )
+    let theme = app.theme();
+    let paths = app.paths();
+    let repo = match paths.target {
+        Target::Repo => app.repo_root().display().to_string(),
+        Target::User => format!("User profile {}", paths.vscode_dir.display()),
+    };
+    let dirty = if app.dirty() && app.auto_save() {
+        Span::styled("saving…", theme.fg(theme.warning))
+    } else if app.dirty() {
+        Span::styled("DIRTY", theme.bold(theme.warning))
     } else {
         Span::raw("clean")
     };
     let filter = app.search_state();
     let filter_text = if filter.query().is_empty() {
         "(none)".to_string()
+    } else if filter.full_text() {
+        format!("{} (full text)", filter.query())
     } else {
         filter.query().to_string()
     };
-    let line = Line::from(vec![
-        Span::styled(repo, Style::default().add_modifier(Modifier::BOLD)),
+    let mut spans = vec![Span::styled(
+        repo,
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    if !app.projects().is_empty() {
+        spans.push(Span::raw(" | Project:"));
+        spans.push(Span::styled(
+            paths.project.as_deref().unwrap_or("(root)").to_string(),
+            theme.fg(theme.accent),
+        ));
+    }
+    spans.extend([
         Span::raw(" | Tab:"),
-        Span::styled(tab_title(app.tab()), Style::default().fg(Color::Cyan)),
+        Span::styled(tab_title(app.tab()), theme.fg(theme.accent)),
         Span::raw(" | "),
         dirty,
         Span::raw(" | Filter:"),
         Span::raw(filter_text),
+    ]);
+    let tags = app.facets().chosen(app.tab());
+    if !tags.is_empty() {
+        spans.push(Span::raw(" | Tags:"));
+        spans.push(Span::styled(tags.join("+"), theme.fg(theme.accent)));
+    }
+    if app.state_filter() != StateFilter::All {
+        spans.push(Span::raw(" | Showing:"));
+        spans.push(Span::styled(
+            app.state_filter().label(),
+            theme.fg(theme.accent),
+        ));
+    }
+    spans.extend([
         Span::raw(" | Orphans:"),
         Span::raw(app.orphan_count().to_string()),
+        Span::raw(" | Apply:"),
+        Span::raw(apply_mode_label(app.apply_mode())),
     ]);
+    if app.defer_apply() {
+        spans.push(Span::raw(" | Staged:"));
+        spans.push(Span::styled(
+            format!("{} (S)", app.staged().len()),
+            theme.fg(theme.accent),
+        ));
+    }
+    if app.pending_updates() > 0 {
+        spans.push(Span::raw(" | Updates:"));
+        spans.push(Span::styled(
+            app.pending_updates().to_string(),
+            theme.bold(theme.warning),
+        ));
+    }
+    if app.upstream_change_count() > 0 {
+        spans.push(Span::raw(" | Upstream:"));
+        spans.push(Span::styled(
+            format!("{} changes (w)", app.upstream_change_count()),
+            theme.fg(theme.accent),
+        ));
+    }
+    if let Some((commit, fetched_at)) = app.snapshot_info() {
+        let short: String = commit.chars().take(7).collect();
+        let age = components::format_age(chrono::Utc::now() - fetched_at);
+        spans.push(Span::raw(" | Snapshot:"));
+        spans.push(Span::raw(format!("{short} ({age} ago)")));
+    }
+    if let Some(pin) = app.pinned_ref() {
+        spans.push(Span::raw(" | Pin:"));
+        spans.push(Span::styled(pin, theme.fg(theme.warning)));
+    }
+    let line = Line::from(spans);
     frame.render_widget(Paragraph::new(line), area);
 }
 
-const TABS: [AssetKind; 4] = [
+const TABS: [AssetKind; 6] = [
     AssetKind::Prompt,
     AssetKind::Instruction,
     AssetKind::ChatMode,
+    AssetKind::Agent,
+    AssetKind::McpServer,
     AssetKind::Collection,
 ];
 
 fn render_tabs(frame: &mut Frame<'_>, area: Rect, app: &App) {
     let titles = TABS
         .iter()
-        .map(|kind| Line::from(tab_title(*kind)))
+        .map(|kind| Line::from(tab_label(app, *kind)))
         .collect::<Vec<_>>();
     let selected = TABS.iter().position(|kind| *kind == app.tab()).unwrap_or(0);
     let tabs = Tabs::new(titles)
         .block(Block::default().borders(Borders::ALL).title("Kinds"))
         .select(selected)
-        .highlight_style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        );
+        .highlight_style(app.theme().bold(app.theme().accent));
     frame.render_widget(tabs, area);
 }
 
-fn render_body(frame: &mut Frame<'_>, area: Rect, app: &App) {
-    let body = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
-        .split(area);
+fn render_body(frame: &mut Frame<'_>, layout: &ScreenLayout, app: &App) {
+    if app.facets().is_open() {
+        render_facets(frame, layout.facets, app);
+    }
+    render_table(frame, layout.table, app);
+    render_detail(frame, layout.detail, app);
+}
 
-    render_table(frame, body[0], app);
-    render_detail(frame, body[1], app);
+fn render_facets(frame: &mut Frame<'_>, area: Rect, app: &App) {
+    let theme = app.theme();
+    let panel = app.facets();
+    let facets = app.tag_facets();
+    let items: Vec<ListItem> = facets
+        .iter()
+        .map(|(tag, count)| {
+            let chosen = panel.is_chosen(app.tab(), tag);
+            let check = if chosen { "[x]" } else { "[ ]" };
+            let style = if chosen {
+                theme.fg(theme.accent)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{check} {tag} "), style),
+                Span::styled(format!("({count})"), theme.fg(theme.muted)),
+            ]))
+        })
+        .collect();
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Tags ({})", facets.len()));
+    if panel.is_focused() {
+        block = block.border_style(theme.fg(theme.accent));
+    }
+    let list = if items.is_empty() {
+        List::new([ListItem::new("No tags")]).block(block)
+    } else {
+        List::new(items).block(block)
+    };
+    let list = if panel.is_focused() {
+        list.highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+    } else {
+        list
+    };
+    let mut state = ListState::default();
+    if !facets.is_empty() {
+        state.select(Some(panel.selected().min(facets.len() - 1)));
+    }
+    frame.render_stateful_widget(list, area, &mut state);
 }
 
 fn render_table(frame: &mut Frame<'_>, area: Rect, app: &App) {
+    let theme = app.theme();
     let assets = app.current_assets();
     let rows: Vec<Row> = assets
         .iter()
-        .map(|asset| {
-            let state_cell = {
-                let badge = components::state_badge(asset);
-                let style = if asset.effective {
-                    Style::default().fg(Color::Green)
-                } else {
-                    Style::default().fg(Color::Red)
-                };
-                Cell::from(badge).style(style)
+        .enumerate()
+        .map(|(index, asset)| {
+            let state_style = if asset.is_partial() || asset.is_mixed() {
+                theme.fg(theme.warning)
+            } else {
+                theme.state(asset.effective)
             };
+            let state_cell =
+                Cell::from(components::state_badge(asset, theme.ascii)).style(state_style);
             
-            Row::new(vec![
+            let name = if app.is_marked(&asset.path) {
+                format!("* {}", asset.name)
+            } else {
+                asset.name.clone()
+            };
+            let favorite = if app.is_favorite(&asset.path) { "*" } else { "" };
+            let row = Row::new(vec![
+                Cell::from(favorite).style(theme.fg(theme.warning)),
                 state_cell,
-                Cell::from(asset.name.clone()),
+                Cell::from(name),
                 Cell::from(asset.path.clone()),
-                Cell::from(components::local_status(asset)),
-                Cell::from(components::tags_field(asset)),
-            ])
+                Cell::from(components::local_status(asset)).style(
+                    if asset.local == LocalStatus::Update {
+                        theme.fg(theme.warning)
+                    } else {
+                        Style::default()
+                    },
+                ),
+                Cell::from(components::tags_field(asset)).style(coverage_style(theme, asset)),
+            ]);
+            if app.in_visual_range(index) {
+                row.style(Style::default().bg(theme.selection))
+            } else if app.is_marked(&asset.path) {
+                row.style(theme.fg(theme.marked))
+            } else {
+                row
+            }
         })
         .collect();
 
-    let header = Row::new(vec!["State", "Name", "Path", "Local", "Tags"])
+    let header = Row::new(vec!["", "State", "Name", "Path", "Local", "Tags"])
         .style(Style::default().add_modifier(Modifier::BOLD));
 
     let widths = [
-        Constraint::Length(8),
+        Constraint::Length(1),
+        Constraint::Length(if theme.ascii { 9 } else { 8 }),
         Constraint::Percentage(30),
         Constraint::Percentage(40),
         Constraint::Length(8),
         Constraint::Percentage(22),
     ];
+    let title = match app.mark_count() {
+        0 => format!("Assets — sort: {}", app.sort_mode().label()),
+        count => format!(
+            "Assets — sort: {} ({count} marked)",
+            app.sort_mode().label()
+        ),
+    };
     let table = Table::new(rows, widths)
         .header(header)
-        .block(Block::default().borders(Borders::ALL).title("Assets"))
+        .block(Block::default().borders(Borders::ALL).title(title))
         .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
 
     let mut state = TableState::default();
@@ -153,16 +410,49 @@ fn render_table(frame: &mut Frame<'_>, area: Rect, app: &App) {
     frame.render_stateful_widget(table, area, &mut state);
 }
 
+/// Collections color their member coverage: all enabled, some, or none.
+fn coverage_style(theme: &Theme, asset: &AssetView) -> Style {
+    if asset.kind != AssetKind::Collection || asset.member_count == 0 {
+        Style::default()
+    } else if asset.enabled_members == asset.member_count {
+        theme.fg(theme.enabled)
+    } else if asset.enabled_members > 0 {
+        theme.fg(theme.warning)
+    } else {
+        theme.fg(theme.disabled)
+    }
+}
+
 fn render_detail(frame: &mut Frame<'_>, area: Rect, app: &App) {
-    let block = Block::default().borders(Borders::ALL).title("Details");
-    if let Some(asset) = app.selected_asset_view() {
+    let theme = app.theme();
+    let title = if app.preview().is_some() {
+        "Preview"
+    } else {
+        "Details"
+    };
+    let block = if app.detail_focused() {
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("{title} (wheel/Up/Down scroll, Esc returns)"))
+            .border_style(theme.fg(theme.accent))
+    } else {
+        Block::default().borders(Borders::ALL).title(title)
+    };
+    if let Some(preview) = app.preview() {
+        let paragraph = Paragraph::new(markdown::preview_lines(preview, theme))
+            .wrap(Wrap { trim: false })
+            .block(block.title_bottom(preview.path().to_string()))
+            .scroll((app.detail_scroll(), 0));
+        frame.render_widget(paragraph, area);
+    } else if let Some(asset) = app.selected_asset_view() {
         let mut lines = Vec::new();
         lines.push(Line::from(vec![
             Span::styled(&asset.name, Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(" "),
-            Span::styled(tab_title(asset.kind), Style::default().fg(Color::Cyan)),
+            Span::styled(tab_title(asset.kind), theme.fg(theme.accent)),
         ]));
         lines.push(Line::from(format!("Path: {}", asset.path)));
+        lines.push(Line::from(format!("Source: {}", asset.source)));
         if let Some(slug) = &asset.slug {
             lines.push(Line::from(format!("Slug: {slug}")));
         }
@@ -171,6 +461,45 @@ fn render_detail(frame: &mut Frame<'_>, area: Rect, app: &App) {
             "Collections: {}",
             components::collections_list(asset)
         )));
+        if let Some(inherited) = asset.inherited.as_ref().filter(|i| i.parents.len() > 1) {
+            let resolution = app.domain().enablement.inheritance.resolution;
+            lines.push(Line::from(format!("Inherited by {}:", resolution.label())));
+            for parent in &inherited.parents {
+                let (mark, color) = if parent.value {
+                    ("✓", theme.enabled)
+                } else {
+                    ("✗", theme.disabled)
+                };
+                let decides = if parent.collection.id == inherited.collection.id {
+                    " ← decides"
+                } else {
+                    ""
+                };
+                lines.push(Line::from(Span::styled(
+                    format!("  {mark} {}{decides}", parent.collection.name),
+                    theme.fg(color),
+                )));
+            }
+            if asset.is_mixed() {
+                let names = |value: bool| {
+                    inherited
+                        .parents
+                        .iter()
+                        .filter(|parent| parent.value == value)
+                        .map(|parent| parent.collection.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "  ⚠ Mixed: on in {}, off in {}; an explicit toggle settles it",
+                        names(true),
+                        names(false)
+                    ),
+                    theme.fg(theme.warning),
+                )));
+            }
+        }
         if !asset.tags.is_empty() {
             lines.push(Line::from(format!("Tags: {}", asset.tags.join(", "))));
         }
@@ -183,15 +512,40 @@ fn render_detail(frame: &mut Frame<'_>, area: Rect, app: &App) {
         if !asset.tools.is_empty() {
             lines.push(Line::from(format!("Tools: {}", asset.tools.join(", "))));
         }
+        if let Some(note) = app.domain().enablement.note(&asset.path) {
+            lines.push(Line::from(Span::styled(
+                format!("Note: {note}"),
+                theme.fg(theme.accent),
+            )));
+        }
         if asset.kind == AssetKind::Collection {
-            lines.push(Line::from(format!("Members: {}", asset.member_count)));
+            lines.push(Line::from(format!(
+                "Members: {} ({} enabled)",
+                asset.member_count, asset.enabled_members
+            )));
+        }
+        let dependencies = app.domain().dependency_chain(&asset.path);
+        if !dependencies.is_empty() {
+            lines.push(Line::from("Requires:"));
+            for dependency in dependencies {
+                let indent = "  ".repeat(dependency.depth);
+                let (text, color) = match dependency.asset {
+                    Some(view) if view.effective => (format!("✓ {}", view.name), theme.enabled),
+                    Some(view) => (format!("✗ {}", view.name), theme.disabled),
+                    None => (format!("? {} (not in catalog)", dependency.path), theme.error),
+                };
+                lines.push(Line::from(Span::styled(
+                    format!("{indent}{text}"),
+                    theme.fg(color),
+                )));
+            }
         }
         
         // Add Toggle Preview section
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
             "Toggle Preview:",
-            Style::default().add_modifier(Modifier::BOLD).fg(Color::Yellow)
+            theme.bold(theme.warning)
         )));
         for line in components::toggle_preview(asset).lines() {
             lines.push(Line::from(line.to_string()));
@@ -212,7 +566,10 @@ fn render_detail(frame: &mut Frame<'_>, area: Rect, app: &App) {
             lines.push(Line::from(line.to_string()));
         }
 
-        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true }).block(block);
+        let paragraph = Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(block)
+            .scroll((app.detail_scroll(), 0));
         frame.render_widget(paragraph, area);
     } else {
         frame.render_widget(Paragraph::new("No asset selected").block(block), area);
@@ -220,28 +577,37 @@ fn render_detail(frame: &mut Frame<'_>, area: Rect, app: &App) {
 }
 
 fn render_footer(frame: &mut Frame<'_>, area: Rect, app: &App) {
+    let theme = app.theme();
     let footer_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(1), Constraint::Length(1)])
         .split(area);
 
     let mut spans = Vec::new();
+    if app.is_loading() {
+        spans.extend(progress_spans(app));
+        spans.push(Span::raw(" | "));
+    }
     if let Some(err) = app.error() {
         spans.push(Span::styled(
             format!("Error: {err}"),
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            theme.bold(theme.error),
         ));
     } else if let Some(info) = app.info_message() {
-        spans.push(Span::styled(info, Style::default().fg(Color::Green)));
+        spans.push(Span::styled(info, theme.fg(theme.success)));
     }
 
     if !app.warnings().is_empty() {
         if !spans.is_empty() {
             spans.push(Span::raw(" | "));
         }
+        let latest = app.warnings().last().map_or("", |warning| &warning.text);
         spans.push(Span::styled(
-            format!("Warnings: {}", app.warnings().join("; ")),
-            Style::default().fg(Color::Yellow),
+            match app.warnings().len() {
+                1 => format!("Warning: {latest} (W)"),
+                count => format!("{count} warnings, latest: {latest} (W lists them)"),
+            },
+            theme.fg(theme.warning),
         ));
     }
 
@@ -251,9 +617,7 @@ fn render_footer(frame: &mut Frame<'_>, area: Rect, app: &App) {
         }
         spans.push(Span::styled(
             prompt_text(prompt),
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
+            theme.bold(theme.warning),
         ));
     }
 
@@ -265,23 +629,65 @@ fn render_footer(frame: &mut Frame<'_>, area: Rect, app: &App) {
     frame.render_widget(Paragraph::new(line), footer_layout[0]);
 
     let search = app.search_state();
-    if search.is_active() {
-        let prompt = format!("Search > {}_", search.draft());
+    if let Some(editor) = app.note_editor() {
+        let prompt = format!("Note for {} > {}_", editor.name(), editor.draft());
         frame.render_widget(
-            Paragraph::new(prompt).style(Style::default().fg(Color::Cyan)),
+            Paragraph::new(prompt).style(theme.fg(theme.accent)),
+            footer_layout[1],
+        );
+    } else if search.is_active() {
+        let scope = if search.full_text() { " (full text)" } else { "" };
+        let prompt = format!("Search{scope} > {}_", search.draft());
+        frame.render_widget(
+            Paragraph::new(prompt).style(theme.fg(theme.accent)),
             footer_layout[1],
         );
     } else {
-        let hints = format!("{}  |  a=Apply from upstream", input::key_hints());
-        frame.render_widget(Paragraph::new(hints), footer_layout[1]);
+        frame.render_widget(Paragraph::new(input::key_hints(app.keymap())), footer_layout[1]);
+    }
+}
+
+/// Snapshot progress for the footer, so a slow download is visible even
+/// when the loading popup is covered by an overlay.
+fn progress_spans(app: &App) -> Vec<Span<'static>> {
+    let theme = app.theme();
+    let Some(progress) = app.load_progress() else {
+        return vec![Span::styled("Loading catalog…", theme.fg(theme.accent))];
+    };
+    let mut spans = Vec::new();
+    if let Some(ratio) = progress.ratio() {
+        let filled = (ratio * PROGRESS_BAR_WIDTH as f64).round() as usize;
+        spans.push(Span::styled(
+            format!(
+                "[{}{}] ",
+                "#".repeat(filled),
+                "-".repeat(PROGRESS_BAR_WIDTH - filled)
+            ),
+            theme.fg(theme.accent),
+        ));
+    }
+    spans.push(Span::styled(progress.to_string(), theme.fg(theme.accent)));
+    spans
+}
+
+const PROGRESS_BAR_WIDTH: usize = 20;
+
+/// Tab title with the enabled/total count once the catalog has loaded.
+fn tab_label(app: &App, kind: AssetKind) -> String {
+    if app.is_loading() {
+        return tab_title(kind).to_string();
     }
+    let (enabled, total) = app.domain().counts(kind);
+    format!("{} ({enabled}/{total})", tab_title(kind))
 }
 
-fn tab_title(kind: AssetKind) -> &'static str {
+pub fn tab_title(kind: AssetKind) -> &'static str {
     match kind {
         AssetKind::Prompt => "Prompts",
         AssetKind::Instruction => "Instructions",
         AssetKind::ChatMode => "Chat Modes",
+        AssetKind::Agent => "Agents",
+        AssetKind::McpServer => "MCP Servers",
         AssetKind::Collection => "Collections",
     }
 }
@@ -290,6 +696,14 @@ fn prompt_text(prompt: PendingPrompt) -> &'static str {
     match prompt {
         PendingPrompt::Quit => "Confirm quit: y=Yes / n=No",
         PendingPrompt::Reload => "Confirm reload (discard changes): y=Yes / n=No",
+        PendingPrompt::Refresh => "Confirm refresh (discard changes): y=Yes / n=No",
         PendingPrompt::ToggleCollection => "Confirm collection toggle: y=Yes / n=No",
+        PendingPrompt::BulkToggle => "Confirm toggle of marked assets: y=Yes / n=No",
+        PendingPrompt::BulkApply => "Confirm apply of marked assets: y=Yes / n=No",
+        PendingPrompt::Import => "Import profile: y=Merge / R=Replace / n=Cancel",
+        PendingPrompt::ExternalReload => "Reload from disk: y=Yes / n=No",
+        PendingPrompt::ApplyAll => "Confirm apply of all enabled assets: y=Yes / n=No",
+        PendingPrompt::Reset(_) => "Confirm reset: y=Yes / n=No",
+        PendingPrompt::Adopt => "Adopt them as enabled: y=Yes / n=No",
     }
 }
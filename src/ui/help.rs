@@ -0,0 +1,77 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::app::{apply_mode_label, help::HelpView, App};
+
+use super::{
+    input::{self, KeyContext},
+    theme::Theme,
+};
+
+/// Scrollable keybinding reference generated from `input::KEYMAP`.
+pub fn render(frame: &mut Frame<'_>, area: Rect, app: &App, view: &HelpView) {
+    let theme = app.theme();
+    let mut lines = vec![Line::from(vec![
+        Span::styled(
+            "Apply mode: ",
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(apply_mode_label(app.apply_mode())),
+        Span::raw(format!("   Marked on this tab: {}", app.mark_count())),
+        Span::raw(format!("   Keymap: {}", app.keymap().preset_name())),
+        Span::raw(format!("   Theme: {}", theme.name)),
+    ])];
+
+    let mut contexts = vec![view.context()];
+    contexts.extend(
+        KeyContext::ALL
+            .into_iter()
+            .filter(|context| *context != view.context()),
+    );
+    for context in contexts {
+        lines.push(Line::from(""));
+        let mut title = context.title().to_string();
+        if context == view.context() {
+            title.push_str(" (current)");
+        }
+        lines.push(Line::styled(title, theme.bold(theme.accent)));
+        if context == KeyContext::Normal {
+            let keymap = app.keymap();
+            for action in keymap.actions() {
+                lines.push(binding_line(
+                    theme,
+                    &keymap.keys_for(action).join(" / "),
+                    action.description(),
+                ));
+            }
+        }
+        for binding in input::bindings(context) {
+            lines.push(binding_line(
+                theme,
+                &binding.keys.join(" / "),
+                binding.description,
+            ));
+        }
+    }
+
+    let help = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Help — Up/Down/PgUp/PgDn scroll • Esc closes"),
+        )
+        .scroll((view.scroll(), 0));
+    frame.render_widget(help, area);
+}
+
+fn binding_line(theme: &Theme, keys: &str, description: &'static str) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("  {keys:<24}"), theme.fg(theme.code)),
+        Span::raw(description),
+    ])
+}
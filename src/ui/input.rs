@@ -1,9 +1,212 @@
-use anyhow::Result;
+use super::keymap::Keymap;
 
-pub fn handle_input() -> Result<()> {
-    todo!("Input handling not implemented");
+/// Where a binding is active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    Normal,
+    Detail,
+    Facets,
+    Search,
+    Note,
+    Prompt,
+    Backups,
+    Orphans,
+    Members,
+    Changelog,
+    Prune,
+    Unmanaged,
+    Staged,
+    Warnings,
+    Cache,
+    Repos,
+    Projects,
+    Reader,
+    SaveReport,
+    Wizard,
+    GlobTester,
+    History,
+    Log,
+    Help,
 }
 
-pub fn key_hints() -> &'static str {
-    "Tab/Shift+Tab switch tabs • Up/Down move • Enter/Space toggle (collections ask for confirmation) • / search • Ctrl+S save • c cleanup • r reload • q quit"
+impl KeyContext {
+    pub const ALL: [KeyContext; 24] = [
+        KeyContext::Normal,
+        KeyContext::Detail,
+        KeyContext::Facets,
+        KeyContext::Search,
+        KeyContext::Note,
+        KeyContext::Prompt,
+        KeyContext::Backups,
+        KeyContext::Orphans,
+        KeyContext::Members,
+        KeyContext::Changelog,
+        KeyContext::Prune,
+        KeyContext::Unmanaged,
+        KeyContext::Staged,
+        KeyContext::Warnings,
+        KeyContext::Cache,
+        KeyContext::Repos,
+        KeyContext::Projects,
+        KeyContext::Reader,
+        KeyContext::SaveReport,
+        KeyContext::Wizard,
+        KeyContext::GlobTester,
+        KeyContext::History,
+        KeyContext::Log,
+        KeyContext::Help,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            KeyContext::Normal => "Asset table",
+            KeyContext::Detail => "Details pane (focused with Right or by clicking it)",
+            KeyContext::Facets => "Tag panel (focused with t or by clicking it)",
+            KeyContext::Search => "Search input",
+            KeyContext::Note => "Note input",
+            KeyContext::Prompt => "Confirmation prompts",
+            KeyContext::Backups => "Backup browser",
+            KeyContext::Orphans => "Orphans view",
+            KeyContext::Members => "Collection members",
+            KeyContext::Changelog => "What changed upstream",
+            KeyContext::Prune => "Prune view",
+            KeyContext::Unmanaged => "Unmanaged files",
+            KeyContext::Staged => "Staged file changes",
+            KeyContext::Warnings => "Warnings",
+            KeyContext::Cache => "Snapshot cache",
+            KeyContext::Repos => "Repositories",
+            KeyContext::Projects => "Sub-projects",
+            KeyContext::Reader => "Full-screen reader",
+            KeyContext::SaveReport => "Save summary",
+            KeyContext::Wizard => "New-asset form",
+            KeyContext::GlobTester => "applyTo tester",
+            KeyContext::History => "Activity log",
+            KeyContext::Log => "Message log",
+            KeyContext::Help => "Help",
+        }
+    }
+}
+
+pub struct Binding {
+    pub context: KeyContext,
+    pub keys: &'static [&'static str],
+    pub description: &'static str,
+}
+
+const fn bind(
+    context: KeyContext,
+    keys: &'static [&'static str],
+    description: &'static str,
+) -> Binding {
+    Binding {
+        context,
+        keys,
+        description,
+    }
+}
+
+/// Bindings outside the asset table. Table bindings come from the active
+/// [`Keymap`] so they follow the user's preset and overrides.
+#[rustfmt::skip]
+pub const KEYMAP: &[Binding] = &[
+    bind(KeyContext::Normal, &["Click"], "Select a row, switch tabs, or focus the details pane"),
+    bind(KeyContext::Normal, &["Wheel"], "Move the selection, or scroll the details pane under the pointer"),
+    bind(KeyContext::Detail, &["Up", "Down", "PageUp", "PageDown"], "Scroll the details"),
+    bind(KeyContext::Detail, &["Home"], "Scroll back to the top"),
+    bind(KeyContext::Detail, &["Esc", "Right"], "Return focus to the table"),
+    bind(KeyContext::Facets, &["Up", "Down", "PageUp", "PageDown"], "Select a tag"),
+    bind(KeyContext::Facets, &["Space", "Enter", "Click"], "Include or exclude the tag; the table shows assets with every included tag"),
+    bind(KeyContext::Facets, &["Esc"], "Return focus to the table (Esc there clears the tags)"),
+    bind(KeyContext::Facets, &["t"], "Hide the panel; included tags keep filtering"),
+    bind(KeyContext::Search, &["Enter"], "Apply the query: words AND, OR, -word, \"phrase\", tag:/path:/name:"),
+    bind(KeyContext::Search, &["Esc"], "Cancel the search"),
+    bind(KeyContext::Search, &["Backspace"], "Delete the last character"),
+    bind(KeyContext::Search, &["Ctrl+F"], "Also search the text of upstream files"),
+    bind(KeyContext::Note, &["Enter"], "Keep the note; an empty note removes it"),
+    bind(KeyContext::Note, &["Esc"], "Cancel the edit"),
+    bind(KeyContext::Note, &["Backspace", "Ctrl+U"], "Delete the last character, or the whole note"),
+    bind(KeyContext::Prompt, &["y", "Enter"], "Confirm"),
+    bind(KeyContext::Prompt, &["R"], "Replace instead of merge (import only)"),
+    bind(KeyContext::Prompt, &["n", "Esc"], "Cancel"),
+    bind(KeyContext::Prompt, &["Up", "Down", "PgUp", "PgDn"], "Scroll the plan of a bulk operation or collection toggle"),
+    bind(KeyContext::Backups, &["Up", "Down"], "Select a backup"),
+    bind(KeyContext::Backups, &["PageUp", "PageDown"], "Scroll the diff"),
+    bind(KeyContext::Backups, &["Enter"], "Restore the selected backup"),
+    bind(KeyContext::Backups, &["Esc", "q"], "Close"),
+    bind(KeyContext::Orphans, &["Up", "Down"], "Select an entry"),
+    bind(KeyContext::Orphans, &["d", "Delete"], "Delete the entry"),
+    bind(KeyContext::Orphans, &["Enter", "r"], "Remap the entry to its likely successor"),
+    bind(KeyContext::Orphans, &["Esc", "q"], "Close"),
+    bind(KeyContext::Members, &["Up", "Down", "j", "k"], "Select a member"),
+    bind(KeyContext::Members, &["Space", "Enter"], "Enable or disable the member on its own"),
+    bind(KeyContext::Members, &["a"], "Apply the member's file from upstream"),
+    bind(KeyContext::Members, &["Esc", "q"], "Back to the Collections tab"),
+    bind(KeyContext::Changelog, &["Up", "Down", "PageUp", "PageDown"], "Scroll"),
+    bind(KeyContext::Changelog, &["Esc", "q", "Enter"], "Close"),
+    bind(KeyContext::History, &["Up", "Down", "PageUp", "PageDown"], "Scroll"),
+    bind(KeyContext::History, &["Esc", "q", "Enter"], "Close"),
+    bind(KeyContext::Log, &["Up", "Down", "PageUp", "PageDown"], "Scroll"),
+    bind(KeyContext::Log, &["Esc", "q", "Enter"], "Close"),
+    bind(KeyContext::Prune, &["Up", "Down"], "Select a file"),
+    bind(KeyContext::Prune, &["d", "Delete"], "Back up and delete the selected file"),
+    bind(KeyContext::Prune, &["y", "Enter"], "Back up and delete every listed file"),
+    bind(KeyContext::Prune, &["Esc", "q"], "Close without deleting"),
+    bind(KeyContext::Unmanaged, &["Up", "Down"], "Select a file"),
+    bind(KeyContext::Unmanaged, &["i"], "Ignore the file from now on"),
+    bind(KeyContext::Unmanaged, &["a"], "Adopt the file as an enabled local asset"),
+    bind(KeyContext::Unmanaged, &["d", "Delete"], "Back up and delete the file"),
+    bind(KeyContext::Unmanaged, &["Esc", "q"], "Close"),
+    bind(KeyContext::Staged, &["Up", "Down"], "Select a change"),
+    bind(KeyContext::Staged, &["d", "Delete"], "Unstage the change, leaving the file alone"),
+    bind(KeyContext::Staged, &["y", "Enter"], "Save and run every staged change"),
+    bind(KeyContext::Staged, &["Esc", "q"], "Close"),
+    bind(KeyContext::Warnings, &["Up", "Down", "Home", "End"], "Select a warning"),
+    bind(KeyContext::Warnings, &["d", "Delete"], "Dismiss the selected warning"),
+    bind(KeyContext::Warnings, &["D"], "Dismiss every warning"),
+    bind(KeyContext::Warnings, &["Esc", "q"], "Close"),
+    bind(KeyContext::Cache, &["Up", "Down"], "Select a snapshot"),
+    bind(KeyContext::Cache, &["Enter"], "Pin the official source to the snapshot and reload"),
+    bind(KeyContext::Cache, &["u"], "Unpin the official source and reload from its branch"),
+    bind(KeyContext::Cache, &["d", "Delete"], "Delete the snapshot unless it is in use"),
+    bind(KeyContext::Cache, &["D"], "Delete every snapshot not in use"),
+    bind(KeyContext::Cache, &["Esc", "q"], "Close"),
+    bind(KeyContext::Repos, &["Up", "Down"], "Select a repository"),
+    bind(KeyContext::Repos, &["Enter"], "Switch the session to the repository"),
+    bind(KeyContext::Repos, &["d", "Delete"], "Forget the repository"),
+    bind(KeyContext::Repos, &["Esc", "q"], "Close"),
+    bind(KeyContext::Projects, &["Up", "Down"], "Select the repository root or a sub-project"),
+    bind(KeyContext::Projects, &["Enter"], "Switch the session to it"),
+    bind(KeyContext::Projects, &["Esc", "q"], "Close"),
+    bind(KeyContext::Reader, &["Up", "Down", "j", "k"], "Scroll one line"),
+    bind(KeyContext::Reader, &["PageUp", "PageDown", "Space"], "Scroll one page"),
+    bind(KeyContext::Reader, &["Home", "End", "g", "G"], "Jump to the start or end"),
+    bind(KeyContext::Reader, &["/"], "Search the document; Enter runs the search"),
+    bind(KeyContext::Reader, &["n", "N"], "Jump to the next or previous match"),
+    bind(KeyContext::Reader, &["Esc", "q"], "Clear the search, or return to the table"),
+    bind(KeyContext::SaveReport, &["Up", "Down", "PageUp", "PageDown"], "Scroll"),
+    bind(KeyContext::SaveReport, &["Esc", "q", "Enter"], "Close"),
+    bind(KeyContext::Wizard, &["Enter", "Tab", "Down"], "Next field (Enter on the last one creates the file)"),
+    bind(KeyContext::Wizard, &["Shift+Tab", "Up"], "Previous field"),
+    bind(KeyContext::Wizard, &["Esc"], "Cancel"),
+    bind(KeyContext::GlobTester, &["Backspace", "Ctrl+U"], "Delete the last character, or the whole path"),
+    bind(KeyContext::GlobTester, &["Up", "Down", "PageUp", "PageDown"], "Scroll the matches"),
+    bind(KeyContext::GlobTester, &["Esc"], "Close"),
+    bind(KeyContext::Help, &["Up", "Down", "PageUp", "PageDown"], "Scroll"),
+    bind(KeyContext::Help, &["Esc", "q", "?"], "Close"),
+];
+
+pub fn bindings(context: KeyContext) -> impl Iterator<Item = &'static Binding> {
+    KEYMAP.iter().filter(move |b| b.context == context)
+}
+
+pub fn key_hints(keymap: &Keymap) -> String {
+    keymap
+        .actions()
+        .filter_map(|action| {
+            action
+                .hint()
+                .map(|hint| format!("{} {hint}", keymap.keys_for(action).join("/")))
+        })
+        .collect::<Vec<_>>()
+        .join(" • ")
 }
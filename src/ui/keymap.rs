@@ -0,0 +1,600 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use anyhow::{anyhow, bail, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// Everything a key can trigger in the asset table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    NextTab,
+    PrevTab,
+    Down,
+    Up,
+    PageDown,
+    PageUp,
+    First,
+    Last,
+    Toggle,
+    Open,
+    Mark,
+    Favorite,
+    Note,
+    Visual,
+    CycleSort,
+    CycleStateFilter,
+    Preview,
+    Reader,
+    YankPath,
+    YankUrl,
+    YankContent,
+    NarrowTable,
+    WidenTable,
+    FocusDetail,
+    Apply,
+    ApplyAll,
+    CycleApplyMode,
+    Backups,
+    Orphans,
+    Members,
+    Facets,
+    GlobTester,
+    History,
+    Log,
+    Changelog,
+    Prune,
+    Unmanaged,
+    Staged,
+    Warnings,
+    Cache,
+    Repos,
+    Projects,
+    Cleanup,
+    Export,
+    ExportReport,
+    DiffTool,
+    Import,
+    Search,
+    NewAsset,
+    Commit,
+    Save,
+    Reload,
+    Refresh,
+    Reset,
+    ResetTab,
+    Cancel,
+    Help,
+    Quit,
+}
+
+impl Action {
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::NextTab => "Next asset kind",
+            Action::PrevTab => "Previous asset kind",
+            Action::Down => "Move the selection down",
+            Action::Up => "Move the selection up",
+            Action::PageDown => "Move the selection down ten rows",
+            Action::PageUp => "Move the selection up ten rows",
+            Action::First => "Jump to the first row",
+            Action::Last => "Jump to the last row",
+            Action::Toggle => "Toggle the selected asset, or every marked asset after confirmation",
+            Action::Open => "Open the selected collection's members; toggle any other asset",
+            Action::Mark => "Mark or unmark the selected asset",
+            Action::Favorite => "Star or unstar the selected asset",
+            Action::Note => "Write a note on the selected asset, kept in the enablement file",
+            Action::Visual => "Start a visual range; press again to mark it",
+            Action::CycleSort => {
+                "Cycle the sort order (name, path, enabled, local status, kind, favorites)"
+            }
+            Action::CycleStateFilter => {
+                "Cycle the state filter (all, enabled, disabled, explicit, inherited, out of sync)"
+            }
+            Action::Preview => "Toggle the rendered preview of the upstream file",
+            Action::Reader => "Read the upstream file full-screen",
+            Action::YankPath => "Copy the selected asset's file path to the clipboard",
+            Action::YankUrl => "Copy the selected asset's GitHub URL to the clipboard",
+            Action::YankContent => "Copy the selected asset's upstream content to the clipboard",
+            Action::NarrowTable => "Narrow the table and widen the details pane",
+            Action::WidenTable => "Widen the table and narrow the details pane",
+            Action::FocusDetail => "Focus the details pane to scroll it; press again to return",
+            Action::Apply => "Apply the selected (or marked) assets from upstream",
+            Action::ApplyAll => "Apply every enabled asset from upstream",
+            Action::CycleApplyMode => {
+                "Cycle the apply mode between overwrite, merge, patch and symlink"
+            }
+            Action::Backups => "Browse and restore backups",
+            Action::Orphans => "Review orphaned enablement entries",
+            Action::Members => "Enable or disable members of the selected collection one by one",
+            Action::Facets => "Show, focus or hide the tag panel for filtering by tags",
+            Action::GlobTester => "Test which instructions' applyTo globs match a file path",
+            Action::History => "Review recent toggles, applies, resets and saves",
+            Action::Log => "Read every info and error message shown this session",
+            Action::Changelog => {
+                "Show what the last upstream refresh added, removed, renamed or changed"
+            }
+            Action::Prune => "Delete local files of disabled or unknown assets",
+            Action::Staged => "Review the file changes queued until the next save",
+            Action::Warnings => "List every warning with its source, to read or dismiss",
+            Action::Cache => "List cached snapshots, to switch to or delete",
+            Action::Repos => "List registered repositories, to switch the session to",
+            Action::Projects => "List the monorepo sub-projects, to switch the session to",
+            Action::Unmanaged => {
+                "List files under .github that no catalog asset maps to, to ignore, adopt or delete"
+            }
+            Action::Cleanup => "Remove all orphaned enablement entries",
+            Action::Export => "Export the selection to awesome-copilot.profile.json",
+            Action::ExportReport => "Write a markdown list of enabled assets to copilot-assets.md",
+            Action::DiffTool => {
+                "Compare the selected asset's local file with upstream in $DIFFTOOL"
+            }
+            Action::Import => "Import awesome-copilot.profile.json",
+            Action::Search => "Search names, paths, descriptions and tags",
+            Action::NewAsset => {
+                "Create a prompt, instruction, chat mode or agent in the local catalog"
+            }
+            Action::Commit => "Commit .github and the enablement file with a generated message",
+            Action::Save => "Save the enablement file",
+            Action::Reload => "Reload catalog and enablement (asks if unsaved)",
+            Action::Refresh => {
+                "Download the upstream snapshots again, then reload (asks if unsaved)"
+            }
+            Action::Reset => "Remove every local asset and clear enablement",
+            Action::ResetTab => {
+                "Reset only this tab, or the rows matching the search, tags and state filter"
+            }
+            Action::Cancel => "Cancel visual mode, or clear marks, message and filter",
+            Action::Help => "Show the keybinding reference",
+            Action::Quit => "Quit (asks if unsaved)",
+        }
+    }
+
+    /// Short label shown in the footer, if any.
+    pub fn hint(self) -> Option<&'static str> {
+        match self {
+            Action::NextTab => Some("switch tabs"),
+            Action::Down => Some("move"),
+            Action::Toggle => Some("toggle"),
+            Action::Mark => Some("mark"),
+            Action::Apply => Some("apply"),
+            Action::Backups => Some("backups"),
+            Action::Search => Some("search"),
+            Action::Save => Some("save"),
+            Action::Reload => Some("reload"),
+            Action::Help => Some("help"),
+            Action::Quit => Some("quit"),
+            _ => None,
+        }
+    }
+}
+
+/// A single key press. Shift is folded into the character (`G`) or key
+/// (`Shift+Tab`) so it never has to be matched separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn from_event(event: KeyEvent) -> Self {
+        Self {
+            code: event.code,
+            modifiers: event.modifiers - KeyModifiers::SHIFT,
+        }
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = value;
+        let mut shift = false;
+        while let Some((modifier, tail)) = rest.split_once('+').filter(|(_, tail)| !tail.is_empty())
+        {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+                "alt" => modifiers |= KeyModifiers::ALT,
+                "shift" => shift = true,
+                other => bail!("unknown modifier '{other}' in '{value}'"),
+            }
+            rest = tail;
+        }
+        let code = match rest.to_ascii_lowercase().as_str() {
+            "enter" => KeyCode::Enter,
+            "space" => KeyCode::Char(' '),
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" if shift => KeyCode::BackTab,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            lower => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if shift => KeyCode::Char(c.to_ascii_uppercase()),
+                    // Terminals report Ctrl/Alt letters in lower case.
+                    (Some(c), None) if !modifiers.is_empty() => {
+                        KeyCode::Char(c.to_ascii_lowercase())
+                    }
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => match lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                        Some(n) => KeyCode::F(n),
+                        None => bail!("unknown key '{value}'"),
+                    },
+                }
+            }
+        };
+        Ok(Self { code, modifiers })
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("Alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::BackTab => f.write_str("Shift+Tab"),
+            KeyCode::PageUp => f.write_str("PageUp"),
+            KeyCode::PageDown => f.write_str("PageDown"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
+
+/// One or more chords pressed in order, written space separated (`g g`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySequence(Vec<KeyChord>);
+
+impl FromStr for KeySequence {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let chords = value
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<KeyChord>>>()?;
+        if chords.is_empty() {
+            bail!("empty key binding");
+        }
+        Ok(Self(chords))
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, chord) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{chord}")?;
+        }
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+const DEFAULT_PRESET: &[(Action, &[&str])] = &[
+    (Action::NextTab, &["Tab"]),
+    (Action::PrevTab, &["Shift+Tab"]),
+    (Action::Down, &["Down"]),
+    (Action::Up, &["Up"]),
+    (Action::PageDown, &["PageDown"]),
+    (Action::PageUp, &["PageUp"]),
+    (Action::First, &["Home"]),
+    (Action::Last, &["End"]),
+    (Action::Toggle, &["Space"]),
+    (Action::Open, &["Enter"]),
+    (Action::Mark, &["m"]),
+    (Action::Favorite, &["s"]),
+    (Action::Note, &["N"]),
+    (Action::Visual, &["v"]),
+    (Action::CycleSort, &["o"]),
+    (Action::CycleStateFilter, &["F"]),
+    (Action::Preview, &["p"]),
+    (Action::Reader, &["f"]),
+    (Action::YankPath, &["y p"]),
+    (Action::YankUrl, &["y u"]),
+    (Action::YankContent, &["y c"]),
+    (Action::NarrowTable, &["<"]),
+    (Action::WidenTable, &[">"]),
+    (Action::FocusDetail, &["Right"]),
+    (Action::Apply, &["a"]),
+    (Action::ApplyAll, &["A"]),
+    (Action::CycleApplyMode, &["M"]),
+    (Action::Backups, &["b"]),
+    (Action::Orphans, &["O"]),
+    (Action::Members, &["d"]),
+    (Action::Facets, &["t"]),
+    (Action::GlobTester, &["T"]),
+    (Action::History, &["H"]),
+    (Action::Log, &["L"]),
+    (Action::Changelog, &["w"]),
+    (Action::Prune, &["P"]),
+    (Action::Unmanaged, &["u"]),
+    (Action::Staged, &["S"]),
+    (Action::Warnings, &["W"]),
+    (Action::Cache, &["K"]),
+    (Action::Repos, &["Ctrl+O"]),
+    (Action::Projects, &["Ctrl+P"]),
+    (Action::Cleanup, &["c"]),
+    (Action::Export, &["e"]),
+    (Action::ExportReport, &["E"]),
+    (Action::DiffTool, &["D"]),
+    (Action::Import, &["i"]),
+    (Action::Commit, &["C"]),
+    (Action::NewAsset, &["n"]),
+    (Action::Search, &["/"]),
+    (Action::Save, &["Ctrl+S"]),
+    (Action::Reload, &["r"]),
+    (Action::Refresh, &["R"]),
+    (Action::Reset, &["x"]),
+    (Action::ResetTab, &["X"]),
+    (Action::Cancel, &["Esc"]),
+    (Action::Help, &["?"]),
+    (Action::Quit, &["q"]),
+];
+
+/// Vim-style navigation layered over the default preset.
+#[rustfmt::skip]
+const VIM_PRESET: &[(Action, &[&str])] = &[
+    (Action::NextTab, &["l", "Tab"]),
+    (Action::PrevTab, &["h", "Shift+Tab"]),
+    (Action::Down, &["j", "Down"]),
+    (Action::Up, &["k", "Up"]),
+    (Action::PageDown, &["Ctrl+D", "PageDown"]),
+    (Action::PageUp, &["Ctrl+U", "PageUp"]),
+    (Action::First, &["g g", "Home"]),
+    (Action::Last, &["G", "End"]),
+];
+
+pub const PRESETS: [&str; 2] = ["default", "vim"];
+
+/// Result of feeding a key to the keymap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Action(Action),
+    /// The key starts a longer sequence; wait for the next one.
+    Pending,
+    Unbound,
+}
+
+/// Key sequences bound to actions, built from a preset plus user overrides.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    preset: String,
+    bindings: BTreeMap<Action, Vec<KeySequence>>,
+    pending: Vec<KeyChord>,
+}
+
+impl Keymap {
+    pub fn preset(name: &str) -> Result<Self> {
+        let layers: &[&[(Action, &[&str])]] = match name {
+            "default" => &[DEFAULT_PRESET],
+            "vim" => &[DEFAULT_PRESET, VIM_PRESET],
+            other => bail!(
+                "unknown keymap preset '{other}' (expected one of: {})",
+                PRESETS.join(", ")
+            ),
+        };
+        let mut bindings = BTreeMap::new();
+        for layer in layers {
+            for (action, keys) in *layer {
+                let sequences = keys
+                    .iter()
+                    .map(|key| key.parse())
+                    .collect::<Result<Vec<_>>>()
+                    .expect("built-in key bindings parse");
+                bindings.insert(*action, sequences);
+            }
+        }
+        Ok(Self {
+            preset: name.to_string(),
+            bindings,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Replace the keys of every action listed in `overrides`.
+    pub fn with_overrides(mut self, overrides: &BTreeMap<Action, Vec<String>>) -> Result<Self> {
+        for (action, keys) in overrides {
+            let sequences = keys
+                .iter()
+                .map(|key| {
+                    key.parse()
+                        .with_context(|| format!("invalid binding '{key}' for {action:?}"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            self.bindings.insert(*action, sequences);
+        }
+        Ok(self)
+    }
+
+    pub fn preset_name(&self) -> &str {
+        &self.preset
+    }
+
+    /// Keys bound to `action`, for display.
+    pub fn keys_for(&self, action: Action) -> Vec<String> {
+        self.bindings
+            .get(&action)
+            .map(|sequences| sequences.iter().map(ToString::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn actions(&self) -> impl Iterator<Item = Action> + '_ {
+        self.bindings.keys().copied()
+    }
+
+    pub fn resolve(&mut self, event: KeyEvent) -> Resolution {
+        let chord = KeyChord::from_event(event);
+        self.pending.push(chord);
+        match self.lookup(&self.pending) {
+            Resolution::Pending => Resolution::Pending,
+            Resolution::Action(action) => {
+                self.pending.clear();
+                Resolution::Action(action)
+            }
+            Resolution::Unbound if self.pending.len() > 1 => {
+                // An abandoned sequence: treat the key as a fresh press.
+                self.pending.clear();
+                self.resolve(event)
+            }
+            Resolution::Unbound => {
+                self.pending.clear();
+                Resolution::Unbound
+            }
+        }
+    }
+
+    fn lookup(&self, pressed: &[KeyChord]) -> Resolution {
+        let mut prefix = false;
+        for (action, sequences) in &self.bindings {
+            for sequence in sequences {
+                if sequence.0 == pressed {
+                    return Resolution::Action(*action);
+                }
+                if sequence.0.starts_with(pressed) {
+                    prefix = true;
+                }
+            }
+        }
+        if prefix {
+            Resolution::Pending
+        } else {
+            Resolution::Unbound
+        }
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::preset("default").expect("default preset exists")
+    }
+}
+
+/// Contents of the user's `keymap.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeymapConfig {
+    pub preset: Option<String>,
+    /// Keys per action, replacing the preset's keys for that action.
+    pub bindings: BTreeMap<Action, Vec<String>>,
+}
+
+impl KeymapConfig {
+    /// Build the keymap; `preset` (from the command line) wins over the file.
+    pub fn build(&self, preset: Option<&str>) -> Result<Keymap> {
+        let name = preset.or(self.preset.as_deref()).unwrap_or("default");
+        Keymap::preset(name)?.with_overrides(&self.bindings)
+    }
+}
+
+pub fn parse_preset(name: &str) -> Result<String> {
+    if PRESETS.contains(&name) {
+        Ok(name.to_string())
+    } else {
+        Err(anyhow!(
+            "unknown keymap preset '{name}' (expected one of: {})",
+            PRESETS.join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(keymap: &mut Keymap, code: KeyCode, modifiers: KeyModifiers) -> Resolution {
+        keymap.resolve(KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn vim_preset_resolves_sequences() {
+        let mut keymap = Keymap::preset("vim").unwrap();
+        assert_eq!(
+            press(&mut keymap, KeyCode::Char('j'), KeyModifiers::NONE),
+            Resolution::Action(Action::Down)
+        );
+        assert_eq!(
+            press(&mut keymap, KeyCode::Char('g'), KeyModifiers::NONE),
+            Resolution::Pending
+        );
+        assert_eq!(
+            press(&mut keymap, KeyCode::Char('g'), KeyModifiers::NONE),
+            Resolution::Action(Action::First)
+        );
+        assert_eq!(
+            press(&mut keymap, KeyCode::Char('G'), KeyModifiers::SHIFT),
+            Resolution::Action(Action::Last)
+        );
+        // An unfinished sequence falls back to the next key on its own.
+        press(&mut keymap, KeyCode::Char('g'), KeyModifiers::NONE);
+        assert_eq!(
+            press(&mut keymap, KeyCode::Char('q'), KeyModifiers::NONE),
+            Resolution::Action(Action::Quit)
+        );
+    }
+
+    #[test]
+    fn config_file_selects_preset_and_overrides() {
+        let config: KeymapConfig = toml::from_str(
+            r#"
+            preset = "vim"
+
+            [bindings]
+            toggle = ["t", "Enter"]
+            "#,
+        )
+        .unwrap();
+        let keymap = config.build(None).unwrap();
+        assert_eq!(keymap.preset_name(), "vim");
+        assert_eq!(keymap.keys_for(Action::Toggle), vec!["t", "Enter"]);
+        assert_eq!(
+            config
+                .build(Some("default"))
+                .unwrap()
+                .keys_for(Action::Down),
+            vec!["Down"]
+        );
+    }
+
+    #[test]
+    fn overrides_replace_preset_keys() {
+        let overrides = BTreeMap::from([(Action::Save, vec!["Ctrl+W".to_string()])]);
+        let mut keymap = Keymap::default().with_overrides(&overrides).unwrap();
+        assert_eq!(keymap.keys_for(Action::Save), vec!["Ctrl+W"]);
+        assert_eq!(
+            press(&mut keymap, KeyCode::Char('w'), KeyModifiers::CONTROL),
+            Resolution::Action(Action::Save)
+        );
+        assert_eq!(
+            press(&mut keymap, KeyCode::Char('s'), KeyModifiers::CONTROL),
+            Resolution::Unbound
+        );
+        assert!(Keymap::default()
+            .with_overrides(&BTreeMap::from([(Action::Save, vec!["Hyper+S".into()])]))
+            .is_err());
+    }
+}
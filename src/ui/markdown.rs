@@ -0,0 +1,274 @@
+use std::ops::Range;
+
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::app::preview::AssetPreview;
+
+use super::theme::Theme;
+
+/// Lines for a loaded preview: markdown is rendered, other files are shown
+/// verbatim and read errors in red.
+pub fn preview_lines(preview: &AssetPreview, theme: &Theme) -> Vec<Line<'static>> {
+    match preview.content() {
+        Ok(content) if preview.is_markdown() => render(content, theme),
+        Ok(content) => render_plain(content, theme),
+        Err(err) => vec![Line::styled(err.to_string(), theme.fg(theme.error))],
+    }
+}
+
+/// Render markdown into styled lines: headings, list items, block quotes,
+/// fenced code and inline `code`/**bold** spans. The frontmatter block is
+/// skipped since the detail view already shows its fields.
+pub fn render(text: &str, theme: &Theme) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    for line in strip_frontmatter(text).lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            let language = trimmed.trim_start_matches(['`', '~']).trim();
+            let label = if in_code && !language.is_empty() {
+                format!("┌─ {language}")
+            } else if in_code {
+                "┌─".to_string()
+            } else {
+                "└─".to_string()
+            };
+            lines.push(Line::styled(label, theme.fg(theme.code)));
+            continue;
+        }
+        if in_code {
+            lines.push(Line::styled(format!("│ {line}"), theme.fg(theme.code)));
+            continue;
+        }
+        lines.push(render_line(line, theme));
+    }
+    lines
+}
+
+/// Show a non-markdown file (collection YAML) as a single code block.
+fn render_plain(text: &str, theme: &Theme) -> Vec<Line<'static>> {
+    text.lines()
+        .map(|line| Line::styled(line.to_string(), theme.fg(theme.code)))
+        .collect()
+}
+
+fn render_line(line: &str, theme: &Theme) -> Line<'static> {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+
+    let level = trimmed.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+        let mut style = theme.bold(theme.accent);
+        if level == 1 {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        return Line::styled(trimmed[level..].trim().to_string(), style);
+    }
+
+    if let Some(quote) = trimmed.strip_prefix('>') {
+        let mut spans = vec![Span::styled("▌ ", theme.fg(theme.muted))];
+        spans.extend(
+            inline_spans(theme, quote.trim_start())
+                .into_iter()
+                .map(|span| span.style(Style::default().add_modifier(Modifier::ITALIC))),
+        );
+        return Line::from(spans);
+    }
+
+    if trimmed == "---" || trimmed == "***" || trimmed == "___" {
+        return Line::styled("─".repeat(40), theme.fg(theme.muted));
+    }
+
+    let bullet = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| trimmed.strip_prefix(marker));
+    if let Some(item) = bullet {
+        let mut spans = vec![Span::styled(format!("{indent}• "), theme.fg(theme.code))];
+        spans.extend(inline_spans(theme, item));
+        return Line::from(spans);
+    }
+
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 && trimmed[digits..].starts_with(". ") {
+        let mut spans = vec![Span::styled(
+            format!("{indent}{} ", &trimmed[..digits + 1]),
+            theme.fg(theme.code),
+        )];
+        spans.extend(inline_spans(theme, &trimmed[digits + 2..]));
+        return Line::from(spans);
+    }
+
+    Line::from(inline_spans(theme, line))
+}
+
+/// Split a line into plain, `code` and **bold** spans.
+fn inline_spans(theme: &Theme, text: &str) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let code = rest.find('`');
+        let bold = rest.find("**");
+        let bold_style = Style::default().add_modifier(Modifier::BOLD);
+        let (start, marker, style) = match (code, bold) {
+            (Some(c), Some(b)) if b < c => (b, "**", bold_style),
+            (Some(c), _) => (c, "`", theme.fg(theme.code)),
+            (None, Some(b)) => (b, "**", bold_style),
+            (None, None) => break,
+        };
+        let after = &rest[start + marker.len()..];
+        let Some(end) = after.find(marker) else {
+            break;
+        };
+        if start > 0 {
+            spans.push(Span::raw(rest[..start].to_string()));
+        }
+        spans.push(Span::styled(after[..end].to_string(), style));
+        rest = &after[end + marker.len()..];
+    }
+    if !rest.is_empty() {
+        spans.push(Span::raw(rest.to_string()));
+    }
+    spans
+}
+
+fn strip_frontmatter(text: &str) -> &str {
+    let Some(body) = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))
+    else {
+        return text;
+    };
+    match body.find("\n---") {
+        Some(end) => {
+            let rest = &body[end + 4..];
+            rest.strip_prefix("\r\n")
+                .or_else(|| rest.strip_prefix('\n'))
+                .unwrap_or(rest)
+        }
+        None => text,
+    }
+}
+
+/// Line index and byte range of every occurrence of `query` in `lines`,
+/// ignoring ASCII case, in document order.
+pub fn find_matches(lines: &[Line], query: &str) -> Vec<(usize, Range<usize>)> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let needle = query.to_ascii_lowercase();
+    lines
+        .iter()
+        .enumerate()
+        .flat_map(|(index, line)| {
+            let text = line_text(line).to_ascii_lowercase();
+            text.match_indices(&needle)
+                .map(|(start, _)| (index, start..start + needle.len()))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// `line` with each of the sorted byte `ranges` of its text patched with its
+/// style, splitting spans where a range starts or ends inside them.
+pub fn highlight(line: &Line<'static>, ranges: &[(Range<usize>, Style)]) -> Line<'static> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for span in &line.spans {
+        let text = span.content.as_ref();
+        let end = offset + text.len();
+        let mut cursor = offset;
+        for (range, style) in ranges {
+            let start = range.start.max(cursor);
+            let stop = range.end.min(end);
+            if start >= stop {
+                continue;
+            }
+            if start > cursor {
+                spans.push(Span::styled(
+                    text[cursor - offset..start - offset].to_string(),
+                    span.style,
+                ));
+            }
+            spans.push(Span::styled(
+                text[start - offset..stop - offset].to_string(),
+                span.style.patch(*style),
+            ));
+            cursor = stop;
+        }
+        if cursor < end {
+            spans.push(Span::styled(
+                text[cursor - offset..].to_string(),
+                span.style,
+            ));
+        }
+        offset = end;
+    }
+    let mut highlighted = line.clone();
+    highlighted.spans = spans;
+    highlighted
+}
+
+fn line_text(line: &Line) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &Line) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn renders_blocks_without_frontmatter() {
+        let source = "---\ndescription: x\n---\n# Title\n- item `code`\n1. first\n```rust\nlet a = 1;\n```\n";
+        let lines = render(source, &Theme::default());
+        let rendered: Vec<String> = lines.iter().map(text).collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "Title",
+                "• item code",
+                "1. first",
+                "┌─ rust",
+                "│ let a = 1;",
+                "└─",
+            ]
+        );
+        assert!(lines[0].style.add_modifier.contains(Modifier::UNDERLINED));
+        assert_eq!(lines[1].spans[2].style.fg, Some(Theme::default().code));
+    }
+
+    #[test]
+    fn highlights_matches_across_spans() {
+        let lines = render("- run `cargo test` then Cargo fmt", &Theme::default());
+        let matches = find_matches(&lines, "CARGO");
+        assert_eq!(matches, [(0, 8..13), (0, 24..29)]);
+
+        let mark = Style::default().add_modifier(Modifier::REVERSED);
+        let ranges: Vec<_> = matches
+            .into_iter()
+            .map(|(_, range)| (range, mark))
+            .collect();
+        let line = highlight(&lines[0], &ranges);
+        assert_eq!(text(&line), text(&lines[0]));
+        let marked: Vec<_> = line
+            .spans
+            .iter()
+            .filter(|span| span.style.add_modifier.contains(Modifier::REVERSED))
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(marked, ["cargo", "Cargo"]);
+    }
+}
@@ -1,3 +1,8 @@
 pub mod components;
 pub mod draw;
+pub mod help;
 pub mod input;
+pub mod keymap;
+pub mod markdown;
+pub mod overlay;
+pub mod theme;
@@ -0,0 +1,945 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table, TableState,
+        Wrap,
+    },
+    Frame,
+};
+
+use crate::{
+    app::{
+        backups::BackupBrowser,
+        cache::CacheView,
+        changelog::ChangelogView,
+        glob_tester::GlobTester,
+        history::HistoryView,
+        log::LogView,
+        members::MemberPicker,
+        orphans::OrphanBrowser,
+        plan::{ImpactView, PlanView},
+        projects::ProjectPicker,
+        prune::PruneView,
+        reader::ContentReader,
+        repos::RepoSwitcher,
+        save_report::SaveReport,
+        staging::StagedView,
+        unmanaged::UnmanagedView,
+        warnings::WarningsView,
+        wizard::NewAssetWizard,
+        App, Overlay,
+    },
+    domain::toggle::MemberToggleImpact,
+    io::{
+        changelog::CatalogChange, history::HistoryEvent, message_log::MessageLevel,
+        plan::PlannedChange, sync::LocalStatus, worker::FileOp,
+    },
+};
+
+use super::{
+    components::{self, local_status_label},
+    draw::tab_title,
+    help, markdown,
+    theme::Theme,
+};
+
+pub fn render(frame: &mut Frame<'_>, area: Rect, app: &App, overlay: &Overlay) {
+    frame.render_widget(Clear, area);
+    match overlay {
+        Overlay::Backups(browser) => render_backups(frame, area, app.theme(), browser),
+        Overlay::Orphans(browser) => render_orphans(frame, area, app, browser),
+        Overlay::Members(picker) => render_members(frame, area, app, picker),
+        Overlay::Changelog(view) => render_changelog(frame, area, app, view),
+        Overlay::Help(view) => help::render(frame, area, app, view),
+        Overlay::Reader(reader) => render_reader(frame, area, reader, app.theme()),
+        Overlay::Prune(view) => render_prune(frame, area, app, view),
+        Overlay::Unmanaged(view) => render_unmanaged(frame, area, app, view),
+        Overlay::Staged(view) => render_staged(frame, area, app, view),
+        Overlay::Warnings(view) => render_warnings(frame, area, app, view),
+        Overlay::Cache(view) => render_cache(frame, area, app, view),
+        Overlay::Repos(view) => render_repos(frame, area, app, view),
+        Overlay::Projects(view) => render_projects(frame, area, app, view),
+        Overlay::Plan(view) => render_plan(frame, area, app.theme(), view),
+        Overlay::Impact(view) => render_impact(frame, area, app.theme(), view),
+        Overlay::SaveReport(report) => render_save_report(frame, area, app.theme(), report),
+        Overlay::Wizard(wizard) => render_wizard(frame, area, app.theme(), wizard),
+        Overlay::GlobTester(tester) => render_glob_tester(frame, area, app, tester),
+        Overlay::History(view) => render_history(frame, area, app, view),
+        Overlay::Log(view) => render_log(frame, area, app, view),
+    }
+}
+
+fn render_backups(frame: &mut Frame<'_>, area: Rect, theme: &Theme, browser: &BackupBrowser) {
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(area);
+
+    let items: Vec<ListItem> = browser
+        .entries()
+        .iter()
+        .map(|entry| ListItem::new(format!("{}  {}", entry.timestamp, entry.relative.display())))
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Backups ({})", browser.entries().len())),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default();
+    state.select(Some(browser.selected()));
+    frame.render_stateful_widget(list, columns[0], &mut state);
+
+    let lines: Vec<Line> = browser
+        .diff()
+        .iter()
+        .map(|line| diff_line(theme, line))
+        .collect();
+    let diff = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Current → backup (Enter restores, PgUp/PgDn scroll)"),
+        )
+        .scroll((browser.scroll(), 0));
+    frame.render_widget(diff, columns[1]);
+}
+
+fn render_orphans(frame: &mut Frame<'_>, area: Rect, app: &App, browser: &OrphanBrowser) {
+    let rows: Vec<Row> = app
+        .orphans()
+        .iter()
+        .map(|orphan| {
+            let value = if orphan.value { "enabled" } else { "disabled" };
+            let style = app.theme().state(orphan.value);
+            Row::new(vec![
+                Cell::from(tab_title(orphan.kind)),
+                Cell::from(orphan.path.clone()),
+                Cell::from(value).style(style),
+                Cell::from(orphan.successor.clone().unwrap_or_else(|| "—".into())),
+            ])
+        })
+        .collect();
+    let header = Row::new(vec!["Kind", "Stored path", "Value", "Likely successor"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Length(14),
+        Constraint::Percentage(40),
+        Constraint::Length(9),
+        Constraint::Percentage(40),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Orphans ({}) — d delete • Enter remap • Esc close",
+            app.orphans().len()
+        )))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = TableState::default();
+    state.select(Some(browser.selected()));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn render_members(frame: &mut Frame<'_>, area: Rect, app: &App, picker: &MemberPicker) {
+    let members = app.collection_members(picker.collection());
+    let enabled = members.iter().filter(|member| member.effective).count();
+    let rows: Vec<Row> = members
+        .iter()
+        .map(|member| {
+            let badge = components::state_badge(member, app.theme().ascii);
+            let source = match (member.explicit, &member.inherited, &member.rule) {
+                (Some(_), _, _) => "explicit",
+                (None, Some(_), _) => "collection",
+                (None, None, Some(_)) => "rule",
+                (None, None, None) => "default",
+            };
+            Row::new(vec![
+                Cell::from(badge).style(app.theme().state(member.effective)),
+                Cell::from(tab_title(member.kind)),
+                Cell::from(member.name.clone()),
+                Cell::from(source),
+                Cell::from(components::local_status(member)),
+            ])
+        })
+        .collect();
+    let header = Row::new(vec!["State", "Kind", "Name", "Set by", "Local"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Length(9),
+        Constraint::Length(14),
+        Constraint::Percentage(50),
+        Constraint::Length(10),
+        Constraint::Length(8),
+    ];
+    let name = app
+        .domain()
+        .catalog
+        .collection_by_path(picker.collection())
+        .map_or(picker.collection(), |collection| collection.name.as_str());
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Collections ▸ {name} — {enabled}/{} enabled — Space toggle • a apply • Esc back",
+            members.len()
+        )))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = TableState::default();
+    state.select(Some(picker.selected()));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn render_prune(frame: &mut Frame<'_>, area: Rect, app: &App, view: &PruneView) {
+    let theme = app.theme();
+    let rows: Vec<Row> = view
+        .candidates()
+        .iter()
+        .map(|candidate| {
+            Row::new(vec![
+                Cell::from(tab_title(candidate.kind)),
+                Cell::from(candidate.display_path(app.paths())),
+                Cell::from(candidate.reason.label()).style(theme.fg(theme.warning)),
+            ])
+        })
+        .collect();
+    let header = Row::new(vec!["Kind", "Local file", "Reason"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Length(14),
+        Constraint::Percentage(65),
+        Constraint::Length(16),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Prune ({}) — d delete • y delete all • Esc close (backups are kept)",
+            view.candidates().len()
+        )))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = TableState::default();
+    state.select(Some(view.selected()));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn render_unmanaged(frame: &mut Frame<'_>, area: Rect, app: &App, view: &UnmanagedView) {
+    let theme = app.theme();
+    let status = local_status_label(LocalStatus::Unmanaged);
+    let rows: Vec<Row> = view
+        .files()
+        .iter()
+        .map(|file| {
+            Row::new(vec![
+                Cell::from(tab_title(file.kind)),
+                Cell::from(file.display_path(app.paths())),
+                Cell::from(status).style(theme.fg(theme.warning)),
+            ])
+        })
+        .collect();
+    let header = Row::new(vec!["Kind", "Local file", "Status"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Length(14),
+        Constraint::Percentage(65),
+        Constraint::Length(16),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Unmanaged ({}) — i ignore • a adopt as local asset • d delete • Esc close",
+            view.files().len()
+        )))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = TableState::default();
+    state.select(Some(view.selected()));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn render_staged(frame: &mut Frame<'_>, area: Rect, app: &App, view: &StagedView) {
+    let theme = app.theme();
+    let rows: Vec<Row> = app
+        .staged()
+        .iter()
+        .map(|op| {
+            let (kind, path) = op.asset();
+            let (sign, color) = match op {
+                FileOp::Apply(..) => ("+ apply", theme.success),
+                FileOp::Remove(..) => ("- remove", theme.error),
+            };
+            let name = app
+                .domain()
+                .asset_by_path(path)
+                .map_or(path, |view| view.name.as_str());
+            Row::new(vec![
+                Cell::from(sign).style(theme.fg(color)),
+                Cell::from(tab_title(kind)),
+                Cell::from(name.to_string()),
+                Cell::from(path.to_string()).style(theme.fg(theme.muted)),
+            ])
+        })
+        .collect();
+    let header = Row::new(vec!["Change", "Kind", "Asset", "Catalog path"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Length(10),
+        Constraint::Length(14),
+        Constraint::Percentage(35),
+        Constraint::Percentage(45),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Staged ({}) — d unstage • y save and run all • Esc close",
+            app.staged().len()
+        )))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = TableState::default();
+    state.select(Some(view.selected()));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn render_warnings(frame: &mut Frame<'_>, area: Rect, app: &App, view: &WarningsView) {
+    let theme = app.theme();
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(area);
+
+    let items: Vec<ListItem> = app
+        .warnings()
+        .iter()
+        .map(|warning| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:<11} ", warning.source.label()),
+                    theme.fg(theme.muted),
+                ),
+                Span::styled(warning.text.clone(), theme.fg(theme.warning)),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Warnings ({}) — d dismiss • D dismiss all • Esc close",
+            app.warnings().len()
+        )))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default();
+    state.select(Some(view.selected()));
+    frame.render_stateful_widget(list, rows[0], &mut state);
+
+    let text = app
+        .warnings()
+        .get(view.selected())
+        .map_or(String::new(), |warning| warning.text.clone());
+    let detail = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Selected warning"),
+    );
+    frame.render_widget(detail, rows[1]);
+}
+
+fn render_repos(frame: &mut Frame<'_>, area: Rect, app: &App, view: &RepoSwitcher) {
+    let theme = app.theme();
+    let rows: Vec<Row> = view
+        .repos()
+        .iter()
+        .map(|repo| {
+            let opened = repo
+                .opened_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string();
+            let state = if app.is_current_repo(&repo.path) {
+                Cell::from("current").style(theme.fg(theme.success))
+            } else if !repo.path.is_dir() {
+                Cell::from("missing").style(theme.fg(theme.error))
+            } else {
+                Cell::from("")
+            };
+            Row::new(vec![
+                Cell::from(repo.path.display().to_string()),
+                Cell::from(opened),
+                state,
+            ])
+        })
+        .collect();
+    let header = Row::new(vec!["Repository", "Last opened", ""])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Min(30),
+        Constraint::Length(18),
+        Constraint::Length(8),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Repositories ({}) — Enter switch • d forget • Esc close",
+            view.repos().len()
+        )))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = TableState::default();
+    state.select(Some(view.selected()));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn render_projects(frame: &mut Frame<'_>, area: Rect, app: &App, view: &ProjectPicker) {
+    let theme = app.theme();
+    let paths = app.paths();
+    let rows: Vec<Row> = view
+        .projects()
+        .iter()
+        .map(|project| {
+            let (name, dir) = match project {
+                Some(project) => (project.clone(), paths.root.join(project)),
+                None => ("(repository root)".to_string(), paths.root.clone()),
+            };
+            let state = if *project == paths.project {
+                Cell::from("current").style(theme.fg(theme.success))
+            } else if !dir.is_dir() {
+                Cell::from("missing").style(theme.fg(theme.error))
+            } else if !dir.join(".github").is_dir() {
+                Cell::from("no .github yet").style(theme.fg(theme.muted))
+            } else {
+                Cell::from("")
+            };
+            Row::new(vec![Cell::from(name), state])
+        })
+        .collect();
+    let header = Row::new(vec!["Project", ""]).style(Style::default().add_modifier(Modifier::BOLD));
+    let widths = [Constraint::Min(30), Constraint::Length(16)];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Sub-projects of {} — Enter switch • Esc close",
+            paths.root.display()
+        )))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = TableState::default();
+    state.select(Some(view.selected()));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn render_cache(frame: &mut Frame<'_>, area: Rect, app: &App, view: &CacheView) {
+    let theme = app.theme();
+    let rows: Vec<Row> = view
+        .snapshots()
+        .iter()
+        .map(|(source, snapshot)| {
+            let fetched = snapshot.fetched_at.map_or("unreadable".to_string(), |at| {
+                at.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            });
+            let state = if app.snapshot_in_use(source, snapshot) {
+                Cell::from("in use").style(theme.fg(theme.success))
+            } else {
+                Cell::from("")
+            };
+            let pinned = app.pinned_ref() == Some(snapshot.commit.as_str());
+            Row::new(vec![
+                Cell::from(source.clone()),
+                Cell::from(format!(
+                    "{}{}",
+                    snapshot.commit.chars().take(12).collect::<String>(),
+                    if pinned { " (pinned)" } else { "" }
+                )),
+                Cell::from(fetched),
+                Cell::from(human_size(snapshot.size)),
+                state,
+            ])
+        })
+        .collect();
+    let total: u64 = view.snapshots().iter().map(|(_, s)| s.size).sum();
+    let header = Row::new(vec!["Source", "Commit", "Fetched", "Size", ""])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Length(14),
+        Constraint::Length(22),
+        Constraint::Length(18),
+        Constraint::Length(10),
+        Constraint::Length(8),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Snapshot cache ({}, {}) — Enter switch • u unpin • d delete • D delete unused • Esc close",
+            view.snapshots().len(),
+            human_size(total)
+        )))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = TableState::default();
+    state.select(Some(view.selected()));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+fn render_plan(frame: &mut Frame<'_>, area: Rect, theme: &Theme, view: &PlanView) {
+    let lines: Vec<Line> = view
+        .plan()
+        .entries
+        .iter()
+        .map(|entry| {
+            let color = match entry.change {
+                PlannedChange::Create => theme.success,
+                PlannedChange::Overwrite | PlannedChange::Merge => theme.warning,
+                PlannedChange::Remove => theme.error,
+            };
+            if view.is_uncommitted(&entry.path) {
+                Line::from(vec![
+                    Span::styled(entry.to_string(), theme.fg(color)),
+                    Span::styled("  (uncommitted changes)", theme.bold(theme.error)),
+                ])
+            } else {
+                Line::styled(entry.to_string(), theme.fg(color))
+            }
+        })
+        .collect();
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(match view.uncommitted_count() {
+                    0 => format!("Plan: {} — {}", view.operation(), view.plan()),
+                    count => format!(
+                        "Plan: {} — {} — {count} with uncommitted changes",
+                        view.operation(),
+                        view.plan()
+                    ),
+                })
+                .title_bottom("y apply • n/Esc cancel • PgUp/PgDn scroll"),
+        )
+        .scroll((view.scroll(), 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn render_impact(frame: &mut Frame<'_>, area: Rect, theme: &Theme, view: &ImpactView) {
+    let impact = view.impact();
+    // Requirements outside the collection follow its members.
+    let required = impact.dependencies.iter().map(|member| (member, true));
+    let lines: Vec<Line> = impact
+        .affected_members
+        .iter()
+        .map(|member| (member, false))
+        .chain(required)
+        .map(|(member, required)| {
+            let (label, color) = match member.impact {
+                _ if required => ("+ required ", theme.success),
+                MemberToggleImpact::WillEnable => ("+ enable   ", theme.success),
+                MemberToggleImpact::WillDisable => ("- disable  ", theme.error),
+                MemberToggleImpact::Unchanged => ("= unchanged", theme.muted),
+            };
+            Line::from(vec![
+                Span::styled(label, theme.fg(color)),
+                Span::raw(format!(
+                    "  {} ({}, {})",
+                    member.name,
+                    tab_title(member.kind),
+                    member.path
+                )),
+            ])
+        })
+        .collect();
+    let action = if impact.collection_will_enable {
+        "Enable"
+    } else {
+        "Disable"
+    };
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "{action} {} — {} of {} members enable, {} disable, {} unchanged",
+                    impact.collection_name,
+                    impact.enable_count,
+                    impact.total_members,
+                    impact.disable_count,
+                    impact.unchanged_count
+                ))
+                .title_bottom("y toggle • n/Esc cancel • PgUp/PgDn scroll"),
+        )
+        .scroll((view.scroll(), 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn render_changelog(frame: &mut Frame<'_>, area: Rect, app: &App, view: &ChangelogView) {
+    let theme = app.theme();
+    let changelog = app.changelog();
+    let short = |commit: Option<&String>| {
+        commit.map_or("none".to_string(), |commit| {
+            commit.chars().take(7).collect()
+        })
+    };
+    let mut lines: Vec<Line> = changelog
+        .to
+        .iter()
+        .filter(|(source, commit)| changelog.from.get(*source) != Some(*commit))
+        .map(|(source, commit)| {
+            Line::styled(
+                format!(
+                    "{source}: {} → {}",
+                    short(changelog.from.get(source)),
+                    short(Some(commit))
+                ),
+                theme.fg(theme.muted),
+            )
+        })
+        .collect();
+    lines.push(Line::default());
+    for change in &changelog.changes {
+        let entry = change.entry();
+        let (text, color) = match change {
+            CatalogChange::Added(_) => ("+ added  ".to_string(), theme.success),
+            CatalogChange::Removed(_) => ("- removed".to_string(), theme.error),
+            CatalogChange::Changed(_) => ("~ changed".to_string(), theme.warning),
+            CatalogChange::Renamed { from, .. } => {
+                (format!("→ renamed from {}", from.path), theme.accent)
+            }
+        };
+        lines.push(Line::styled(
+            format!(
+                "  {text}  {} {} ({})",
+                tab_title(entry.kind),
+                entry.name,
+                entry.path
+            ),
+            theme.fg(color),
+        ));
+    }
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "What changed upstream ({})",
+                    changelog.changes.len()
+                ))
+                .title_bottom("Up/Down scroll • Esc close"),
+        )
+        .scroll((view.scroll(), 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn render_save_report(frame: &mut Frame<'_>, area: Rect, theme: &Theme, report: &SaveReport) {
+    let heading = |text: String| Line::styled(text, theme.bold(theme.accent));
+    let mut lines = vec![heading(format!(
+        "Enablement entries ({})",
+        report.entries().len()
+    ))];
+    for change in report.entries() {
+        let (text, color) = match (change.before, change.after) {
+            (None, Some(value)) => (format!("+ added = {value}"), theme.success),
+            (Some(_), None) => ("- removed".to_string(), theme.error),
+            (before, after) => (
+                format!(
+                    "~ {} → {}",
+                    before.unwrap_or_default(),
+                    after.unwrap_or_default()
+                ),
+                theme.warning,
+            ),
+        };
+        lines.push(Line::styled(
+            format!("  {text}  {} {}", tab_title(change.kind), change.path),
+            theme.fg(color),
+        ));
+    }
+    let files = report.files();
+    lines.push(Line::default());
+    lines.push(heading(format!("Files under .github ({files})")));
+    for (marker, paths, color) in [
+        ("+ copied ", &files.created, theme.success),
+        ("~ updated", &files.updated, theme.warning),
+        ("- deleted", &files.deleted, theme.error),
+    ] {
+        for path in paths {
+            lines.push(Line::styled(format!("  {marker}  {path}"), theme.fg(color)));
+        }
+    }
+    if !report.warnings().is_empty() {
+        lines.push(Line::default());
+        lines.push(heading(format!("Warnings ({})", report.warnings().len())));
+        for warning in report.warnings() {
+            lines.push(Line::styled(
+                format!("  {warning}"),
+                theme.fg(theme.warning),
+            ));
+        }
+    }
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Saved")
+                .title_bottom("Up/Down scroll • Esc close"),
+        )
+        .scroll((report.scroll(), 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn render_wizard(frame: &mut Frame<'_>, area: Rect, theme: &Theme, wizard: &NewAssetWizard) {
+    let mut lines = Vec::new();
+    for (index, field) in wizard.fields().iter().enumerate() {
+        let focused = index == wizard.current();
+        let label_style = if focused {
+            theme.bold(theme.accent)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<12}", field.label), label_style),
+            Span::styled(field.value.clone(), theme.fg(theme.code)),
+            Span::raw(if focused { "▏" } else { "" }),
+        ]));
+        lines.push(Line::styled(
+            format!("{:<12}{}", "", field.hint),
+            theme.fg(theme.muted),
+        ));
+    }
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                "New {} — saved to .awesome-copilot-tui/local",
+                tab_title(wizard.kind())
+                    .trim_end_matches('s')
+                    .to_lowercase()
+            ))
+            .title_bottom("Enter next/create • Shift+Tab back • Esc cancel"),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+fn render_history(frame: &mut Frame<'_>, area: Rect, app: &App, view: &HistoryView) {
+    let theme = app.theme();
+    let (entries, session_start) = app.history();
+    let mut lines = Vec::new();
+    for (index, entry) in entries.iter().enumerate().rev() {
+        if index + 1 == session_start {
+            lines.push(Line::styled(
+                "── earlier sessions ──",
+                theme.fg(theme.muted),
+            ));
+        }
+        let local = entry.at.with_timezone(&chrono::Local);
+        let time = if index < session_start {
+            local.format("%Y-%m-%d %H:%M").to_string()
+        } else {
+            local.format("%H:%M:%S").to_string()
+        };
+        let color = match entry.event {
+            HistoryEvent::Toggle => theme.accent,
+            HistoryEvent::Apply => theme.success,
+            HistoryEvent::Reset => theme.error,
+            HistoryEvent::Save => theme.muted,
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{time}  "), theme.fg(theme.muted)),
+            Span::styled(format!("{:<7}", entry.event.label()), theme.fg(color)),
+            Span::raw(entry.summary.clone()),
+        ]));
+    }
+    let unsaved = entries[session_start..]
+        .iter()
+        .rev()
+        .take_while(|entry| entry.event != HistoryEvent::Save)
+        .filter(|entry| entry.event != HistoryEvent::Apply)
+        .count();
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "Activity — {unsaved} enablement change(s) since the last save"
+                ))
+                .title_bottom("Newest first • Up/Down scroll • Esc close"),
+        )
+        .scroll((view.scroll(), 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn render_log(frame: &mut Frame<'_>, area: Rect, app: &App, view: &LogView) {
+    let theme = app.theme();
+    let lines: Vec<Line> = app
+        .message_log()
+        .iter()
+        .rev()
+        .map(|entry| {
+            let time = entry.at.with_timezone(&chrono::Local).format("%H:%M:%S");
+            let color = match entry.level {
+                MessageLevel::Info => theme.success,
+                MessageLevel::Error => theme.error,
+            };
+            Line::from(vec![
+                Span::styled(format!("{time}  "), theme.fg(theme.muted)),
+                Span::styled(format!("{:<6}", entry.level.label()), theme.fg(color)),
+                Span::raw(entry.text.clone()),
+            ])
+        })
+        .collect();
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Messages ({})", app.message_log().len()))
+                .title_bottom("Newest first • Up/Down scroll • Esc close"),
+        )
+        .scroll((view.scroll(), 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn render_glob_tester(frame: &mut Frame<'_>, area: Rect, app: &App, tester: &GlobTester) {
+    let theme = app.theme();
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Path: ", theme.bold(theme.accent)),
+            Span::styled(tester.input().to_string(), theme.fg(theme.code)),
+            Span::raw("▏"),
+        ]),
+        Line::from(""),
+    ];
+    let matches = app.apply_to_matches(tester.input());
+    if tester.input().trim().is_empty() {
+        lines.push(Line::styled(
+            "Type a path relative to the repository, e.g. src/lib.rs",
+            theme.fg(theme.muted),
+        ));
+    } else if matches.is_empty() {
+        lines.push(Line::from("No instruction's applyTo matches this path."));
+    }
+    let (enabled, disabled): (Vec<_>, Vec<_>) = matches
+        .iter()
+        .partition(|found| found.instruction.effective);
+    for (title, group, mark, color) in [
+        ("Enabled — Copilot uses these", &enabled, "✓", theme.enabled),
+        (
+            "Disabled — would apply once enabled",
+            &disabled,
+            "✗",
+            theme.disabled,
+        ),
+    ] {
+        if group.is_empty() {
+            continue;
+        }
+        lines.push(Line::styled(
+            format!("{title} ({})", group.len()),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        for found in group {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {mark} "), theme.fg(color)),
+                Span::raw(found.instruction.name.clone()),
+                Span::styled(
+                    format!("  {}", found.instruction.path),
+                    theme.fg(theme.muted),
+                ),
+            ]));
+            lines.push(Line::styled(
+                format!("      applyTo: {}", found.patterns.join(", ")),
+                theme.fg(theme.muted),
+            ));
+        }
+        lines.push(Line::from(""));
+    }
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("applyTo tester — which instructions apply to a file")
+                .title_bottom("Type a path • Ctrl+U clear • Up/Down scroll • Esc close"),
+        )
+        .scroll((tester.scroll(), 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn render_reader(frame: &mut Frame<'_>, area: Rect, reader: &ContentReader, theme: &Theme) {
+    let scroll = reader
+        .scroll()
+        .min(reader.max_scroll(area.width, area.height));
+    let mut lines = reader.lines().to_vec();
+    let mark = Style::default().add_modifier(Modifier::REVERSED);
+    let current = theme.bold(theme.accent).add_modifier(Modifier::REVERSED);
+    for (index, line) in lines.iter_mut().enumerate() {
+        let ranges: Vec<_> = reader
+            .matches()
+            .iter()
+            .enumerate()
+            .filter(|(_, (line, _))| *line == index)
+            .map(|(position, (_, range))| {
+                let style = if position == reader.current() {
+                    current
+                } else {
+                    mark
+                };
+                (range.clone(), style)
+            })
+            .collect();
+        if !ranges.is_empty() {
+            *line = markdown::highlight(line, &ranges);
+        }
+    }
+    let footer = match reader.input() {
+        Some(input) => format!("/{input}▏ Enter search • Esc cancel"),
+        None if !reader.query().is_empty() && reader.matches().is_empty() => {
+            format!(
+                "/{}: no matches • / search again • Esc clears",
+                reader.query()
+            )
+        }
+        None if !reader.query().is_empty() => format!(
+            "/{}: match {} of {} • n/N next/previous • Esc clears",
+            reader.query(),
+            reader.current() + 1,
+            reader.matches().len()
+        ),
+        None => "PgUp/PgDn scroll • Home/End jump • / search • Esc returns".to_string(),
+    };
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("{} — {}", reader.name(), reader.path()))
+                .title_bottom(footer),
+        )
+        .scroll((scroll, 0));
+    frame.render_widget(paragraph, area);
+}
+
+/// Color a line of unified diff output.
+pub fn diff_line(theme: &Theme, line: &str) -> Line<'static> {
+    let style = if line.starts_with("+++") || line.starts_with("---") {
+        Style::default().add_modifier(Modifier::BOLD)
+    } else if line.starts_with('+') {
+        theme.fg(theme.success)
+    } else if line.starts_with('-') {
+        theme.fg(theme.error)
+    } else if line.starts_with("@@") {
+        theme.fg(theme.accent)
+    } else {
+        Style::default()
+    };
+    Line::styled(line.to_string(), style)
+}
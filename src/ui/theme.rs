@@ -0,0 +1,255 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use anyhow::{anyhow, bail, Result};
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier, Style},
+};
+use serde::Deserialize;
+
+/// Semantic colors used by every widget, so no view hard-codes a palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    /// Headings, the active tab, focused borders and the search prompt.
+    pub accent: Color,
+    pub enabled: Color,
+    pub disabled: Color,
+    /// Informational messages and added diff lines.
+    pub success: Color,
+    /// Warnings, prompts and the snapshot pin.
+    pub warning: Color,
+    pub error: Color,
+    pub marked: Color,
+    /// Background of the visual range.
+    pub selection: Color,
+    /// Dividers and other de-emphasized chrome.
+    pub muted: Color,
+    /// Code, key names and other literal text.
+    pub code: Color,
+    /// Off when `NO_COLOR` is set: only bold and reversed text remain.
+    pub color: bool,
+    /// Draw badges, borders and markers with ASCII characters only.
+    pub ascii: bool,
+}
+
+const DARK: Theme = Theme {
+    name: "dark",
+    accent: Color::Cyan,
+    enabled: Color::Green,
+    disabled: Color::Red,
+    success: Color::Green,
+    warning: Color::Yellow,
+    error: Color::Red,
+    marked: Color::Magenta,
+    selection: Color::DarkGray,
+    muted: Color::DarkGray,
+    code: Color::Yellow,
+    color: true,
+    ascii: false,
+};
+
+const LIGHT: Theme = Theme {
+    name: "light",
+    accent: Color::Blue,
+    enabled: Color::Indexed(28),
+    disabled: Color::Indexed(160),
+    success: Color::Indexed(28),
+    warning: Color::Indexed(130),
+    error: Color::Indexed(160),
+    marked: Color::Magenta,
+    selection: Color::Indexed(252),
+    muted: Color::Indexed(244),
+    code: Color::Indexed(94),
+    color: true,
+    ascii: false,
+};
+
+const HIGH_CONTRAST: Theme = Theme {
+    name: "high-contrast",
+    accent: Color::LightCyan,
+    enabled: Color::LightGreen,
+    disabled: Color::LightRed,
+    success: Color::LightGreen,
+    warning: Color::LightYellow,
+    error: Color::LightRed,
+    marked: Color::LightMagenta,
+    selection: Color::Blue,
+    muted: Color::White,
+    code: Color::LightYellow,
+    color: true,
+    ascii: false,
+};
+
+pub const THEMES: [&str; 3] = ["dark", "light", "high-contrast"];
+
+impl Theme {
+    pub fn builtin(name: &str) -> Result<Self> {
+        match name {
+            "dark" => Ok(DARK),
+            "light" => Ok(LIGHT),
+            "high-contrast" => Ok(HIGH_CONTRAST),
+            other => bail!(
+                "unknown theme '{other}' (expected one of: {})",
+                THEMES.join(", ")
+            ),
+        }
+    }
+
+    pub fn fg(&self, color: Color) -> Style {
+        if self.color {
+            Style::default().fg(color)
+        } else {
+            Style::default()
+        }
+    }
+
+    pub fn bold(&self, color: Color) -> Style {
+        self.fg(color).add_modifier(Modifier::BOLD)
+    }
+
+    /// Style for an enabled/disabled state.
+    pub fn state(&self, enabled: bool) -> Style {
+        self.fg(if enabled { self.enabled } else { self.disabled })
+    }
+
+    /// Rewrite a rendered frame for terminals without color or Unicode:
+    /// without color, backgrounds become reversed text; in ASCII mode, box
+    /// drawing and symbols get ASCII stand-ins and other non-ASCII
+    /// characters become `?`.
+    pub fn degrade(&self, buffer: &mut Buffer) {
+        if self.color && !self.ascii {
+            return;
+        }
+        for cell in &mut buffer.content {
+            if !self.color {
+                if cell.bg != Color::Reset {
+                    cell.modifier |= Modifier::REVERSED;
+                }
+                cell.fg = Color::Reset;
+                cell.bg = Color::Reset;
+            }
+            if self.ascii && !cell.symbol().is_ascii() {
+                let symbol = ascii_symbol(cell.symbol());
+                cell.set_char(symbol);
+            }
+        }
+    }
+
+    fn set(&mut self, slot: &str, color: Color) -> Result<()> {
+        let target = match slot {
+            "accent" => &mut self.accent,
+            "enabled" => &mut self.enabled,
+            "disabled" => &mut self.disabled,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            "marked" => &mut self.marked,
+            "selection" => &mut self.selection,
+            "muted" => &mut self.muted,
+            "code" => &mut self.code,
+            other => bail!("unknown theme color '{other}'"),
+        };
+        *target = color;
+        Ok(())
+    }
+}
+
+/// An ASCII character standing in for a one-cell glyph.
+fn ascii_symbol(symbol: &str) -> char {
+    match symbol {
+        "─" | "━" | "═" | "▔" | "▁" => '-',
+        "│" | "┃" | "║" | "▏" | "▕" => '|',
+        "┌" | "┐" | "└" | "┘" | "├" | "┤" | "┬" | "┴" | "┼" | "╭" | "╮" | "╰" | "╯" => {
+            '+'
+        }
+        "•" | "·" | "●" | "★" => '*',
+        "→" | "↳" | "▶" | "▸" | "»" => '>',
+        "←" | "◀" | "«" => '<',
+        "↑" => '^',
+        "↓" => 'v',
+        "✓" => '+',
+        "✗" => 'x',
+        "⚠" => '!',
+        "⚙" => '#',
+        "◐" => '~',
+        "…" => '.',
+        "—" | "–" => '-',
+        "█" | "▉" | "▊" | "▋" | "▌" | "▍" | "▎" => '#',
+        // The second cell of a wide character.
+        "" => ' ',
+        _ => '?',
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        DARK
+    }
+}
+
+/// Contents of the user's `theme.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    /// Built-in theme the colors are layered on.
+    pub base: Option<String>,
+    /// Color per slot: a name (`cyan`, `lightred`), `#rrggbb`, or a 256-color index.
+    pub colors: BTreeMap<String, String>,
+}
+
+impl ThemeConfig {
+    /// Build the theme; `name` (from the command line) wins over the file.
+    pub fn build(&self, name: Option<&str>) -> Result<Theme> {
+        let mut theme = Theme::builtin(name.or(self.base.as_deref()).unwrap_or("dark"))?;
+        for (slot, value) in &self.colors {
+            let color = Color::from_str(value)
+                .map_err(|_| anyhow!("invalid color '{value}' for '{slot}'"))?;
+            theme.set(slot, color)?;
+        }
+        Ok(theme)
+    }
+}
+
+pub fn parse_theme(name: &str) -> Result<String> {
+    Theme::builtin(name).map(|theme| theme.name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_layer_on_base_theme() {
+        let config: ThemeConfig =
+            toml::from_str("base = \"light\"\n[colors]\naccent = \"#ff8800\"\nmarked = \"201\"\n")
+                .unwrap();
+        let theme = config.build(None).unwrap();
+        assert_eq!(theme.name, "light");
+        assert_eq!(theme.accent, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(theme.marked, Color::Indexed(201));
+        assert_eq!(config.build(Some("dark")).unwrap().enabled, Color::Green);
+
+        let bad: ThemeConfig = toml::from_str("[colors]\nborder = \"red\"\n").unwrap();
+        assert!(bad.build(None).is_err());
+    }
+
+    #[test]
+    fn degrades_to_plain_ascii() {
+        let mut buffer = Buffer::empty(ratatui::layout::Rect::new(0, 0, 6, 1));
+        buffer.set_string(0, 0, "┌✓ On", Style::default().fg(Color::Green));
+        buffer.set_string(5, 0, "é", Style::default().bg(Color::DarkGray));
+        let theme = Theme {
+            color: false,
+            ascii: true,
+            ..Theme::default()
+        };
+        theme.degrade(&mut buffer);
+
+        let text: String = buffer.content.iter().map(|cell| cell.symbol()).collect();
+        assert_eq!(text, "++ On?");
+        assert!(buffer.content.iter().all(|cell| cell.fg == Color::Reset));
+        assert!(buffer.content[5].modifier.contains(Modifier::REVERSED));
+        assert!(!buffer.content[0].modifier.contains(Modifier::REVERSED));
+    }
+}